@@ -0,0 +1,32 @@
+//! Automatic context compaction - summarizing old history before it falls out of the window
+
+use opensam_provider::Message;
+
+/// Instruction given to the model when summarizing history
+pub const COMPACTION_PROMPT: &str = "Summarize the conversation below for your own future reference. \
+Keep facts, decisions, user preferences and open tasks; drop small talk. Reply with the summary only.";
+
+/// History length at which compaction starts for a `window`-message context
+///
+/// `threshold` is a fraction of the window, e.g. 0.8.
+pub fn trigger_len(window: usize, threshold: f32) -> usize {
+    ((window as f32 * threshold).ceil() as usize).max(1)
+}
+
+/// Messages to keep verbatim after compacting a `window`-message context
+pub fn keep_recent(window: usize) -> usize {
+    window / 2
+}
+
+/// Build the summarization request from `old` session messages
+pub fn summary_request(old: &[opensam_session::Message]) -> Vec<Message> {
+    let transcript = old
+        .iter()
+        .map(|m| format!("{}: {}", m.role, m.content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    vec![
+        Message::system(COMPACTION_PROMPT),
+        Message::user(transcript),
+    ]
+}
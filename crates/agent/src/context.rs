@@ -6,9 +6,36 @@ use tracing::debug;
 
 use opensam_provider::Message;
 
+/// Placeholder in bootstrap files replaced with the assistant name
+pub const NAME_PLACEHOLDER: &str = "{{name}}";
+
+/// Assistant name used when none is configured
+pub const DEFAULT_NAME: &str = "OpenSAM";
+
+/// Longest replied-to text quoted back to the model, in characters
+pub const MAX_QUOTE_CHARS: usize = 500;
+
+/// Prefix `content` with the message it replies to, quoted
+///
+/// The quote is trimmed to `MAX_QUOTE_CHARS` so a reply to a long message
+/// does not crowd out the rest of the context.
+pub fn quote_reply(content: &str, replied_text: &str, sender: Option<&str>) -> String {
+    let mut quoted: String = replied_text.trim().chars().take(MAX_QUOTE_CHARS).collect();
+    if replied_text.trim().chars().count() > MAX_QUOTE_CHARS {
+        quoted.push('…');
+    }
+    let header = match sender {
+        Some(sender) => format!("[Replying to {}]", sender),
+        None => "[Replying to]".to_string(),
+    };
+    let quote: Vec<String> = quoted.lines().map(|line| format!("> {}", line)).collect();
+    format!("{}\n{}\n\n{}", header, quote.join("\n"), content)
+}
+
 /// Builds context (system prompt + messages) for the agent
 pub struct ContextBuilder {
     workspace: PathBuf,
+    name: String,
 }
 
 impl ContextBuilder {
@@ -19,11 +46,28 @@ impl ContextBuilder {
     pub fn new(workspace: impl AsRef<Path>) -> Self {
         Self {
             workspace: workspace.as_ref().to_path_buf(),
+            name: DEFAULT_NAME.to_string(),
         }
     }
 
+    /// Set the assistant name substituted into bootstrap files
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Assistant name substituted into bootstrap files
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Build the system prompt
     pub async fn build_system_prompt(&self) -> String {
+        self.build_system_prompt_with_suffix(None).await
+    }
+
+    /// Build the system prompt, appending a per-session `suffix`
+    pub async fn build_system_prompt_with_suffix(&self, suffix: Option<&str>) -> String {
         let mut parts = vec![self.identity()];
 
         // Load bootstrap files
@@ -40,6 +84,10 @@ impl ContextBuilder {
             }
         }
 
+        if let Some(suffix) = suffix.map(str::trim).filter(|s| !s.is_empty()) {
+            parts.push(suffix.to_string());
+        }
+
         parts.join("\n\n---\n\n")
     }
 
@@ -82,6 +130,7 @@ When remembering something, write to {}/lifepod/MEMORY.md"#,
             if path.exists() {
                 match tokio::fs::read_to_string(&path).await {
                     Ok(content) => {
+                        let content = content.replace(NAME_PLACEHOLDER, &self.name);
                         parts.push(format!("## {}\n\n{}", filename, content));
                     }
                     Err(e) => debug!("Failed to read {}: {}", filename, e),
@@ -107,7 +156,18 @@ When remembering something, write to {}/lifepod/MEMORY.md"#,
         history: Vec<Message>,
         current_message: &str,
     ) -> Vec<Message> {
-        let system_prompt = self.build_system_prompt().await;
+        self.build_messages_with_suffix(history, current_message, None)
+            .await
+    }
+
+    /// Build complete messages list, appending `suffix` to the system prompt
+    pub async fn build_messages_with_suffix(
+        &self,
+        history: Vec<Message>,
+        current_message: &str,
+        suffix: Option<&str>,
+    ) -> Vec<Message> {
+        let system_prompt = self.build_system_prompt_with_suffix(suffix).await;
 
         let mut messages = vec![Message::system(system_prompt)];
         messages.extend(history);
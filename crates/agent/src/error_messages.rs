@@ -0,0 +1,69 @@
+//! User-facing error messages
+//!
+//! Internal errors carry operator jargon ("NODE REJECTED", "SIGNAL LOST")
+//! that means nothing to someone chatting with the agent. This maps each
+//! error kind to a short, friendly message, with optional per-kind overrides
+//! from `operative.defaults.error_messages` in the config.
+
+use std::collections::HashMap;
+
+use opensam_config::Config;
+
+use crate::AgentError;
+
+/// Fallback for error kinds without a specific message
+const GENERIC_MESSAGE: &str = "Something went wrong on my end. Please try again.";
+
+/// Maps agent and provider errors to messages shown to end users
+#[derive(Debug, Clone, Default)]
+pub struct ErrorMessages {
+    overrides: HashMap<String, String>,
+}
+
+impl ErrorMessages {
+    /// Create with the given overrides, keyed by error kind
+    pub fn new(overrides: HashMap<String, String>) -> Self {
+        Self { overrides }
+    }
+
+    /// Create from the configured overrides
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.operative.defaults.error_messages.clone())
+    }
+
+    /// Default message for an error kind
+    pub fn default_for(kind: &str) -> &'static str {
+        match kind {
+            "rate_limited" => "I'm getting throttled, try again shortly.",
+            "no_api_key" => "I'm not configured yet: no API key has been set up.",
+            "request" | "unavailable" => {
+                "I couldn't reach my language model. Please try again in a moment."
+            }
+            "timeout" => "My language model took too long to answer. Please try again.",
+            "json" | "invalid_response" => {
+                "I got a garbled answer from my language model. Please try again."
+            }
+            "api" => "My language model rejected that request. Please try again later.",
+            "prompt_too_large" => {
+                "This conversation is too long for me to process. Try a shorter message."
+            }
+            "max_iterations" => {
+                "That took more steps than I'm allowed. Try breaking it into smaller pieces."
+            }
+            _ => GENERIC_MESSAGE,
+        }
+    }
+
+    /// Message for an error kind, preferring a configured override
+    pub fn for_kind(&self, kind: &str) -> String {
+        self.overrides
+            .get(kind)
+            .cloned()
+            .unwrap_or_else(|| Self::default_for(kind).to_string())
+    }
+
+    /// Message to show the user for an agent error
+    pub fn render(&self, err: &AgentError) -> String {
+        self.for_kind(err.kind())
+    }
+}
@@ -0,0 +1,44 @@
+//! Agent events - notices raised while processing a turn
+
+/// Routing prefix some configurations put in front of model names
+const ROUTING_PREFIX: &str = "openrouter/";
+
+/// Something noteworthy that happened during a turn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgentEvent {
+    /// The provider answered with a different model than the one requested
+    ModelSwitched {
+        session_key: String,
+        requested: String,
+        actual: String,
+    },
+}
+
+impl AgentEvent {
+    /// Short user-facing notice for the event
+    pub fn notice(&self) -> String {
+        match self {
+            AgentEvent::ModelSwitched { actual, .. } => format!("switched to {}", actual),
+        }
+    }
+}
+
+/// Whether a response served by `actual` came from a different model than
+/// `requested`.
+///
+/// The routing prefix is ignored, as are dated or versioned variants the
+/// node reports for an alias (`gpt-4o` served as `gpt-4o-2024-08-06`).
+pub fn is_model_switch(requested: &str, actual: &str) -> bool {
+    let requested = requested.strip_prefix(ROUTING_PREFIX).unwrap_or(requested);
+    let actual = actual.strip_prefix(ROUTING_PREFIX).unwrap_or(actual);
+    if requested.is_empty() || actual == requested {
+        return false;
+    }
+    let variant = actual.strip_prefix(requested).is_some_and(|rest| {
+        rest.starts_with(':')
+            || rest
+                .strip_prefix('-')
+                .is_some_and(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+    });
+    !variant
+}
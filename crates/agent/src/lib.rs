@@ -4,15 +4,33 @@
 
 use thiserror::Error;
 
+pub mod compaction;
 pub mod context;
+pub mod error_messages;
+pub mod events;
 pub mod loop_agent;
+pub mod max_tokens;
+pub mod output;
+pub mod reasoning;
+pub mod sampling;
 pub mod subagent;
+pub mod temperature;
 pub mod tools;
+pub mod turns;
+pub mod usage;
 
 pub use context::ContextBuilder;
-pub use loop_agent::AgentLoop;
+pub use error_messages::ErrorMessages;
+pub use events::AgentEvent;
+pub use loop_agent::{default_sessions_dir, session_manager, AgentLoop, TurnResult};
+pub use max_tokens::{ContextLengths, MaxTokensPolicy};
+pub use reasoning::ReasoningFilter;
+pub use sampling::Sampling;
 pub use subagent::SubagentManager;
-pub use tools::{ToolRegistry, ToolTrait};
+pub use temperature::TemperatureSchedule;
+pub use tools::{ToolOutcome, ToolRegistry, ToolTrait};
+pub use turns::TurnScheduler;
+pub use usage::UsageTracker;
 
 /// Operative errors
 #[derive(Error, Debug)]
@@ -27,10 +45,24 @@ pub enum AgentError {
     Io(#[from] std::io::Error),
 
     #[error("◆ SOLITON ERROR: {0}")]
-    Provider(String),
+    Provider(#[from] opensam_provider::ProviderError),
 
     #[error("◆ MAX ITERATIONS EXCEEDED")]
     MaxIterations,
 }
 
+impl AgentError {
+    /// Stable machine-readable name for the error, used to look up
+    /// user-facing messages. Provider errors report the provider's kind.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AgentError::ToolNotFound(_) => "tool_not_found",
+            AgentError::ToolExecution(_) => "tool_execution",
+            AgentError::Io(_) => "io",
+            AgentError::Provider(e) => e.kind(),
+            AgentError::MaxIterations => "max_iterations",
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, AgentError>;
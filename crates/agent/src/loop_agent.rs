@@ -1,17 +1,47 @@
 //! Agent loop - core processing engine
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
-use opensam_bus::{InboundMessage, MessageBus, OutboundMessage};
+use opensam_bus::{InboundMessage, MessageBus, OutboundMessage, USAGE_KEY};
 use opensam_config::Config;
-use opensam_provider::{ChatParams, Message, Provider, ToolCallDef, ToolChoice};
-use opensam_session::SessionManager;
+use opensam_provider::{
+    estimate_tokens, ChatParams, Message, Provider, ToolCallDef, ToolChoice, Usage,
+};
+use opensam_session::{SessionManager, SharedSessionManager};
+
+use crate::compaction;
+use crate::context::{self, ContextBuilder};
+use crate::error_messages::ErrorMessages;
+use crate::events::{self, AgentEvent};
+use crate::max_tokens::MaxTokensPolicy;
+use crate::output;
+use crate::reasoning::ReasoningFilter;
+use crate::sampling::Sampling;
+use crate::temperature::TemperatureSchedule;
+use crate::tools::{self, validation, MessageTool, ToolRegistry, ToolResultFormatter};
+use crate::usage::UsageTracker;
+use crate::AgentError;
+
+/// Directory the agent keeps its sessions in
+pub fn default_sessions_dir() -> PathBuf {
+    dirs::home_dir()
+        .map(|h| h.join(".opensam").join("ops").join("logs"))
+        .unwrap_or_else(|| PathBuf::from(".opensam").join("ops").join("logs"))
+}
 
-use crate::context::ContextBuilder;
-use crate::tools::{self, MessageTool, ToolRegistry};
+/// Session manager for `sessions_dir` with the limits from `config`
+pub fn session_manager(config: &Config, sessions_dir: PathBuf) -> SessionManager {
+    SessionManager::with_limits(
+        sessions_dir,
+        config.session_max_messages(),
+        config.session_context_window(),
+    )
+    .with_save_attempts(config.session_save_attempts())
+}
 
 /// The agent loop processes messages and handles tool calls
 #[allow(dead_code)]
@@ -24,9 +54,23 @@ pub struct AgentLoop<P: Provider> {
     brave_api_key: Option<String>,
     context: ContextBuilder,
     tools: ToolRegistry,
-    session_manager: Arc<Mutex<SessionManager>>,
-    max_history_messages: usize,
+    session_manager: SharedSessionManager,
+    max_history_messages: Option<usize>,
     message_tool: Arc<MessageTool>,
+    error_messages: ErrorMessages,
+    result_formatter: ToolResultFormatter,
+    events: Option<mpsc::UnboundedSender<AgentEvent>>,
+    announce_model_switch: bool,
+    validate_arguments: bool,
+    reasoning_filter: Option<ReasoningFilter>,
+    temperature: TemperatureSchedule,
+    sampling: Sampling,
+    max_output_chars: Option<usize>,
+    usage_footer: bool,
+    /// Per-model prices for the footer and usage metadata
+    pricing: UsageTracker,
+    compact_threshold: Option<f32>,
+    max_tokens: MaxTokensPolicy,
 }
 
 impl<P: Provider> AgentLoop<P> {
@@ -61,21 +105,13 @@ impl<P: Provider> AgentLoop<P> {
         brave_api_key: Option<String>,
         config: &Config,
     ) -> Self {
-        let context = ContextBuilder::new(&workspace);
+        let context = ContextBuilder::new(&workspace).with_name(&config.operative.defaults.name);
         let mut tools = ToolRegistry::new();
         let message_tool =
             Self::register_default_tools(&mut tools, config, &workspace, bus.clone());
 
-        // Initialize session manager with max_messages from config
-        let sessions_dir = dirs::home_dir()
-            .map(|h| h.join(".opensam").join("ops").join("logs"))
-            .unwrap_or_else(|| PathBuf::from(".opensam").join("ops").join("logs"));
-
-        let max_messages = config.session_max_messages();
-        let session_manager = Arc::new(Mutex::new(SessionManager::with_max_messages(
-            sessions_dir,
-            max_messages,
-        )));
+        let session_manager =
+            SharedSessionManager::new(session_manager(config, default_sessions_dir()));
 
         Self {
             bus,
@@ -87,8 +123,21 @@ impl<P: Provider> AgentLoop<P> {
             context,
             tools,
             session_manager,
-            max_history_messages: 20, // Default: keep last 20 messages
+            max_history_messages: None,
             message_tool,
+            error_messages: ErrorMessages::from_config(config),
+            result_formatter: ToolResultFormatter::from_config(config),
+            events: None,
+            announce_model_switch: false,
+            validate_arguments: config.toolkit.validate_arguments,
+            reasoning_filter: ReasoningFilter::from_config(config),
+            temperature: TemperatureSchedule::from_config(config),
+            sampling: Sampling::from_config(config),
+            max_output_chars: config.operative.defaults.max_output_chars,
+            usage_footer: config.operative.defaults.usage_footer,
+            pricing: UsageTracker::from_config(config),
+            compact_threshold: config.operative.defaults.compact_threshold,
+            max_tokens: MaxTokensPolicy::from_config(config),
         }
     }
 
@@ -127,16 +176,12 @@ impl<P: Provider> AgentLoop<P> {
         config: &Config,
         sessions_dir: PathBuf,
     ) -> Self {
-        let context = ContextBuilder::new(&workspace);
+        let context = ContextBuilder::new(&workspace).with_name(&config.operative.defaults.name);
         let mut tools = ToolRegistry::new();
         let message_tool =
             Self::register_default_tools(&mut tools, config, &workspace, bus.clone());
 
-        let max_messages = config.session_max_messages();
-        let session_manager = Arc::new(Mutex::new(SessionManager::with_max_messages(
-            sessions_dir,
-            max_messages,
-        )));
+        let session_manager = SharedSessionManager::new(session_manager(config, sessions_dir));
 
         Self {
             bus,
@@ -148,14 +193,108 @@ impl<P: Provider> AgentLoop<P> {
             context,
             tools,
             session_manager,
-            max_history_messages: 20,
+            max_history_messages: None,
             message_tool,
+            error_messages: ErrorMessages::from_config(config),
+            result_formatter: ToolResultFormatter::from_config(config),
+            events: None,
+            announce_model_switch: false,
+            validate_arguments: config.toolkit.validate_arguments,
+            reasoning_filter: ReasoningFilter::from_config(config),
+            temperature: TemperatureSchedule::from_config(config),
+            sampling: Sampling::from_config(config),
+            max_output_chars: config.operative.defaults.max_output_chars,
+            usage_footer: config.operative.defaults.usage_footer,
+            pricing: UsageTracker::from_config(config),
+            compact_threshold: config.operative.defaults.compact_threshold,
+            max_tokens: MaxTokensPolicy::from_config(config),
         }
     }
 
-    /// Set the maximum number of history messages to keep
+    /// Replace how max_tokens is fitted to the model's context
+    pub fn set_max_tokens_policy(&mut self, policy: MaxTokensPolicy) {
+        self.max_tokens = policy;
+    }
+
+    /// Override the session context window for history sent to the model
     pub fn set_max_history_messages(&mut self, max: usize) {
-        self.max_history_messages = max;
+        self.max_history_messages = Some(max);
+    }
+
+    /// Set the formatter applied to tool results
+    pub fn set_result_formatter(&mut self, formatter: ToolResultFormatter) {
+        self.result_formatter = formatter;
+    }
+
+    /// Check tool-call arguments against tool schemas before executing
+    pub fn set_validate_arguments(&mut self, validate: bool) {
+        self.validate_arguments = validate;
+    }
+
+    /// Set the user-facing error messages
+    pub fn set_error_messages(&mut self, messages: ErrorMessages) {
+        self.error_messages = messages;
+    }
+
+    /// Receive events raised while processing turns
+    ///
+    /// Replaces any previous subscriber.
+    pub fn subscribe_events(&mut self) -> mpsc::UnboundedReceiver<AgentEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.events = Some(tx);
+        rx
+    }
+
+    /// Prepend a short note to replies answered by a different model
+    pub fn set_announce_model_switch(&mut self, announce: bool) {
+        self.announce_model_switch = announce;
+    }
+
+    /// Set how temperature rises while the tool loop stalls
+    pub fn set_temperature_schedule(&mut self, schedule: TemperatureSchedule) {
+        self.temperature = schedule;
+    }
+
+    /// Set top_p, penalties and stop sequences sent with each request
+    pub fn set_sampling(&mut self, sampling: Sampling) {
+        self.sampling = sampling;
+    }
+
+    /// Trim replies to `max_chars`; the session keeps the full text
+    pub fn set_max_output_chars(&mut self, max_chars: Option<usize>) {
+        self.max_output_chars = max_chars;
+    }
+
+    /// Append a usage footer to replies, pricing models without a
+    /// `model_prices` entry at `cost_per_1k_tokens`
+    pub fn set_usage_footer(&mut self, enabled: bool, cost_per_1k_tokens: f64) {
+        self.usage_footer = enabled;
+        self.pricing = std::mem::take(&mut self.pricing).with_default_price(cost_per_1k_tokens);
+    }
+
+    /// Price per 1000 tokens, keyed by model, for the footer and usage metadata
+    pub fn set_model_prices(&mut self, prices: HashMap<String, f64>) {
+        self.pricing = std::mem::take(&mut self.pricing).with_prices(prices);
+    }
+
+    /// Summarize older history once it fills `threshold` of the context window
+    pub fn set_compact_threshold(&mut self, threshold: Option<f32>) {
+        self.compact_threshold = threshold;
+    }
+
+    /// Sessions this agent reads and writes
+    pub fn sessions(&self) -> &SharedSessionManager {
+        &self.session_manager
+    }
+
+    /// Registered tools
+    pub fn tools(&self) -> &ToolRegistry {
+        &self.tools
+    }
+
+    /// Strip reasoning blocks from replies; `None` passes content through
+    pub fn set_reasoning_filter(&mut self, filter: Option<ReasoningFilter>) {
+        self.reasoning_filter = filter;
     }
 
     /// Generate a session key from an inbound message
@@ -176,12 +315,22 @@ impl<P: Provider> AgentLoop<P> {
         registry.register(tools::EditFileTool::new(workspace.to_path_buf()));
         registry.register(tools::ListDirTool::new(workspace.to_path_buf()));
 
+        // Memory tool - appends to lifepod/MEMORY.md
+        if config.toolkit.memory.enabled {
+            registry.register(tools::MemoryTool::new(workspace.to_path_buf()));
+        }
+
         // Shell tool - with workspace
         registry.register(tools::ExecTool::with_workspace(workspace.to_path_buf()));
 
+        // Clock - current date/time so the model need not guess
+        if config.toolkit.time.enabled {
+            registry.register(tools::TimeTool::new());
+        }
+
         // Web tools - use config for max_results
         registry.register(tools::WebSearchTool::from_config(config));
-        registry.register(tools::WebFetchTool::default());
+        registry.register(tools::WebFetchTool::from_config(config));
 
         // Message tool - create with real outbound sender from the bus
         let sender = bus.outbound_sender();
@@ -200,8 +349,30 @@ impl<P: Provider> AgentLoop<P> {
         }
     }
 
-    /// Process a single message
+    /// Process a single message, replying with an error notice on failure
+    ///
+    /// Adapter over `process_turn` for callers that only want something to send.
     pub async fn process_message(&self, msg: InboundMessage) -> Option<OutboundMessage> {
+        let (channel, chat_id) = (msg.channel.clone(), msg.chat_id.clone());
+        match self.process_turn(msg).await {
+            TurnResult::Reply(reply) => Some(reply),
+            TurnResult::NoReply => None,
+            TurnResult::Error(e) => Some(self.error_notice(&channel, &chat_id, &e)),
+        }
+    }
+
+    /// User-facing notice for a failed turn
+    pub fn error_notice(
+        &self,
+        channel: &str,
+        chat_id: &str,
+        error: &AgentError,
+    ) -> OutboundMessage {
+        OutboundMessage::new(channel, chat_id, self.error_messages.render(error))
+    }
+
+    /// Process a single message
+    pub async fn process_turn(&self, msg: InboundMessage) -> TurnResult {
         info!("Processing message from {}:{}", msg.channel, msg.sender_id);
         debug!("Content: {}", &msg.content[..msg.content.len().min(100)]);
 
@@ -212,71 +383,246 @@ impl<P: Provider> AgentLoop<P> {
         // Generate session key from the message
         let session_key = Self::generate_session_key(&msg);
 
+        self.compact_if_needed(&session_key).await;
+
         // Load or create session and get history
-        let history = {
-            let mut session_manager = self.session_manager.lock().await;
-            let session = session_manager.get_or_create(&session_key).await;
-            session.get_history(self.max_history_messages)
-        };
+        let (history, suffix) = self.history_and_suffix(&session_key).await;
 
-        // Build messages with history: system prompt + history + current message
-        let messages = self.context.build_messages(history, &msg.content).await;
+        // Build messages with history: system prompt + history + current message,
+        // quoting the message being replied to so the model sees what it refers to
+        let current = match msg.replied_text() {
+            Some(replied) => context::quote_reply(&msg.content, replied, msg.replied_sender()),
+            None => msg.content.clone(),
+        };
+        let messages = self
+            .context
+            .build_messages_with_suffix(history, &current, suffix.as_deref())
+            .await;
+
+        // Record the user message up front so it survives a turn that is
+        // cancelled before the reply is written
+        self.session_manager
+            .with_session(&session_key, |session| {
+                session.add_message("user", &msg.content);
+            })
+            .await;
 
         // Run agent loop
-        match self.run_agent_loop(messages).await {
-            Ok(content) => {
-                // Save session in a separate scope
-                {
-                    let mut session_manager = self.session_manager.lock().await;
-                    let session = session_manager.get_or_create(&session_key).await;
-
-                    // Append user message to session
-                    session.add_message("user", &msg.content);
-
-                    // Append assistant response to session
-                    session.add_message("assistant", &content);
-
-                    // Clone the session to save it
-                    let session_clone = session.clone();
-                    let _ = session; // Release mutable borrow
+        match self.run_agent_loop(messages, &session_key).await {
+            Ok(LoopOutput {
+                content,
+                switched_to,
+                usage,
+                model,
+            }) => {
+                let content = self.strip_reasoning(content, &session_key);
+
+                // Append the reply and save the session
+                self.session_manager
+                    .with_session(&session_key, |session| {
+                        session.add_message("assistant", &content);
+                        crate::usage::record_in_session(session, &model, &usage);
+                    })
+                    .await;
+                if let Err(e) = self.session_manager.save(&session_key).await {
+                    warn!("Failed to save session {}: {}", session_key, e);
+                }
 
-                    if let Err(e) = session_manager.save(&session_clone).await {
-                        warn!("Failed to save session {}: {}", session_key, e);
-                    }
+                if content.trim().is_empty() {
+                    debug!("◆ NO REPLY FOR {}", session_key);
+                    return TurnResult::NoReply;
                 }
 
-                Some(OutboundMessage::new(&msg.channel, &msg.chat_id, content))
+                let content = match self.max_output_chars {
+                    Some(max) => output::trim_output(&content, max),
+                    None => content,
+                };
+                let content = match switched_to {
+                    Some(model) if self.announce_model_switch => {
+                        format!("({})\n\n{}", model, content)
+                    }
+                    _ => content,
+                };
+                let price = self.pricing.price_for(&model);
+                let content = if self.usage_footer {
+                    format!("{}\n\n{}", content, output::usage_footer(&usage, price))
+                } else {
+                    content
+                };
+                let usage_metadata = serde_json::json!({
+                    "prompt_tokens": usage.prompt_tokens,
+                    "completion_tokens": usage.completion_tokens,
+                    "total_tokens": usage.total_tokens,
+                    "cost": output::estimate_cost(&usage, price),
+                });
+                TurnResult::Reply(
+                    OutboundMessage::new(&msg.channel, &msg.chat_id, content)
+                        .with_metadata(USAGE_KEY, usage_metadata),
+                )
             }
             Err(e) => {
                 error!("Agent loop error: {}", e);
 
-                // Even on error, try to save the user message
-                {
-                    let mut session_manager = self.session_manager.lock().await;
-                    let session = session_manager.get_or_create(&session_key).await;
-                    session.add_message("user", &msg.content);
-                    session.add_message("assistant", format!("Error: {}", e));
+                // Even on error, save the user message
+                self.session_manager
+                    .with_session(&session_key, |session| {
+                        session.add_message("assistant", format!("Error: {}", e));
+                    })
+                    .await;
+                if let Err(save_err) = self.session_manager.save(&session_key).await {
+                    warn!("Failed to save session {}: {}", session_key, save_err);
+                }
 
-                    let session_clone = session.clone();
-                    let _ = session; // Release mutable borrow
+                TurnResult::Error(e)
+            }
+        }
+    }
 
-                    if let Err(save_err) = session_manager.save(&session_clone).await {
-                        warn!("Failed to save session {}: {}", session_key, save_err);
-                    }
+    /// History sent to the model and the session's system prompt suffix
+    async fn history_and_suffix(&self, session_key: &str) -> (Vec<Message>, Option<String>) {
+        self.session_manager
+            .read_session(session_key, |session| {
+                let history = match self.max_history_messages {
+                    Some(max) => session.get_history(max),
+                    None => session.history(),
+                };
+                (history, session.system_suffix().map(str::to_string))
+            })
+            .await
+    }
+
+    /// Request the first model call of a turn would send, without sending it
+    ///
+    /// History is read as-is: nothing is compacted, saved, or appended.
+    /// With no `message` the prompt ends at the stored history.
+    pub async fn preview_params(&self, session_key: &str, message: Option<&str>) -> ChatParams {
+        let (history, suffix) = self.history_and_suffix(session_key).await;
+        let suffix = suffix.as_deref();
+        let messages = match message {
+            Some(message) => {
+                self.context
+                    .build_messages_with_suffix(history, message, suffix)
+                    .await
+            }
+            None => {
+                let mut messages = vec![Message::system(
+                    self.context.build_system_prompt_with_suffix(suffix).await,
+                )];
+                messages.extend(history);
+                messages
+            }
+        };
+        self.chat_params(messages, 0)
+    }
+
+    /// Chat request for `messages`, with tools when the provider takes them
+    fn chat_params(&self, messages: Vec<Message>, stalls: u32) -> ChatParams {
+        // Providers without tool support, or an empty registry, get a
+        // plain chat request with no tool choice
+        let tools = if self.provider.capabilities().tools {
+            self.tools.definitions()
+        } else {
+            Vec::new()
+        };
+        let tool_choice = if tools.is_empty() {
+            ToolChoice::None
+        } else {
+            ToolChoice::Auto
+        };
+        let prompt_tokens = estimate_tokens(&messages);
+        let mut params = ChatParams {
+            model: self.model.clone(),
+            max_tokens: self.max_tokens.max_tokens_for(&self.model, prompt_tokens),
+            messages,
+            tools,
+            tool_choice,
+            temperature: self.temperature.temperature(stalls),
+            ..Default::default()
+        };
+        self.sampling.apply(&mut params);
+        params
+    }
+
+    /// Summarize old history when the session nears the context window
+    ///
+    /// Returns whether a summary was written. Failures leave history untouched.
+    async fn compact_if_needed(&self, session_key: &str) -> bool {
+        let Some(threshold) = self.compact_threshold else {
+            return false;
+        };
+        let pending = self
+            .session_manager
+            .read_session(session_key, |session| {
+                let window = self
+                    .max_history_messages
+                    .unwrap_or_else(|| session.context_window());
+                if session.messages.len() < compaction::trigger_len(window, threshold) {
+                    return None;
                 }
+                let keep = compaction::keep_recent(window);
+                let old = session.old_messages(keep);
+                (!old.is_empty()).then(|| (keep, compaction::summary_request(old)))
+            })
+            .await;
+        let Some((keep, messages)) = pending else {
+            return false;
+        };
 
-                Some(OutboundMessage::new(
-                    &msg.channel,
-                    &msg.chat_id,
-                    format!("Error: {}", e),
-                ))
+        let params = ChatParams {
+            model: self.model.clone(),
+            messages,
+            tool_choice: ToolChoice::None,
+            ..Default::default()
+        };
+        let summary = match self.provider.chat(params).await {
+            Ok(response) => response.content.unwrap_or_default(),
+            Err(e) => {
+                warn!("◆ COMPACTION FAILED FOR {}: {}", session_key, e);
+                return false;
             }
+        };
+        if summary.trim().is_empty() {
+            warn!("◆ COMPACTION FOR {} RETURNED NO SUMMARY", session_key);
+            return false;
+        }
+
+        let replaced = self
+            .session_manager
+            .with_session(session_key, |session| {
+                session.summarize_old(keep, summary.trim())
+            })
+            .await;
+        info!(
+            "◆ CONTEXT COMPACTED: {} ({} messages)",
+            session_key, replaced
+        );
+        true
+    }
+
+    /// Remove reasoning blocks, logging them at debug level
+    fn strip_reasoning(&self, content: String, session_key: &str) -> String {
+        let Some(filter) = &self.reasoning_filter else {
+            return content;
+        };
+        let (visible, reasoning) = filter.strip(&content);
+        for block in &reasoning {
+            debug!("Reasoning for {}: {}", session_key, block.trim());
         }
+        visible
     }
 
     /// Run the agent loop with tool calling
-    async fn run_agent_loop(&self, mut messages: Vec<Message>) -> crate::Result<String> {
+    async fn run_agent_loop(
+        &self,
+        mut messages: Vec<Message>,
+        session_key: &str,
+    ) -> crate::Result<LoopOutput> {
         let mut iteration = 0;
+        let mut usage = Usage::default();
+        let mut switched_to: Option<String> = None;
+        let mut model = self.model.clone();
+        let mut stalls = 0;
+        let mut previous_calls: Option<Vec<(String, String)>> = None;
 
         loop {
             iteration += 1;
@@ -287,19 +633,32 @@ impl<P: Provider> AgentLoop<P> {
             debug!("Agent iteration {}", iteration);
 
             // Call LLM
-            let params = ChatParams {
-                model: self.model.clone(),
-                messages: messages.clone(),
-                tools: self.tools.definitions(),
-                tool_choice: ToolChoice::Auto,
-                ..Default::default()
-            };
+            let params = self.chat_params(messages.clone(), stalls);
 
-            let response = self
-                .provider
-                .chat(params)
-                .await
-                .map_err(|e| crate::AgentError::Provider(e.to_string()))?;
+            let response = match self.provider.chat(params).await {
+                Ok(response) => response,
+                Err(e) => {
+                    self.bus.stats().record_provider_error();
+                    return Err(e.into());
+                }
+            };
+            usage.add(&response.usage);
+
+            if let Some(actual) = response.model.as_deref() {
+                model = actual.to_string();
+                if switched_to.is_none() && events::is_model_switch(&self.model, actual) {
+                    let event = AgentEvent::ModelSwitched {
+                        session_key: session_key.to_string(),
+                        requested: self.model.clone(),
+                        actual: actual.to_string(),
+                    };
+                    info!("◆ MODEL SWITCHED: {} -> {}", self.model, actual);
+                    switched_to = Some(event.notice());
+                    if let Some(tx) = &self.events {
+                        let _ = tx.send(event);
+                    }
+                }
+            }
 
             // Handle tool calls
             if response.has_tool_calls() {
@@ -316,15 +675,60 @@ impl<P: Provider> AgentLoop<P> {
                     Some(tool_call_defs),
                 );
 
+                // Repeating the previous calls verbatim is a cycle
+                let calls: Vec<(String, String)> = response
+                    .tool_calls
+                    .iter()
+                    .map(|tc| (tc.name.clone(), tc.arguments.to_string()))
+                    .collect();
+                let repeated = previous_calls.as_ref() == Some(&calls);
+                previous_calls = Some(calls);
+                let mut progressed = false;
+
                 // Execute tools
                 for tool_call in &response.tool_calls {
+                    if self.validate_arguments {
+                        if let Err(problems) =
+                            self.tools.validate(&tool_call.name, &tool_call.arguments)
+                        {
+                            warn!(
+                                "◆ INVALID ARGUMENTS FOR {}: {}",
+                                tool_call.name,
+                                problems.join("; ")
+                            );
+                            let schema = self
+                                .tools
+                                .get(&tool_call.name)
+                                .map(|t| t.parameters())
+                                .unwrap_or_default();
+                            ContextBuilder::add_tool_result(
+                                &mut messages,
+                                &tool_call.id,
+                                &tool_call.name,
+                                &validation::correction_message(
+                                    &tool_call.name,
+                                    &schema,
+                                    &problems,
+                                ),
+                            );
+                            continue;
+                        }
+                    }
+
                     debug!("Executing tool: {}", tool_call.name);
 
-                    let result = self
+                    let outcome = self
                         .tools
-                        .execute(&tool_call.name, tool_call.arguments.clone())
-                        .await
-                        .unwrap_or_else(|e| format!("Error: {}", e));
+                        .run(&tool_call.name, tool_call.arguments.clone())
+                        .await;
+                    self.bus.stats().record_tool_execution();
+                    if matches!(outcome, tools::ToolOutcome::Ok(_)) {
+                        progressed = true;
+                    } else {
+                        debug!("Tool {} outcome: {:?}", tool_call.name, outcome);
+                    }
+                    let result = outcome.to_model_message(&tool_call.name);
+                    let result = self.result_formatter.format(&tool_call.name, &result);
 
                     ContextBuilder::add_tool_result(
                         &mut messages,
@@ -333,12 +737,70 @@ impl<P: Provider> AgentLoop<P> {
                         &result,
                     );
                 }
+
+                if progressed && !repeated {
+                    stalls = 0;
+                } else {
+                    stalls += 1;
+                    debug!(
+                        "◆ TOOL LOOP STALLED ({}), TEMPERATURE {}",
+                        stalls,
+                        self.temperature.temperature(stalls)
+                    );
+                }
             } else {
                 // No tool calls, return the content
-                return Ok(response
+                let content = response
                     .content
-                    .unwrap_or_else(|| "Task completed.".to_string()));
+                    .unwrap_or_else(|| "Task completed.".to_string());
+                return Ok(LoopOutput {
+                    content,
+                    switched_to,
+                    usage,
+                    model,
+                });
             }
         }
     }
 }
+
+impl<P: Provider + 'static> AgentLoop<P> {
+    /// Register the memory search tool if `toolkit.memory.search` is set
+    ///
+    /// Separate from construction because the tool shares the provider for
+    /// embeddings.
+    pub fn enable_memory_search(&mut self, config: &Config) {
+        let memory = &config.toolkit.memory;
+        if !memory.search {
+            return;
+        }
+        let provider: Arc<dyn Provider> = self.provider.clone();
+        self.tools.register(
+            tools::MemorySearchTool::new(self.workspace.clone(), provider)
+                .with_files(memory.search_files.clone())
+                .with_top_k(memory.search_top_k),
+        );
+    }
+}
+
+/// Outcome of processing one inbound message
+#[derive(Debug)]
+pub enum TurnResult {
+    /// Reply to send back to the sender
+    Reply(OutboundMessage),
+    /// The turn succeeded but produced nothing to send
+    NoReply,
+    /// The turn failed; the exchange is still recorded in the session
+    Error(AgentError),
+}
+
+/// Outcome of one run of the tool loop
+struct LoopOutput {
+    content: String,
+    /// Switch notice, if the provider switched models during the turn
+    switched_to: Option<String>,
+    /// Usage summed over every provider call in the turn
+    usage: Usage,
+    /// Model that produced the final response
+    model: String,
+}
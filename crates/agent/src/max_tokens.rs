@@ -0,0 +1,76 @@
+//! Output budget - fits max_tokens into the model's context window
+
+use opensam_config::Config;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Looks up how many tokens a model's context window holds
+pub trait ContextLengths: Send + Sync {
+    /// Context length of `model`, or `None` when unknown
+    fn context_length(&self, model: &str) -> Option<usize>;
+}
+
+impl ContextLengths for HashMap<String, usize> {
+    fn context_length(&self, model: &str) -> Option<usize> {
+        self.get(model).copied()
+    }
+}
+
+/// Clamps the requested `max_tokens` to what the context has left
+///
+/// For a model with a known context length, the reply budget is the
+/// context minus the estimated prompt, but never below `floor` (or the
+/// configured value, if that is smaller). Unknown models get the
+/// configured value unchanged.
+#[derive(Clone)]
+pub struct MaxTokensPolicy {
+    /// Configured reply budget
+    pub max_tokens: u32,
+    /// Smallest budget the clamp will produce
+    pub floor: u32,
+    lengths: Arc<dyn ContextLengths>,
+}
+
+impl MaxTokensPolicy {
+    /// Policy with no known context lengths
+    pub fn new(max_tokens: u32, floor: u32) -> Self {
+        Self {
+            max_tokens,
+            floor,
+            lengths: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// Policy configured for the operative
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = &config.operative.defaults;
+        Self::new(defaults.max_tokens, defaults.min_max_tokens)
+            .with_context_lengths(defaults.model_context_lengths.clone())
+    }
+
+    /// Replace the context length lookup
+    pub fn with_context_lengths(mut self, lengths: impl ContextLengths + 'static) -> Self {
+        self.lengths = Arc::new(lengths);
+        self
+    }
+
+    /// Reply budget for `model` given a prompt of about `prompt_tokens`
+    pub fn max_tokens_for(&self, model: &str, prompt_tokens: usize) -> u32 {
+        let Some(context) = self.lengths.context_length(model) else {
+            return self.max_tokens;
+        };
+        let remaining = u32::try_from(context.saturating_sub(prompt_tokens)).unwrap_or(u32::MAX);
+        self.max_tokens
+            .min(remaining)
+            .max(self.floor.min(self.max_tokens))
+    }
+}
+
+impl std::fmt::Debug for MaxTokensPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaxTokensPolicy")
+            .field("max_tokens", &self.max_tokens)
+            .field("floor", &self.floor)
+            .finish_non_exhaustive()
+    }
+}
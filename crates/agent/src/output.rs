@@ -0,0 +1,59 @@
+//! Reply post-processing - length limits and usage footers
+
+use opensam_provider::Usage;
+
+/// Appended to replies that were cut short
+pub const TRUNCATION_MARKER: &str = "…(truncated)";
+
+/// Trim `text` to at most `max_chars` characters plus the marker
+///
+/// The cut prefers the end of a sentence, then a word boundary, as long as
+/// that keeps at least half of the allowed length; otherwise it cuts mid-word.
+pub fn trim_output(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let end = text
+        .char_indices()
+        .nth(max_chars)
+        .map_or(text.len(), |(i, _)| i);
+    let head = &text[..end];
+    let min = head.len() / 2;
+
+    let sentence_end = head
+        .char_indices()
+        .filter(|&(i, c)| {
+            matches!(c, '.' | '!' | '?')
+                && text[i + c.len_utf8()..]
+                    .chars()
+                    .next()
+                    .is_some_and(char::is_whitespace)
+        })
+        .map(|(i, c)| i + c.len_utf8())
+        .next_back()
+        .filter(|&i| i >= min);
+    let cut = sentence_end
+        .or_else(|| head.rfind(char::is_whitespace).filter(|&i| i >= min))
+        .unwrap_or(end);
+
+    format!("{} {}", text[..cut].trim_end(), TRUNCATION_MARKER)
+}
+
+/// Estimated cost of `usage` at `cost_per_1k_tokens`
+pub fn estimate_cost(usage: &Usage, cost_per_1k_tokens: f64) -> f64 {
+    f64::from(usage.total_tokens) / 1000.0 * cost_per_1k_tokens
+}
+
+/// Footer such as "(42 tokens, ~$0.001)"; the cost is omitted without a price
+pub fn usage_footer(usage: &Usage, cost_per_1k_tokens: f64) -> String {
+    if cost_per_1k_tokens > 0.0 {
+        format!(
+            "({} tokens, ~${:.3})",
+            usage.total_tokens,
+            estimate_cost(usage, cost_per_1k_tokens)
+        )
+    } else {
+        format!("({} tokens)", usage.total_tokens)
+    }
+}
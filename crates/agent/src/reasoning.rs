@@ -0,0 +1,108 @@
+//! Reasoning suppression - strips visible chain-of-thought from replies
+
+use opensam_config::Config;
+
+/// Default opening marker
+pub const DEFAULT_OPEN: &str = "<think>";
+
+/// Default closing marker
+pub const DEFAULT_CLOSE: &str = "</think>";
+
+/// Removes text between reasoning markers from model output
+///
+/// Nested blocks are removed as a whole. An unclosed block hides everything
+/// after its opening marker, and a closing marker without an opener is
+/// dropped while the text around it is kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReasoningFilter {
+    open: String,
+    close: String,
+}
+
+impl Default for ReasoningFilter {
+    fn default() -> Self {
+        Self::new(DEFAULT_OPEN, DEFAULT_CLOSE)
+    }
+}
+
+impl ReasoningFilter {
+    /// Create a filter for the given markers
+    pub fn new(open: impl Into<String>, close: impl Into<String>) -> Self {
+        Self {
+            open: open.into(),
+            close: close.into(),
+        }
+    }
+
+    /// Build the filter configured for the operative, if enabled
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let defaults = &config.operative.defaults;
+        if !defaults.strip_reasoning || defaults.reasoning_open.is_empty() {
+            return None;
+        }
+        Some(Self::new(
+            &defaults.reasoning_open,
+            &defaults.reasoning_close,
+        ))
+    }
+
+    /// Split `text` into the visible reply and the removed reasoning
+    pub fn strip(&self, text: &str) -> (String, Vec<String>) {
+        let mut visible = String::new();
+        let mut reasoning = Vec::new();
+        let mut current = String::new();
+        let mut depth = 0usize;
+        let mut rest = text;
+
+        while !rest.is_empty() {
+            let next_open = rest.find(&self.open);
+            let next_close = (!self.close.is_empty())
+                .then(|| rest.find(&self.close))
+                .flatten();
+
+            let (pos, is_open) = match (next_open, next_close) {
+                (Some(o), Some(c)) if o < c => (o, true),
+                (_, Some(c)) => (c, false),
+                (Some(o), None) => (o, true),
+                (None, None) => {
+                    if depth == 0 {
+                        visible.push_str(rest);
+                    } else {
+                        current.push_str(rest);
+                    }
+                    break;
+                }
+            };
+
+            let (before, after) = rest.split_at(pos);
+            if depth == 0 {
+                visible.push_str(before);
+            } else {
+                current.push_str(before);
+            }
+
+            if is_open {
+                if depth > 0 {
+                    current.push_str(&self.open);
+                }
+                depth += 1;
+                rest = &after[self.open.len()..];
+            } else {
+                match depth {
+                    // Stray closing marker
+                    0 => {}
+                    1 => reasoning.push(std::mem::take(&mut current)),
+                    _ => current.push_str(&self.close),
+                }
+                depth = depth.saturating_sub(1);
+                rest = &after[self.close.len()..];
+            }
+        }
+
+        if depth > 0 && !current.is_empty() {
+            reasoning.push(current);
+        }
+
+        (visible.trim().to_string(), reasoning)
+    }
+}
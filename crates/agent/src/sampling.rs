@@ -0,0 +1,34 @@
+//! Sampling knobs beyond temperature, sent with every chat request
+
+use opensam_config::Config;
+use opensam_provider::ChatParams;
+
+/// Optional sampling parameters; unset values leave the node's defaults
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sampling {
+    pub top_p: Option<f32>,
+    pub frequency_penalty: Option<f32>,
+    pub presence_penalty: Option<f32>,
+    pub stop: Vec<String>,
+}
+
+impl Sampling {
+    /// Sampling configured for the operative
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = &config.operative.defaults;
+        Self {
+            top_p: defaults.top_p,
+            frequency_penalty: defaults.frequency_penalty,
+            presence_penalty: defaults.presence_penalty,
+            stop: defaults.stop.clone(),
+        }
+    }
+
+    /// Copy these parameters onto a request
+    pub fn apply(&self, params: &mut ChatParams) {
+        params.top_p = self.top_p;
+        params.frequency_penalty = self.frequency_penalty;
+        params.presence_penalty = self.presence_penalty;
+        params.stop = self.stop.clone();
+    }
+}
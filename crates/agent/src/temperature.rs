@@ -0,0 +1,50 @@
+//! Temperature schedule - varies sampling when a tool loop stalls
+
+use opensam_config::Config;
+
+/// Raises temperature after each stalled iteration, up to a cap
+///
+/// A stall is an iteration whose tool calls all failed or that repeats the
+/// previous iteration's calls exactly. Any productive iteration resets the
+/// schedule to `base`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureSchedule {
+    /// Temperature used while the loop is making progress
+    pub base: f32,
+    /// Increase per consecutive stall; zero disables the schedule
+    pub step: f32,
+    /// Highest temperature the schedule will reach
+    pub max: f32,
+}
+
+impl TemperatureSchedule {
+    /// Fixed temperature, never raised
+    pub fn fixed(base: f32) -> Self {
+        Self {
+            base,
+            step: 0.0,
+            max: base,
+        }
+    }
+
+    /// Create a schedule
+    pub fn new(base: f32, step: f32, max: f32) -> Self {
+        Self { base, step, max }
+    }
+
+    /// Schedule configured for the operative
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = &config.operative.defaults;
+        Self::new(
+            defaults.temperature,
+            defaults.stall_temperature_step,
+            defaults.max_temperature,
+        )
+    }
+
+    /// Temperature after `stalls` consecutive stalled iterations
+    pub fn temperature(&self, stalls: u32) -> f32 {
+        let raised = self.base + self.step.max(0.0) * stalls as f32;
+        raised.min(self.max.max(self.base))
+    }
+}
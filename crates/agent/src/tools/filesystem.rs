@@ -8,6 +8,7 @@ use std::path::PathBuf;
 use tracing::debug;
 
 use super::path_utils::validate_workspace_path;
+use super::text_utils::{looks_binary, render_bytes, truncate_at_char_boundary};
 use super::ToolTrait;
 
 /// INTEL retrieval tool
@@ -24,6 +25,46 @@ impl ReadFileTool {
 #[derive(Deserialize)]
 struct ReadFileArgs {
     path: String,
+    #[serde(default)]
+    start_line: Option<usize>,
+    #[serde(default)]
+    end_line: Option<usize>,
+    #[serde(default)]
+    max_bytes: Option<usize>,
+}
+
+/// Keep lines `start..=end` (1-based, clamped) under a range header
+fn select_lines(text: &str, start: Option<usize>, end: Option<usize>) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let total = lines.len();
+    let start = start.unwrap_or(1).max(1);
+    let end = end.unwrap_or(total).min(total);
+    if start > end {
+        return format!(
+            "◆ NO LINES IN RANGE {}-{}: FILE HAS {} LINES",
+            start, end, total
+        );
+    }
+    format!(
+        "◆ LINES {}-{} OF {}\n{}",
+        start,
+        end,
+        total,
+        lines[start - 1..end].join("\n")
+    )
+}
+
+/// Cap `text` at `max_bytes`, noting the cut
+fn cap_bytes(text: String, max_bytes: Option<usize>) -> String {
+    match max_bytes {
+        Some(max) if text.len() > max => format!(
+            "{}\n◆ TRUNCATED AT {} OF {} BYTES",
+            truncate_at_char_boundary(&text, max),
+            max,
+            text.len()
+        ),
+        _ => text,
+    }
 }
 
 #[async_trait]
@@ -37,10 +78,19 @@ impl ToolTrait for ReadFileTool {
     fn parameters(&self) -> serde_json::Value {
         json!({
             "type": "object",
-            "properties": { "path": { "type": "string", "description": "Target data path" } },
+            "properties": {
+                "path": { "type": "string", "description": "Target data path" },
+                "start_line": { "type": "integer", "minimum": 1, "description": "First line to return (1-based)" },
+                "end_line": { "type": "integer", "minimum": 1, "description": "Last line to return (inclusive)" },
+                "max_bytes": { "type": "integer", "minimum": 1, "description": "Cap on returned bytes" }
+            },
             "required": ["path"]
         })
     }
+    fn self_test(&self) -> Result<(), String> {
+        super::check_workspace(&self.workspace)
+    }
+
     async fn execute(
         &self,
         args: serde_json::Value,
@@ -55,8 +105,17 @@ impl ToolTrait for ReadFileTool {
         if !path.is_file() {
             return Ok(format!("◆ NOT A DATA FILE: {}", args.path));
         }
-        match tokio::fs::read_to_string(&path).await {
-            Ok(content) => Ok(content),
+        match tokio::fs::read(&path).await {
+            Ok(bytes) if looks_binary(&bytes) => Ok(render_bytes(&bytes, "FILE")),
+            Ok(bytes) => {
+                let text = render_bytes(&bytes, "FILE");
+                let text = if args.start_line.is_some() || args.end_line.is_some() {
+                    select_lines(&text, args.start_line, args.end_line)
+                } else {
+                    text
+                };
+                Ok(cap_bytes(text, args.max_bytes))
+            }
             Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
                 Ok(format!("◆ ACCESS DENIED: {}", args.path))
             }
@@ -100,6 +159,10 @@ impl ToolTrait for WriteFileTool {
             "required": ["path", "content"]
         })
     }
+    fn self_test(&self) -> Result<(), String> {
+        super::check_workspace(&self.workspace)
+    }
+
     async fn execute(
         &self,
         args: serde_json::Value,
@@ -162,6 +225,10 @@ impl ToolTrait for EditFileTool {
             "required": ["path", "old_text", "new_text"]
         })
     }
+    fn self_test(&self) -> Result<(), String> {
+        super::check_workspace(&self.workspace)
+    }
+
     async fn execute(
         &self,
         args: serde_json::Value,
@@ -223,6 +290,10 @@ impl ToolTrait for ListDirTool {
             "required": ["path"]
         })
     }
+    fn self_test(&self) -> Result<(), String> {
+        super::check_workspace(&self.workspace)
+    }
+
     async fn execute(
         &self,
         args: serde_json::Value,
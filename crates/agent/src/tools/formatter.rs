@@ -0,0 +1,48 @@
+//! Tool result formatting
+//!
+//! Optional post-processing of raw tool output before it is handed back to
+//! the model, configured under `toolkit.results`.
+
+use opensam_config::{Config, ToolResultsConfig};
+
+/// Applies the configured envelope and JSON pretty-printing to tool results
+#[derive(Debug, Clone, Default)]
+pub struct ToolResultFormatter {
+    config: ToolResultsConfig,
+}
+
+impl ToolResultFormatter {
+    pub fn new(config: ToolResultsConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.toolkit.results.clone())
+    }
+
+    /// Format `result` produced by the tool `name`
+    pub fn format(&self, name: &str, result: &str) -> String {
+        let format = self.config.format_for(name);
+
+        let body = if format.pretty_json {
+            pretty_json(result).unwrap_or_else(|| result.to_string())
+        } else {
+            result.to_string()
+        };
+
+        if format.envelope {
+            format!("tool {} returned:\n{}", name, body)
+        } else {
+            body
+        }
+    }
+}
+
+/// Re-indent `text` if it is a JSON object or array
+fn pretty_json(text: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(text.trim()).ok()?;
+    if !(value.is_object() || value.is_array()) {
+        return None;
+    }
+    serde_json::to_string_pretty(&value).ok()
+}
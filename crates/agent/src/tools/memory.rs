@@ -0,0 +1,144 @@
+//! TOOLKIT: Long-term Memory
+
+use async_trait::async_trait;
+use chrono::Local;
+use serde::Deserialize;
+use serde_json::json;
+use std::path::PathBuf;
+
+use tracing::debug;
+
+use super::path_utils::validate_workspace_path;
+use super::ToolTrait;
+
+/// Memory file location relative to the workspace
+pub const MEMORY_FILE: &str = "lifepod/MEMORY.md";
+
+/// Section used when none is given
+const DEFAULT_SECTION: &str = "Notes";
+
+/// Header written when the memory file does not exist yet
+const MEMORY_HEADER: &str = "# Long-term Memory\n";
+
+/// Memory recording tool
+pub struct MemoryTool {
+    workspace: PathBuf,
+}
+
+impl MemoryTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[derive(Deserialize)]
+struct MemoryArgs {
+    entry: String,
+    #[serde(default)]
+    section: Option<String>,
+}
+
+#[async_trait]
+impl ToolTrait for MemoryTool {
+    fn name(&self) -> &str {
+        "remember"
+    }
+    fn description(&self) -> &str {
+        "Record a fact in long-term memory under a section (e.g. Facts, Preferences, Notes)."
+    }
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "entry": { "type": "string", "description": "Fact to remember" },
+                "section": { "type": "string", "description": "Memory section, defaults to Notes" }
+            },
+            "required": ["entry"]
+        })
+    }
+    fn self_test(&self) -> Result<(), String> {
+        super::check_workspace(&self.workspace)
+    }
+
+    async fn execute(
+        &self,
+        args: serde_json::Value,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let args: MemoryArgs = serde_json::from_value(args)?;
+        let entry = args.entry.trim().replace('\n', " ");
+        if entry.is_empty() {
+            return Ok("◆ NOTHING TO REMEMBER".to_string());
+        }
+        let section = args
+            .section
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(DEFAULT_SECTION);
+
+        let path = validate_workspace_path(MEMORY_FILE, &self.workspace).await?;
+        debug!("◆ RECORDING MEMORY: {:?}", path);
+
+        let existing = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => MEMORY_HEADER.to_string(),
+            Err(e) => return Ok(format!("◆ MEMORY ERROR: {}", e)),
+        };
+
+        let line = format!("- [{}] {}", Local::now().format("%Y-%m-%d %H:%M"), entry);
+        let updated = append_to_section(&existing, section, &line);
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, updated).await?;
+
+        Ok(format!(
+            "◆ MEMORY RECORDED UNDER {}",
+            section.to_uppercase()
+        ))
+    }
+}
+
+/// Insert `line` at the end of the `## section` block, creating it if missing
+pub fn append_to_section(content: &str, section: &str, line: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    let heading = lines.iter().position(|l| {
+        l.strip_prefix("## ")
+            .is_some_and(|title| title.trim().eq_ignore_ascii_case(section))
+    });
+
+    match heading {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|l| l.starts_with("## ") || l.starts_with("# "))
+                .map(|offset| start + 1 + offset)
+                .unwrap_or(lines.len());
+
+            // Insert after the last non-blank line of the section
+            let mut insert_at = end;
+            while insert_at > start + 1 && lines[insert_at - 1].trim().is_empty() {
+                insert_at -= 1;
+            }
+            lines.insert(insert_at, line.to_string());
+            if insert_at == start + 1 {
+                lines.insert(insert_at, String::new());
+            }
+        }
+        None => {
+            while lines.last().is_some_and(|l| l.trim().is_empty()) {
+                lines.pop();
+            }
+            lines.push(String::new());
+            lines.push(format!("## {}", section));
+            lines.push(String::new());
+            lines.push(line.to_string());
+        }
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
@@ -0,0 +1,210 @@
+//! TOOLKIT: Memory Search
+
+use async_trait::async_trait;
+use opensam_provider::{cosine_similarity, Provider};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use tracing::debug;
+
+use super::memory::MEMORY_FILE;
+use super::ToolTrait;
+
+/// Snippets returned when the call does not ask for a number
+pub const DEFAULT_TOP_K: usize = 3;
+
+/// Embedded chunks of one file, valid while its mtime is unchanged
+struct CachedFile {
+    modified: SystemTime,
+    chunks: Vec<(String, Vec<f32>)>,
+}
+
+/// Semantic search over long-term memory and other workspace docs
+///
+/// Files are split into chunks with `chunk_markdown` and embedded through
+/// the provider. Chunk embeddings are cached per file and only recomputed
+/// when the file's mtime changes.
+pub struct MemorySearchTool {
+    workspace: PathBuf,
+    provider: Arc<dyn Provider>,
+    files: Vec<String>,
+    top_k: usize,
+    cache: Mutex<HashMap<PathBuf, CachedFile>>,
+}
+
+impl MemorySearchTool {
+    /// Search `lifepod/MEMORY.md` using `provider` for embeddings
+    pub fn new(workspace: PathBuf, provider: Arc<dyn Provider>) -> Self {
+        Self {
+            workspace,
+            provider,
+            files: vec![MEMORY_FILE.to_string()],
+            top_k: DEFAULT_TOP_K,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Also search these workspace-relative files
+    pub fn with_files(mut self, files: impl IntoIterator<Item = String>) -> Self {
+        for file in files {
+            if !self.files.contains(&file) {
+                self.files.push(file);
+            }
+        }
+        self
+    }
+
+    /// Snippets returned by default
+    pub fn with_top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k.max(1);
+        self
+    }
+
+    /// Embedded chunks of `path`, from cache while its mtime is unchanged
+    async fn chunks_for(
+        &self,
+        path: &Path,
+    ) -> Result<Vec<(String, Vec<f32>)>, Box<dyn std::error::Error + Send + Sync>> {
+        let modified = match tokio::fs::metadata(path).await {
+            Ok(meta) => meta.modified()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        if let Some(cached) = self.cache.lock().unwrap().get(path) {
+            if cached.modified == modified {
+                return Ok(cached.chunks.clone());
+            }
+        }
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let texts = chunk_markdown(&content);
+        debug!("◆ EMBEDDING {} CHUNKS OF {:?}", texts.len(), path);
+        let vectors = if texts.is_empty() {
+            Vec::new()
+        } else {
+            self.provider.embed(&texts).await?
+        };
+        let chunks: Vec<(String, Vec<f32>)> = texts.into_iter().zip(vectors).collect();
+
+        self.cache.lock().unwrap().insert(
+            path.to_path_buf(),
+            CachedFile {
+                modified,
+                chunks: chunks.clone(),
+            },
+        );
+        Ok(chunks)
+    }
+}
+
+#[derive(Deserialize)]
+struct MemorySearchArgs {
+    query: String,
+    #[serde(default)]
+    top_k: Option<usize>,
+}
+
+#[async_trait]
+impl ToolTrait for MemorySearchTool {
+    fn name(&self) -> &str {
+        "recall"
+    }
+    fn description(&self) -> &str {
+        "Search long-term memory for the snippets most relevant to a query."
+    }
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string", "description": "What to look for" },
+                "top_k": { "type": "integer", "minimum": 1, "description": "How many snippets to return" }
+            },
+            "required": ["query"]
+        })
+    }
+    fn self_test(&self) -> Result<(), String> {
+        super::check_workspace(&self.workspace)
+    }
+
+    async fn execute(
+        &self,
+        args: serde_json::Value,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let args: MemorySearchArgs = serde_json::from_value(args)?;
+        let query = args.query.trim();
+        if query.is_empty() {
+            return Ok("◆ NOTHING TO RECALL".to_string());
+        }
+        let top_k = args.top_k.unwrap_or(self.top_k).max(1);
+
+        let mut scored = Vec::new();
+        for file in &self.files {
+            let path = self.workspace.join(file);
+            let chunks = match self.chunks_for(&path).await {
+                Ok(chunks) => chunks,
+                Err(e) => return Ok(format!("◆ MEMORY SEARCH ERROR: {}", e)),
+            };
+            scored.extend(
+                chunks
+                    .into_iter()
+                    .map(|(text, vector)| (file, text, vector)),
+            );
+        }
+        if scored.is_empty() {
+            return Ok("◆ MEMORY IS EMPTY".to_string());
+        }
+
+        let query_vector = match self.provider.embed(&[query.to_string()]).await {
+            Ok(mut vectors) if !vectors.is_empty() => vectors.remove(0),
+            Ok(_) => return Ok("◆ MEMORY SEARCH ERROR: no query embedding".to_string()),
+            Err(e) => return Ok(format!("◆ MEMORY SEARCH ERROR: {}", e)),
+        };
+
+        let mut ranked: Vec<(f32, &String, String)> = scored
+            .into_iter()
+            .map(|(file, text, vector)| (cosine_similarity(&query_vector, &vector), file, text))
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        Ok(ranked
+            .into_iter()
+            .take(top_k)
+            .enumerate()
+            .map(|(i, (score, file, text))| format!("{}. [{:.2}] {}: {}", i + 1, score, file, text))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// Split markdown into searchable chunks
+///
+/// Paragraphs and list items each become a chunk; headings are dropped.
+pub fn chunk_markdown(content: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut flush = |current: &mut Vec<&str>| {
+        if !current.is_empty() {
+            chunks.push(current.join(" "));
+            current.clear();
+        }
+    };
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            flush(&mut current);
+            continue;
+        }
+        if line.starts_with("- ") || line.starts_with("* ") {
+            flush(&mut current);
+        }
+        current.push(line);
+    }
+    flush(&mut current);
+    chunks
+}
@@ -1,15 +1,27 @@
 //! OPERATIVE TOOLKIT
 
 pub mod filesystem;
+pub mod formatter;
+pub mod memory;
+pub mod memory_search;
 pub mod message;
 pub mod shell;
+pub mod time;
 pub mod web;
 // pub mod spawn;  // Disabled - subagent support not yet implemented
 pub mod path_utils;
+pub mod text_utils;
+pub mod url_policy;
+pub mod validation;
 
 pub use filesystem::{EditFileTool, ListDirTool, ReadFileTool, WriteFileTool};
+pub use formatter::ToolResultFormatter;
+pub use memory::MemoryTool;
+pub use memory_search::MemorySearchTool;
 pub use message::MessageTool;
 pub use shell::ExecTool;
+pub use time::TimeTool;
+pub use url_policy::UrlPolicy;
 pub use web::{WebFetchTool, WebSearchTool};
 // pub use spawn::SpawnTool;  // Disabled - subagent support not yet implemented
 
@@ -30,6 +42,73 @@ pub trait ToolTrait: Send + Sync {
         &self,
         args: Value,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Cheap, side-effect-free check that the tool can work at all
+    fn self_test(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Self-test helper: the workspace must exist and be a directory
+pub(crate) fn check_workspace(workspace: &std::path::Path) -> Result<(), String> {
+    match std::fs::metadata(workspace) {
+        Ok(meta) if meta.is_dir() => Ok(()),
+        Ok(_) => Err(format!(
+            "workspace {} is not a directory",
+            workspace.display()
+        )),
+        Err(e) => Err(format!("workspace {}: {}", workspace.display(), e)),
+    }
+}
+
+/// Error a tool returns to refuse a call rather than fail it
+#[derive(Debug, Clone)]
+pub struct ToolDenied(pub String);
+
+impl std::fmt::Display for ToolDenied {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ToolDenied {}
+
+/// How a tool call turned out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolOutcome {
+    /// The tool ran and produced output
+    Ok(String),
+    /// The tool ran and failed
+    Error(String),
+    /// The tool refused the call, e.g. a path outside the workspace
+    Denied(String),
+    /// No tool with that name is registered
+    NotFound,
+}
+
+impl ToolOutcome {
+    /// Classify a tool's result; refusals are told apart from failures
+    pub fn from_result(result: Result<String, Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        match result {
+            Ok(output) => ToolOutcome::Ok(output),
+            Err(e) if e.is::<ToolDenied>() || e.is::<path_utils::PathValidationError>() => {
+                ToolOutcome::Denied(e.to_string())
+            }
+            Err(e) => ToolOutcome::Error(e.to_string()),
+        }
+    }
+
+    /// Text handed to the model for this outcome
+    pub fn to_model_message(&self, tool: &str) -> String {
+        match self {
+            ToolOutcome::Ok(output) => output.clone(),
+            ToolOutcome::Error(e) => format!("Error: {}", e),
+            ToolOutcome::Denied(reason) => {
+                format!("Denied: {}. Do not retry this call unchanged.", reason)
+            }
+            ToolOutcome::NotFound => format!("Error: ◆ TOOLKIT '{}' NOT FOUND", tool),
+        }
+    }
 }
 
 pub fn to_provider_tool(tool: &dyn ToolTrait) -> Tool {
@@ -37,20 +116,28 @@ pub fn to_provider_tool(tool: &dyn ToolTrait) -> Tool {
 }
 
 /// TOOLKIT registry
+///
+/// `definitions` and `names` list tools in registration order, so the model
+/// sees the same tool list on every run.
 pub struct ToolRegistry {
     tools: HashMap<String, BoxedTool>,
+    order: Vec<String>,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            order: Vec::new(),
         }
     }
 
+    /// Register a tool; re-registering a name replaces it in place
     pub fn register<T: ToolTrait + 'static>(&mut self, tool: T) {
         let name = tool.name().to_string();
-        self.tools.insert(name, Box::new(tool));
+        if self.tools.insert(name.clone(), Box::new(tool)).is_none() {
+            self.order.push(name);
+        }
     }
 
     pub fn get(&self, name: &str) -> Option<&(dyn ToolTrait + Send + Sync)> {
@@ -62,8 +149,9 @@ impl ToolRegistry {
     }
 
     pub fn definitions(&self) -> Vec<Tool> {
-        self.tools
-            .values()
+        self.order
+            .iter()
+            .filter_map(|name| self.tools.get(name))
             .map(|t| to_provider_tool(t.as_ref()))
             .collect()
     }
@@ -80,8 +168,53 @@ impl ToolRegistry {
         tool.execute(args).await
     }
 
+    /// Run a tool and classify the outcome
+    pub async fn run(&self, name: &str, args: Value) -> ToolOutcome {
+        match self.tools.get(name) {
+            Some(tool) => ToolOutcome::from_result(tool.execute(args).await),
+            None => ToolOutcome::NotFound,
+        }
+    }
+
+    /// Check arguments against the named tool's parameter schema
+    ///
+    /// Unknown tools pass; `execute` reports them.
+    pub fn validate(&self, name: &str, args: &Value) -> Result<(), Vec<String>> {
+        match self.tools.get(name) {
+            Some(tool) => validation::validate_arguments(&tool.parameters(), args),
+            None => Ok(()),
+        }
+    }
+
+    /// Export every tool's description and parameters, keyed by name
+    pub fn export_schema(&self) -> Value {
+        let schema = self
+            .order
+            .iter()
+            .filter_map(|name| self.tools.get(name).map(|t| (name, t)))
+            .map(|(name, tool)| {
+                (
+                    name.clone(),
+                    serde_json::json!({
+                        "description": tool.description(),
+                        "parameters": tool.parameters(),
+                    }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+        Value::Object(schema)
+    }
+
     pub fn names(&self) -> Vec<String> {
-        self.tools.keys().cloned().collect()
+        self.order.clone()
+    }
+
+    /// Run every tool's self-test, in registration order
+    pub fn self_test(&self) -> Vec<(String, Result<(), String>)> {
+        self.order
+            .iter()
+            .filter_map(|name| self.tools.get(name).map(|t| (name.clone(), t.self_test())))
+            .collect()
     }
 }
 
@@ -92,9 +225,11 @@ impl Default for ToolRegistry {
 }
 
 /// Register default tools with the given workspace
+///
+/// Optional tools follow the same `toolkit` switches as `AgentLoop`.
 pub fn register_default_tools(
     registry: &mut ToolRegistry,
-    brave_key: Option<String>,
+    config: &opensam_config::Config,
     workspace: &std::path::Path,
     _bus: opensam_bus::MessageBus,
 ) {
@@ -104,12 +239,22 @@ pub fn register_default_tools(
     registry.register(EditFileTool::new(workspace.to_path_buf()));
     registry.register(ListDirTool::new(workspace.to_path_buf()));
 
+    // Memory tool
+    if config.toolkit.memory.enabled {
+        registry.register(MemoryTool::new(workspace.to_path_buf()));
+    }
+
     // Shell tool
     registry.register(ExecTool::with_workspace(workspace.to_path_buf()));
 
+    // Clock
+    if config.toolkit.time.enabled {
+        registry.register(TimeTool::new());
+    }
+
     // Web tools
-    registry.register(WebSearchTool::new(brave_key, 5));
-    registry.register(WebFetchTool::default());
+    registry.register(WebSearchTool::from_config(config));
+    registry.register(WebFetchTool::from_config(config));
 
     // Message tool
     let (sender, _receiver) = tokio::sync::mpsc::unbounded_channel();
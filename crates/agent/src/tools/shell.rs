@@ -9,6 +9,7 @@ use tokio::process::Command;
 use tracing::debug;
 
 use super::path_utils::validate_workspace_path;
+use super::text_utils::{render_bytes, truncate_at_char_boundary};
 use super::ToolTrait;
 
 /// Terminal command tool
@@ -59,6 +60,10 @@ impl ToolTrait for ExecTool {
             "required": ["command"]
         })
     }
+    fn self_test(&self) -> Result<(), String> {
+        super::check_workspace(&self.workspace)
+    }
+
     async fn execute(
         &self,
         args: serde_json::Value,
@@ -98,12 +103,12 @@ impl ToolTrait for ExecTool {
         };
         let mut parts = Vec::new();
         if !result.stdout.is_empty() {
-            parts.push(String::from_utf8_lossy(&result.stdout).to_string());
+            parts.push(render_bytes(&result.stdout, "OUTPUT"));
         }
         if !result.stderr.is_empty() {
             parts.push(format!(
                 "STDERR:\n{}",
-                String::from_utf8_lossy(&result.stderr)
+                render_bytes(&result.stderr, "OUTPUT")
             ));
         }
         if result.status.code() != Some(0) {
@@ -116,10 +121,11 @@ impl ToolTrait for ExecTool {
         };
         const MAX_LEN: usize = 10000;
         if result.len() > MAX_LEN {
+            let shown = truncate_at_char_boundary(&result, MAX_LEN);
             Ok(format!(
                 "{}\n◆ OUTPUT TRUNCATED: {} BYTES REMAINING",
-                &result[..MAX_LEN],
-                result.len() - MAX_LEN
+                shown,
+                result.len() - shown.len()
             ))
         } else {
             Ok(result)
@@ -0,0 +1,71 @@
+//! Text decoding utilities for tool output
+//!
+//! Tools read files and capture process output as raw bytes. These helpers
+//! turn those bytes into something safe to hand to the model: likely-binary
+//! content is summarized instead of dumped, and invalid UTF-8 is decoded
+//! lossily with a note so the model knows characters were replaced.
+
+/// Number of leading bytes inspected when sniffing for binary content
+const SNIFF_LEN: usize = 8192;
+
+/// Fraction of control bytes above which content is treated as binary
+const CONTROL_RATIO: f64 = 0.3;
+
+/// Note appended when invalid UTF-8 sequences were replaced
+pub const LOSSY_NOTE: &str = "◆ NOTE: INVALID UTF-8 REPLACED WITH \u{FFFD}";
+
+/// Heuristically decide whether `bytes` are binary rather than text.
+///
+/// Content is binary if the sniffed prefix contains a NUL byte, or if a large
+/// share of it is non-whitespace control characters.
+pub fn looks_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if sample.is_empty() {
+        return false;
+    }
+    if sample.contains(&0) {
+        return true;
+    }
+    let control = sample
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t' | 0x0c | 0x1b))
+        .count();
+    control as f64 / sample.len() as f64 > CONTROL_RATIO
+}
+
+/// Decode bytes as UTF-8, replacing invalid sequences.
+///
+/// Returns the decoded text and whether any replacement occurred.
+pub fn decode_lossy(bytes: &[u8]) -> (String, bool) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), false),
+        Err(_) => (String::from_utf8_lossy(bytes).into_owned(), true),
+    }
+}
+
+/// Render raw bytes for the model.
+///
+/// `label` names the source in the binary summary (e.g. "FILE", "OUTPUT").
+pub fn render_bytes(bytes: &[u8], label: &str) -> String {
+    if looks_binary(bytes) {
+        return format!("◆ BINARY {}: {} BYTES, NOT SHOWN", label, bytes.len());
+    }
+    let (text, replaced) = decode_lossy(bytes);
+    if replaced {
+        format!("{}\n{}", text, LOSSY_NOTE)
+    } else {
+        text
+    }
+}
+
+/// Truncate `text` to at most `max` bytes without splitting a character.
+pub fn truncate_at_char_boundary(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
@@ -0,0 +1,51 @@
+//! TOOLKIT: System Clock
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use serde_json::json;
+
+use super::ToolTrait;
+
+/// Current date/time tool
+#[derive(Default)]
+pub struct TimeTool;
+
+impl TimeTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Render `now` as local time, UTC time, offset and epoch millis
+    pub fn render(now: DateTime<Local>) -> serde_json::Value {
+        json!({
+            "local": now.to_rfc3339_opts(SecondsFormat::Secs, false),
+            "utc": now.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true),
+            "timezone": now.format("%:z").to_string(),
+            "weekday": now.format("%A").to_string(),
+            "epoch_ms": now.timestamp_millis(),
+        })
+    }
+}
+
+#[async_trait]
+impl ToolTrait for TimeTool {
+    fn name(&self) -> &str {
+        "current_time"
+    }
+    fn description(&self) -> &str {
+        "Get the current local date/time, UTC time, timezone offset and epoch milliseconds."
+    }
+    fn parameters(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    async fn execute(
+        &self,
+        _args: serde_json::Value,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self::render(Local::now()).to_string())
+    }
+}
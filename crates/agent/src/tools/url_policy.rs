@@ -0,0 +1,224 @@
+//! URL policy for web_fetch - keeps the model away from internal addresses
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::Url;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Hostnames that always resolve somewhere internal
+const INTERNAL_HOSTS: &[&str] = &["localhost", "metadata.google.internal"];
+
+/// Which URLs `web_fetch` may request
+///
+/// Only http(s) is allowed. Loopback, private, link-local and other
+/// non-public IP literals are refused unless the host is in `allow_hosts`,
+/// as are names resolving to such addresses when the policy is used as the
+/// client's DNS resolver. A non-empty `allow_hosts` also restricts fetching to those hosts, and
+/// `deny_hosts` always wins. Host entries match the host and its subdomains.
+#[derive(Debug, Clone, Default)]
+pub struct UrlPolicy {
+    allow_hosts: Vec<String>,
+    deny_hosts: Vec<String>,
+}
+
+impl UrlPolicy {
+    pub fn new(allow_hosts: Vec<String>, deny_hosts: Vec<String>) -> Self {
+        let normalize =
+            |hosts: Vec<String>| hosts.into_iter().map(|h| h.to_ascii_lowercase()).collect();
+        Self {
+            allow_hosts: normalize(allow_hosts),
+            deny_hosts: normalize(deny_hosts),
+        }
+    }
+
+    pub fn from_config(config: &opensam_config::Config) -> Self {
+        let fetch = &config.toolkit.web.fetch;
+        Self::new(fetch.allow_hosts.clone(), fetch.deny_hosts.clone())
+    }
+
+    /// Parse and check a URL, returning the reason when it is refused
+    pub fn check(&self, url: &str) -> Result<Url, String> {
+        let parsed = Url::parse(url).map_err(|e| format!("invalid URL '{}': {}", url, e))?;
+        self.check_url(&parsed)?;
+        Ok(parsed)
+    }
+
+    /// Check an already parsed URL, such as a redirect target
+    pub fn check_url(&self, url: &Url) -> Result<(), String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("scheme '{}' is not allowed", url.scheme()));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("URL '{}' has no host", url))?;
+        // IP literals arrive normalized, with IPv6 in brackets
+        let ip = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .ok();
+        let name = match ip {
+            Some(ip) => ip.to_string(),
+            None => host.trim_end_matches('.').to_ascii_lowercase(),
+        };
+
+        if matches_any(&name, &self.deny_hosts) {
+            return Err(format!("host '{}' is denied", name));
+        }
+        let allowed = matches_any(&name, &self.allow_hosts);
+        if !self.allow_hosts.is_empty() && !allowed {
+            return Err(format!("host '{}' is not in the allow list", name));
+        }
+        if allowed {
+            return Ok(());
+        }
+
+        let internal = match ip {
+            Some(ip) => !is_public_ip(ip),
+            None => INTERNAL_HOSTS
+                .iter()
+                .any(|h| name == *h || name.ends_with(&format!(".{}", h))),
+        };
+        if internal {
+            return Err(format!("host '{}' is an internal address", name));
+        }
+        Ok(())
+    }
+
+    /// Resolve a host name, refusing it when any address is not public
+    ///
+    /// Hosts in `allow_hosts` may resolve anywhere.
+    pub async fn resolve_host(&self, host: &str) -> Result<Vec<SocketAddr>, String> {
+        let name = host.trim_end_matches('.').to_ascii_lowercase();
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+            .await
+            .map_err(|e| format!("host '{}' could not be resolved: {}", name, e))?
+            .collect();
+        if !matches_any(&name, &self.allow_hosts) {
+            if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+                return Err(format!(
+                    "host '{}' resolves to internal address {}",
+                    name,
+                    addr.ip()
+                ));
+            }
+        }
+        Ok(addrs)
+    }
+}
+
+/// Connect only to the addresses the policy checked, redirects included
+impl Resolve for UrlPolicy {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.clone();
+        Box::pin(async move {
+            let addrs = policy.resolve_host(name.as_str()).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+fn matches_any(host: &str, patterns: &[String]) -> bool {
+    patterns
+        .iter()
+        .any(|p| host == p || host.ends_with(&format!(".{}", p)))
+}
+
+fn is_internal_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || a == 0
+        // Shared address space (100.64.0.0/10)
+        || (a == 100 && (b & 0xc0) == 64)
+}
+
+fn is_internal_v6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_internal_v4(v4);
+    }
+    ip.is_loopback() || ip.is_unspecified() || ip.is_unique_local() || ip.is_unicast_link_local()
+}
+
+/// Whether an address is reachable from the public internet
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => !is_internal_v4(v4),
+        IpAddr::V6(v6) => !is_internal_v6(v6),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_metadata_and_loopback() {
+        let policy = UrlPolicy::default();
+        assert!(policy
+            .check("http://169.254.169.254/latest/meta-data/")
+            .is_err());
+        assert!(policy.check("http://localhost:8080/admin").is_err());
+        assert!(policy.check("http://127.0.0.1/").is_err());
+        assert!(policy.check("http://[::1]/").is_err());
+        assert!(policy.check("http://10.0.0.5/").is_err());
+        assert!(policy.check("http://metadata.google.internal/").is_err());
+    }
+
+    #[test]
+    fn test_blocks_obfuscated_ip_literals() {
+        let policy = UrlPolicy::default();
+        // Decimal, hex and IPv4-mapped forms of internal addresses
+        assert!(policy.check("http://2852039166/").is_err());
+        assert!(policy.check("http://0x7f000001/").is_err());
+        assert!(policy.check("http://[::ffff:127.0.0.1]/").is_err());
+        assert!(policy.check("http://LOCALHOST./").is_err());
+    }
+
+    #[test]
+    fn test_allows_public_urls() {
+        let policy = UrlPolicy::default();
+        assert!(policy.check("https://example.com/page").is_ok());
+        assert!(policy.check("http://93.184.216.34/").is_ok());
+    }
+
+    #[test]
+    fn test_rejects_non_http_schemes() {
+        let policy = UrlPolicy::default();
+        assert!(policy.check("file:///etc/passwd").is_err());
+        assert!(policy.check("ftp://example.com/").is_err());
+    }
+
+    #[test]
+    fn test_allow_and_deny_lists() {
+        let policy = UrlPolicy::new(
+            vec!["example.com".to_string(), "localhost".to_string()],
+            vec!["bad.example.com".to_string()],
+        );
+        assert!(policy.check("https://docs.example.com/").is_ok());
+        assert!(policy.check("http://localhost:3000/").is_ok());
+        assert!(policy.check("https://bad.example.com/").is_err());
+        assert!(policy.check("https://rust-lang.org/").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_blocks_names_resolving_to_loopback() {
+        let policy = UrlPolicy::default();
+        let err = policy.resolve_host("localhost").await.unwrap_err();
+        assert!(err.contains("internal address"));
+
+        let allowed = UrlPolicy::new(vec!["localhost".to_string()], Vec::new());
+        assert!(!allowed.resolve_host("localhost").await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        assert!(is_public_ip("8.8.8.8".parse().unwrap()));
+        assert!(!is_public_ip("192.168.1.1".parse().unwrap()));
+        assert!(!is_public_ip("fe80::1".parse().unwrap()));
+    }
+}
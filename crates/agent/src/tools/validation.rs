@@ -0,0 +1,105 @@
+//! Tool-call argument validation
+//!
+//! Checks model-supplied arguments against the subset of JSON Schema the
+//! toolkit declares: object shape, required fields, property types and enums.
+//! Mismatches become a correction message for the model instead of a
+//! cryptic failure inside the tool.
+
+use serde_json::{json, Value};
+
+/// Validate `args` against a tool's `parameters` schema.
+///
+/// Returns every problem found, so the model can fix them in one retry.
+pub fn validate_arguments(schema: &Value, args: &Value) -> Result<(), Vec<String>> {
+    let mut problems = Vec::new();
+    check_value(schema, args, "arguments", &mut problems);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+/// Structured tool message telling the model how to fix its call
+pub fn correction_message(tool: &str, schema: &Value, problems: &[String]) -> String {
+    json!({
+        "error": "invalid_arguments",
+        "tool": tool,
+        "problems": problems,
+        "expected_schema": schema,
+        "hint": "Tool was not executed. Call it again with arguments matching expected_schema.",
+    })
+    .to_string()
+}
+
+fn check_value(schema: &Value, value: &Value, path: &str, problems: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        if !matches_type(expected, value) {
+            problems.push(format!(
+                "{} should be {} but got {}",
+                path,
+                expected,
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            problems.push(format!(
+                "{} must be one of {}",
+                path,
+                Value::from(allowed.clone())
+            ));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for field in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(field) {
+                    problems.push(format!("missing required field '{}'", field));
+                }
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (field, field_schema) in properties {
+                if let Some(field_value) = object.get(field) {
+                    check_value(field_schema, field_value, &format!("'{}'", field), problems);
+                }
+            }
+        }
+    }
+
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in array.iter().enumerate() {
+            check_value(items, item, &format!("{}[{}]", path, i), problems);
+        }
+    }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "null" => value.is_null(),
+        // Unknown types are not ours to reject
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
@@ -4,12 +4,19 @@ use async_trait::async_trait;
 use regex::Regex;
 use serde::Deserialize;
 use serde_json::json;
-use tracing::debug;
+use std::sync::Arc;
+use tracing::{debug, warn};
 
-use super::ToolTrait;
+use super::url_policy::UrlPolicy;
+use super::{ToolDenied, ToolTrait};
 
 const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36";
 
+/// Download cap for web_fetch when none is configured
+pub const DEFAULT_MAX_FETCH_BYTES: usize = 2_000_000;
+
+const BRAVE_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+
 /// Web search tool using Brave Search API
 pub struct WebSearchTool {
     api_key: String,
@@ -62,6 +69,18 @@ impl ToolTrait for WebSearchTool {
         })
     }
 
+    fn self_test(&self) -> Result<(), String> {
+        if self.api_key.is_empty() {
+            return Err("BRAVE_API_KEY not configured".to_string());
+        }
+        reqwest::Client::new()
+            .get(BRAVE_SEARCH_URL)
+            .header("X-Subscription-Token", &self.api_key)
+            .build()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
     async fn execute(
         &self,
         args: serde_json::Value,
@@ -75,7 +94,7 @@ impl ToolTrait for WebSearchTool {
 
         let client = reqwest::Client::new();
         let response = client
-            .get("https://api.search.brave.com/res/v1/web/search")
+            .get(BRAVE_SEARCH_URL)
             .query(&[("q", &args.query), ("count", &count.to_string())])
             .header("Accept", "application/json")
             .header("X-Subscription-Token", &self.api_key)
@@ -118,10 +137,35 @@ impl ToolTrait for WebSearchTool {
 
 pub struct WebFetchTool {
     max_chars: usize,
+    max_bytes: usize,
+    policy: Arc<UrlPolicy>,
 }
 impl WebFetchTool {
     pub fn new(max_chars: usize) -> Self {
-        Self { max_chars }
+        Self {
+            max_chars,
+            max_bytes: DEFAULT_MAX_FETCH_BYTES,
+            policy: Arc::new(UrlPolicy::default()),
+        }
+    }
+
+    /// Create from config, applying the fetch host lists and download cap
+    pub fn from_config(config: &opensam_config::Config) -> Self {
+        Self::default()
+            .with_policy(UrlPolicy::from_config(config))
+            .with_max_bytes(config.toolkit.web.fetch.max_bytes)
+    }
+
+    /// Stop downloading after this many bytes
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Replace the URL policy
+    pub fn with_policy(mut self, policy: UrlPolicy) -> Self {
+        self.policy = Arc::new(policy);
+        self
     }
 }
 impl Default for WebFetchTool {
@@ -160,6 +204,15 @@ impl ToolTrait for WebFetchTool {
         })
     }
 
+    fn self_test(&self) -> Result<(), String> {
+        reqwest::Client::new()
+            .get("https://example.com/")
+            .header("User-Agent", USER_AGENT)
+            .build()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
     async fn execute(
         &self,
         args: serde_json::Value,
@@ -169,9 +222,35 @@ impl ToolTrait for WebFetchTool {
         let extract_mode = args.extract_mode.as_deref().unwrap_or("markdown");
         debug!("Fetching URL: {} (mode: {})", args.url, extract_mode);
 
-        let client = reqwest::Client::new();
+        let url = self.policy.check(&args.url).map_err(|reason| {
+            warn!("◆ WEB FETCH BLOCKED: {}", reason);
+            ToolDenied(reason)
+        })?;
+
+        if let Some(host) = url.domain() {
+            self.policy.resolve_host(host).await.map_err(|reason| {
+                warn!("◆ WEB FETCH BLOCKED: {}", reason);
+                ToolDenied(reason)
+            })?;
+        }
+
+        // Redirects are checked too, so a public URL cannot bounce inward;
+        // the policy also resolves every host the client connects to
+        let policy = self.policy.clone();
+        let client = reqwest::Client::builder()
+            .dns_resolver(Arc::clone(&self.policy))
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() >= 10 {
+                    attempt.error("too many redirects")
+                } else if let Err(reason) = policy.check_url(attempt.url()) {
+                    attempt.error(reason)
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .build()?;
         let response = client
-            .get(&args.url)
+            .get(url)
             .header("User-Agent", USER_AGENT)
             .timeout(std::time::Duration::from_secs(30))
             .send()
@@ -179,23 +258,36 @@ impl ToolTrait for WebFetchTool {
 
         let status = response.status();
         let final_url = response.url().clone();
-        let headers = response.headers().clone();
-        let content_type = headers
+        let content_type = response
+            .headers()
             .get("content-type")
             .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-        let text = response.text().await?;
+            .unwrap_or("")
+            .to_string();
+
+        let mut response = response;
+        let mut body = Vec::new();
+        let mut capped = false;
+        while let Some(chunk) = response.chunk().await? {
+            let room = self.max_bytes - body.len();
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                capped = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+        if capped {
+            debug!(
+                "◆ WEB FETCH CAPPED AT {} BYTES: {}",
+                self.max_bytes, args.url
+            );
+        }
 
-        let (content, extractor) = if content_type.contains("application/json") {
-            (text, "json")
-        } else if extract_mode == "text" {
-            (strip_tags(&text), "text")
-        } else {
-            (html_to_markdown(&text), "markdown")
-        };
+        let (content, extractor) = extract_body(&content_type, &body, extract_mode);
 
-        let truncated = content.len() > max_chars;
-        let content = if truncated {
+        let truncated = capped || content.len() > max_chars;
+        let content = if content.len() > max_chars {
             content[..max_chars].to_string()
         } else {
             content
@@ -203,12 +295,56 @@ impl ToolTrait for WebFetchTool {
 
         Ok(json!({
             "url": args.url, "finalUrl": final_url.as_str(), "status": status.as_u16(),
-            "extractor": extractor, "truncated": truncated, "length": content.len(), "text": content
+            "contentType": content_type, "extractor": extractor, "truncated": truncated, "length": content.len(), "text": content
         })
         .to_string())
     }
 }
 
+/// Turn a downloaded body into text according to its `Content-Type`
+///
+/// JSON is pretty-printed, other text passes through, HTML is converted per
+/// `extract_mode`, and anything else is summarized instead of dumped. An
+/// untyped body is treated as HTML when it is valid UTF-8, ignoring a
+/// character left incomplete at the end.
+fn extract_body(content_type: &str, body: &[u8], extract_mode: &str) -> (String, &'static str) {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let text = match std::str::from_utf8(body) {
+        Ok(text) => Some(text),
+        // The size cap can cut a character in half; keep the complete prefix
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&body[..e.valid_up_to()]).ok(),
+        Err(_) => None,
+    };
+
+    match (mime.as_str(), text) {
+        (m, Some(text)) if m == "application/json" || m.ends_with("+json") => {
+            let pretty = serde_json::from_str::<serde_json::Value>(text)
+                .ok()
+                .and_then(|v| serde_json::to_string_pretty(&v).ok());
+            (pretty.unwrap_or_else(|| text.to_string()), "json")
+        }
+        ("text/html" | "application/xhtml+xml" | "", Some(text)) => {
+            if extract_mode == "text" {
+                (strip_tags(text), "text")
+            } else {
+                (html_to_markdown(text), "markdown")
+            }
+        }
+        (m, Some(text)) if m.starts_with("text/") || m.ends_with("xml") => {
+            (text.to_string(), "raw")
+        }
+        _ => {
+            let kind = if mime.is_empty() { "unknown" } else { &mime };
+            (format!("binary ({}), {} bytes", kind, body.len()), "binary")
+        }
+    }
+}
+
 fn strip_tags(html: &str) -> String {
     let re = Regex::new(r"(?is)<script[\s\S]*?</script>|<style[\s\S]*?</style>").unwrap();
     let text = re.replace_all(html, "");
@@ -0,0 +1,151 @@
+//! Turn scheduling for the gateway
+//!
+//! Runs agent turns concurrently up to a fixed limit while keeping turns for
+//! the same session strictly ordered, so one slow conversation no longer
+//! blocks every other operative. Optionally a new turn supersedes the one
+//! still running for its session.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{oneshot, Semaphore};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::debug;
+
+/// Id and completion signal of a session's most recently queued turn
+type SessionTail = (u64, oneshot::Receiver<()>);
+
+/// Bounded, per-session serialized task runner
+#[derive(Clone)]
+pub struct TurnScheduler {
+    permits: Arc<Semaphore>,
+    /// Id and completion signal of the most recently queued turn per session
+    tails: Arc<Mutex<HashMap<String, SessionTail>>>,
+    next_turn: Arc<AtomicU64>,
+    /// Cancellation token of the most recently queued turn per session
+    latest: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    /// Every spawned turn, so shutdown can wait for the ones in flight
+    tracker: TaskTracker,
+    cancel_superseded: bool,
+    max_concurrent: usize,
+}
+
+impl TurnScheduler {
+    /// Create a scheduler running at most `max_concurrent` turns at once
+    pub fn new(max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            tails: Arc::new(Mutex::new(HashMap::new())),
+            next_turn: Arc::new(AtomicU64::new(0)),
+            latest: Arc::new(Mutex::new(HashMap::new())),
+            tracker: TaskTracker::new(),
+            cancel_superseded: false,
+            max_concurrent,
+        }
+    }
+
+    /// Cancel a session's running or queued turn when a newer one arrives
+    ///
+    /// The superseded turn's future is dropped at its next await point, so
+    /// its reply is never sent. The new turn still waits for it to unwind.
+    pub fn with_cancel_superseded(mut self, cancel: bool) -> Self {
+        self.cancel_superseded = cancel;
+        self
+    }
+
+    /// Sessions with a turn that can still be superseded
+    pub fn cancellable_sessions(&self) -> usize {
+        self.latest.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Sessions with a turn running or queued
+    pub fn active_sessions(&self) -> usize {
+        self.tails.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Maximum number of concurrent turns
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Wait up to `timeout` for every running and queued turn to finish
+    ///
+    /// Returns true when none were left unfinished. Turns spawned while
+    /// draining are waited for as well.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.tracker.close();
+        tokio::time::timeout(timeout, self.tracker.wait())
+            .await
+            .is_ok()
+    }
+
+    /// Queue a turn for `session_key`
+    ///
+    /// The turn starts once every earlier turn for the same session has
+    /// finished and a concurrency slot is free.
+    pub fn spawn<Fut>(&self, session_key: impl Into<String>, turn: Fut) -> JoinHandle<()>
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let session_key = session_key.into();
+        let (done_tx, done_rx) = oneshot::channel();
+        let turn_id = self.next_turn.fetch_add(1, Ordering::Relaxed);
+        let previous = self
+            .tails
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(session_key.clone(), (turn_id, done_rx))
+            .map(|(_, done)| done);
+        let cancel = CancellationToken::new();
+        if self.cancel_superseded {
+            // Cancel under the lock so a token still in the map is never cancelled
+            let mut latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(superseded) = latest.insert(session_key.clone(), cancel.clone()) {
+                superseded.cancel();
+            }
+        }
+        let permits = Arc::clone(&self.permits);
+        let tails = Arc::clone(&self.tails);
+        let latest = Arc::clone(&self.latest);
+        let cancel_superseded = self.cancel_superseded;
+
+        self.tracker.spawn(async move {
+            if let Some(previous) = previous {
+                // Err means the previous turn finished (or panicked) and dropped its sender
+                let _ = previous.await;
+            }
+            tokio::select! {
+                biased;
+                _ = cancel.cancelled() => {
+                    debug!("◆ TURN SUPERSEDED FOR {}", session_key);
+                }
+                _ = async {
+                    let _permit = permits.acquire_owned().await;
+                    turn.await;
+                } => {}
+            }
+            if cancel_superseded {
+                // An uncancelled token is still the session's latest
+                let mut latest = latest.lock().unwrap_or_else(|e| e.into_inner());
+                if !cancel.is_cancelled() {
+                    latest.remove(&session_key);
+                }
+            }
+            // The last queued turn of a session leaves no entry behind
+            let mut tails = tails.lock().unwrap_or_else(|e| e.into_inner());
+            if tails
+                .get(&session_key)
+                .is_some_and(|(id, _)| *id == turn_id)
+            {
+                tails.remove(&session_key);
+            }
+            drop(tails);
+            drop(done_tx);
+        })
+    }
+}
@@ -0,0 +1,135 @@
+//! Token usage and cost accumulated per session
+
+use crate::output::estimate_cost;
+use opensam_config::Config;
+use opensam_provider::Usage;
+use opensam_session::Session;
+use std::collections::{BTreeMap, HashMap};
+
+/// Session metadata key holding usage per model
+pub const SESSION_USAGE_KEY: &str = "usage";
+
+/// Usage keyed by model
+pub type ModelUsage = BTreeMap<String, Usage>;
+
+/// Add one turn's `usage` under `model` to the totals stored on `session`
+///
+/// Returns whether the totals were stored (see `Session::set_metadata`).
+pub fn record_in_session(session: &mut Session, model: &str, usage: &Usage) -> bool {
+    let mut totals = session_usage(session);
+    totals.entry(model.to_string()).or_default().add(usage);
+    session.set_metadata(SESSION_USAGE_KEY, totals)
+}
+
+/// Usage totals stored on `session`, empty when none were recorded
+pub fn session_usage(session: &Session) -> ModelUsage {
+    session
+        .metadata
+        .get(SESSION_USAGE_KEY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Sums usage per session and prices it per model
+///
+/// Models missing from the price table fall back to the default price.
+#[derive(Debug, Clone, Default)]
+pub struct UsageTracker {
+    sessions: BTreeMap<String, ModelUsage>,
+    prices: HashMap<String, f64>,
+    default_price: f64,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prices from `operative.defaults.model_prices` and `cost_per_1k_tokens`
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = &config.operative.defaults;
+        Self::new()
+            .with_prices(defaults.model_prices.clone())
+            .with_default_price(defaults.cost_per_1k_tokens)
+    }
+
+    /// Price per 1000 tokens, keyed by model
+    pub fn with_prices(mut self, prices: HashMap<String, f64>) -> Self {
+        self.prices = prices;
+        self
+    }
+
+    /// Price per 1000 tokens for models without an entry
+    pub fn with_default_price(mut self, price: f64) -> Self {
+        self.default_price = price;
+        self
+    }
+
+    /// Price per 1000 tokens for `model`
+    pub fn price_for(&self, model: &str) -> f64 {
+        self.prices
+            .get(model)
+            .copied()
+            .unwrap_or(self.default_price)
+    }
+
+    /// Add `usage` by `model` to the totals for `session_key`
+    pub fn record(&mut self, session_key: &str, model: &str, usage: &Usage) {
+        self.sessions
+            .entry(session_key.to_string())
+            .or_default()
+            .entry(model.to_string())
+            .or_default()
+            .add(usage);
+    }
+
+    /// Add the totals stored on a saved session
+    pub fn record_session(&mut self, session: &Session) {
+        for (model, usage) in session_usage(session) {
+            self.record(&session.key, &model, &usage);
+        }
+    }
+
+    /// Tracked session keys, sorted
+    pub fn sessions(&self) -> impl Iterator<Item = &str> {
+        self.sessions.keys().map(String::as_str)
+    }
+
+    /// Usage for one session across every model
+    pub fn session_usage(&self, session_key: &str) -> Usage {
+        let mut total = Usage::default();
+        for usage in self
+            .sessions
+            .get(session_key)
+            .into_iter()
+            .flat_map(|m| m.values())
+        {
+            total.add(usage);
+        }
+        total
+    }
+
+    /// Estimated cost of one session
+    pub fn session_cost(&self, session_key: &str) -> f64 {
+        self.sessions
+            .get(session_key)
+            .into_iter()
+            .flatten()
+            .map(|(model, usage)| estimate_cost(usage, self.price_for(model)))
+            .sum()
+    }
+
+    /// Usage across every session
+    pub fn total_usage(&self) -> Usage {
+        let mut total = Usage::default();
+        for key in self.sessions.keys() {
+            total.add(&self.session_usage(key));
+        }
+        total
+    }
+
+    /// Estimated cost across every session
+    pub fn total_cost(&self) -> f64 {
+        self.sessions.keys().map(|key| self.session_cost(key)).sum()
+    }
+}
@@ -0,0 +1,122 @@
+//! Tests for automatic context compaction
+
+use async_trait::async_trait;
+use opensam_agent::compaction::{keep_recent, trigger_len, COMPACTION_PROMPT};
+use opensam_agent::AgentLoop;
+use opensam_bus::MessageBus;
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use opensam_session::{SUMMARY_KEY, SUMMARY_PREFIX};
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+/// Answers summary requests with a fixed summary and everything else with "ok"
+#[derive(Clone, Default)]
+struct MockProvider {
+    summaries: Arc<Mutex<u32>>,
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        let is_summary = params
+            .messages
+            .first()
+            .and_then(|m| m.content.as_deref())
+            .is_some_and(|c| c == COMPACTION_PROMPT);
+        if is_summary {
+            *self.summaries.lock().unwrap() += 1;
+            return Ok(ChatResponse::text("user asked several questions"));
+        }
+        Ok(ChatResponse::text("ok"))
+    }
+
+    fn default_model(&self) -> String {
+        "test/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+fn agent(temp_dir: &TempDir, provider: MockProvider) -> AgentLoop<MockProvider> {
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let mut agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        provider,
+        temp_dir.path().to_path_buf(),
+        "test/model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    );
+    agent.set_max_history_messages(10);
+    agent.set_compact_threshold(Some(0.8));
+    agent
+}
+
+#[test]
+fn test_trigger_len_and_keep_recent() {
+    assert_eq!(trigger_len(20, 0.8), 16);
+    assert_eq!(trigger_len(5, 0.5), 3);
+    assert_eq!(trigger_len(1, 0.1), 1);
+    assert_eq!(keep_recent(20), 10);
+}
+
+#[tokio::test]
+async fn test_under_threshold_does_not_compact() {
+    let temp_dir = TempDir::new().unwrap();
+    let provider = MockProvider::default();
+    let agent = agent(&temp_dir, provider.clone());
+
+    // History seen before each turn: 0, 2, 4, 6 messages - all under 8
+    for i in 0..4 {
+        agent.process_direct(&format!("question {}", i), "s").await;
+    }
+
+    assert_eq!(*provider.summaries.lock().unwrap(), 0);
+    let len = agent
+        .sessions()
+        .read_session("cli:s", |s| s.messages.len())
+        .await;
+    assert_eq!(len, 8);
+}
+
+#[tokio::test]
+async fn test_crossing_threshold_compacts_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let provider = MockProvider::default();
+    let agent = agent(&temp_dir, provider.clone());
+
+    for i in 0..5 {
+        agent.process_direct(&format!("question {}", i), "s").await;
+    }
+
+    assert_eq!(*provider.summaries.lock().unwrap(), 1);
+    let (first, len) = agent
+        .sessions()
+        .read_session("cli:s", |s| (s.messages[0].clone(), s.messages.len()))
+        .await;
+    assert_eq!(first.role, "system");
+    assert_eq!(
+        first.content,
+        format!("{}user asked several questions", SUMMARY_PREFIX)
+    );
+    assert_eq!(first.extra[SUMMARY_KEY], true);
+    // Summary + 5 kept + the latest exchange
+    assert_eq!(len, 8);
+}
+
+#[tokio::test]
+async fn test_compaction_disabled_by_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let provider = MockProvider::default();
+    let mut agent = agent(&temp_dir, provider.clone());
+    agent.set_compact_threshold(None);
+
+    for i in 0..8 {
+        agent.process_direct(&format!("question {}", i), "s").await;
+    }
+
+    assert_eq!(*provider.summaries.lock().unwrap(), 0);
+}
@@ -1,5 +1,6 @@
 //! Tests for context builder
 
+use opensam_agent::context::{quote_reply, MAX_QUOTE_CHARS};
 use opensam_agent::ContextBuilder;
 use opensam_provider::Message;
 use std::fs;
@@ -44,6 +45,49 @@ async fn test_context_builder_with_directive_md() {
     assert!(prompt.contains("Test Directive"));
 }
 
+#[tokio::test]
+async fn test_context_builder_substitutes_name() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("PERSONA.md"),
+        "# Persona\n\nName: {{name}}\nI am {{name}}.",
+    )
+    .unwrap();
+
+    let builder = ContextBuilder::new(temp_dir.path()).with_name("Otacon");
+    let prompt = builder.build_system_prompt().await;
+
+    assert!(prompt.contains("Name: Otacon\nI am Otacon."));
+    assert!(!prompt.contains("{{name}}"));
+}
+
+#[tokio::test]
+async fn test_context_builder_default_name() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("PERSONA.md"), "Name: {{name}}").unwrap();
+
+    let builder = ContextBuilder::new(temp_dir.path());
+    let prompt = builder.build_system_prompt().await;
+
+    assert!(prompt.contains("Name: OpenSAM"));
+}
+
+#[tokio::test]
+async fn test_context_builder_without_placeholder_unchanged() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("DIRECTIVE.md"),
+        "# Directive\n\nName: Snake",
+    )
+    .unwrap();
+
+    let builder = ContextBuilder::new(temp_dir.path()).with_name("Otacon");
+    let prompt = builder.build_system_prompt().await;
+
+    assert!(prompt.contains("# Directive\n\nName: Snake"));
+    assert!(!prompt.contains("Otacon"));
+}
+
 #[tokio::test]
 async fn test_context_builder_with_memory() {
     let temp_dir = TempDir::new().unwrap();
@@ -171,3 +215,50 @@ fn test_context_builder_add_assistant_message_empty_content() {
     assert_eq!(messages[1].role, "assistant");
     assert_eq!(messages[1].content.as_deref(), Some(""));
 }
+
+#[test]
+fn test_quote_reply_includes_sender_and_quote() {
+    let quoted = quote_reply("Yes, do it", "Deploy on Friday?\nOr Monday?", Some("Ops"));
+
+    assert_eq!(
+        quoted,
+        "[Replying to Ops]\n> Deploy on Friday?\n> Or Monday?\n\nYes, do it"
+    );
+}
+
+#[test]
+fn test_quote_reply_truncates_long_text() {
+    let long = "x".repeat(MAX_QUOTE_CHARS + 100);
+    let quoted = quote_reply("ok", &long, None);
+
+    assert!(quoted.starts_with("[Replying to]\n> "));
+    assert!(quoted.contains('…'));
+    assert!(quoted.len() < long.len());
+    assert!(quoted.ends_with("\n\nok"));
+}
+
+#[tokio::test]
+async fn test_context_builder_appends_session_suffix() {
+    let temp_dir = TempDir::new().unwrap();
+    let builder = ContextBuilder::new(temp_dir.path());
+
+    let plain = builder.build_system_prompt().await;
+    let with_suffix = builder
+        .build_system_prompt_with_suffix(Some("Sprint goal: ship v2"))
+        .await;
+    assert!(!plain.contains("Sprint goal"));
+    assert!(with_suffix.ends_with("---\n\nSprint goal: ship v2"));
+
+    let blank = builder.build_system_prompt_with_suffix(Some("  ")).await;
+    assert!(!blank.ends_with("---\n\n"));
+
+    let messages = builder
+        .build_messages_with_suffix(vec![], "Hello", Some("Be brief"))
+        .await;
+    assert!(messages[0]
+        .content
+        .as_deref()
+        .unwrap()
+        .ends_with("Be brief"));
+    assert_eq!(messages.len(), 2);
+}
@@ -0,0 +1,132 @@
+//! Tests for user-facing error messages
+
+use async_trait::async_trait;
+use mockall::mock;
+use opensam_agent::{AgentError, AgentLoop, ErrorMessages};
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+mock! {
+    pub Provider {}
+
+    #[async_trait]
+    impl Provider for Provider {
+        async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError>;
+        fn default_model(&self) -> String;
+        fn is_configured(&self) -> bool;
+    }
+}
+
+fn json_error() -> serde_json::Error {
+    serde_json::from_str::<serde_json::Value>("{").unwrap_err()
+}
+
+#[test]
+fn test_each_provider_error_maps_to_friendly_text() {
+    let messages = ErrorMessages::default();
+
+    let cases = vec![
+        (
+            ProviderError::RateLimited,
+            "I'm getting throttled, try again shortly.",
+        ),
+        (
+            ProviderError::NoApiKey,
+            "I'm not configured yet: no API key has been set up.",
+        ),
+        (
+            ProviderError::Api("quota exceeded".to_string()),
+            "My language model rejected that request. Please try again later.",
+        ),
+        (
+            ProviderError::InvalidResponse("no choices".to_string()),
+            "I got a garbled answer from my language model. Please try again.",
+        ),
+        (
+            ProviderError::Json(json_error()),
+            "I got a garbled answer from my language model. Please try again.",
+        ),
+    ];
+
+    for (err, expected) in cases {
+        let rendered = messages.render(&AgentError::Provider(err));
+        assert_eq!(rendered, expected);
+        assert!(!rendered.contains("NODE REJECTED"));
+    }
+}
+
+#[test]
+fn test_agent_errors_map_to_friendly_text() {
+    let messages = ErrorMessages::default();
+
+    assert_eq!(
+        messages.render(&AgentError::MaxIterations),
+        "That took more steps than I'm allowed. Try breaking it into smaller pieces."
+    );
+    assert_eq!(
+        messages.render(&AgentError::ToolNotFound("x".to_string())),
+        "Something went wrong on my end. Please try again."
+    );
+}
+
+#[test]
+fn test_override_replaces_default() {
+    let mut overrides = HashMap::new();
+    overrides.insert("rate_limited".to_string(), "Slow down, please.".to_string());
+    let messages = ErrorMessages::new(overrides);
+
+    assert_eq!(
+        messages.render(&AgentError::Provider(ProviderError::RateLimited)),
+        "Slow down, please."
+    );
+    // Kinds without an override keep their default
+    assert_eq!(
+        messages.render(&AgentError::Provider(ProviderError::NoApiKey)),
+        ErrorMessages::default_for("no_api_key")
+    );
+}
+
+#[test]
+fn test_overrides_from_config() {
+    let config: opensam_config::Config = serde_json::from_str(
+        r#"{"operative": {"defaults": {"error_messages": {"api": "Upstream said no."}}}}"#,
+    )
+    .unwrap();
+    let messages = ErrorMessages::from_config(&config);
+
+    assert_eq!(
+        messages.render(&AgentError::Provider(ProviderError::Api("x".to_string()))),
+        "Upstream said no."
+    );
+}
+
+#[tokio::test]
+async fn test_agent_loop_sends_friendly_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let mut mock = MockProvider::new();
+    mock.expect_chat()
+        .times(1)
+        .returning(|_| Err(ProviderError::RateLimited));
+
+    let agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        mock,
+        PathBuf::from("."),
+        "test-model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    );
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hello");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(
+        response.content,
+        "I'm getting throttled, try again shortly."
+    );
+}
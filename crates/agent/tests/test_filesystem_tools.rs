@@ -405,3 +405,129 @@ fn test_filesystem_tool_metadata() {
         "Reconnaissance: List contents of data directory."
     );
 }
+
+#[tokio::test]
+async fn test_read_file_tool_invalid_utf8_is_lossy() {
+    let workspace = TempDir::new().unwrap();
+    let test_file = workspace.path().join("latin1.txt");
+    fs::write(&test_file, b"caf\xe9 au lait").unwrap();
+
+    let tool = ReadFileTool::new(workspace.path().to_path_buf());
+    let result = tool.execute(json!({"path": "latin1.txt"})).await.unwrap();
+
+    assert!(result.starts_with("caf\u{FFFD} au lait"));
+    assert!(result.contains("INVALID UTF-8 REPLACED"));
+}
+
+#[tokio::test]
+async fn test_read_file_tool_binary_is_summarized() {
+    let workspace = TempDir::new().unwrap();
+    let test_file = workspace.path().join("image.bin");
+    let bytes: Vec<u8> = vec![0x89, b'P', b'N', b'G', 0x00, 0x00, 0x00, 0x0d, 0xff, 0x00];
+    fs::write(&test_file, &bytes).unwrap();
+
+    let tool = ReadFileTool::new(workspace.path().to_path_buf());
+    let result = tool.execute(json!({"path": "image.bin"})).await.unwrap();
+
+    assert_eq!(result, "◆ BINARY FILE: 10 BYTES, NOT SHOWN");
+}
+
+#[tokio::test]
+async fn test_read_file_tool_valid_utf8_unchanged() {
+    let workspace = TempDir::new().unwrap();
+    fs::write(workspace.path().join("plain.txt"), "héllo wörld\n").unwrap();
+
+    let tool = ReadFileTool::new(workspace.path().to_path_buf());
+    let result = tool.execute(json!({"path": "plain.txt"})).await.unwrap();
+
+    assert_eq!(result, "héllo wörld\n");
+}
+
+fn numbered_file(dir: &TempDir, lines: usize) -> String {
+    let content: Vec<String> = (1..=lines).map(|i| format!("line {}", i)).collect();
+    fs::write(dir.path().join("big.txt"), content.join("\n")).unwrap();
+    "big.txt".to_string()
+}
+
+#[tokio::test]
+async fn test_read_file_line_range_returns_slice_with_header() {
+    let dir = TempDir::new().unwrap();
+    let path = numbered_file(&dir, 10);
+    let tool = ReadFileTool::new(dir.path().to_path_buf());
+
+    let result = tool
+        .execute(json!({"path": path, "start_line": 3, "end_line": 5}))
+        .await
+        .unwrap();
+
+    assert_eq!(result, "◆ LINES 3-5 OF 10\nline 3\nline 4\nline 5");
+}
+
+#[tokio::test]
+async fn test_read_file_line_range_clamps() {
+    let dir = TempDir::new().unwrap();
+    let path = numbered_file(&dir, 4);
+    let tool = ReadFileTool::new(dir.path().to_path_buf());
+
+    let result = tool
+        .execute(json!({"path": path, "start_line": 3, "end_line": 99}))
+        .await
+        .unwrap();
+    assert_eq!(result, "◆ LINES 3-4 OF 4\nline 3\nline 4");
+
+    let result = tool
+        .execute(json!({"path": path, "start_line": 0, "end_line": 1}))
+        .await
+        .unwrap();
+    assert_eq!(result, "◆ LINES 1-1 OF 4\nline 1");
+
+    let result = tool
+        .execute(json!({"path": path, "start_line": 20}))
+        .await
+        .unwrap();
+    assert!(result.contains("NO LINES IN RANGE"));
+    assert!(result.contains("FILE HAS 4 LINES"));
+}
+
+#[tokio::test]
+async fn test_read_file_without_range_returns_whole_file() {
+    let dir = TempDir::new().unwrap();
+    let path = numbered_file(&dir, 3);
+    let tool = ReadFileTool::new(dir.path().to_path_buf());
+
+    let result = tool.execute(json!({"path": path})).await.unwrap();
+
+    assert_eq!(result, "line 1\nline 2\nline 3");
+}
+
+#[tokio::test]
+async fn test_read_file_max_bytes_truncates() {
+    let dir = TempDir::new().unwrap();
+    let path = numbered_file(&dir, 3);
+    let tool = ReadFileTool::new(dir.path().to_path_buf());
+
+    let result = tool
+        .execute(json!({"path": path, "max_bytes": 6}))
+        .await
+        .unwrap();
+
+    assert!(result.starts_with("line 1\n◆ TRUNCATED AT 6 OF 20 BYTES"));
+}
+
+#[tokio::test]
+async fn test_read_file_range_stays_in_workspace() {
+    let dir = TempDir::new().unwrap();
+    let outside = TempDir::new().unwrap();
+    fs::write(outside.path().join("secret.txt"), "a\nb").unwrap();
+    let tool = ReadFileTool::new(dir.path().to_path_buf());
+
+    let result = tool
+        .execute(json!({
+            "path": outside.path().join("secret.txt").to_str().unwrap(),
+            "start_line": 1,
+            "end_line": 1
+        }))
+        .await;
+
+    assert!(result.is_err());
+}
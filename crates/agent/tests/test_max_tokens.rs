@@ -0,0 +1,106 @@
+//! Tests for fitting max_tokens into the model's context window
+
+use async_trait::async_trait;
+use mockall::mock;
+use opensam_agent::{AgentLoop, ContextLengths, MaxTokensPolicy};
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+mock! {
+    pub Provider {}
+
+    #[async_trait]
+    impl Provider for Provider {
+        async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError>;
+        fn default_model(&self) -> String;
+        fn is_configured(&self) -> bool;
+    }
+}
+
+/// Capability source that knows a single model
+struct StubLengths(&'static str, usize);
+
+impl ContextLengths for StubLengths {
+    fn context_length(&self, model: &str) -> Option<usize> {
+        (model == self.0).then_some(self.1)
+    }
+}
+
+#[test]
+fn test_large_max_tokens_clamped_for_small_context() {
+    let policy = MaxTokensPolicy::new(8192, 256).with_context_lengths(StubLengths("tiny", 4096));
+    assert_eq!(policy.max_tokens_for("tiny", 1000), 3096);
+}
+
+#[test]
+fn test_max_tokens_kept_when_it_fits() {
+    let policy =
+        MaxTokensPolicy::new(8192, 256).with_context_lengths(StubLengths("large", 200_000));
+    assert_eq!(policy.max_tokens_for("large", 1000), 8192);
+}
+
+#[test]
+fn test_max_tokens_kept_for_unknown_model() {
+    let policy = MaxTokensPolicy::new(8192, 256).with_context_lengths(StubLengths("tiny", 4096));
+    assert_eq!(policy.max_tokens_for("other", 100_000), 8192);
+}
+
+#[test]
+fn test_clamp_respects_floor() {
+    let policy = MaxTokensPolicy::new(8192, 256).with_context_lengths(StubLengths("tiny", 4096));
+    assert_eq!(policy.max_tokens_for("tiny", 5000), 256);
+
+    // The floor never raises the budget above what was configured
+    let policy = MaxTokensPolicy::new(100, 256).with_context_lengths(StubLengths("tiny", 4096));
+    assert_eq!(policy.max_tokens_for("tiny", 5000), 100);
+}
+
+#[test]
+fn test_policy_from_config() {
+    let mut config = opensam_config::Config::default();
+    config.operative.defaults.max_tokens = 4000;
+    config.operative.defaults.min_max_tokens = 50;
+    config.operative.defaults.model_context_lengths =
+        HashMap::from([("small/model".to_string(), 1000)]);
+
+    let policy = MaxTokensPolicy::from_config(&config);
+    assert_eq!(policy.max_tokens_for("small/model", 100), 900);
+    assert_eq!(policy.max_tokens_for("other/model", 100), 4000);
+}
+
+#[tokio::test]
+async fn test_agent_sends_clamped_max_tokens() {
+    let workspace = TempDir::new().unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let mut mock = MockProvider::new();
+    let recorded = Arc::clone(&seen);
+    mock.expect_chat().returning(move |params| {
+        recorded.lock().unwrap().push(params.max_tokens);
+        Ok(ChatResponse::text("done"))
+    });
+
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let mut agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        mock,
+        workspace.path().to_path_buf(),
+        "tiny".to_string(),
+        10,
+        None,
+        workspace.path().join("sessions"),
+    );
+    agent.set_max_tokens_policy(
+        MaxTokensPolicy::new(1_000_000, 256).with_context_lengths(StubLengths("tiny", 100_000)),
+    );
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Status report");
+    agent.process_message(msg).await.unwrap();
+
+    let sent = seen.lock().unwrap()[0];
+    assert!(sent < 100_000, "{} was not clamped", sent);
+    assert!(sent > 90_000, "{} clamped too far", sent);
+}
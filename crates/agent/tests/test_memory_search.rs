@@ -0,0 +1,153 @@
+//! Tests for the memory search tool
+
+use async_trait::async_trait;
+use opensam_agent::tools::memory_search::chunk_markdown;
+use opensam_agent::tools::{MemorySearchTool, ToolTrait};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use serde_json::json;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Topic words; a text embeds as its counts of each
+const TOPICS: [&str; 4] = ["coffee", "dog", "python", "birthday"];
+
+/// Embeds by keyword counts and records how many texts it embedded
+#[derive(Default)]
+struct StubEmbedder {
+    embedded: AtomicUsize,
+}
+
+#[async_trait]
+impl Provider for StubEmbedder {
+    async fn chat(&self, _params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        Ok(ChatResponse::text("stub"))
+    }
+
+    fn default_model(&self) -> String {
+        "stub/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        self.embedded.fetch_add(texts.len(), Ordering::SeqCst);
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let text = text.to_lowercase();
+                TOPICS
+                    .iter()
+                    .map(|topic| text.matches(topic).count() as f32)
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+const MEMORY: &str = "# Long-term Memory\n\n## Facts\n\n\
+- [2026-01-02 10:00] Has a dog named Biscuit\n\
+- [2026-01-03 11:00] Drinks coffee black, no sugar\n\
+- [2026-01-04 12:00] Writes python at work\n";
+
+fn setup(memory: &str) -> (TempDir, Arc<StubEmbedder>, MemorySearchTool) {
+    let workspace = TempDir::new().unwrap();
+    let lifepod = workspace.path().join("lifepod");
+    fs::create_dir_all(&lifepod).unwrap();
+    fs::write(lifepod.join("MEMORY.md"), memory).unwrap();
+
+    let embedder = Arc::new(StubEmbedder::default());
+    let tool = MemorySearchTool::new(workspace.path().to_path_buf(), embedder.clone());
+    (workspace, embedder, tool)
+}
+
+#[tokio::test]
+async fn test_memory_search_ranks_relevant_chunk_first() {
+    let (_workspace, _embedder, tool) = setup(MEMORY);
+
+    let result = tool
+        .execute(json!({"query": "How does the user take their coffee?", "top_k": 1}))
+        .await
+        .unwrap();
+
+    assert!(result.starts_with("1. [1.00] lifepod/MEMORY.md:"));
+    assert!(result.contains("Drinks coffee black"));
+    assert_eq!(result.lines().count(), 1);
+}
+
+#[tokio::test]
+async fn test_memory_search_reuses_cache_for_unchanged_file() {
+    let (_workspace, embedder, tool) = setup(MEMORY);
+
+    tool.execute(json!({"query": "dog"})).await.unwrap();
+    // Three chunks plus the query
+    assert_eq!(embedder.embedded.load(Ordering::SeqCst), 4);
+
+    tool.execute(json!({"query": "python"})).await.unwrap();
+    // Only the new query is embedded
+    assert_eq!(embedder.embedded.load(Ordering::SeqCst), 5);
+}
+
+#[tokio::test]
+async fn test_memory_search_reembeds_changed_file() {
+    let (workspace, embedder, tool) = setup(MEMORY);
+    tool.execute(json!({"query": "dog"})).await.unwrap();
+
+    let path = workspace.path().join("lifepod").join("MEMORY.md");
+    fs::write(&path, format!("{}- Birthday is in May\n", MEMORY)).unwrap();
+    // Make sure the mtime moves even on coarse-grained filesystems
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(5);
+    fs::File::options()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+
+    let result = tool.execute(json!({"query": "birthday"})).await.unwrap();
+    assert!(result.starts_with("1. [1.00]"));
+    assert!(result.contains("Birthday is in May"));
+    assert_eq!(embedder.embedded.load(Ordering::SeqCst), 4 + 4 + 1);
+}
+
+#[tokio::test]
+async fn test_memory_search_missing_memory() {
+    let workspace = TempDir::new().unwrap();
+    let tool = MemorySearchTool::new(
+        workspace.path().to_path_buf(),
+        Arc::new(StubEmbedder::default()),
+    );
+
+    let result = tool.execute(json!({"query": "anything"})).await.unwrap();
+    assert_eq!(result, "◆ MEMORY IS EMPTY");
+}
+
+#[tokio::test]
+async fn test_memory_search_includes_extra_files() {
+    let (workspace, _embedder, tool) = setup(MEMORY);
+    fs::write(
+        workspace.path().join("NOTES.md"),
+        "Birthday party planned for Saturday\n",
+    )
+    .unwrap();
+    let tool = tool.with_files(["NOTES.md".to_string()]);
+
+    let result = tool
+        .execute(json!({"query": "birthday", "top_k": 1}))
+        .await
+        .unwrap();
+    assert!(result.contains("NOTES.md: Birthday party"));
+}
+
+#[test]
+fn test_chunk_markdown_splits_items_and_paragraphs() {
+    let chunks =
+        chunk_markdown("# Title\n\nFirst line\nsame paragraph\n\n- one\n- two\n  more\n## Next\n");
+    assert_eq!(
+        chunks,
+        vec!["First line same paragraph", "- one", "- two more"]
+    );
+}
@@ -0,0 +1,95 @@
+//! Tests for the memory tool
+
+use opensam_agent::tools::memory::append_to_section;
+use opensam_agent::tools::{MemoryTool, ToolTrait};
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+fn memory_file(workspace: &TempDir) -> String {
+    fs::read_to_string(workspace.path().join("lifepod").join("MEMORY.md")).unwrap()
+}
+
+#[tokio::test]
+async fn test_memory_tool_creates_file_section_and_entry() {
+    let workspace = TempDir::new().unwrap();
+    let tool = MemoryTool::new(workspace.path().to_path_buf());
+
+    let result = tool
+        .execute(json!({"entry": "Prefers metric units", "section": "Preferences"}))
+        .await
+        .unwrap();
+    assert!(result.contains("MEMORY RECORDED"));
+
+    let content = memory_file(&workspace);
+    assert!(content.starts_with("# Long-term Memory\n"));
+    assert!(content.contains("## Preferences\n\n- ["));
+    assert!(content.contains("] Prefers metric units\n"));
+}
+
+#[tokio::test]
+async fn test_memory_tool_repeated_appends_accumulate() {
+    let workspace = TempDir::new().unwrap();
+    let tool = MemoryTool::new(workspace.path().to_path_buf());
+
+    for entry in ["First fact", "Second fact"] {
+        tool.execute(json!({"entry": entry, "section": "Facts"}))
+            .await
+            .unwrap();
+    }
+    tool.execute(json!({"entry": "Loose note"})).await.unwrap();
+
+    let content = memory_file(&workspace);
+    assert_eq!(content.matches("## Facts").count(), 1);
+    let first = content.find("First fact").unwrap();
+    let second = content.find("Second fact").unwrap();
+    let notes = content.find("## Notes").unwrap();
+    assert!(first < second && second < notes);
+    assert!(content.contains("Loose note"));
+}
+
+#[tokio::test]
+async fn test_memory_tool_appends_into_existing_template() {
+    let workspace = TempDir::new().unwrap();
+    fs::create_dir(workspace.path().join("lifepod")).unwrap();
+    fs::write(
+        workspace.path().join("lifepod").join("MEMORY.md"),
+        "# Long-term Memory\n\n## Facts\n\n- old fact\n\n## Notes\n\n(notes)\n",
+    )
+    .unwrap();
+
+    let tool = MemoryTool::new(workspace.path().to_path_buf());
+    tool.execute(json!({"entry": "new fact", "section": "facts"}))
+        .await
+        .unwrap();
+
+    let content = memory_file(&workspace);
+    let new_fact = content.find("new fact").unwrap();
+    assert!(content.find("old fact").unwrap() < new_fact);
+    assert!(new_fact < content.find("## Notes").unwrap());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_memory_tool_confined_to_workspace() {
+    let workspace = TempDir::new().unwrap();
+    let outside = TempDir::new().unwrap();
+    std::os::unix::fs::symlink(outside.path(), workspace.path().join("lifepod")).unwrap();
+
+    let tool = MemoryTool::new(workspace.path().to_path_buf());
+    let result = tool.execute(json!({"entry": "secret"})).await;
+
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("outside workspace"));
+    assert!(!outside.path().join("MEMORY.md").exists());
+}
+
+#[test]
+fn test_append_to_section_empty_section() {
+    let content = "# Memory\n\n## Facts\n\n## Notes\n";
+    let updated = append_to_section(content, "Facts", "- entry");
+    assert_eq!(updated, "# Memory\n\n## Facts\n\n- entry\n\n## Notes\n");
+}
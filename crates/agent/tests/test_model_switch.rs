@@ -0,0 +1,121 @@
+//! Tests for model switch notices
+
+use async_trait::async_trait;
+use opensam_agent::events::is_model_switch;
+use opensam_agent::{AgentEvent, AgentLoop};
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// Provider whose primary model is always rate limited, falling back to a
+/// backup model
+struct RateLimitedPrimary {
+    primary_limited: bool,
+}
+
+impl RateLimitedPrimary {
+    async fn call(&self, model: &str) -> Result<ChatResponse, ProviderError> {
+        if self.primary_limited && model == "primary/model" {
+            return Err(ProviderError::RateLimited);
+        }
+        Ok(ChatResponse::text(format!("answered by {}", model)).with_model(model))
+    }
+}
+
+#[async_trait]
+impl Provider for RateLimitedPrimary {
+    async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        match self.call(&params.model).await {
+            Err(ProviderError::RateLimited) => self.call("backup/model").await,
+            other => other,
+        }
+    }
+
+    fn default_model(&self) -> String {
+        "primary/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+fn agent(temp_dir: &TempDir, primary_limited: bool) -> AgentLoop<RateLimitedPrimary> {
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    AgentLoop::new_with_sessions_dir(
+        bus,
+        RateLimitedPrimary { primary_limited },
+        PathBuf::from("."),
+        "primary/model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    )
+}
+
+#[tokio::test]
+async fn test_fallback_emits_switched_event() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut agent = agent(&temp_dir, true);
+    let mut events = agent.subscribe_events();
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hello");
+    let response = agent.process_message(msg).await.unwrap();
+    assert_eq!(response.content, "answered by backup/model");
+
+    let event = events.try_recv().expect("switch event");
+    assert_eq!(
+        event,
+        AgentEvent::ModelSwitched {
+            session_key: "telegram:chat1".to_string(),
+            requested: "primary/model".to_string(),
+            actual: "backup/model".to_string(),
+        }
+    );
+    assert_eq!(event.notice(), "switched to backup/model");
+    assert!(events.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_no_event_when_primary_succeeds() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut agent = agent(&temp_dir, false);
+    let mut events = agent.subscribe_events();
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hello");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(response.content, "answered by primary/model");
+    assert!(events.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_announced_switch_prepends_notice() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut agent = agent(&temp_dir, true);
+    agent.set_announce_model_switch(true);
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hello");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(
+        response.content,
+        "(switched to backup/model)\n\nanswered by backup/model"
+    );
+}
+
+#[test]
+fn test_is_model_switch_ignores_prefix_and_versions() {
+    assert!(!is_model_switch(
+        "openrouter/openai/gpt-4o",
+        "openai/gpt-4o"
+    ));
+    assert!(!is_model_switch(
+        "openai/gpt-4o",
+        "openai/gpt-4o-2024-08-06"
+    ));
+    assert!(is_model_switch("openai/gpt-4o", "anthropic/claude-3"));
+    assert!(is_model_switch("openai/gpt-4o", "openai/gpt-4o-mini"));
+    assert!(!is_model_switch("", "anthropic/claude-3"));
+}
@@ -0,0 +1,110 @@
+//! Tests for trimming long replies
+
+use async_trait::async_trait;
+use opensam_agent::output::{trim_output, TRUNCATION_MARKER};
+use opensam_agent::AgentLoop;
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use opensam_session::SessionManager;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+struct FixedReply(String);
+
+#[async_trait]
+impl Provider for FixedReply {
+    async fn chat(&self, _params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        Ok(ChatResponse::text(self.0.clone()))
+    }
+
+    fn default_model(&self) -> String {
+        "test/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_trim_prefers_sentence_boundary() {
+    let text = "First sentence here. Second sentence is much longer and goes on.";
+    let trimmed = trim_output(text, 30);
+
+    assert_eq!(
+        trimmed,
+        format!("First sentence here. {}", TRUNCATION_MARKER)
+    );
+}
+
+#[test]
+fn test_trim_falls_back_to_word_boundary() {
+    let text = "one two three four five six seven eight nine ten";
+    let trimmed = trim_output(text, 20);
+
+    assert_eq!(trimmed, format!("one two three four {}", TRUNCATION_MARKER));
+}
+
+#[test]
+fn test_trim_leaves_short_text_unchanged() {
+    assert_eq!(trim_output("Short answer.", 100), "Short answer.");
+}
+
+#[test]
+fn test_trim_handles_multibyte_text() {
+    let text = "こんにちは世界".repeat(10);
+    let trimmed = trim_output(&text, 5);
+
+    assert_eq!(trimmed, format!("こんにちは {}", TRUNCATION_MARKER));
+}
+
+#[tokio::test]
+async fn test_agent_trims_output_but_stores_full_reply() {
+    let temp_dir = TempDir::new().unwrap();
+    let sessions_dir = temp_dir.path().join("sessions");
+    let reply = "This is the short part. And this is a long tail that should be cut.";
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let mut agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        FixedReply(reply.to_string()),
+        PathBuf::from("."),
+        "test/model".to_string(),
+        5,
+        None,
+        sessions_dir.clone(),
+    );
+    agent.set_max_output_chars(Some(30));
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hi");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(
+        response.content,
+        format!("This is the short part. {}", TRUNCATION_MARKER)
+    );
+
+    let mut manager = SessionManager::new(&sessions_dir);
+    let session = manager.get_or_create("telegram:chat1").await;
+    assert_eq!(session.messages[1].content, reply);
+}
+
+#[tokio::test]
+async fn test_agent_under_limit_unchanged() {
+    let temp_dir = TempDir::new().unwrap();
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let mut agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        FixedReply("Brief.".to_string()),
+        PathBuf::from("."),
+        "test/model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    );
+    agent.set_max_output_chars(Some(30));
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hi");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(response.content, "Brief.");
+}
@@ -0,0 +1,81 @@
+//! Tests for adapting requests to provider capabilities
+
+use async_trait::async_trait;
+use opensam_agent::AgentLoop;
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_provider::{
+    ChatParams, ChatResponse, Provider, ProviderCapabilities, ProviderError, ToolChoice,
+};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+/// Provider recording how many tools each request carried and its tool choice
+struct RecordingProvider {
+    tools: bool,
+    seen: Arc<Mutex<Vec<(usize, ToolChoice)>>>,
+}
+
+#[async_trait]
+impl Provider for RecordingProvider {
+    async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        self.seen
+            .lock()
+            .unwrap()
+            .push((params.tools.len(), params.tool_choice));
+        Ok(ChatResponse::text("ok"))
+    }
+
+    fn default_model(&self) -> String {
+        "test/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            tools: self.tools,
+            ..Default::default()
+        }
+    }
+}
+
+async fn request_sent(tools: bool) -> (usize, ToolChoice) {
+    let temp_dir = TempDir::new().unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        RecordingProvider {
+            tools,
+            seen: Arc::clone(&seen),
+        },
+        PathBuf::from("."),
+        "test/model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    );
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hello");
+    agent.process_message(msg).await.unwrap();
+
+    let seen = seen.lock().unwrap();
+    seen[0].clone()
+}
+
+#[tokio::test]
+async fn test_tools_sent_when_supported() {
+    let (tools, choice) = request_sent(true).await;
+    assert!(tools > 0);
+    assert!(matches!(choice, ToolChoice::Auto));
+}
+
+#[tokio::test]
+async fn test_tools_omitted_when_unsupported() {
+    let (tools, choice) = request_sent(false).await;
+    assert_eq!(tools, 0);
+    assert!(matches!(choice, ToolChoice::None));
+}
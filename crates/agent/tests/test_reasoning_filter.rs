@@ -0,0 +1,129 @@
+//! Tests for reasoning suppression
+
+use async_trait::async_trait;
+use opensam_agent::{AgentLoop, ReasoningFilter};
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_config::Config;
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+struct FixedReply(String);
+
+#[async_trait]
+impl Provider for FixedReply {
+    async fn chat(&self, _params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        Ok(ChatResponse::text(self.0.clone()))
+    }
+
+    fn default_model(&self) -> String {
+        "test/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+fn agent(temp_dir: &TempDir, reply: &str) -> AgentLoop<FixedReply> {
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    AgentLoop::new_with_sessions_dir(
+        bus,
+        FixedReply(reply.to_string()),
+        PathBuf::from("."),
+        "test/model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    )
+}
+
+#[test]
+fn test_strip_removes_tagged_reasoning() {
+    let filter = ReasoningFilter::default();
+    let (visible, reasoning) = filter.strip("<think>count the r's</think>There are 3.");
+
+    assert_eq!(visible, "There are 3.");
+    assert_eq!(reasoning, vec!["count the r's".to_string()]);
+}
+
+#[test]
+fn test_strip_passes_untagged_content() {
+    let filter = ReasoningFilter::default();
+    let (visible, reasoning) = filter.strip("Just an answer with a < sign.");
+
+    assert_eq!(visible, "Just an answer with a < sign.");
+    assert!(reasoning.is_empty());
+}
+
+#[test]
+fn test_strip_nested_tags_removed_whole() {
+    let filter = ReasoningFilter::default();
+    let (visible, reasoning) = filter.strip("A <think>outer <think>inner</think> tail</think>B");
+
+    assert_eq!(visible, "A B");
+    assert_eq!(
+        reasoning,
+        vec!["outer <think>inner</think> tail".to_string()]
+    );
+}
+
+#[test]
+fn test_strip_unclosed_tag_hides_rest() {
+    let filter = ReasoningFilter::default();
+    let (visible, reasoning) = filter.strip("Answer first. <think>never closed");
+
+    assert_eq!(visible, "Answer first.");
+    assert_eq!(reasoning, vec!["never closed".to_string()]);
+}
+
+#[test]
+fn test_strip_stray_close_is_dropped() {
+    let filter = ReasoningFilter::default();
+    let (visible, _) = filter.strip("Hello</think> world");
+
+    assert_eq!(visible, "Hello world");
+}
+
+#[test]
+fn test_custom_markers() {
+    let filter = ReasoningFilter::new("[[", "]]");
+    let (visible, _) = filter.strip("[[hidden]]shown");
+
+    assert_eq!(visible, "shown");
+}
+
+#[test]
+fn test_from_config_disabled_by_default() {
+    let mut config = Config::default();
+    assert!(ReasoningFilter::from_config(&config).is_none());
+
+    config.operative.defaults.strip_reasoning = true;
+    assert_eq!(
+        ReasoningFilter::from_config(&config),
+        Some(ReasoningFilter::default())
+    );
+}
+
+#[tokio::test]
+async fn test_agent_strips_reasoning_from_reply() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut agent = agent(&temp_dir, "<think>the user said hi</think>Hello!");
+    agent.set_reasoning_filter(Some(ReasoningFilter::default()));
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hi");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(response.content, "Hello!");
+}
+
+#[tokio::test]
+async fn test_agent_without_filter_keeps_content() {
+    let temp_dir = TempDir::new().unwrap();
+    let agent = agent(&temp_dir, "<think>visible</think>Hello!");
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hi");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(response.content, "<think>visible</think>Hello!");
+}
@@ -0,0 +1,92 @@
+//! Tests for sending top_p, penalties and stop sequences
+
+use async_trait::async_trait;
+use opensam_agent::{AgentLoop, Sampling};
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_config::Config;
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+/// Answers once and keeps the params it was sent
+struct CapturingProvider {
+    sent: Arc<Mutex<Vec<ChatParams>>>,
+}
+
+#[async_trait]
+impl Provider for CapturingProvider {
+    async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        self.sent.lock().unwrap().push(params);
+        Ok(ChatResponse::text("Done."))
+    }
+
+    fn default_model(&self) -> String {
+        "test/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+async fn sent_params(sampling: Option<Sampling>) -> ChatParams {
+    let temp_dir = TempDir::new().unwrap();
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let mut agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        CapturingProvider { sent: sent.clone() },
+        temp_dir.path().to_path_buf(),
+        "test/model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    );
+    if let Some(sampling) = sampling {
+        agent.set_sampling(sampling);
+    }
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Hi");
+    agent.process_message(msg).await.unwrap();
+    let params = sent.lock().unwrap().remove(0);
+    params
+}
+
+#[tokio::test]
+async fn test_sampling_unset_by_default() {
+    let params = sent_params(None).await;
+    assert_eq!(params.top_p, None);
+    assert_eq!(params.frequency_penalty, None);
+    assert_eq!(params.presence_penalty, None);
+    assert!(params.stop.is_empty());
+}
+
+#[tokio::test]
+async fn test_sampling_sent_with_requests() {
+    let params = sent_params(Some(Sampling {
+        top_p: Some(0.8),
+        frequency_penalty: Some(0.3),
+        presence_penalty: Some(-0.4),
+        stop: vec!["END".to_string()],
+    }))
+    .await;
+
+    assert_eq!(params.top_p, Some(0.8));
+    assert_eq!(params.frequency_penalty, Some(0.3));
+    assert_eq!(params.presence_penalty, Some(-0.4));
+    assert_eq!(params.stop, vec!["END".to_string()]);
+}
+
+#[test]
+fn test_sampling_from_config() {
+    let config: Config = serde_json::from_str(
+        r#"{"operative": {"defaults": {"top_p": 0.9, "presence_penalty": 1.0, "stop": ["END"]}}}"#,
+    )
+    .unwrap();
+    let sampling = Sampling::from_config(&config);
+
+    assert_eq!(sampling.top_p, Some(0.9));
+    assert_eq!(sampling.frequency_penalty, None);
+    assert_eq!(sampling.presence_penalty, Some(1.0));
+    assert_eq!(sampling.stop, vec!["END".to_string()]);
+}
@@ -352,9 +352,12 @@ async fn test_session_error_still_saves() {
     );
     let response: Option<opensam_bus::OutboundMessage> = agent.process_message(msg).await;
 
-    // Should still return a response with the error
+    // Should still return a friendly response for the error
     assert!(response.is_some());
-    assert!(response.unwrap().content.contains("Error"));
+    assert_eq!(
+        response.unwrap().content,
+        opensam_agent::ErrorMessages::default_for("api")
+    );
 
     // Verify session was still saved
     let session_file = sessions_dir.join("telegram_error_chat.json");
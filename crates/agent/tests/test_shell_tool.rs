@@ -251,3 +251,24 @@ async fn test_exec_tool_nested_escape_attempt() {
         err
     );
 }
+
+#[tokio::test]
+async fn test_exec_tool_invalid_utf8_output_is_lossy() {
+    let workspace = TempDir::new().unwrap();
+    let tool = ExecTool::with_workspace(workspace.path().to_path_buf());
+    let args = json!({"command": "printf 'caf\\351\\n'"});
+
+    let result = tool.execute(args).await.unwrap();
+    assert!(result.contains("caf\u{FFFD}"), "got: {}", result);
+    assert!(result.contains("INVALID UTF-8 REPLACED"));
+}
+
+#[tokio::test]
+async fn test_exec_tool_binary_output_is_summarized() {
+    let workspace = TempDir::new().unwrap();
+    let tool = ExecTool::with_workspace(workspace.path().to_path_buf());
+    let args = json!({"command": "printf 'a\\000b\\000c'"});
+
+    let result = tool.execute(args).await.unwrap();
+    assert_eq!(result, "◆ BINARY OUTPUT: 5 BYTES, NOT SHOWN");
+}
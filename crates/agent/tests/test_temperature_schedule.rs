@@ -0,0 +1,126 @@
+//! Tests for raising temperature while the tool loop stalls
+
+use async_trait::async_trait;
+use mockall::mock;
+use opensam_agent::{AgentLoop, TemperatureSchedule};
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError, ToolCall};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+mock! {
+    pub Provider {}
+
+    #[async_trait]
+    impl Provider for Provider {
+        async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError>;
+        fn default_model(&self) -> String;
+        fn is_configured(&self) -> bool;
+    }
+}
+
+fn tool_call(name: &str, arguments: Value) -> ChatResponse {
+    ChatResponse {
+        content: None,
+        tool_calls: vec![ToolCall {
+            id: "call".to_string(),
+            name: name.to_string(),
+            arguments,
+        }],
+        finish_reason: "tool_calls".to_string(),
+        model: None,
+        usage: Default::default(),
+    }
+}
+
+/// Run one turn over scripted responses, returning the temperatures used
+async fn temperatures(script: Vec<ChatResponse>, schedule: TemperatureSchedule) -> Vec<f32> {
+    let workspace = TempDir::new().unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let script = Arc::new(Mutex::new(script.into_iter()));
+
+    let mut mock = MockProvider::new();
+    let recorded = Arc::clone(&seen);
+    mock.expect_chat().returning(move |params| {
+        recorded.lock().unwrap().push(params.temperature);
+        Ok(script
+            .lock()
+            .unwrap()
+            .next()
+            .unwrap_or_else(|| ChatResponse::text("done")))
+    });
+
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let mut agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        mock,
+        workspace.path().to_path_buf(),
+        "test-model".to_string(),
+        10,
+        None,
+        workspace.path().join("sessions"),
+    );
+    agent.set_temperature_schedule(schedule);
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Look around");
+    agent.process_message(msg).await.unwrap();
+
+    let seen = seen.lock().unwrap().clone();
+    seen
+}
+
+fn assert_temps(actual: &[f32], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[tokio::test]
+async fn test_temperature_rises_per_stall_and_resets_on_progress() {
+    let script = vec![
+        tool_call("no_such_tool", json!({})),
+        tool_call("no_such_tool", json!({})),
+        tool_call("no_such_tool", json!({"retry": true})),
+        tool_call("list_dir", json!({"path": "."})),
+    ];
+
+    let temps = temperatures(script, TemperatureSchedule::new(0.5, 0.2, 0.8)).await;
+
+    // Three stalls climb to the cap, then the successful listing resets it
+    assert_temps(&temps, &[0.5, 0.7, 0.8, 0.8, 0.5]);
+}
+
+#[tokio::test]
+async fn test_repeated_successful_calls_count_as_stall() {
+    let script = vec![
+        tool_call("list_dir", json!({"path": "."})),
+        tool_call("list_dir", json!({"path": "."})),
+    ];
+
+    let temps = temperatures(script, TemperatureSchedule::new(0.5, 0.25, 1.0)).await;
+
+    assert_temps(&temps, &[0.5, 0.5, 0.75]);
+}
+
+#[tokio::test]
+async fn test_fixed_schedule_keeps_temperature() {
+    let script = vec![
+        tool_call("no_such_tool", json!({})),
+        tool_call("no_such_tool", json!({})),
+    ];
+
+    let temps = temperatures(script, TemperatureSchedule::fixed(0.3)).await;
+
+    assert_temps(&temps, &[0.3, 0.3, 0.3]);
+}
+
+#[test]
+fn test_schedule_caps_at_max() {
+    let schedule = TemperatureSchedule::new(0.7, 0.3, 1.0);
+
+    assert!((schedule.temperature(0) - 0.7).abs() < 1e-5);
+    assert!((schedule.temperature(1) - 1.0).abs() < 1e-5);
+    assert!((schedule.temperature(5) - 1.0).abs() < 1e-5);
+}
@@ -0,0 +1,53 @@
+//! Tests for the current-time tool
+
+use chrono::{DateTime, FixedOffset, Local, TimeZone, Utc};
+use opensam_agent::tools::{TimeTool, ToolTrait};
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_time_tool_outputs_parseable_local_and_utc() {
+    let output = TimeTool::new().execute(json!({})).await.unwrap();
+    let value: Value = serde_json::from_str(&output).unwrap();
+
+    let local = DateTime::parse_from_rfc3339(value["local"].as_str().unwrap()).unwrap();
+    let utc = DateTime::parse_from_rfc3339(value["utc"].as_str().unwrap()).unwrap();
+
+    assert_eq!(local.timestamp(), utc.timestamp());
+    assert!(value["utc"].as_str().unwrap().ends_with('Z'));
+    assert_eq!(
+        value["timezone"].as_str().unwrap(),
+        local.offset().to_string()
+    );
+}
+
+#[tokio::test]
+async fn test_time_tool_epoch_is_close_to_now() {
+    let before = Utc::now().timestamp_millis();
+    let output = TimeTool::new().execute(json!({})).await.unwrap();
+    let after = Utc::now().timestamp_millis();
+
+    let value: Value = serde_json::from_str(&output).unwrap();
+    let epoch = value["epoch_ms"].as_i64().unwrap();
+
+    assert!(
+        epoch >= before && epoch <= after,
+        "{} not in {}..{}",
+        epoch,
+        before,
+        after
+    );
+}
+
+#[test]
+fn test_time_tool_render_fixed_instant() {
+    let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+    let instant = offset.with_ymd_and_hms(2026, 3, 14, 9, 30, 0).unwrap();
+    let now = instant.with_timezone(&Local);
+
+    let value = TimeTool::render(now);
+
+    assert_eq!(value["utc"], "2026-03-14T07:30:00Z");
+    assert_eq!(value["epoch_ms"], instant.timestamp_millis());
+    let local = DateTime::parse_from_rfc3339(value["local"].as_str().unwrap()).unwrap();
+    assert_eq!(local, instant);
+}
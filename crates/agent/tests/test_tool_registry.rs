@@ -1,8 +1,8 @@
 //! Tests for tool registry
 
 use opensam_agent::tools::{
-    to_provider_tool, EditFileTool, ExecTool, ListDirTool, ReadFileTool, ToolRegistry, ToolTrait,
-    WebFetchTool, WebSearchTool, WriteFileTool,
+    register_default_tools, to_provider_tool, EditFileTool, ExecTool, ListDirTool, ReadFileTool,
+    ToolDenied, ToolOutcome, ToolRegistry, ToolTrait, WebFetchTool, WebSearchTool, WriteFileTool,
 };
 use serde_json::json;
 
@@ -115,6 +115,67 @@ fn test_registry_names() {
     assert!(names.contains(&"web_fetch".to_string()));
 }
 
+#[test]
+fn test_registry_preserves_registration_order() {
+    for _ in 0..10 {
+        let mut registry = ToolRegistry::new();
+        registry.register(WriteFileTool::new(std::path::PathBuf::from("/tmp")));
+        registry.register(ReadFileTool::new(std::path::PathBuf::from("/tmp")));
+        registry.register(ListDirTool::new(std::path::PathBuf::from("/tmp")));
+
+        let expected = vec!["write_file", "read_file", "list_dir"];
+        assert_eq!(registry.names(), expected);
+        let defined: Vec<String> = registry
+            .definitions()
+            .into_iter()
+            .map(|t| t.function.name)
+            .collect();
+        assert_eq!(defined, expected);
+    }
+}
+
+#[test]
+fn test_registry_reregister_keeps_position() {
+    let mut registry = ToolRegistry::new();
+    registry.register(ReadFileTool::new(std::path::PathBuf::from("/tmp")));
+    registry.register(ListDirTool::new(std::path::PathBuf::from("/tmp")));
+    registry.register(ReadFileTool::new(std::path::PathBuf::from("/var")));
+
+    assert_eq!(registry.names(), vec!["read_file", "list_dir"]);
+}
+
+#[test]
+fn test_self_test_healthy_registry() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let workspace = temp_dir.path().to_path_buf();
+    let mut registry = ToolRegistry::new();
+    registry.register(ReadFileTool::new(workspace.clone()));
+    registry.register(ListDirTool::new(workspace.clone()));
+    registry.register(ExecTool::with_workspace(workspace));
+    registry.register(WebSearchTool::new(Some("key".to_string()), 5));
+    registry.register(WebFetchTool::default());
+
+    let results = registry.self_test();
+    assert_eq!(results.len(), 5);
+    for (name, result) in results {
+        assert!(result.is_ok(), "{} failed: {:?}", name, result);
+    }
+}
+
+#[test]
+fn test_self_test_reports_missing_workspace() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut registry = ToolRegistry::new();
+    registry.register(ReadFileTool::new(temp_dir.path().to_path_buf()));
+    registry.register(WriteFileTool::new(temp_dir.path().join("missing")));
+
+    let results = registry.self_test();
+    assert_eq!(results[0], ("read_file".to_string(), Ok(())));
+    let (name, result) = &results[1];
+    assert_eq!(name, "write_file");
+    assert!(result.as_ref().unwrap_err().contains("missing"));
+}
+
 #[test]
 fn test_to_provider_tool() {
     let tool = ReadFileTool::new(std::path::PathBuf::from("/tmp"));
@@ -168,3 +229,129 @@ async fn test_full_toolkit_registry() {
     let definitions = registry.definitions();
     assert_eq!(definitions.len(), 7);
 }
+
+#[tokio::test]
+async fn test_registry_run_outcomes() {
+    let workspace = tempfile::TempDir::new().unwrap();
+    std::fs::write(workspace.path().join("intel.txt"), "codec").unwrap();
+    let mut registry = ToolRegistry::new();
+    registry.register(ReadFileTool::new(workspace.path().to_path_buf()));
+
+    let ok = registry
+        .run("read_file", json!({"path": "intel.txt"}))
+        .await;
+    assert_eq!(ok, ToolOutcome::Ok("codec".to_string()));
+
+    let error = registry
+        .run("read_file", json!({"file": "intel.txt"}))
+        .await;
+    assert!(matches!(error, ToolOutcome::Error(_)));
+
+    let denied = registry
+        .run("read_file", json!({"path": "/etc/passwd"}))
+        .await;
+    assert!(matches!(denied, ToolOutcome::Denied(ref r) if r.contains("outside workspace")));
+
+    let missing = registry.run("nonexistent", json!({})).await;
+    assert_eq!(missing, ToolOutcome::NotFound);
+}
+
+#[test]
+fn test_tool_outcome_model_messages() {
+    assert_eq!(
+        ToolOutcome::Ok("done".to_string()).to_model_message("exec"),
+        "done"
+    );
+    assert_eq!(
+        ToolOutcome::Error("disk full".to_string()).to_model_message("write_file"),
+        "Error: disk full"
+    );
+    assert_eq!(
+        ToolOutcome::Denied("path outside workspace".to_string()).to_model_message("read_file"),
+        "Denied: path outside workspace. Do not retry this call unchanged."
+    );
+    assert_eq!(
+        ToolOutcome::NotFound.to_model_message("teleport"),
+        "Error: ◆ TOOLKIT 'teleport' NOT FOUND"
+    );
+}
+
+#[test]
+fn test_tool_denied_error_maps_to_denied() {
+    let result: Result<String, Box<dyn std::error::Error + Send + Sync>> =
+        Err(Box::new(ToolDenied("command blocked".to_string())));
+    assert_eq!(
+        ToolOutcome::from_result(result),
+        ToolOutcome::Denied("command blocked".to_string())
+    );
+}
+
+#[test]
+fn test_export_schema_includes_every_tool() {
+    let mut registry = ToolRegistry::new();
+    registry.register(ReadFileTool::new(std::path::PathBuf::from("/tmp")));
+    registry.register(ExecTool::with_workspace(std::path::PathBuf::from("/tmp")));
+    registry.register(WebFetchTool::default());
+
+    let schema = registry.export_schema();
+    let tools = schema.as_object().unwrap();
+
+    assert_eq!(tools.len(), 3);
+    for name in registry.names() {
+        let tool = registry.get(&name).unwrap();
+        assert_eq!(tools[&name]["description"], tool.description());
+        assert_eq!(tools[&name]["parameters"], tool.parameters());
+    }
+}
+
+#[test]
+fn test_export_schema_round_trips_through_json() {
+    #[derive(serde::Deserialize)]
+    struct Exported {
+        description: String,
+        parameters: serde_json::Value,
+    }
+
+    let mut registry = ToolRegistry::new();
+    registry.register(ReadFileTool::new(std::path::PathBuf::from("/tmp")));
+
+    let text = serde_json::to_string(&registry.export_schema()).unwrap();
+    let parsed: std::collections::HashMap<String, Exported> = serde_json::from_str(&text).unwrap();
+
+    let read_file = &parsed["read_file"];
+    assert!(!read_file.description.is_empty());
+    assert_eq!(read_file.parameters["required"], json!(["path"]));
+}
+
+#[test]
+fn test_default_tools_respect_memory_switch() {
+    let workspace = tempfile::TempDir::new().unwrap();
+    let (bus, _in_rx, _out_rx) = opensam_bus::MessageBus::channels();
+    let mut config = opensam_config::Config::default();
+
+    let mut registry = ToolRegistry::new();
+    register_default_tools(&mut registry, &config, workspace.path(), bus.clone());
+    assert!(registry.has("remember"));
+
+    config.toolkit.memory.enabled = false;
+    let mut registry = ToolRegistry::new();
+    register_default_tools(&mut registry, &config, workspace.path(), bus);
+    assert!(!registry.has("remember"));
+    assert!(registry.has("read_file"));
+}
+
+#[test]
+fn test_default_tools_respect_time_switch() {
+    let workspace = tempfile::TempDir::new().unwrap();
+    let (bus, _in_rx, _out_rx) = opensam_bus::MessageBus::channels();
+    let mut config = opensam_config::Config::default();
+
+    let mut registry = ToolRegistry::new();
+    register_default_tools(&mut registry, &config, workspace.path(), bus.clone());
+    assert!(registry.has("current_time"));
+
+    config.toolkit.time.enabled = false;
+    let mut registry = ToolRegistry::new();
+    register_default_tools(&mut registry, &config, workspace.path(), bus);
+    assert!(!registry.has("current_time"));
+}
@@ -0,0 +1,158 @@
+//! Tests for tool result formatting
+
+use async_trait::async_trait;
+use mockall::mock;
+use opensam_agent::tools::ToolResultFormatter;
+use opensam_agent::AgentLoop;
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_config::{Config, ToolResultFormat, ToolResultsConfig};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError, ToolCall};
+use serde_json::json;
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+mock! {
+    pub Provider {}
+
+    #[async_trait]
+    impl Provider for Provider {
+        async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError>;
+        fn default_model(&self) -> String;
+        fn is_configured(&self) -> bool;
+    }
+}
+
+fn formatter(envelope: bool, pretty_json: bool) -> ToolResultFormatter {
+    ToolResultFormatter::new(ToolResultsConfig {
+        default: ToolResultFormat {
+            envelope,
+            pretty_json,
+        },
+        tools: HashMap::new(),
+    })
+}
+
+#[test]
+fn test_default_passes_results_through() {
+    let formatter = ToolResultFormatter::default();
+    assert_eq!(formatter.format("web_search", r#"{"a":1}"#), r#"{"a":1}"#);
+}
+
+#[test]
+fn test_json_result_is_pretty_printed_and_wrapped() {
+    let formatter = formatter(true, true);
+    let result = formatter.format("web_search", r#"{"results":[{"title":"x"}]}"#);
+
+    assert_eq!(
+        result,
+        "tool web_search returned:\n{\n  \"results\": [\n    {\n      \"title\": \"x\"\n    }\n  ]\n}"
+    );
+}
+
+#[test]
+fn test_plain_text_result_only_gets_envelope() {
+    let formatter = formatter(true, true);
+    let result = formatter.format("exec", "total 0\nfile.txt");
+
+    assert_eq!(result, "tool exec returned:\ntotal 0\nfile.txt");
+}
+
+#[test]
+fn test_json_scalars_are_not_reformatted() {
+    let formatter = formatter(false, true);
+    assert_eq!(formatter.format("exec", "42"), "42");
+    assert_eq!(formatter.format("exec", "\"quoted\""), "\"quoted\"");
+}
+
+#[test]
+fn test_per_tool_override() {
+    let mut tools = HashMap::new();
+    tools.insert(
+        "web_search".to_string(),
+        ToolResultFormat {
+            envelope: true,
+            pretty_json: true,
+        },
+    );
+    let formatter = ToolResultFormatter::new(ToolResultsConfig {
+        default: ToolResultFormat::default(),
+        tools,
+    });
+
+    assert!(formatter
+        .format("web_search", "[1]")
+        .starts_with("tool web_search returned:\n"));
+    assert_eq!(formatter.format("exec", "[1]"), "[1]");
+}
+
+#[test]
+fn test_formatter_from_config() {
+    let config: Config = serde_json::from_value(json!({
+        "toolkit": {
+            "results": {
+                "default": {"envelope": true},
+                "tools": {"read_file": {"envelope": true, "pretty_json": true}}
+            }
+        }
+    }))
+    .unwrap();
+    let formatter = ToolResultFormatter::from_config(&config);
+
+    assert_eq!(formatter.format("exec", "ok"), "tool exec returned:\nok");
+    assert_eq!(
+        formatter.format("read_file", r#"{"k":true}"#),
+        "tool read_file returned:\n{\n  \"k\": true\n}"
+    );
+}
+
+#[tokio::test]
+async fn test_agent_loop_formats_tool_results() {
+    let workspace = TempDir::new().unwrap();
+    std::fs::write(workspace.path().join("data.json"), r#"{"status":"ok"}"#).unwrap();
+
+    let mut mock = MockProvider::new();
+    let mut seq = mockall::Sequence::new();
+    mock.expect_chat()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_| {
+            Ok(ChatResponse {
+                content: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: "read_file".to_string(),
+                    arguments: json!({"path": "data.json"}),
+                }],
+                finish_reason: "tool_calls".to_string(),
+                model: None,
+                usage: Default::default(),
+            })
+        });
+    mock.expect_chat()
+        .times(1)
+        .in_sequence(&mut seq)
+        .withf(|params| {
+            params.messages.iter().any(|m| {
+                m.role == "tool"
+                    && m.content.as_deref()
+                        == Some("tool read_file returned:\n{\n  \"status\": \"ok\"\n}")
+            })
+        })
+        .returning(|_| Ok(ChatResponse::text("done")));
+
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let mut agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        mock,
+        workspace.path().to_path_buf(),
+        "test-model".to_string(),
+        5,
+        None,
+        workspace.path().join("sessions"),
+    );
+    agent.set_result_formatter(formatter(true, true));
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Read it");
+    let response = agent.process_message(msg).await.unwrap();
+    assert_eq!(response.content, "done");
+}
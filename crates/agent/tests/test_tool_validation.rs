@@ -0,0 +1,131 @@
+//! Tests for tool-call argument validation
+
+use async_trait::async_trait;
+use mockall::mock;
+use opensam_agent::tools::validation::{correction_message, validate_arguments};
+use opensam_agent::AgentLoop;
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError, ToolCall};
+use serde_json::{json, Value};
+use tempfile::TempDir;
+
+mock! {
+    pub Provider {}
+
+    #[async_trait]
+    impl Provider for Provider {
+        async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError>;
+        fn default_model(&self) -> String;
+        fn is_configured(&self) -> bool;
+    }
+}
+
+fn write_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "path": { "type": "string" },
+            "content": { "type": "string" },
+            "mode": { "type": "string", "enum": ["append", "overwrite"] },
+            "lines": { "type": "array", "items": { "type": "integer" } }
+        },
+        "required": ["path", "content"]
+    })
+}
+
+#[test]
+fn test_valid_arguments_pass() {
+    let args = json!({"path": "a.txt", "content": "hi", "mode": "append", "lines": [1, 2]});
+    assert!(validate_arguments(&write_schema(), &args).is_ok());
+}
+
+#[test]
+fn test_missing_required_field_reported() {
+    let problems = validate_arguments(&write_schema(), &json!({"path": "a.txt"})).unwrap_err();
+    assert_eq!(problems, vec!["missing required field 'content'"]);
+}
+
+#[test]
+fn test_wrong_types_and_enum_reported() {
+    let args = json!({"path": 7, "content": "hi", "mode": "truncate", "lines": [1, "x"]});
+    let problems = validate_arguments(&write_schema(), &args).unwrap_err();
+
+    assert!(problems.contains(&"'path' should be string but got number".to_string()));
+    assert!(problems
+        .iter()
+        .any(|p| p.starts_with("'mode' must be one of")));
+    assert!(problems.contains(&"'lines'[1] should be integer but got string".to_string()));
+}
+
+#[test]
+fn test_non_object_arguments_reported() {
+    let problems = validate_arguments(&write_schema(), &json!("a.txt")).unwrap_err();
+    assert_eq!(problems, vec!["arguments should be object but got string"]);
+}
+
+#[test]
+fn test_correction_message_is_structured() {
+    let problems = vec!["missing required field 'content'".to_string()];
+    let message = correction_message("write_file", &write_schema(), &problems);
+    let parsed: Value = serde_json::from_str(&message).unwrap();
+
+    assert_eq!(parsed["error"], "invalid_arguments");
+    assert_eq!(parsed["tool"], "write_file");
+    assert_eq!(parsed["problems"][0], "missing required field 'content'");
+    assert_eq!(parsed["expected_schema"], write_schema());
+}
+
+#[tokio::test]
+async fn test_agent_loop_feeds_correction_instead_of_executing() {
+    let workspace = TempDir::new().unwrap();
+
+    let mut mock = MockProvider::new();
+    let mut seq = mockall::Sequence::new();
+    mock.expect_chat()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_| {
+            Ok(ChatResponse {
+                content: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: "write_file".to_string(),
+                    arguments: json!({"path": "notes.txt"}),
+                }],
+                finish_reason: "tool_calls".to_string(),
+                model: None,
+                usage: Default::default(),
+            })
+        });
+    mock.expect_chat()
+        .times(1)
+        .in_sequence(&mut seq)
+        .withf(|params| {
+            params.messages.iter().any(|m| {
+                m.role == "tool"
+                    && m.tool_call_id.as_deref() == Some("call_1")
+                    && m.content.as_deref().is_some_and(|c| {
+                        c.contains("invalid_arguments")
+                            && c.contains("missing required field 'content'")
+                    })
+            })
+        })
+        .returning(|_| Ok(ChatResponse::text("fixed")));
+
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let agent = AgentLoop::new_with_sessions_dir(
+        bus,
+        mock,
+        workspace.path().to_path_buf(),
+        "test-model".to_string(),
+        5,
+        None,
+        workspace.path().join("sessions"),
+    );
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "Save a note");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(response.content, "fixed");
+    assert!(!workspace.path().join("notes.txt").exists());
+}
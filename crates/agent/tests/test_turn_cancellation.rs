@@ -0,0 +1,119 @@
+//! Tests for cancelling a session's in-flight turn
+
+use async_trait::async_trait;
+use opensam_agent::{AgentLoop, TurnResult, TurnScheduler};
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// Counts requests that were dropped before the reply came back
+#[derive(Clone, Default)]
+struct SlowProvider {
+    cancelled: Arc<AtomicUsize>,
+}
+
+/// Bumps the counter unless disarmed by a completed request
+struct CancelGuard {
+    cancelled: Arc<AtomicUsize>,
+    armed: bool,
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        if self.armed {
+            self.cancelled.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for SlowProvider {
+    async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        let mut guard = CancelGuard {
+            cancelled: Arc::clone(&self.cancelled),
+            armed: true,
+        };
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        guard.armed = false;
+        let last = params
+            .messages
+            .last()
+            .and_then(|m| m.content.clone())
+            .unwrap_or_default();
+        Ok(ChatResponse::text(format!("re: {}", last)))
+    }
+
+    fn default_model(&self) -> String {
+        "test/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test]
+async fn test_new_message_cancels_in_flight_turn_for_same_session() {
+    let temp_dir = TempDir::new().unwrap();
+    let provider = SlowProvider::default();
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let agent = Arc::new(AgentLoop::new_with_sessions_dir(
+        bus,
+        provider.clone(),
+        temp_dir.path().to_path_buf(),
+        "test/model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    ));
+    let scheduler = TurnScheduler::new(4).with_cancel_superseded(true);
+    let replies = Arc::new(Mutex::new(Vec::new()));
+
+    let spawn = |msg: InboundMessage| {
+        let agent = Arc::clone(&agent);
+        let replies = Arc::clone(&replies);
+        scheduler.spawn(msg.session_key(), async move {
+            if let TurnResult::Reply(reply) = agent.process_turn(msg).await {
+                replies.lock().unwrap().push(reply.content);
+            }
+        })
+    };
+
+    let first = spawn(InboundMessage::new("telegram", "1", "chat-a", "first"));
+    let other = spawn(InboundMessage::new("telegram", "2", "chat-b", "other"));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let second = spawn(InboundMessage::new("telegram", "1", "chat-a", "second"));
+    for handle in [first, other, second] {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(provider.cancelled.load(Ordering::SeqCst), 1);
+    let mut replies = replies.lock().unwrap().clone();
+    replies.sort();
+    assert_eq!(replies, vec!["re: other", "re: second"]);
+
+    // The cancelled turn's message is still in the history
+    let history: Vec<(String, String)> = agent
+        .sessions()
+        .read_session("telegram:chat-a", |session| {
+            session
+                .messages
+                .iter()
+                .map(|m| (m.role.clone(), m.content.clone()))
+                .collect()
+        })
+        .await;
+    let pair = |role: &str, content: &str| (role.to_string(), content.to_string());
+    assert_eq!(
+        history,
+        vec![
+            pair("user", "first"),
+            pair("user", "second"),
+            pair("assistant", "re: second"),
+        ]
+    );
+    assert_eq!(scheduler.cancellable_sessions(), 0);
+}
@@ -0,0 +1,106 @@
+//! Tests for the structured result of processing a message
+
+use async_trait::async_trait;
+use opensam_agent::{AgentError, AgentLoop, TurnResult};
+use opensam_bus::{InboundMessage, MessageBus};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError};
+use tempfile::TempDir;
+
+/// Replies with fixed text, or fails when `reply` is `None`
+struct FixedProvider {
+    reply: Option<&'static str>,
+}
+
+#[async_trait]
+impl Provider for FixedProvider {
+    async fn chat(&self, _params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        match self.reply {
+            Some(text) => Ok(ChatResponse::text(text)),
+            None => Err(ProviderError::Api("node down".to_string())),
+        }
+    }
+
+    fn default_model(&self) -> String {
+        "test/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+fn agent(temp_dir: &TempDir, reply: Option<&'static str>) -> AgentLoop<FixedProvider> {
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    AgentLoop::new_with_sessions_dir(
+        bus,
+        FixedProvider { reply },
+        temp_dir.path().to_path_buf(),
+        "test/model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    )
+}
+
+fn inbound() -> InboundMessage {
+    InboundMessage::new("telegram", "42", "chat-1", "hello")
+}
+
+#[tokio::test]
+async fn test_reply_turn() {
+    let temp_dir = TempDir::new().unwrap();
+    let agent = agent(&temp_dir, Some("Kept you waiting, huh?"));
+
+    match agent.process_turn(inbound()).await {
+        TurnResult::Reply(reply) => {
+            assert_eq!(reply.channel, "telegram");
+            assert_eq!(reply.chat_id, "chat-1");
+            assert_eq!(reply.content, "Kept you waiting, huh?");
+        }
+        other => panic!("expected a reply, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_blank_reply_is_no_reply() {
+    let temp_dir = TempDir::new().unwrap();
+    let agent = agent(&temp_dir, Some("  "));
+
+    assert!(matches!(
+        agent.process_turn(inbound()).await,
+        TurnResult::NoReply
+    ));
+    assert!(agent.process_message(inbound()).await.is_none());
+}
+
+#[tokio::test]
+async fn test_failed_turn_is_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let agent = agent(&temp_dir, None);
+
+    match agent.process_turn(inbound()).await {
+        TurnResult::Error(AgentError::Provider(ProviderError::Api(message))) => {
+            assert_eq!(message, "node down");
+        }
+        other => panic!("expected a provider error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_adapter_turns_error_into_notice() {
+    let temp_dir = TempDir::new().unwrap();
+    let agent = agent(&temp_dir, None);
+
+    let reply = agent
+        .process_message(inbound())
+        .await
+        .expect("errors still produce a notice");
+
+    assert_eq!(reply.chat_id, "chat-1");
+    let notice = agent.error_notice(
+        "telegram",
+        "chat-1",
+        &AgentError::Provider(ProviderError::Api("node down".to_string())),
+    );
+    assert_eq!(reply.content, notice.content);
+}
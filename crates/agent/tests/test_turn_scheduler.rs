@@ -0,0 +1,167 @@
+//! Tests for concurrent turn scheduling
+
+use opensam_agent::TurnScheduler;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Tracks how many turns are running at once
+#[derive(Clone, Default)]
+struct Probe {
+    running: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+    order: Arc<Mutex<Vec<String>>>,
+}
+
+impl Probe {
+    fn turn(&self, label: &str) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let probe = self.clone();
+        let label = label.to_string();
+        async move {
+            let now = probe.running.fetch_add(1, Ordering::SeqCst) + 1;
+            probe.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            probe.order.lock().unwrap().push(label);
+            probe.running.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_different_sessions_run_concurrently() {
+    let scheduler = TurnScheduler::new(4);
+    let probe = Probe::default();
+
+    let a = scheduler.spawn("telegram:1", probe.turn("a"));
+    let b = scheduler.spawn("telegram:2", probe.turn("b"));
+    a.await.unwrap();
+    b.await.unwrap();
+
+    assert_eq!(probe.peak.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_same_session_turns_are_serialized_in_order() {
+    let scheduler = TurnScheduler::new(4);
+    let probe = Probe::default();
+
+    let handles: Vec<_> = ["first", "second", "third"]
+        .iter()
+        .map(|label| scheduler.spawn("telegram:1", probe.turn(label)))
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(probe.peak.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        *probe.order.lock().unwrap(),
+        vec!["first", "second", "third"]
+    );
+    assert_eq!(scheduler.active_sessions(), 0);
+}
+
+#[tokio::test]
+async fn test_concurrency_limit_is_respected() {
+    let scheduler = TurnScheduler::new(2);
+    let probe = Probe::default();
+
+    let handles: Vec<_> = (0..5)
+        .map(|i| scheduler.spawn(format!("telegram:{}", i), probe.turn("t")))
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(probe.peak.load(Ordering::SeqCst), 2);
+    assert_eq!(probe.order.lock().unwrap().len(), 5);
+}
+
+#[tokio::test]
+async fn test_zero_limit_still_makes_progress() {
+    let scheduler = TurnScheduler::new(0);
+    assert_eq!(scheduler.max_concurrent(), 1);
+
+    let probe = Probe::default();
+    scheduler
+        .spawn("telegram:1", probe.turn("a"))
+        .await
+        .unwrap();
+    assert_eq!(probe.order.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_superseded_turns_still_run_without_cancellation() {
+    let scheduler = TurnScheduler::new(4).with_cancel_superseded(false);
+    let probe = Probe::default();
+
+    let first = scheduler.spawn("telegram:1", probe.turn("first"));
+    let second = scheduler.spawn("telegram:1", probe.turn("second"));
+    first.await.unwrap();
+    second.await.unwrap();
+
+    assert_eq!(*probe.order.lock().unwrap(), vec!["first", "second"]);
+}
+
+#[tokio::test]
+async fn test_newer_turn_cancels_queued_and_running_turns() {
+    let scheduler = TurnScheduler::new(4).with_cancel_superseded(true);
+    let probe = Probe::default();
+
+    let first = scheduler.spawn("telegram:1", probe.turn("first"));
+    let other = scheduler.spawn("telegram:2", probe.turn("other"));
+    // Let the first turn start before it is superseded
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let second = scheduler.spawn("telegram:1", probe.turn("second"));
+    let third = scheduler.spawn("telegram:1", probe.turn("third"));
+    for handle in [first, other, second, third] {
+        handle.await.unwrap();
+    }
+
+    let mut order = probe.order.lock().unwrap().clone();
+    order.sort();
+    assert_eq!(order, vec!["other", "third"]);
+    // Finished sessions no longer hold a token
+    assert_eq!(scheduler.cancellable_sessions(), 0);
+}
+
+#[tokio::test]
+async fn test_finished_sessions_are_forgotten() {
+    let scheduler = TurnScheduler::new(4);
+    let probe = Probe::default();
+
+    let first = scheduler.spawn("telegram:1", probe.turn("first"));
+    let other = scheduler.spawn("telegram:2", probe.turn("other"));
+    assert_eq!(scheduler.active_sessions(), 2);
+    first.await.unwrap();
+    other.await.unwrap();
+    assert_eq!(scheduler.active_sessions(), 0);
+
+    // A session seen before queues and clears like a new one
+    let again = scheduler.spawn("telegram:1", probe.turn("again"));
+    assert_eq!(scheduler.active_sessions(), 1);
+    again.await.unwrap();
+    assert_eq!(scheduler.active_sessions(), 0);
+}
+
+#[tokio::test]
+async fn test_drain_waits_for_dropped_turn_handles() {
+    let scheduler = TurnScheduler::new(4);
+    let probe = Probe::default();
+
+    drop(scheduler.spawn("telegram:1", probe.turn("first")));
+    drop(scheduler.spawn("telegram:1", probe.turn("second")));
+    drop(scheduler.spawn("telegram:2", probe.turn("other")));
+
+    assert!(scheduler.drain(Duration::from_secs(5)).await);
+    assert_eq!(probe.order.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_drain_gives_up_after_timeout() {
+    let scheduler = TurnScheduler::new(1);
+    let handle = scheduler.spawn("telegram:1", tokio::time::sleep(Duration::from_secs(60)));
+
+    assert!(!scheduler.drain(Duration::from_millis(50)).await);
+    handle.abort();
+}
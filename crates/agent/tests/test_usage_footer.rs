@@ -0,0 +1,139 @@
+//! Tests for per-turn usage metadata and footers
+
+use async_trait::async_trait;
+use opensam_agent::output::{estimate_cost, usage_footer};
+use opensam_agent::AgentLoop;
+use opensam_bus::{InboundMessage, MessageBus, USAGE_KEY};
+use opensam_provider::{ChatParams, ChatResponse, Provider, ProviderError, ToolCall, Usage};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+/// Replies with a tool call first, then text, each reporting usage
+struct TwoStepProvider {
+    calls: Mutex<u32>,
+}
+
+fn usage(prompt: u32, completion: u32) -> Usage {
+    Usage {
+        prompt_tokens: prompt,
+        completion_tokens: completion,
+        total_tokens: prompt + completion,
+    }
+}
+
+#[async_trait]
+impl Provider for TwoStepProvider {
+    async fn chat(&self, _params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        let mut calls = self.calls.lock().unwrap();
+        *calls += 1;
+        let mut response = if *calls == 1 {
+            let mut response = ChatResponse::text("");
+            response.tool_calls = vec![ToolCall {
+                id: "call_1".to_string(),
+                name: "list_dir".to_string(),
+                arguments: json!({"path": "."}),
+            }];
+            response.finish_reason = "tool_calls".to_string();
+            response
+        } else {
+            ChatResponse::text("Done.")
+        };
+        response.usage = if *calls == 1 {
+            usage(20, 4)
+        } else {
+            usage(15, 3)
+        };
+        Ok(response)
+    }
+
+    fn default_model(&self) -> String {
+        "test/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+fn agent(temp_dir: &TempDir) -> AgentLoop<TwoStepProvider> {
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    AgentLoop::new_with_sessions_dir(
+        bus,
+        TwoStepProvider {
+            calls: Mutex::new(0),
+        },
+        temp_dir.path().to_path_buf(),
+        "test/model".to_string(),
+        5,
+        None,
+        temp_dir.path().join("sessions"),
+    )
+}
+
+#[test]
+fn test_usage_footer_formats_tokens_and_cost() {
+    assert_eq!(usage_footer(&usage(30, 12), 0.025), "(42 tokens, ~$0.001)");
+    assert_eq!(usage_footer(&usage(30, 12), 0.0), "(42 tokens)");
+    assert!((estimate_cost(&usage(500, 500), 0.002) - 0.002).abs() < 1e-12);
+}
+
+#[tokio::test]
+async fn test_metadata_carries_accumulated_usage() {
+    let temp_dir = TempDir::new().unwrap();
+    let agent = agent(&temp_dir);
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "List files");
+    let response = agent.process_message(msg).await.unwrap();
+
+    let recorded = &response.metadata[USAGE_KEY];
+    assert_eq!(recorded["prompt_tokens"], 35);
+    assert_eq!(recorded["completion_tokens"], 7);
+    assert_eq!(recorded["total_tokens"], 42);
+    assert_eq!(response.content, "Done.");
+}
+
+#[tokio::test]
+async fn test_footer_appended_when_enabled() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut agent = agent(&temp_dir);
+    agent.set_usage_footer(true, 0.025);
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "List files");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(response.content, "Done.\n\n(42 tokens, ~$0.001)");
+    assert!((response.metadata[USAGE_KEY]["cost"].as_f64().unwrap() - 0.00105).abs() < 1e-12);
+}
+
+#[tokio::test]
+async fn test_footer_uses_model_price() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut agent = agent(&temp_dir);
+    agent.set_usage_footer(true, 0.025);
+    agent.set_model_prices(HashMap::from([("test/model".to_string(), 1.0)]));
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "List files");
+    let response = agent.process_message(msg).await.unwrap();
+
+    assert_eq!(response.content, "Done.\n\n(42 tokens, ~$0.042)");
+    assert!((response.metadata[USAGE_KEY]["cost"].as_f64().unwrap() - 0.042).abs() < 1e-12);
+}
+
+#[tokio::test]
+async fn test_turn_usage_recorded_on_session() {
+    let temp_dir = TempDir::new().unwrap();
+    let agent = agent(&temp_dir);
+
+    let msg = InboundMessage::new("telegram", "user1", "chat1", "List files");
+    agent.process_message(msg).await.unwrap();
+
+    let stored = agent
+        .sessions()
+        .read_session("telegram:chat1", |session| {
+            opensam_agent::usage::session_usage(session)
+        })
+        .await;
+    assert_eq!(stored["test/model"].total_tokens, 42);
+}
@@ -0,0 +1,101 @@
+//! Tests for session usage totals and per-model pricing
+
+use opensam_agent::usage::{record_in_session, session_usage};
+use opensam_agent::UsageTracker;
+use opensam_config::Config;
+use opensam_provider::Usage;
+use opensam_session::Session;
+use std::collections::HashMap;
+
+fn usage(prompt: u32, completion: u32) -> Usage {
+    Usage {
+        prompt_tokens: prompt,
+        completion_tokens: completion,
+        total_tokens: prompt + completion,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-12,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_tracker_accumulates_per_session() {
+    let mut tracker = UsageTracker::new();
+    tracker.record("telegram:1", "gpt-4o", &usage(100, 20));
+    tracker.record("telegram:1", "gpt-4o", &usage(50, 10));
+    tracker.record("telegram:1", "llama", &usage(5, 5));
+    tracker.record("field:alpha", "gpt-4o", &usage(1, 1));
+
+    let session = tracker.session_usage("telegram:1");
+    assert_eq!(session.prompt_tokens, 155);
+    assert_eq!(session.completion_tokens, 35);
+    assert_eq!(session.total_tokens, 190);
+
+    assert_eq!(tracker.total_usage().total_tokens, 192);
+    assert_eq!(tracker.session_usage("missing").total_tokens, 0);
+    assert_eq!(
+        tracker.sessions().collect::<Vec<_>>(),
+        vec!["field:alpha", "telegram:1"]
+    );
+}
+
+#[test]
+fn test_cost_applies_per_model_rate() {
+    let mut tracker = UsageTracker::new()
+        .with_prices(HashMap::from([
+            ("gpt-4o".to_string(), 0.01),
+            ("llama".to_string(), 0.0),
+        ]))
+        .with_default_price(0.002);
+    tracker.record("s", "gpt-4o", &usage(1500, 500));
+    tracker.record("s", "llama", &usage(4000, 0));
+    tracker.record("s", "unpriced", &usage(500, 500));
+
+    assert_close(tracker.price_for("gpt-4o"), 0.01);
+    assert_close(tracker.price_for("unpriced"), 0.002);
+    // 2k tokens at 0.01 + 4k free + 1k at the default 0.002
+    assert_close(tracker.session_cost("s"), 0.022);
+    assert_close(tracker.total_cost(), 0.022);
+}
+
+#[test]
+fn test_tracker_prices_from_config() {
+    let mut config = Config::default();
+    config.operative.defaults.cost_per_1k_tokens = 0.5;
+    config
+        .operative
+        .defaults
+        .model_prices
+        .insert("gpt-4o".to_string(), 2.0);
+    let tracker = UsageTracker::from_config(&config);
+
+    assert_close(tracker.price_for("gpt-4o"), 2.0);
+    assert_close(tracker.price_for("other"), 0.5);
+}
+
+#[test]
+fn test_session_metadata_accumulates_and_loads() {
+    let mut session = Session::new("telegram:1");
+    assert!(session_usage(&session).is_empty());
+
+    assert!(record_in_session(&mut session, "gpt-4o", &usage(10, 2)));
+    assert!(record_in_session(&mut session, "gpt-4o", &usage(30, 8)));
+    assert!(record_in_session(&mut session, "llama", &usage(1, 1)));
+
+    let stored = session_usage(&session);
+    assert_eq!(stored["gpt-4o"].total_tokens, 50);
+    assert_eq!(stored["llama"].total_tokens, 2);
+
+    // Survives a save round trip
+    let session: Session = serde_json::from_str(&serde_json::to_string(&session).unwrap()).unwrap();
+    let mut tracker = UsageTracker::new().with_default_price(1.0);
+    tracker.record_session(&session);
+    assert_eq!(tracker.session_usage("telegram:1").prompt_tokens, 41);
+    assert_close(tracker.session_cost("telegram:1"), 0.052);
+}
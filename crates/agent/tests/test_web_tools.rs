@@ -1,6 +1,6 @@
 //! Tests for web tools
 
-use opensam_agent::tools::{ToolTrait, WebFetchTool, WebSearchTool};
+use opensam_agent::tools::{ToolOutcome, ToolTrait, WebFetchTool, WebSearchTool};
 use serde_json::json;
 
 // Test strip_tags functionality through WebFetchTool
@@ -104,3 +104,119 @@ fn test_web_fetch_max_chars_validation() {
     let max_chars = &params["properties"]["maxChars"];
     assert_eq!(max_chars["minimum"], 100);
 }
+
+#[tokio::test]
+async fn test_web_fetch_refuses_internal_urls() {
+    let tool = WebFetchTool::default();
+
+    for url in [
+        "http://169.254.169.254/latest/meta-data/",
+        "http://localhost:8080/",
+        "http://2130706433/",
+    ] {
+        let err = tool.execute(json!({"url": url})).await.unwrap_err();
+        assert!(
+            matches!(ToolOutcome::from_result(Err(err)), ToolOutcome::Denied(_)),
+            "{} should be denied",
+            url
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_web_fetch_deny_list_from_config() {
+    let mut config = opensam_config::Config::default();
+    config.toolkit.web.fetch.deny_hosts = vec!["example.com".to_string()];
+    let tool = WebFetchTool::from_config(&config);
+
+    let err = tool
+        .execute(json!({"url": "https://www.example.com/"}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("denied"));
+}
+
+/// Fetch tool allowed to reach the local mock server
+fn local_fetch_tool(max_bytes: usize) -> WebFetchTool {
+    let mut config = opensam_config::Config::default();
+    config.toolkit.web.fetch.allow_hosts = vec!["127.0.0.1".to_string()];
+    config.toolkit.web.fetch.max_bytes = max_bytes;
+    WebFetchTool::from_config(&config)
+}
+
+async fn fetch(content_type: &str, body: &[u8], max_bytes: usize) -> serde_json::Value {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/page")
+        .with_header("content-type", content_type)
+        .with_body(body)
+        .create_async()
+        .await;
+
+    let result = local_fetch_tool(max_bytes)
+        .execute(json!({"url": format!("{}/page", server.url())}))
+        .await
+        .unwrap();
+    serde_json::from_str(&result).unwrap()
+}
+
+#[tokio::test]
+async fn test_web_fetch_pretty_prints_json() {
+    let result = fetch(
+        "application/json; charset=utf-8",
+        br#"{"a":1,"b":[2]}"#,
+        1000,
+    )
+    .await;
+    assert_eq!(result["extractor"], "json");
+    assert_eq!(result["text"], "{\n  \"a\": 1,\n  \"b\": [\n    2\n  ]\n}");
+}
+
+#[tokio::test]
+async fn test_web_fetch_passes_plain_text_through() {
+    let result = fetch("text/plain", b"line one\n<not a tag>\n", 1000).await;
+    assert_eq!(result["extractor"], "raw");
+    assert_eq!(result["text"], "line one\n<not a tag>\n");
+}
+
+#[tokio::test]
+async fn test_web_fetch_converts_html() {
+    let html = b"<html><script>x()</script><h1>Title</h1><p>Body</p></html>";
+    let result = fetch("text/html", html, 1000).await;
+    assert_eq!(result["extractor"], "markdown");
+    let text = result["text"].as_str().unwrap();
+    assert!(text.contains("# Title"));
+    assert!(!text.contains("x()"));
+}
+
+#[tokio::test]
+async fn test_web_fetch_summarizes_binary() {
+    let pdf = b"%PDF-1.7\n\xff\xfe\x00\x01binary";
+    let result = fetch("application/pdf", pdf, 1000).await;
+    assert_eq!(result["extractor"], "binary");
+    assert_eq!(
+        result["text"],
+        format!("binary (application/pdf), {} bytes", pdf.len())
+    );
+    assert_eq!(result["contentType"], "application/pdf");
+}
+
+#[tokio::test]
+async fn test_web_fetch_caps_download() {
+    let body = "a".repeat(5000);
+    let result = fetch("text/plain", body.as_bytes(), 1024).await;
+    assert_eq!(result["truncated"], true);
+    assert_eq!(result["length"], 1024);
+}
+
+#[tokio::test]
+async fn test_web_fetch_cap_inside_multibyte_character() {
+    // "é" is two bytes; the cap keeps "<p>caf" plus its first byte
+    let html = "<p>café</p>".repeat(10);
+    let cut = html.find('é').unwrap() + 1;
+    let result = fetch("text/html", html.as_bytes(), cut).await;
+
+    assert_eq!(result["extractor"], "markdown");
+    assert_eq!(result["truncated"], true);
+    assert_eq!(result["text"], "caf");
+}
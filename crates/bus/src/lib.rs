@@ -5,12 +5,49 @@
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, trace};
 
+pub mod metadata;
+pub mod metrics;
+pub mod policy;
+pub mod stats;
+
+pub use metadata::MetadataLimit;
+pub use metrics::{BusMetrics, MetricsSnapshot};
+pub use policy::InboundPolicy;
+pub use stats::{ChannelTraffic, GatewayStats, GatewayStatsSnapshot};
+
+/// Metadata key holding the id of the message being replied to
+pub const REPLY_TO_ID_KEY: &str = "reply_to_message_id";
+
+/// Metadata key holding the text of the message being replied to
+pub const REPLY_TO_TEXT_KEY: &str = "reply_to_text";
+
+/// Metadata key holding who sent the message being replied to
+pub const REPLY_TO_SENDER_KEY: &str = "reply_to_sender";
+
+/// Metadata key holding the token usage of the turn that produced a reply
+pub const USAGE_KEY: &str = "usage";
+
+/// Content given to caption-less media; `{n}` is replaced by the attachment count
+pub const DEFAULT_ATTACHMENT_PROMPT: &str = "The user sent {n} attachment(s) with no text.";
+
+/// Short random identifier for a new transmission
+fn new_message_id() -> String {
+    uuid::Uuid::new_v4().to_string()[..8].to_string()
+}
+
 /// Incoming transmission from field
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InboundMessage {
+    /// Transmission ID; messages stored without one get a fresh ID on load
+    #[serde(default = "new_message_id")]
+    pub id: String,
     /// Frequency/channel
     pub channel: String,
     /// Operative ID
@@ -27,6 +64,9 @@ pub struct InboundMessage {
     /// Operational metadata
     #[serde(default)]
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Bounds on `metadata` growth
+    #[serde(skip)]
+    pub metadata_limit: MetadataLimit,
 }
 
 impl InboundMessage {
@@ -38,6 +78,7 @@ impl InboundMessage {
         content: impl Into<String>,
     ) -> Self {
         Self {
+            id: new_message_id(),
             channel: channel.into(),
             sender_id: sender_id.into(),
             chat_id: chat_id.into(),
@@ -45,14 +86,78 @@ impl InboundMessage {
             timestamp: Local::now(),
             media: Vec::new(),
             metadata: HashMap::new(),
+            metadata_limit: MetadataLimit::default(),
         }
     }
 
+    /// Use an ID supplied by the originating platform
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = id.into();
+        self
+    }
+
     /// Get operation identifier
     pub fn session_key(&self) -> String {
         format!("{}:{}", self.channel, self.chat_id)
     }
 
+    /// Set when the transmission was sent
+    pub fn with_timestamp(mut self, timestamp: DateTime<Local>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Whether the transmission is older than `max_age` at `now`
+    pub fn is_stale(&self, max_age: std::time::Duration, now: DateTime<Local>) -> bool {
+        match (now - self.timestamp).to_std() {
+            Ok(age) => age > max_age,
+            // Timestamps in the future are not stale
+            Err(_) => false,
+        }
+    }
+
+    /// Prepare the transmission for the operative
+    ///
+    /// Blank text with media becomes `attachment_prompt`, with `{n}` replaced
+    /// by the attachment count. Returns `None` for a message with neither.
+    pub fn normalize(mut self, attachment_prompt: &str) -> Option<Self> {
+        if !self.content.trim().is_empty() {
+            return Some(self);
+        }
+        if self.media.is_empty() {
+            return None;
+        }
+        self.content = attachment_prompt.replace("{n}", &self.media.len().to_string());
+        Some(self)
+    }
+
+    /// Record the earlier message this one replies to
+    pub fn with_reply_to(
+        self,
+        message_id: impl Serialize,
+        text: Option<&str>,
+        sender: Option<&str>,
+    ) -> Self {
+        let mut msg = self.with_metadata(REPLY_TO_ID_KEY, message_id);
+        if let Some(text) = text {
+            msg = msg.with_metadata(REPLY_TO_TEXT_KEY, text);
+        }
+        if let Some(sender) = sender {
+            msg = msg.with_metadata(REPLY_TO_SENDER_KEY, sender);
+        }
+        msg
+    }
+
+    /// Text of the message this one replies to, if known
+    pub fn replied_text(&self) -> Option<&str> {
+        self.metadata.get(REPLY_TO_TEXT_KEY)?.as_str()
+    }
+
+    /// Sender of the message this one replies to, if known
+    pub fn replied_sender(&self) -> Option<&str> {
+        self.metadata.get(REPLY_TO_SENDER_KEY)?.as_str()
+    }
+
     /// Attach intel
     pub fn with_media(mut self, path: impl Into<String>) -> Self {
         self.media.push(path.into());
@@ -60,17 +165,29 @@ impl InboundMessage {
     }
 
     /// Add operational data
+    ///
+    /// Entries beyond the metadata limit are dropped with a warning.
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
         if let Ok(value) = serde_json::to_value(value) {
-            self.metadata.insert(key.into(), value);
+            self.metadata_limit
+                .insert(&mut self.metadata, key.into(), value);
         }
         self
     }
+
+    /// Set the limit applied by later `with_metadata` calls
+    pub fn with_metadata_limit(mut self, limit: MetadataLimit) -> Self {
+        self.metadata_limit = limit;
+        self
+    }
 }
 
 /// Outgoing transmission to field
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OutboundMessage {
+    /// Transmission ID; messages stored without one get a fresh ID on load
+    #[serde(default = "new_message_id")]
+    pub id: String,
     /// Target frequency
     pub channel: String,
     /// Secure channel ID
@@ -80,12 +197,18 @@ pub struct OutboundMessage {
     /// Response to
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reply_to: Option<String>,
+    /// Identifier echoed back in delivery reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
     /// Intel attachments
     #[serde(default)]
     pub media: Vec<String>,
     /// Operational metadata
     #[serde(default)]
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Bounds on `metadata` growth
+    #[serde(skip)]
+    pub metadata_limit: MetadataLimit,
 }
 
 impl OutboundMessage {
@@ -96,20 +219,52 @@ impl OutboundMessage {
         content: impl Into<String>,
     ) -> Self {
         Self {
+            id: new_message_id(),
             channel: channel.into(),
             chat_id: chat_id.into(),
             content: content.into(),
             reply_to: None,
+            correlation_id: None,
             media: Vec::new(),
             metadata: HashMap::new(),
+            metadata_limit: MetadataLimit::default(),
         }
     }
 
+    /// Use an explicit ID, e.g. one the target platform already knows
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = id.into();
+        self
+    }
+
     /// Set response target
     pub fn reply_to(mut self, msg_id: impl Into<String>) -> Self {
         self.reply_to = Some(msg_id.into());
         self
     }
+
+    /// Tag the transmission for delivery reports
+    pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(id.into());
+        self
+    }
+
+    /// Add operational data
+    ///
+    /// Entries beyond the metadata limit are dropped with a warning.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.metadata_limit
+                .insert(&mut self.metadata, key.into(), value);
+        }
+        self
+    }
+
+    /// Set the limit applied by later `with_metadata` calls
+    pub fn with_metadata_limit(mut self, limit: MetadataLimit) -> Self {
+        self.metadata_limit = limit;
+        self
+    }
 }
 
 /// Channel types for CODEC
@@ -117,18 +272,108 @@ pub type InboundSender = mpsc::UnboundedSender<InboundMessage>;
 pub type InboundReceiver = mpsc::UnboundedReceiver<InboundMessage>;
 pub type OutboundSender = mpsc::UnboundedSender<OutboundMessage>;
 pub type OutboundReceiver = mpsc::UnboundedReceiver<OutboundMessage>;
+pub type BoundedInboundSender = mpsc::Sender<InboundMessage>;
+pub type BoundedInboundReceiver = mpsc::Receiver<InboundMessage>;
+
+/// Why an inbound message could not be queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusError {
+    /// The receiving side has been dropped
+    Closed,
+    /// A bounded queue is at capacity
+    Full,
+}
+
+impl std::fmt::Display for BusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BusError::Closed => write!(f, "◆ CODEC CHANNEL CLOSED"),
+            BusError::Full => write!(f, "◆ CODEC CHANNEL SATURATED"),
+        }
+    }
+}
+
+impl std::error::Error for BusError {}
+
+/// Sending side of the inbound queue
+#[derive(Debug, Clone)]
+enum InboundTx {
+    Unbounded(InboundSender),
+    Bounded(BoundedInboundSender),
+}
+
+/// Receiving side of either kind of inbound queue
+#[derive(Debug)]
+pub enum InboundQueue {
+    Unbounded(InboundReceiver),
+    Bounded(BoundedInboundReceiver),
+}
+
+impl InboundQueue {
+    /// Next inbound message, or `None` once every sender is gone
+    pub async fn recv(&mut self) -> Option<InboundMessage> {
+        match self {
+            InboundQueue::Unbounded(rx) => rx.recv().await,
+            InboundQueue::Bounded(rx) => rx.recv().await,
+        }
+    }
+
+    /// Next inbound message if one is already queued
+    pub fn try_recv(&mut self) -> Result<InboundMessage, mpsc::error::TryRecvError> {
+        match self {
+            InboundQueue::Unbounded(rx) => rx.try_recv(),
+            InboundQueue::Bounded(rx) => rx.try_recv(),
+        }
+    }
+}
+
+impl From<InboundReceiver> for InboundQueue {
+    fn from(rx: InboundReceiver) -> Self {
+        InboundQueue::Unbounded(rx)
+    }
+}
+
+impl From<BoundedInboundReceiver> for InboundQueue {
+    fn from(rx: BoundedInboundReceiver) -> Self {
+        InboundQueue::Bounded(rx)
+    }
+}
 
 /// CODEC communications bus
 #[derive(Debug, Clone)]
 pub struct MessageBus {
-    inbound: InboundSender,
+    inbound: InboundTx,
     outbound: OutboundSender,
+    metrics: BusMetrics,
+    stats: GatewayStats,
+    policy: Arc<InboundPolicy>,
 }
 
 impl MessageBus {
     /// Initialize CODEC with channels
     pub fn new(inbound: InboundSender, outbound: OutboundSender) -> Self {
-        Self { inbound, outbound }
+        Self::with_inbound(InboundTx::Unbounded(inbound), outbound)
+    }
+
+    fn with_inbound(inbound: InboundTx, outbound: OutboundSender) -> Self {
+        Self {
+            inbound,
+            outbound,
+            metrics: BusMetrics::new(),
+            stats: GatewayStats::new(),
+            policy: Arc::new(InboundPolicy::default()),
+        }
+    }
+
+    /// Apply a cross-channel inbound policy on top of per-channel checks
+    pub fn with_inbound_policy(mut self, policy: InboundPolicy) -> Self {
+        self.policy = Arc::new(policy);
+        self
+    }
+
+    /// Active inbound policy
+    pub fn inbound_policy(&self) -> &InboundPolicy {
+        &self.policy
     }
 
     /// Establish new CODEC frequency
@@ -139,14 +384,78 @@ impl MessageBus {
         (Self::new(in_tx, out_tx), in_rx, out_rx)
     }
 
+    /// Establish a CODEC frequency holding at most `capacity` inbound messages
+    ///
+    /// Once full, `publish_inbound` fails with `BusError::Full` and
+    /// `publish_inbound_wait` waits for room. Outbound stays unbounded.
+    pub fn bounded(capacity: usize) -> (Self, BoundedInboundReceiver, OutboundReceiver) {
+        let (in_tx, in_rx) = mpsc::channel(capacity.max(1));
+        let (out_tx, out_rx) = mpsc::unbounded_channel();
+
+        (
+            Self::with_inbound(InboundTx::Bounded(in_tx), out_tx),
+            in_rx,
+            out_rx,
+        )
+    }
+
+    /// Inbound queue capacity, or `None` if unbounded
+    pub fn inbound_capacity(&self) -> Option<usize> {
+        match &self.inbound {
+            InboundTx::Unbounded(_) => None,
+            InboundTx::Bounded(tx) => Some(tx.max_capacity()),
+        }
+    }
+
     /// Transmit to operative
-    #[allow(clippy::result_large_err)]
-    pub fn publish_inbound(
-        &self,
-        msg: InboundMessage,
-    ) -> Result<(), mpsc::error::SendError<InboundMessage>> {
+    ///
+    /// Messages rejected by the inbound policy are dropped and counted as
+    /// unauthorized. A saturated bounded bus fails with `BusError::Full`.
+    pub fn publish_inbound(&self, msg: InboundMessage) -> Result<(), BusError> {
+        if !self.admit(&msg) {
+            return Ok(());
+        }
+        let channel = msg.channel.clone();
+        match &self.inbound {
+            InboundTx::Unbounded(tx) => tx.send(msg).map_err(|_| BusError::Closed)?,
+            InboundTx::Bounded(tx) => tx.try_send(msg).map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => BusError::Full,
+                mpsc::error::TrySendError::Closed(_) => BusError::Closed,
+            })?,
+        }
+        self.stats.record_inbound(&channel);
+        Ok(())
+    }
+
+    /// Transmit to operative, waiting for room on a saturated bounded bus
+    ///
+    /// This is the backpressure path: a channel awaiting it stops reading
+    /// from its source until the operative catches up.
+    pub async fn publish_inbound_wait(&self, msg: InboundMessage) -> Result<(), BusError> {
+        if !self.admit(&msg) {
+            return Ok(());
+        }
+        let channel = msg.channel.clone();
+        match &self.inbound {
+            InboundTx::Unbounded(tx) => tx.send(msg).map_err(|_| BusError::Closed)?,
+            InboundTx::Bounded(tx) => tx.send(msg).await.map_err(|_| BusError::Closed)?,
+        }
+        self.stats.record_inbound(&channel);
+        Ok(())
+    }
+
+    /// Apply the inbound policy, counting rejections
+    fn admit(&self, msg: &InboundMessage) -> bool {
+        if let Err(reason) = self.policy.check(msg) {
+            debug!(
+                "◆ INBOUND BLOCKED: {} -> {} ({})",
+                msg.sender_id, msg.channel, reason
+            );
+            self.metrics.record_dropped_unauthorized(&msg.channel);
+            return false;
+        }
         trace!("◆ INBOUND: {} -> {}", msg.sender_id, msg.channel);
-        self.inbound.send(msg)
+        true
     }
 
     /// Transmit to command
@@ -163,12 +472,100 @@ impl MessageBus {
     pub fn outbound_sender(&self) -> OutboundSender {
         self.outbound.clone()
     }
+
+    /// Shared telemetry counters
+    pub fn metrics(&self) -> &BusMetrics {
+        &self.metrics
+    }
+
+    /// Shared gateway traffic counters
+    pub fn stats(&self) -> &GatewayStats {
+        &self.stats
+    }
 }
 
+/// Outcome of handing one transmission to its channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryReport {
+    /// `correlation_id` of the transmission
+    pub correlation_id: Option<String>,
+    /// Target frequency
+    pub channel: String,
+    /// Secure channel ID
+    pub chat_id: String,
+    /// `Err` carries the failure reason
+    pub result: Result<(), String>,
+}
+
+impl DeliveryReport {
+    fn new(msg: &OutboundMessage, result: Result<(), String>) -> Self {
+        Self {
+            correlation_id: msg.correlation_id.clone(),
+            channel: msg.channel.clone(),
+            chat_id: msg.chat_id.clone(),
+            result,
+        }
+    }
+
+    /// Whether the transmission was delivered
+    pub fn is_delivered(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// A handler is already registered for the channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateHandler(pub String);
+
+impl std::fmt::Display for DuplicateHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "◆ FREQUENCY '{}' ALREADY HAS A HANDLER", self.0)
+    }
+}
+
+impl std::error::Error for DuplicateHandler {}
+
+/// Whether `next` may be appended to the batch started by `batch`
+fn can_batch(batch: &OutboundMessage, next: &OutboundMessage) -> bool {
+    next.channel == batch.channel
+        && next.chat_id == batch.chat_id
+        && next.media.is_empty()
+        && next.reply_to.is_none()
+        && next.correlation_id.is_none()
+        && batch.correlation_id.is_none()
+}
+
+/// Stops a running dispatcher from outside its task
+#[derive(Debug, Clone, Default)]
+pub struct DispatcherStop(CancellationToken);
+
+impl DispatcherStop {
+    /// Stop dispatching; queued and later messages are not delivered
+    pub fn stop(&self) {
+        self.0.cancel();
+    }
+
+    /// Whether `stop` has been called
+    pub fn is_stopped(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+type OutboundHandler = Box<dyn Fn(OutboundMessage) + Send + Sync>;
+
 /// CODEC dispatcher for routing
 pub struct OutboundDispatcher {
     receiver: OutboundReceiver,
-    handlers: HashMap<String, Box<dyn Fn(OutboundMessage) + Send + Sync>>,
+    /// Handlers per channel, called in registration order
+    handlers: HashMap<String, Vec<OutboundHandler>>,
+    /// Called for channels with no handler of their own
+    fallback: Option<OutboundHandler>,
+    reports: Option<mpsc::UnboundedSender<DeliveryReport>>,
+    stop: DispatcherStop,
+    batch_window: Option<Duration>,
+    /// Message that ended the previous batch, dispatched next
+    pending: Option<OutboundMessage>,
+    stats: Option<GatewayStats>,
 }
 
 impl OutboundDispatcher {
@@ -177,45 +574,234 @@ impl OutboundDispatcher {
         Self {
             receiver,
             handlers: HashMap::new(),
+            fallback: None,
+            reports: None,
+            stop: DispatcherStop::default(),
+            batch_window: None,
+            pending: None,
+            stats: None,
         }
     }
 
-    /// Register frequency handler
+    /// Merge consecutive text messages to one chat that arrive within `window`
+    ///
+    /// Media, a `reply_to`, a correlation id, or a different chat ends the
+    /// batch; that message then starts the next one.
+    pub fn with_batching(mut self, window: Duration) -> Self {
+        self.batch_window = Some(window);
+        self
+    }
+
+    /// Count each delivered message in `stats`
+    ///
+    /// Pass the bus's own counters so inbound and outbound land together.
+    pub fn with_stats(mut self, stats: GatewayStats) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Handle that ends `run` or `run_async` from another task
+    ///
+    /// Take it before the dispatcher is moved into its loop.
+    pub fn stop_handle(&self) -> DispatcherStop {
+        self.stop.clone()
+    }
+
+    /// Drop every registered handler along with whatever it captured
+    pub fn clear_handlers(&mut self) {
+        self.handlers.clear();
+        self.fallback = None;
+    }
+
+    /// Report the outcome of each async delivery on `reports`
+    pub fn with_delivery_reports(mut self, reports: mpsc::UnboundedSender<DeliveryReport>) -> Self {
+        self.reports = Some(reports);
+        self
+    }
+
+    /// Register frequency handler alongside any existing ones
+    ///
+    /// Every handler on a channel receives each message, in the order they
+    /// were registered.
     pub fn on_channel<F>(&mut self, channel: impl Into<String>, handler: F)
     where
         F: Fn(OutboundMessage) + Send + Sync + 'static,
     {
-        self.handlers.insert(channel.into(), Box::new(handler));
+        self.handlers
+            .entry(channel.into())
+            .or_default()
+            .push(Box::new(handler));
+    }
+
+    /// Register the handler for channels without one, replacing any previous
+    ///
+    /// Useful for catch-all routing or a dead-letter sink; channels with
+    /// their own handlers never reach it.
+    pub fn on_default<F>(&mut self, handler: F)
+    where
+        F: Fn(OutboundMessage) + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+    }
+
+    /// Register the first frequency handler, refusing if one already exists
+    pub fn try_on_channel<F>(
+        &mut self,
+        channel: impl Into<String>,
+        handler: F,
+    ) -> Result<(), DuplicateHandler>
+    where
+        F: Fn(OutboundMessage) + Send + Sync + 'static,
+    {
+        match self.handlers.entry(channel.into()) {
+            std::collections::hash_map::Entry::Occupied(entry) => {
+                Err(DuplicateHandler(entry.key().clone()))
+            }
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(vec![Box::new(handler)]);
+                Ok(())
+            }
+        }
+    }
+
+    /// Channels with a registered handler, sorted by name
+    ///
+    /// `run` consumes the dispatcher, so capture this beforehand.
+    pub fn registered_channels(&self) -> Vec<String> {
+        let mut channels: Vec<String> = self.handlers.keys().cloned().collect();
+        channels.sort();
+        channels
     }
 
     /// Execute dispatch loop
+    ///
+    /// Ends when the bus closes or the stop handle fires; handlers are
+    /// cleared on the way out.
     pub async fn run(mut self) {
         debug!("◆ CODEC DISPATCHER ONLINE");
 
-        while let Some(msg) = self.receiver.recv().await {
-            if let Some(handler) = self.handlers.get(&msg.channel) {
-                handler(msg);
+        while let Some(msg) = self.next().await {
+            if let Some((last, rest)) = self
+                .handlers
+                .get(&msg.channel)
+                .and_then(|handlers| handlers.split_last())
+            {
+                if let Some(stats) = &self.stats {
+                    stats.record_outbound(&msg.channel);
+                }
+                for handler in rest {
+                    handler(msg.clone());
+                }
+                last(msg);
+            } else if let Some(fallback) = &self.fallback {
+                trace!("◆ DEFAULT ROUTE FOR {}", msg.channel);
+                if let Some(stats) = &self.stats {
+                    stats.record_outbound(&msg.channel);
+                }
+                fallback(msg);
             } else {
                 error!("◆ UNKNOWN FREQUENCY: {}", msg.channel);
             }
         }
 
+        self.clear_handlers();
         debug!("◆ CODEC DISPATCHER OFFLINE");
     }
 
+    /// Next message to dispatch, or `None` once closed or stopped
+    async fn next(&mut self) -> Option<OutboundMessage> {
+        let mut msg = match self.pending.take() {
+            Some(msg) => msg,
+            None => self.recv().await?,
+        };
+        let Some(window) = self.batch_window else {
+            return Some(msg);
+        };
+        if !msg.media.is_empty() {
+            return Some(msg);
+        }
+
+        let deadline = tokio::time::Instant::now() + window;
+        while let Ok(Some(next)) = tokio::time::timeout_at(deadline, self.recv()).await {
+            if !can_batch(&msg, &next) {
+                self.pending = Some(next);
+                break;
+            }
+            trace!("◆ BATCHING TRANSMISSION TO {}", msg.chat_id);
+            msg.content = format!("{}\n\n{}", msg.content, next.content);
+            msg.metadata.extend(next.metadata);
+        }
+        Some(msg)
+    }
+
+    /// Next message off the bus, or `None` once closed or stopped
+    async fn recv(&mut self) -> Option<OutboundMessage> {
+        let stop = self.stop.0.clone();
+        tokio::select! {
+            biased;
+            _ = stop.cancelled() => None,
+            msg = self.receiver.recv() => msg,
+        }
+    }
+
     /// Async dispatch loop
-    pub async fn run_async<F, Fut>(mut self, handler: F)
+    ///
+    /// A handler that completes counts as delivered.
+    pub async fn run_async<F, Fut>(self, handler: F)
     where
         F: Fn(OutboundMessage) -> Fut + Send + Sync + 'static,
         Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.run_async_confirmed(move |msg| {
+            let fut = handler(msg);
+            async move {
+                fut.await;
+                Ok::<(), String>(())
+            }
+        })
+        .await
+    }
+
+    /// Async dispatch loop whose handler reports success or failure
+    ///
+    /// Each outcome is sent as a `DeliveryReport` if reports are enabled.
+    /// Once the loop ends, in-flight deliveries are awaited before it
+    /// returns; aborting the task running the loop cancels them instead.
+    pub async fn run_async_confirmed<F, Fut, E>(mut self, handler: F)
+    where
+        F: Fn(OutboundMessage) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<(), E>> + Send + 'static,
+        E: std::fmt::Display,
     {
         debug!("◆ CODEC DISPATCHER ONLINE (ASYNC)");
+        let mut in_flight = JoinSet::new();
 
-        while let Some(msg) = self.receiver.recv().await {
+        while let Some(msg) = self.next().await {
+            // Reap finished deliveries so the set stays small
+            while in_flight.try_join_next().is_some() {}
+
+            let reports = self.reports.clone();
+            let stats = self.stats.clone();
+            let pending = DeliveryReport::new(&msg, Ok(()));
             let fut = handler(msg);
-            tokio::spawn(fut);
+            in_flight.spawn(async move {
+                let result = fut.await.map_err(|e| e.to_string());
+                match &result {
+                    Ok(()) => {
+                        if let Some(stats) = stats {
+                            stats.record_outbound(&pending.channel);
+                        }
+                    }
+                    Err(e) => error!("◆ DELIVERY FAILED ON {}: {}", pending.channel, e),
+                }
+                if let Some(reports) = reports {
+                    let _ = reports.send(DeliveryReport { result, ..pending });
+                }
+            });
         }
 
+        drop(handler);
+        while in_flight.join_next().await.is_some() {}
         debug!("◆ CODEC DISPATCHER OFFLINE");
     }
 }
@@ -250,6 +836,27 @@ mod tests {
         assert_eq!(msg2.session_key(), "secure-channel:thread-123");
     }
 
+    #[test]
+    fn test_inbound_message_is_stale_outside_window() {
+        let now = Local::now();
+        let msg = InboundMessage::new("radio", "agent-007", "chat-001", "Old intel")
+            .with_timestamp(now - chrono::Duration::hours(3));
+
+        assert!(msg.is_stale(std::time::Duration::from_secs(600), now));
+    }
+
+    #[test]
+    fn test_inbound_message_not_stale_inside_window() {
+        let now = Local::now();
+        let recent = InboundMessage::new("radio", "agent-007", "chat-001", "Fresh intel")
+            .with_timestamp(now - chrono::Duration::seconds(30));
+        let future = InboundMessage::new("radio", "agent-007", "chat-001", "Skewed clock")
+            .with_timestamp(now + chrono::Duration::seconds(30));
+
+        assert!(!recent.is_stale(std::time::Duration::from_secs(600), now));
+        assert!(!future.is_stale(std::time::Duration::from_secs(600), now));
+    }
+
     #[test]
     fn test_inbound_message_with_media() {
         let msg = InboundMessage::new("radio", "agent-007", "chat-001", "Photo attached")
@@ -649,4 +1256,109 @@ mod tests {
         assert_eq!(msg.metadata.get("key_50").unwrap(), &json!("value_50"));
         assert_eq!(msg.metadata.get("key_99").unwrap(), &json!("value_99"));
     }
+
+    #[test]
+    fn test_inbound_reply_to_metadata() {
+        let msg = InboundMessage::new("radio", "agent-007", "chat-001", "Agreed").with_reply_to(
+            42,
+            Some("Move at dawn?"),
+            Some("Command"),
+        );
+
+        assert_eq!(msg.metadata[REPLY_TO_ID_KEY], json!(42));
+        assert_eq!(msg.replied_text(), Some("Move at dawn?"));
+        assert_eq!(msg.replied_sender(), Some("Command"));
+
+        let plain = InboundMessage::new("radio", "agent-007", "chat-001", "Hi");
+        assert_eq!(plain.replied_text(), None);
+    }
+
+    #[test]
+    fn test_metadata_growth_capped() {
+        let mut msg = InboundMessage::new("test", "user", "chat", "content")
+            .with_metadata_limit(MetadataLimit::new(10, usize::MAX));
+        for i in 0..50 {
+            msg = msg.with_metadata(format!("key_{}", i), i);
+        }
+
+        assert_eq!(msg.metadata.len(), 10);
+        // The earliest entries are kept
+        assert_eq!(msg.metadata.get("key_0").unwrap(), &json!(0));
+        assert_eq!(msg.metadata.get("key_9").unwrap(), &json!(9));
+        assert!(!msg.metadata.contains_key("key_10"));
+    }
+
+    #[test]
+    fn test_outbound_metadata_capped() {
+        let msg = OutboundMessage::new("test", "chat", "content")
+            .with_metadata_limit(MetadataLimit::new(1, usize::MAX))
+            .with_metadata("first", true)
+            .with_metadata("second", true);
+
+        assert_eq!(msg.metadata.len(), 1);
+        assert!(msg.metadata.contains_key("first"));
+    }
+
+    #[tokio::test]
+    async fn test_delivery_reports_success_and_failure() {
+        let (bus, _in_rx, out_rx) = MessageBus::channels();
+        let (report_tx, mut report_rx) = mpsc::unbounded_channel();
+
+        let dispatcher = OutboundDispatcher::new(out_rx).with_delivery_reports(report_tx);
+        tokio::spawn(
+            dispatcher.run_async_confirmed(|msg: OutboundMessage| async move {
+                if msg.content == "fail" {
+                    Err("chat not found")
+                } else {
+                    Ok(())
+                }
+            }),
+        );
+
+        bus.publish_outbound(
+            OutboundMessage::new("radio", "chat-1", "ok").with_correlation_id("a"),
+        )
+        .unwrap();
+        bus.publish_outbound(
+            OutboundMessage::new("radio", "chat-2", "fail").with_correlation_id("b"),
+        )
+        .unwrap();
+
+        let mut reports = Vec::new();
+        for _ in 0..2 {
+            let report = tokio::time::timeout(std::time::Duration::from_secs(1), report_rx.recv())
+                .await
+                .expect("report in time")
+                .unwrap();
+            reports.push(report);
+        }
+        reports.sort_by(|a, b| a.correlation_id.cmp(&b.correlation_id));
+
+        assert_eq!(reports[0].correlation_id.as_deref(), Some("a"));
+        assert!(reports[0].is_delivered());
+        assert_eq!(reports[1].correlation_id.as_deref(), Some("b"));
+        assert_eq!(reports[1].chat_id, "chat-2");
+        assert_eq!(reports[1].result, Err("chat not found".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_run_async_reports_completion() {
+        let (bus, _in_rx, out_rx) = MessageBus::channels();
+        let (report_tx, mut report_rx) = mpsc::unbounded_channel();
+
+        let dispatcher = OutboundDispatcher::new(out_rx).with_delivery_reports(report_tx);
+        tokio::spawn(dispatcher.run_async(|_msg| async {}));
+
+        bus.publish_outbound(
+            OutboundMessage::new("radio", "chat-1", "hi").with_correlation_id("c"),
+        )
+        .unwrap();
+
+        let report = tokio::time::timeout(std::time::Duration::from_secs(1), report_rx.recv())
+            .await
+            .expect("report in time")
+            .unwrap();
+        assert_eq!(report.correlation_id.as_deref(), Some("c"));
+        assert!(report.is_delivered());
+    }
 }
@@ -0,0 +1,118 @@
+//! Bounds on operational metadata
+//!
+//! Metadata maps are persisted with sessions, so a misbehaving integration
+//! attaching keys in a loop would bloat every file it touches. Inserts past
+//! the limit are dropped; entries already present are kept.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Default maximum number of metadata entries per message
+pub const DEFAULT_MAX_METADATA_ENTRIES: usize = 256;
+
+/// Default maximum serialized size of all metadata values, in bytes
+pub const DEFAULT_MAX_METADATA_BYTES: usize = 64 * 1024;
+
+/// Limits applied when inserting metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataLimit {
+    /// Maximum number of keys
+    pub max_entries: usize,
+    /// Maximum combined size of keys and serialized values
+    pub max_bytes: usize,
+}
+
+impl Default for MetadataLimit {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_METADATA_ENTRIES,
+            max_bytes: DEFAULT_MAX_METADATA_BYTES,
+        }
+    }
+}
+
+impl MetadataLimit {
+    /// Create a limit
+    pub fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            max_entries,
+            max_bytes,
+        }
+    }
+
+    /// Insert `value` under `key` if the map stays within the limit
+    ///
+    /// Replacing an existing key does not count as a new entry. Returns
+    /// whether the value was stored.
+    pub fn insert(&self, map: &mut HashMap<String, Value>, key: String, value: Value) -> bool {
+        let replaced = map.get(&key);
+        if replaced.is_none() && map.len() >= self.max_entries {
+            warn!(
+                "◆ METADATA CAP REACHED ({} ENTRIES), DROPPING '{}'",
+                self.max_entries, key
+            );
+            return false;
+        }
+
+        let current = size_of(map) - replaced.map_or(0, |old| entry_size(&key, old));
+        if current + entry_size(&key, &value) > self.max_bytes {
+            warn!(
+                "◆ METADATA CAP REACHED ({} BYTES), DROPPING '{}'",
+                self.max_bytes, key
+            );
+            return false;
+        }
+
+        map.insert(key, value);
+        true
+    }
+}
+
+fn entry_size(key: &str, value: &Value) -> usize {
+    key.len() + value.to_string().len()
+}
+
+fn size_of(map: &HashMap<String, Value>) -> usize {
+    map.iter().map(|(k, v)| entry_size(k, v)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_insert_stops_at_entry_cap() {
+        let limit = MetadataLimit::new(2, usize::MAX);
+        let mut map = HashMap::new();
+
+        assert!(limit.insert(&mut map, "a".into(), json!(1)));
+        assert!(limit.insert(&mut map, "b".into(), json!(2)));
+        assert!(!limit.insert(&mut map, "c".into(), json!(3)));
+
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key("a"));
+        assert!(!map.contains_key("c"));
+    }
+
+    #[test]
+    fn test_replacing_key_allowed_at_cap() {
+        let limit = MetadataLimit::new(1, usize::MAX);
+        let mut map = HashMap::new();
+
+        assert!(limit.insert(&mut map, "a".into(), json!(1)));
+        assert!(limit.insert(&mut map, "a".into(), json!(2)));
+        assert_eq!(map["a"], json!(2));
+    }
+
+    #[test]
+    fn test_insert_stops_at_byte_cap() {
+        let limit = MetadataLimit::new(usize::MAX, 16);
+        let mut map = HashMap::new();
+
+        assert!(limit.insert(&mut map, "k".into(), json!("short")));
+        assert!(!limit.insert(&mut map, "big".into(), json!("x".repeat(32))));
+        assert_eq!(map.len(), 1);
+    }
+}
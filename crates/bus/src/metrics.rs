@@ -0,0 +1,78 @@
+//! CODEC telemetry counters
+//!
+//! Shared between every clone of a [`MessageBus`](crate::MessageBus) so channels
+//! can record events that never make it onto the bus itself.
+
+use crate::stats::GatewayStatsSnapshot;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Point-in-time copy of the bus counters, keyed by channel
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// Inbound messages rejected by a channel allow-list
+    #[serde(default)]
+    pub inbound_dropped_unauthorized: HashMap<String, u64>,
+    /// Gateway traffic, filled in by the running gateway
+    #[serde(default)]
+    pub gateway: GatewayStatsSnapshot,
+}
+
+impl MetricsSnapshot {
+    /// Load a snapshot written by [`MetricsSnapshot::save`]
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Persist the snapshot as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, content)
+    }
+}
+
+/// Shared CODEC counters
+#[derive(Debug, Clone, Default)]
+pub struct BusMetrics {
+    inner: Arc<Mutex<MetricsSnapshot>>,
+}
+
+impl BusMetrics {
+    /// Create empty counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count an inbound message rejected as unauthorized on `channel`
+    pub fn record_dropped_unauthorized(&self, channel: &str) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        *inner
+            .inbound_dropped_unauthorized
+            .entry(channel.to_string())
+            .or_insert(0) += 1;
+    }
+
+    /// Unauthorized drops recorded for `channel`
+    pub fn dropped_unauthorized(&self, channel: &str) -> u64 {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner
+            .inbound_dropped_unauthorized
+            .get(channel)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Copy the current counters
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
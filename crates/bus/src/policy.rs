@@ -0,0 +1,122 @@
+//! Cross-channel inbound policy
+//!
+//! Per-channel allow-lists decide who may reach a transport; this policy
+//! is consulted by the bus itself, so a block applies on every frequency.
+
+use std::collections::HashSet;
+
+use crate::InboundMessage;
+
+/// Shared allow/block rules applied before a message reaches the operative
+///
+/// Sender entries match either a bare id (`"42"`) on any channel or a
+/// channel-scoped id (`"telegram:42"`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InboundPolicy {
+    blocked_senders: HashSet<String>,
+    blocked_channels: HashSet<String>,
+    allowed_senders: HashSet<String>,
+}
+
+impl InboundPolicy {
+    /// Policy that admits everything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject `sender` regardless of transport
+    pub fn block_sender(mut self, sender: impl Into<String>) -> Self {
+        self.blocked_senders.insert(sender.into());
+        self
+    }
+
+    /// Reject every message arriving on `channel`
+    pub fn block_channel(mut self, channel: impl Into<String>) -> Self {
+        self.blocked_channels.insert(channel.into());
+        self
+    }
+
+    /// Admit only listed senders (an empty list admits everyone)
+    pub fn allow_sender(mut self, sender: impl Into<String>) -> Self {
+        self.allowed_senders.insert(sender.into());
+        self
+    }
+
+    /// True when no rule is configured
+    pub fn is_open(&self) -> bool {
+        self.blocked_senders.is_empty()
+            && self.blocked_channels.is_empty()
+            && self.allowed_senders.is_empty()
+    }
+
+    /// Check `msg` against the policy, returning the rejection reason
+    pub fn check(&self, msg: &InboundMessage) -> Result<(), &'static str> {
+        if self.blocked_channels.contains(&msg.channel) {
+            return Err("channel blocked");
+        }
+        if Self::matches(&self.blocked_senders, msg) {
+            return Err("sender blocked");
+        }
+        if !self.allowed_senders.is_empty() && !Self::matches(&self.allowed_senders, msg) {
+            return Err("sender not allowed");
+        }
+        Ok(())
+    }
+
+    /// Whether `msg` passes the policy
+    pub fn allows(&self, msg: &InboundMessage) -> bool {
+        self.check(msg).is_ok()
+    }
+
+    fn matches(set: &HashSet<String>, msg: &InboundMessage) -> bool {
+        set.contains(&msg.sender_id) || set.contains(&format!("{}:{}", msg.channel, msg.sender_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(channel: &str, sender: &str) -> InboundMessage {
+        InboundMessage::new(channel, sender, "chat", "hello")
+    }
+
+    #[test]
+    fn test_open_policy_allows_all() {
+        let policy = InboundPolicy::new();
+        assert!(policy.is_open());
+        assert!(policy.allows(&msg("telegram", "42")));
+    }
+
+    #[test]
+    fn test_blocked_sender_any_channel() {
+        let policy = InboundPolicy::new().block_sender("42");
+        assert_eq!(policy.check(&msg("telegram", "42")), Err("sender blocked"));
+        assert!(!policy.allows(&msg("cli", "42")));
+        assert!(policy.allows(&msg("telegram", "7")));
+    }
+
+    #[test]
+    fn test_scoped_sender_only_on_its_channel() {
+        let policy = InboundPolicy::new().block_sender("telegram:42");
+        assert!(!policy.allows(&msg("telegram", "42")));
+        assert!(policy.allows(&msg("cli", "42")));
+    }
+
+    #[test]
+    fn test_blocked_channel() {
+        let policy = InboundPolicy::new().block_channel("telegram");
+        assert_eq!(policy.check(&msg("telegram", "7")), Err("channel blocked"));
+        assert!(policy.allows(&msg("cli", "7")));
+    }
+
+    #[test]
+    fn test_allow_list_restricts_senders() {
+        let policy = InboundPolicy::new().allow_sender("7").block_sender("7");
+        assert!(!policy.allows(&msg("cli", "7")), "block wins over allow");
+
+        let policy = InboundPolicy::new().allow_sender("7");
+        assert!(policy.allows(&msg("cli", "7")));
+        assert_eq!(policy.check(&msg("cli", "8")), Err("sender not allowed"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Gateway traffic telemetry
+//!
+//! Totals are plain atomics; per-channel counts sit behind one lock so a
+//! snapshot never sees a channel half-updated.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Messages seen on one channel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelTraffic {
+    /// Messages received from the channel
+    #[serde(default)]
+    pub inbound: u64,
+    /// Messages delivered to the channel
+    #[serde(default)]
+    pub outbound: u64,
+}
+
+/// Point-in-time copy of [`GatewayStats`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GatewayStatsSnapshot {
+    /// Traffic keyed by channel
+    #[serde(default)]
+    pub channels: HashMap<String, ChannelTraffic>,
+    /// Completed agent turns
+    #[serde(default)]
+    pub turns: u64,
+    /// Tool calls executed
+    #[serde(default)]
+    pub tool_executions: u64,
+    /// Failed provider requests
+    #[serde(default)]
+    pub provider_errors: u64,
+    /// Summed turn latency
+    #[serde(default)]
+    pub latency_ms_total: u64,
+}
+
+impl GatewayStatsSnapshot {
+    /// Inbound messages across every channel
+    pub fn inbound_total(&self) -> u64 {
+        self.channels.values().map(|t| t.inbound).sum()
+    }
+
+    /// Outbound messages across every channel
+    pub fn outbound_total(&self) -> u64 {
+        self.channels.values().map(|t| t.outbound).sum()
+    }
+
+    /// Mean turn latency, zero before the first turn
+    pub fn average_latency_ms(&self) -> u64 {
+        self.latency_ms_total.checked_div(self.turns).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    channels: Mutex<HashMap<String, ChannelTraffic>>,
+    turns: AtomicU64,
+    tool_executions: AtomicU64,
+    provider_errors: AtomicU64,
+    latency_ms_total: AtomicU64,
+}
+
+/// Shared gateway counters
+///
+/// Cheap to clone; every clone updates the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct GatewayStats {
+    inner: Arc<Inner>,
+}
+
+impl GatewayStats {
+    /// Create empty counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn channel(&self, channel: &str, update: impl FnOnce(&mut ChannelTraffic)) {
+        let mut channels = self
+            .inner
+            .channels
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        update(channels.entry(channel.to_string()).or_default());
+    }
+
+    /// Count a message received on `channel`
+    pub fn record_inbound(&self, channel: &str) {
+        self.channel(channel, |t| t.inbound += 1);
+    }
+
+    /// Count a message delivered to `channel`
+    pub fn record_outbound(&self, channel: &str) {
+        self.channel(channel, |t| t.outbound += 1);
+    }
+
+    /// Count a finished turn that took `latency`
+    ///
+    /// Latency is added before the turn count so a concurrent snapshot can
+    /// overstate the average briefly but never divide a turn by zero time.
+    pub fn record_turn(&self, latency: Duration) {
+        let ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+        self.inner.latency_ms_total.fetch_add(ms, Ordering::Relaxed);
+        self.inner.turns.fetch_add(1, Ordering::Release);
+    }
+
+    /// Count an executed tool call
+    pub fn record_tool_execution(&self) {
+        self.inner.tool_executions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a failed provider request
+    pub fn record_provider_error(&self) {
+        self.inner.provider_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Copy the current counters
+    pub fn snapshot(&self) -> GatewayStatsSnapshot {
+        let turns = self.inner.turns.load(Ordering::Acquire);
+        GatewayStatsSnapshot {
+            channels: self
+                .inner
+                .channels
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+            turns,
+            tool_executions: self.inner.tool_executions.load(Ordering::Relaxed),
+            provider_errors: self.inner.provider_errors.load(Ordering::Relaxed),
+            latency_ms_total: self.inner.latency_ms_total.load(Ordering::Relaxed),
+        }
+    }
+}
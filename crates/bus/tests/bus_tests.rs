@@ -5,8 +5,14 @@
 //! - Publishing messages through the bus
 //! - Cloning and sharing bus instances
 //! - Error handling
+//! - Shared telemetry counters
+//! - Bounded inbound queues
 
-use opensam_bus::{InboundMessage, MessageBus, OutboundMessage};
+use opensam_bus::{
+    BusError, InboundMessage, InboundPolicy, InboundQueue, MessageBus, MetricsSnapshot,
+    OutboundMessage,
+};
+use std::time::Duration;
 
 // ============================================================================
 // Channel Creation Tests
@@ -414,3 +420,150 @@ async fn test_full_message_features_through_bus() {
     assert_eq!(received.media.len(), 2);
     assert_eq!(received.metadata.len(), 3);
 }
+
+// ============================================================================
+// Metrics Tests
+// ============================================================================
+
+#[test]
+fn test_metrics_shared_across_clones() {
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let clone = bus.clone();
+
+    clone.metrics().record_dropped_unauthorized("telegram");
+    clone.metrics().record_dropped_unauthorized("telegram");
+    bus.metrics().record_dropped_unauthorized("radio");
+
+    assert_eq!(bus.metrics().dropped_unauthorized("telegram"), 2);
+    assert_eq!(bus.metrics().dropped_unauthorized("radio"), 1);
+    assert_eq!(bus.metrics().dropped_unauthorized("unknown"), 0);
+}
+
+#[test]
+fn test_metrics_snapshot_roundtrip() {
+    let dir = tempfile::tempdir().expect("Should create temp dir");
+    let path = dir.path().join("nested").join("metrics.json");
+
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    bus.metrics().record_dropped_unauthorized("telegram");
+
+    let snapshot = bus.metrics().snapshot();
+    snapshot.save(&path).expect("Should save snapshot");
+
+    let loaded = MetricsSnapshot::load(&path).expect("Should load snapshot");
+    assert_eq!(loaded, snapshot);
+    assert_eq!(
+        loaded.inbound_dropped_unauthorized.get("telegram"),
+        Some(&1)
+    );
+}
+
+#[tokio::test]
+async fn test_inbound_policy_drops_blocked_messages() {
+    let (bus, mut in_rx, _out_rx) = MessageBus::channels();
+    let bus = bus.with_inbound_policy(InboundPolicy::new().block_channel("radio"));
+    let clone = bus.clone();
+
+    clone
+        .publish_inbound(InboundMessage::new("radio", "snake", "c1", "blocked"))
+        .unwrap();
+    clone
+        .publish_inbound(InboundMessage::new("telegram", "snake", "c1", "through"))
+        .unwrap();
+
+    assert_eq!(in_rx.recv().await.unwrap().content, "through");
+    assert!(in_rx.try_recv().is_err());
+    assert_eq!(bus.metrics().dropped_unauthorized("radio"), 1);
+}
+
+// ============================================================================
+// Bounded Inbound Tests
+// ============================================================================
+
+#[test]
+fn test_bounded_bus_rejects_past_capacity() {
+    let (bus, mut in_rx, _out_rx) = MessageBus::bounded(2);
+    assert_eq!(bus.inbound_capacity(), Some(2));
+
+    for i in 0..2 {
+        bus.publish_inbound(InboundMessage::new("ch", "u", "c", format!("msg-{}", i)))
+            .expect("Should publish");
+    }
+    let result = bus.publish_inbound(InboundMessage::new("ch", "u", "c", "overflow"));
+    assert_eq!(result, Err(BusError::Full));
+    // Only accepted messages are counted
+    assert_eq!(bus.stats().snapshot().inbound_total(), 2);
+
+    // Draining one frees a slot
+    assert_eq!(in_rx.try_recv().unwrap().content, "msg-0");
+    bus.publish_inbound(InboundMessage::new("ch", "u", "c", "msg-2"))
+        .expect("Should publish after drain");
+}
+
+#[test]
+fn test_unbounded_bus_has_no_capacity() {
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    assert_eq!(bus.inbound_capacity(), None);
+}
+
+#[tokio::test]
+async fn test_bounded_bus_reports_closed() {
+    let (bus, in_rx, _out_rx) = MessageBus::bounded(1);
+    drop(in_rx);
+
+    let msg = InboundMessage::new("ch", "u", "c", "test");
+    assert_eq!(bus.publish_inbound(msg.clone()), Err(BusError::Closed));
+    assert_eq!(bus.publish_inbound_wait(msg).await, Err(BusError::Closed));
+}
+
+#[tokio::test]
+async fn test_bounded_bus_wait_blocks_until_drained() {
+    let (bus, in_rx, _out_rx) = MessageBus::bounded(1);
+    let mut queue = InboundQueue::from(in_rx);
+
+    bus.publish_inbound_wait(InboundMessage::new("ch", "u", "c", "first"))
+        .await
+        .unwrap();
+    let blocked = tokio::time::timeout(
+        Duration::from_millis(50),
+        bus.publish_inbound_wait(InboundMessage::new("ch", "u", "c", "second")),
+    )
+    .await;
+    assert!(blocked.is_err(), "second publish should wait for room");
+
+    let sender = {
+        let bus = bus.clone();
+        tokio::spawn(async move {
+            bus.publish_inbound_wait(InboundMessage::new("ch", "u", "c", "second"))
+                .await
+        })
+    };
+    assert_eq!(queue.recv().await.unwrap().content, "first");
+    sender.await.unwrap().unwrap();
+    assert_eq!(queue.recv().await.unwrap().content, "second");
+}
+
+#[tokio::test]
+async fn test_bounded_bus_delivers_in_order_under_load() {
+    let (bus, in_rx, _out_rx) = MessageBus::bounded(8);
+    let mut queue = InboundQueue::from(in_rx);
+    const COUNT: usize = 500;
+
+    let producer = tokio::spawn(async move {
+        for i in 0..COUNT {
+            bus.publish_inbound_wait(InboundMessage::new("ch", "u", "c", i.to_string()))
+                .await
+                .unwrap();
+        }
+    });
+
+    for i in 0..COUNT {
+        let msg = queue.recv().await.expect("Should receive");
+        assert_eq!(msg.content, i.to_string());
+        if i % 50 == 0 {
+            tokio::task::yield_now().await;
+        }
+    }
+    producer.await.unwrap();
+    assert!(queue.recv().await.is_none());
+}
@@ -6,8 +6,9 @@
 //! - Async dispatch functionality
 //! - Unknown channel handling
 //! - Multiple handlers and concurrent dispatch
+//! - Clearing handlers and stopping at shutdown
 
-use opensam_bus::{MessageBus, OutboundDispatcher, OutboundMessage};
+use opensam_bus::{DuplicateHandler, MessageBus, OutboundDispatcher, OutboundMessage};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -32,6 +33,59 @@ fn test_single_handler_registration() {
     });
 }
 
+#[test]
+fn test_registered_channels() {
+    let (_, _, out_rx) = MessageBus::channels();
+    let mut dispatcher = OutboundDispatcher::new(out_rx);
+    assert!(dispatcher.registered_channels().is_empty());
+
+    dispatcher.on_channel("telegram", |_msg| {});
+    dispatcher.on_channel("discord", |_msg| {});
+
+    assert_eq!(
+        dispatcher.registered_channels(),
+        vec!["discord".to_string(), "telegram".to_string()]
+    );
+}
+
+#[test]
+fn test_registered_channels_shared_channel_listed_once() {
+    let (_, _, out_rx) = MessageBus::channels();
+    let mut dispatcher = OutboundDispatcher::new(out_rx);
+
+    dispatcher.on_channel("alpha", |_msg| {});
+    dispatcher.on_channel("alpha", |_msg| {});
+
+    assert_eq!(dispatcher.registered_channels(), vec!["alpha".to_string()]);
+}
+
+#[tokio::test]
+async fn test_try_on_channel_rejects_duplicate() {
+    let (bus, _in_rx, out_rx) = MessageBus::channels();
+    let mut dispatcher = OutboundDispatcher::new(out_rx);
+    let (tx, mut rx) = mpsc::unbounded_channel::<&'static str>();
+
+    let first = tx.clone();
+    assert!(dispatcher
+        .try_on_channel("alpha", move |_msg| {
+            let _ = first.send("original");
+        })
+        .is_ok());
+    let second = dispatcher.try_on_channel("alpha", move |_msg| {
+        let _ = tx.send("replacement");
+    });
+    assert_eq!(second, Err(DuplicateHandler("alpha".to_string())));
+
+    tokio::spawn(dispatcher.run());
+    bus.publish_outbound(OutboundMessage::new("alpha", "chat", "Hello"))
+        .expect("Should publish");
+
+    let handled = tokio::time::timeout(std::time::Duration::from_millis(100), rx.recv())
+        .await
+        .unwrap();
+    assert_eq!(handled, Some("original"));
+}
+
 #[test]
 fn test_multiple_handler_registration() {
     let (_, _, out_rx) = MessageBus::channels();
@@ -43,7 +97,7 @@ fn test_multiple_handler_registration() {
 }
 
 #[test]
-fn test_handler_overwrite() {
+fn test_handler_stacking() {
     let (_, _, out_rx) = MessageBus::channels();
     let mut dispatcher = OutboundDispatcher::new(out_rx);
 
@@ -52,7 +106,7 @@ fn test_handler_overwrite() {
         println!("First handler");
     });
 
-    // Register second handler for same channel (should overwrite)
+    // Register second handler for same channel (runs after the first)
     dispatcher.on_channel("channel", |_msg| {
         println!("Second handler");
     });
@@ -163,6 +217,65 @@ async fn test_unknown_channel_handling() {
     assert!(no_more.is_err());
 }
 
+#[tokio::test]
+async fn test_default_handler_fires_only_for_unknown_channels() {
+    let (bus, in_rx, out_rx) = MessageBus::channels();
+    drop(in_rx);
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let known = tx.clone();
+
+    let mut dispatcher = OutboundDispatcher::new(out_rx);
+    dispatcher.on_channel("known", move |msg| {
+        let _ = known.send(format!("known: {}", msg.content));
+    });
+    dispatcher.on_default(move |msg| {
+        let _ = tx.send(format!("default[{}]: {}", msg.channel, msg.content));
+    });
+
+    for (channel, content) in [("known", "one"), ("ghost", "two"), ("known", "three")] {
+        bus.publish_outbound(OutboundMessage::new(channel, "chat", content))
+            .unwrap();
+    }
+    drop(bus);
+    dispatcher.run().await;
+
+    let mut handled = Vec::new();
+    while let Some(line) = rx.recv().await {
+        handled.push(line);
+    }
+    assert_eq!(
+        handled,
+        vec!["known: one", "default[ghost]: two", "known: three"]
+    );
+}
+
+#[tokio::test]
+async fn test_default_handler_replaced_and_not_listed() {
+    let (bus, in_rx, out_rx) = MessageBus::channels();
+    drop(in_rx);
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<&'static str>();
+    let first = tx.clone();
+
+    let mut dispatcher = OutboundDispatcher::new(out_rx);
+    dispatcher.on_default(move |_msg| {
+        let _ = first.send("first");
+    });
+    dispatcher.on_default(move |_msg| {
+        let _ = tx.send("second");
+    });
+    assert!(dispatcher.registered_channels().is_empty());
+
+    bus.publish_outbound(OutboundMessage::new("anywhere", "chat", "hi"))
+        .unwrap();
+    drop(bus);
+    dispatcher.run().await;
+
+    assert_eq!(rx.recv().await, Some("second"));
+    assert_eq!(rx.recv().await, None);
+}
+
 #[tokio::test]
 async fn test_message_ordering() {
     let (bus, in_rx, out_rx) = MessageBus::channels();
@@ -331,49 +444,32 @@ async fn test_handler_modifies_external_state() {
 }
 
 #[tokio::test]
-async fn test_multiple_handlers_same_channel_sequential() {
+async fn test_multiple_handlers_same_channel_all_fire() {
     let (bus, in_rx, out_rx) = MessageBus::channels();
     drop(in_rx);
 
-    let (tx1, mut rx1) = mpsc::unbounded_channel::<String>();
-    let (tx2, _rx2) = mpsc::unbounded_channel::<String>();
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let (tx1, tx2) = (tx.clone(), tx);
 
     let mut dispatcher = OutboundDispatcher::new(out_rx);
 
-    // Register first handler
-    dispatcher.on_channel("shared", move |msg| {
-        let _ = tx1.send(format!("handler1: {}", msg.content));
+    // e.g. one handler sends, another logs to disk
+    dispatcher.on_channel("alpha", move |msg| {
+        let _ = tx1.send(format!("send: {}", msg.content));
     });
-
-    // Register second handler - this overwrites the first
-    dispatcher.on_channel("shared", move |msg| {
-        let _ = tx2.send(format!("handler2: {}", msg.content));
+    dispatcher.on_channel("alpha", move |msg| {
+        let _ = tx2.send(format!("log: {}", msg.content));
     });
 
-    tokio::spawn(async move {
-        dispatcher.run().await;
-    });
-
-    // Give dispatcher time to start
-    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
-
-    bus.publish_outbound(OutboundMessage::new("shared", "chat", "test"))
+    bus.publish_outbound(OutboundMessage::new("alpha", "chat", "test"))
         .unwrap();
+    drop(bus);
+    dispatcher.run().await;
 
-    // Give time for message processing
-    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
-
-    // Only second handler should receive (due to overwrite)
-    // When first handler is overwritten, tx1 is dropped and channel closes
-    let result = tokio::time::timeout(std::time::Duration::from_millis(50), rx1.recv()).await;
-
-    // First handler should NOT receive anything (it was overwritten)
-    // When sender is dropped, recv() returns Ok(None), not a timeout error
-    match result {
-        Ok(None) => (), // Channel closed as expected (sender dropped)
-        Ok(Some(_)) => panic!("First handler should not receive message after being overwritten"),
-        Err(_) => (), // Timeout is also acceptable (message wasn't sent)
-    }
+    assert_eq!(rx.recv().await.as_deref(), Some("send: test"));
+    assert_eq!(rx.recv().await.as_deref(), Some("log: test"));
+    // Both handlers were dropped once the dispatcher finished
+    assert_eq!(rx.recv().await, None);
 }
 
 // ============================================================================
@@ -446,6 +542,196 @@ async fn test_dispatcher_with_bus_drop() {
     assert!(timeout_result.is_ok());
 }
 
+// ============================================================================
+// Shutdown Tests
+// ============================================================================
+
+#[test]
+fn test_clear_handlers_removes_registrations() {
+    let (_bus, _in_rx, out_rx) = MessageBus::channels();
+    let mut dispatcher = OutboundDispatcher::new(out_rx);
+    dispatcher.on_channel("telegram", |_| {});
+    dispatcher.on_channel("whatsapp", |_| {});
+
+    dispatcher.clear_handlers();
+
+    assert!(dispatcher.registered_channels().is_empty());
+}
+
+#[tokio::test]
+async fn test_stop_ends_dispatch_and_drops_handlers() {
+    let (bus, _in_rx, out_rx) = MessageBus::channels();
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+
+    let mut dispatcher = OutboundDispatcher::new(out_rx);
+    dispatcher.on_channel("test", move |msg| {
+        let _ = tx.send(msg.content);
+    });
+    let stop = dispatcher.stop_handle();
+    let handle = tokio::spawn(dispatcher.run());
+
+    bus.publish_outbound(OutboundMessage::new("test", "chat", "Before stop"))
+        .unwrap();
+    let first = tokio::time::timeout(std::time::Duration::from_millis(100), rx.recv()).await;
+    assert_eq!(first.unwrap().unwrap(), "Before stop");
+
+    stop.stop();
+    assert!(stop.is_stopped());
+    tokio::time::timeout(std::time::Duration::from_millis(100), handle)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let _ = bus.publish_outbound(OutboundMessage::new("test", "chat", "After stop"));
+
+    // The handler (and its sender) is gone, so the channel reports closed
+    assert_eq!(rx.recv().await, None);
+}
+
+#[tokio::test]
+async fn test_stop_awaits_in_flight_async_deliveries() {
+    let (bus, _in_rx, out_rx) = MessageBus::channels();
+    let delivered = Arc::new(AtomicUsize::new(0));
+
+    let dispatcher = OutboundDispatcher::new(out_rx);
+    let stop = dispatcher.stop_handle();
+    let counter = Arc::clone(&delivered);
+    let handle = tokio::spawn(dispatcher.run_async(move |_msg| {
+        let counter = Arc::clone(&counter);
+        async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+    }));
+
+    bus.publish_outbound(OutboundMessage::new("any", "chat", "In flight"))
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    stop.stop();
+
+    tokio::time::timeout(std::time::Duration::from_millis(500), handle)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+
+    let _ = bus.publish_outbound(OutboundMessage::new("any", "chat", "Too late"));
+    tokio::time::sleep(std::time::Duration::from_millis(80)).await;
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+}
+
+// ============================================================================
+// Batching Tests
+// ============================================================================
+
+/// Publish `messages` back to back through a batching dispatcher
+async fn batched(messages: Vec<OutboundMessage>) -> Vec<OutboundMessage> {
+    let (bus, _in_rx, out_rx) = MessageBus::channels();
+    let (tx, mut rx) = mpsc::unbounded_channel::<OutboundMessage>();
+
+    let mut dispatcher =
+        OutboundDispatcher::new(out_rx).with_batching(std::time::Duration::from_millis(50));
+    for channel in ["telegram", "whatsapp"] {
+        let tx = tx.clone();
+        dispatcher.on_channel(channel, move |msg| {
+            let _ = tx.send(msg);
+        });
+    }
+    drop(tx);
+    let stop = dispatcher.stop_handle();
+    let handle = tokio::spawn(dispatcher.run());
+
+    for msg in messages {
+        bus.publish_outbound(msg).unwrap();
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    stop.stop();
+    handle.await.unwrap();
+
+    let mut sent = Vec::new();
+    while let Some(msg) = rx.recv().await {
+        sent.push(msg);
+    }
+    sent
+}
+
+#[tokio::test]
+async fn test_batching_merges_quick_text_to_same_chat() {
+    let sent = batched(vec![
+        OutboundMessage::new("telegram", "chat", "Status").with_metadata("step", 1),
+        OutboundMessage::new("telegram", "chat", "Final answer").with_metadata("usage", 42),
+    ])
+    .await;
+
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].content, "Status\n\nFinal answer");
+    assert_eq!(sent[0].metadata.len(), 2);
+}
+
+#[tokio::test]
+async fn test_batching_breaks_on_media() {
+    let sent = batched(vec![
+        OutboundMessage::new("telegram", "chat", "Look"),
+        OutboundMessage {
+            media: vec!["/tmp/photo.jpg".to_string()],
+            ..OutboundMessage::new("telegram", "chat", "Photo")
+        },
+        OutboundMessage::new("telegram", "chat", "After"),
+    ])
+    .await;
+
+    let contents: Vec<&str> = sent.iter().map(|m| m.content.as_str()).collect();
+    assert_eq!(contents, vec!["Look", "Photo", "After"]);
+}
+
+#[tokio::test]
+async fn test_batching_breaks_on_reply_to() {
+    let sent = batched(vec![
+        OutboundMessage::new("telegram", "chat", "One"),
+        OutboundMessage::new("telegram", "chat", "Two").reply_to("42"),
+        OutboundMessage::new("telegram", "chat", "Three"),
+    ])
+    .await;
+
+    let contents: Vec<&str> = sent.iter().map(|m| m.content.as_str()).collect();
+    assert_eq!(contents, vec!["One", "Two\n\nThree"]);
+    assert_eq!(sent[1].reply_to.as_deref(), Some("42"));
+}
+
+#[tokio::test]
+async fn test_batching_breaks_on_different_chat() {
+    let sent = batched(vec![
+        OutboundMessage::new("telegram", "alpha", "To alpha"),
+        OutboundMessage::new("telegram", "bravo", "To bravo"),
+        OutboundMessage::new("whatsapp", "bravo", "Other channel"),
+    ])
+    .await;
+
+    assert_eq!(sent.len(), 3);
+}
+
+#[tokio::test]
+async fn test_batching_window_elapses() {
+    let (bus, _in_rx, out_rx) = MessageBus::channels();
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+
+    let mut dispatcher =
+        OutboundDispatcher::new(out_rx).with_batching(std::time::Duration::from_millis(20));
+    dispatcher.on_channel("telegram", move |msg| {
+        let _ = tx.send(msg.content);
+    });
+    tokio::spawn(dispatcher.run());
+
+    bus.publish_outbound(OutboundMessage::new("telegram", "chat", "First"))
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    bus.publish_outbound(OutboundMessage::new("telegram", "chat", "Second"))
+        .unwrap();
+
+    assert_eq!(rx.recv().await.unwrap(), "First");
+    assert_eq!(rx.recv().await.unwrap(), "Second");
+}
+
 // ============================================================================
 // Channel Name Edge Cases
 // ============================================================================
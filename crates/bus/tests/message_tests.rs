@@ -4,10 +4,12 @@
 //! - Message creation and builder patterns
 //! - Session key generation
 //! - Serialization/deserialization
+//! - Message IDs
 //! - Edge cases and complex metadata
 
-use opensam_bus::{InboundMessage, OutboundMessage};
+use opensam_bus::{InboundMessage, OutboundMessage, DEFAULT_ATTACHMENT_PROMPT};
 use serde_json::json;
+use std::collections::HashSet;
 
 // ============================================================================
 // InboundMessage Tests
@@ -213,10 +215,61 @@ fn test_full_roundtrip_serialization() {
     assert_eq!(deserialized.sender_id, original.sender_id);
     assert_eq!(deserialized.chat_id, original.chat_id);
     assert_eq!(deserialized.content, original.content);
+    assert_eq!(deserialized.id, original.id);
     assert_eq!(deserialized.media, original.media);
     assert_eq!(deserialized.metadata, original.metadata);
 }
 
+// ============================================================================
+// Message ID Tests
+// ============================================================================
+
+#[test]
+fn test_message_ids_are_unique() {
+    let mut ids = HashSet::new();
+    for i in 0..100 {
+        let inbound = InboundMessage::new("telegram", "u", "c", format!("in-{}", i));
+        let outbound = OutboundMessage::new("telegram", "c", format!("out-{}", i));
+        assert_eq!(inbound.id.len(), 8);
+        assert!(ids.insert(inbound.id));
+        assert!(ids.insert(outbound.id));
+    }
+}
+
+#[test]
+fn test_with_id_sets_platform_id() {
+    let inbound = InboundMessage::new("telegram", "u", "c", "hi").with_id("4242");
+    assert_eq!(inbound.id, "4242");
+
+    let reply = OutboundMessage::new("telegram", "c", "hello")
+        .with_id("out-1")
+        .reply_to(&inbound.id);
+    assert_eq!(reply.id, "out-1");
+    assert_eq!(reply.reply_to.as_deref(), Some("4242"));
+}
+
+#[test]
+fn test_message_ids_survive_roundtrip() {
+    let inbound = InboundMessage::new("telegram", "u", "c", "hi");
+    let json_str = serde_json::to_string(&inbound).unwrap();
+    let back: InboundMessage = serde_json::from_str(&json_str).unwrap();
+    assert_eq!(back.id, inbound.id);
+
+    let outbound = OutboundMessage::new("telegram", "c", "hi").with_id("platform-7");
+    let json_str = serde_json::to_string(&outbound).unwrap();
+    let back: OutboundMessage = serde_json::from_str(&json_str).unwrap();
+    assert_eq!(back.id, "platform-7");
+}
+
+#[test]
+fn test_messages_without_id_get_one_on_load() {
+    let json_data = r#"{"channel": "telegram", "chat_id": "c", "content": "old"}"#;
+    let first: OutboundMessage = serde_json::from_str(json_data).unwrap();
+    let second: OutboundMessage = serde_json::from_str(json_data).unwrap();
+    assert_eq!(first.id.len(), 8);
+    assert_ne!(first.id, second.id);
+}
+
 // ============================================================================
 // Complex Metadata Tests
 // ============================================================================
@@ -329,6 +382,29 @@ fn test_many_media_files() {
     assert_eq!(msg.media[25], "/path/to/file25.jpg");
 }
 
+#[test]
+fn test_normalize_keeps_text() {
+    let msg = InboundMessage::new("channel", "sender", "chat", "  hi  ").with_media("/a.jpg");
+    let msg = msg.normalize(DEFAULT_ATTACHMENT_PROMPT).unwrap();
+    assert_eq!(msg.content, "  hi  ");
+}
+
+#[test]
+fn test_normalize_captionless_media() {
+    let msg = InboundMessage::new("channel", "sender", "chat", " \n")
+        .with_media("/a.jpg")
+        .with_media("/b.ogg");
+    let msg = msg.normalize("sent {n} files").unwrap();
+    assert_eq!(msg.content, "sent 2 files");
+    assert_eq!(msg.media.len(), 2);
+}
+
+#[test]
+fn test_normalize_drops_empty() {
+    let msg = InboundMessage::new("channel", "sender", "chat", "   ");
+    assert!(msg.normalize(DEFAULT_ATTACHMENT_PROMPT).is_none());
+}
+
 #[test]
 fn test_many_metadata_entries() {
     let mut msg = InboundMessage::new("channel", "sender", "chat", "Rich metadata");
@@ -0,0 +1,186 @@
+//! Integration tests for gateway traffic counters
+
+use opensam_bus::{
+    GatewayStats, GatewayStatsSnapshot, InboundMessage, InboundPolicy, MessageBus, MetricsSnapshot,
+    OutboundDispatcher, OutboundMessage,
+};
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[test]
+fn test_stats_start_empty() {
+    let snapshot = GatewayStats::new().snapshot();
+    assert_eq!(snapshot, GatewayStatsSnapshot::default());
+    assert_eq!(snapshot.average_latency_ms(), 0);
+}
+
+#[test]
+fn test_stats_count_per_channel() {
+    let stats = GatewayStats::new();
+    stats.record_inbound("telegram");
+    stats.record_inbound("telegram");
+    stats.record_outbound("telegram");
+    stats.record_inbound("cli");
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.channels["telegram"].inbound, 2);
+    assert_eq!(snapshot.channels["telegram"].outbound, 1);
+    assert_eq!(snapshot.channels["cli"].inbound, 1);
+    assert_eq!(snapshot.inbound_total(), 3);
+    assert_eq!(snapshot.outbound_total(), 1);
+}
+
+#[test]
+fn test_stats_average_latency() {
+    let stats = GatewayStats::new();
+    stats.record_turn(Duration::from_millis(100));
+    stats.record_turn(Duration::from_millis(300));
+    stats.record_tool_execution();
+    stats.record_provider_error();
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.turns, 2);
+    assert_eq!(snapshot.average_latency_ms(), 200);
+    assert_eq!(snapshot.tool_executions, 1);
+    assert_eq!(snapshot.provider_errors, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_stats_concurrent_traffic() {
+    let stats = GatewayStats::new();
+    let mut tasks = Vec::new();
+    for i in 0..8 {
+        let stats = stats.clone();
+        let channel = if i % 2 == 0 { "telegram" } else { "cli" };
+        tasks.push(tokio::spawn(async move {
+            for _ in 0..250 {
+                stats.record_inbound(channel);
+                stats.record_tool_execution();
+                stats.record_turn(Duration::from_millis(10));
+                stats.record_outbound(channel);
+                tokio::task::yield_now().await;
+            }
+        }));
+    }
+
+    // Read while writers run; totals only ever grow
+    let reader = {
+        let stats = stats.clone();
+        tokio::spawn(async move {
+            let mut last = GatewayStatsSnapshot::default();
+            for _ in 0..200 {
+                let snapshot = stats.snapshot();
+                assert!(snapshot.inbound_total() >= last.inbound_total());
+                assert!(snapshot.turns >= last.turns);
+                assert!(snapshot.tool_executions >= last.tool_executions);
+                if snapshot.turns > 0 {
+                    assert!(snapshot.average_latency_ms() >= 10);
+                }
+                last = snapshot;
+                tokio::task::yield_now().await;
+            }
+        })
+    };
+
+    for task in tasks {
+        task.await.unwrap();
+    }
+    reader.await.unwrap();
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.channels["telegram"].inbound, 1000);
+    assert_eq!(snapshot.channels["cli"].outbound, 1000);
+    assert_eq!(snapshot.inbound_total(), 2000);
+    assert_eq!(snapshot.turns, 2000);
+    assert_eq!(snapshot.tool_executions, 2000);
+    assert_eq!(snapshot.average_latency_ms(), 10);
+}
+
+#[test]
+fn test_bus_counts_accepted_inbound_only() {
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let bus = bus.with_inbound_policy(InboundPolicy::new().block_channel("radio"));
+
+    bus.publish_inbound(InboundMessage::new("telegram", "u", "c", "hi"))
+        .unwrap();
+    bus.publish_inbound(InboundMessage::new("radio", "u", "c", "hi"))
+        .unwrap();
+
+    let snapshot = bus.stats().snapshot();
+    assert_eq!(snapshot.inbound_total(), 1);
+    assert!(!snapshot.channels.contains_key("radio"));
+}
+
+#[tokio::test]
+async fn test_dispatcher_counts_deliveries() {
+    let (bus, _in_rx, out_rx) = MessageBus::channels();
+    let mut dispatcher = OutboundDispatcher::new(out_rx).with_stats(bus.stats().clone());
+    dispatcher.on_channel("telegram", |_| {});
+
+    bus.publish_outbound(OutboundMessage::new("telegram", "c", "one"))
+        .unwrap();
+    bus.publish_outbound(OutboundMessage::new("telegram", "c", "two"))
+        .unwrap();
+    // No handler, so not delivered
+    bus.publish_outbound(OutboundMessage::new("radio", "c", "lost"))
+        .unwrap();
+    let stats = bus.stats().clone();
+    drop(bus);
+
+    dispatcher.run().await;
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.channels["telegram"].outbound, 2);
+    assert_eq!(snapshot.outbound_total(), 2);
+}
+
+#[tokio::test]
+async fn test_async_dispatcher_skips_failed_deliveries() {
+    let (bus, _in_rx, out_rx) = MessageBus::channels();
+    let dispatcher = OutboundDispatcher::new(out_rx).with_stats(bus.stats().clone());
+
+    bus.publish_outbound(OutboundMessage::new("telegram", "c", "ok"))
+        .unwrap();
+    bus.publish_outbound(OutboundMessage::new("telegram", "c", "fail"))
+        .unwrap();
+    let stats = bus.stats().clone();
+    drop(bus);
+
+    dispatcher
+        .run_async_confirmed(|msg| async move {
+            if msg.content == "fail" {
+                Err("refused")
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+    assert_eq!(stats.snapshot().channels["telegram"].outbound, 1);
+}
+
+#[test]
+fn test_metrics_snapshot_round_trips_gateway_stats() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("metrics.json");
+
+    let stats = GatewayStats::new();
+    stats.record_inbound("telegram");
+    stats.record_turn(Duration::from_millis(42));
+    let snapshot = MetricsSnapshot {
+        gateway: stats.snapshot(),
+        ..MetricsSnapshot::default()
+    };
+    snapshot.save(&path).unwrap();
+
+    assert_eq!(MetricsSnapshot::load(&path).unwrap(), snapshot);
+}
+
+#[test]
+fn test_metrics_snapshot_loads_without_gateway_stats() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("metrics.json");
+    std::fs::write(&path, r#"{"inbound_dropped_unauthorized":{"telegram":3}}"#).unwrap();
+
+    let snapshot = MetricsSnapshot::load(&path).unwrap();
+    assert_eq!(snapshot.inbound_dropped_unauthorized["telegram"], 3);
+    assert_eq!(snapshot.gateway, GatewayStatsSnapshot::default());
+}
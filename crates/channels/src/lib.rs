@@ -4,8 +4,10 @@ use async_trait::async_trait;
 use opensam_bus::OutboundMessage;
 
 pub mod telegram;
+pub mod throttle;
 
 pub use telegram::TelegramChannel;
+pub use throttle::Throttle;
 
 /// Trait for chat channel implementations
 #[async_trait]
@@ -28,3 +30,93 @@ pub trait Channel: Send + Sync {
     /// Check if a sender is allowed
     fn is_allowed(&self, sender_id: &str) -> bool;
 }
+
+/// Send a test message through `channel`, returning what was sent
+pub async fn send_test_message(
+    channel: &dyn Channel,
+    to: &str,
+    text: &str,
+) -> Result<OutboundMessage, Box<dyn std::error::Error + Send + Sync>> {
+    let msg = OutboundMessage::new(channel.name(), to, text);
+    channel.send(&msg).await?;
+    Ok(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Channel that records sends and optionally fails
+    struct StubChannel {
+        sent: Mutex<Vec<OutboundMessage>>,
+        fail: bool,
+    }
+
+    impl StubChannel {
+        fn new(fail: bool) -> Self {
+            Self {
+                sent: Mutex::new(Vec::new()),
+                fail,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Channel for StubChannel {
+        fn name(&self) -> &str {
+            "stub"
+        }
+
+        async fn start(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            Ok(())
+        }
+
+        async fn send(
+            &self,
+            msg: &OutboundMessage,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            if self.fail {
+                return Err("chat not found".into());
+            }
+            self.sent.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+
+        fn is_allowed(&self, _sender_id: &str) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_test_message_builds_outbound() {
+        let channel = StubChannel::new(false);
+
+        let msg = send_test_message(&channel, "12345", "radio check")
+            .await
+            .unwrap();
+
+        assert_eq!(msg.channel, "stub");
+        assert_eq!(msg.chat_id, "12345");
+        assert_eq!(msg.content, "radio check");
+
+        let sent = channel.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].chat_id, "12345");
+    }
+
+    #[tokio::test]
+    async fn test_send_test_message_reports_failure() {
+        let channel = StubChannel::new(true);
+
+        let err = send_test_message(&channel, "12345", "radio check")
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "chat not found");
+    }
+}
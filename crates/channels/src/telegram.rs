@@ -1,11 +1,15 @@
 //! Telegram channel implementation
 
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use opensam_bus::{InboundMessage, MessageBus, OutboundMessage};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use teloxide::prelude::*;
 use teloxide::types::ParseMode;
 use tracing::{debug, error, info};
 
+use crate::throttle::Throttle;
 use crate::Channel;
 
 /// Telegram channel configuration
@@ -16,16 +20,177 @@ pub struct TelegramConfig {
     pub allow_from: Vec<String>,
 }
 
+/// Metadata key marking an inbound message as an edit of an earlier one
+pub const EDITED_KEY: &str = "edited";
+
+/// Metadata key holding the Telegram id of the edited message
+pub const ORIGINAL_MESSAGE_ID_KEY: &str = "original_message_id";
+
+/// Map an edited Telegram text message to an inbound transmission
+///
+/// The edit is flagged in metadata along with the id of the message it
+/// replaces, leaving it to the agent to decide how to treat corrections.
+pub fn edited_to_inbound(
+    sender_id: &str,
+    chat_id: i64,
+    message_id: i32,
+    text: &str,
+    edited_at: DateTime<Local>,
+) -> InboundMessage {
+    InboundMessage::new("telegram", sender_id, chat_id.to_string(), text)
+        .with_timestamp(edited_at)
+        .with_metadata(EDITED_KEY, true)
+        .with_metadata(ORIGINAL_MESSAGE_ID_KEY, message_id)
+}
+
+/// Attach the message a Telegram update replies to
+///
+/// Only the replied-to text and sender are kept; media replies carry the
+/// id alone.
+pub fn with_reply_context(
+    inbound: InboundMessage,
+    reply_id: i32,
+    reply_text: Option<&str>,
+    reply_sender: Option<&str>,
+) -> InboundMessage {
+    inbound.with_reply_to(reply_id, reply_text, reply_sender)
+}
+
+/// Emphasis nesting allowed before further markers are kept literally
+pub const DEFAULT_MARKDOWN_DEPTH: usize = 4;
+
+/// Fence languages whose whole-answer blocks are unwrapped and rendered as prose
+const MARKDOWN_FENCE_LANGS: [&str; 2] = ["markdown", "md"];
+
+/// Split a response that is entirely one fenced block into `(lang, body)`
+///
+/// The opening fence must be followed by a newline and the closing fence
+/// must start its own line, with no other fence in between. Prose around
+/// the block, or a second block, means the response is not a whole fence.
+fn whole_fence(text: &str) -> Option<(&str, &str)> {
+    let inner = text.trim().strip_prefix("```")?.strip_suffix("```")?;
+    let (lang, body) = inner.split_once('\n')?;
+    let body = body
+        .strip_suffix('\n')
+        .or_else(|| body.is_empty().then_some(body))?;
+    let lang = lang.trim();
+    if body.contains("```") || lang.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((lang, body))
+}
+
+/// Open emphasis span, keyed by its marker character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Emphasis {
+    Bold(char),
+    Italic(char),
+}
+
+impl Emphasis {
+    fn tag(self) -> &'static str {
+        match self {
+            Emphasis::Bold(_) => "b",
+            Emphasis::Italic(_) => "i",
+        }
+    }
+
+    fn open(self, result: &mut String) {
+        result.push_str(&format!("<{}>", self.tag()));
+    }
+
+    fn close(self, result: &mut String) {
+        result.push_str(&format!("</{}>", self.tag()));
+    }
+
+    fn literal(self) -> String {
+        match self {
+            Emphasis::Bold(c) => format!("{c}{c}"),
+            Emphasis::Italic(c) => c.to_string(),
+        }
+    }
+}
+
 /// Telegram channel implementation
 pub struct TelegramChannel {
     config: TelegramConfig,
     bus: MessageBus,
+    throttle: Arc<Mutex<Throttle>>,
+    markdown_depth: usize,
+    unwrap_markdown_fences: bool,
 }
 
 impl TelegramChannel {
-    /// Create a new Telegram channel
+    /// Create a new Telegram channel with Telegram's default send limits
     pub fn new(config: TelegramConfig, bus: MessageBus) -> Self {
-        Self { config, bus }
+        Self {
+            config,
+            bus,
+            throttle: Arc::new(Mutex::new(Throttle::default())),
+            markdown_depth: DEFAULT_MARKDOWN_DEPTH,
+            unwrap_markdown_fences: true,
+        }
+    }
+
+    /// Share send limits with other instances for the same bot
+    pub fn with_throttle(mut self, throttle: Arc<Mutex<Throttle>>) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Limit how deeply emphasis may nest in outgoing markdown
+    pub fn with_markdown_depth(mut self, depth: usize) -> Self {
+        self.markdown_depth = depth;
+        self
+    }
+
+    /// Render a whole-answer ```markdown fence as prose instead of code
+    pub fn with_unwrap_markdown_fences(mut self, unwrap: bool) -> Self {
+        self.unwrap_markdown_fences = unwrap;
+        self
+    }
+
+    /// Check a sender against the allow-list, counting rejections
+    ///
+    /// Rejected senders increment the bus `inbound_dropped_unauthorized`
+    /// counter for this channel.
+    pub fn admit(&self, sender_id: &str) -> bool {
+        if self.is_allowed(sender_id) {
+            return true;
+        }
+        debug!("Ignoring message from unauthorized user: {}", sender_id);
+        self.bus.metrics().record_dropped_unauthorized(self.name());
+        false
+    }
+
+    /// Publish an admitted text message (or edit) to the bus
+    async fn relay(&self, msg: &Message, edited: bool) {
+        let Some(text) = msg.text() else {
+            return;
+        };
+        let sender_id = msg.from().map(|u| u.id.to_string()).unwrap_or_default();
+        if !self.admit(&sender_id) {
+            return;
+        }
+
+        let mut inbound = if edited {
+            let edited_at = msg.edit_date().copied().unwrap_or(msg.date);
+            edited_to_inbound(&sender_id, msg.chat.id.0, msg.id.0, text, edited_at.into())
+        } else {
+            InboundMessage::new("telegram", sender_id, msg.chat.id.to_string(), text)
+                .with_timestamp(msg.date.into())
+        };
+        // Telegram's own id, so a reply can point back at this message
+        inbound = inbound.with_id(msg.id.0.to_string());
+        if let Some(reply) = msg.reply_to_message() {
+            let sender = reply.from().map(|u| u.full_name());
+            inbound = with_reply_context(inbound, reply.id.0, reply.text(), sender.as_deref());
+        }
+
+        // Waiting on a saturated bus holds back further polling
+        if let Err(e) = self.bus.publish_inbound_wait(inbound).await {
+            error!("Failed to publish message: {}", e);
+        }
     }
 
     /// Convert markdown to Telegram HTML
@@ -33,7 +198,43 @@ impl TelegramChannel {
     /// Process:
     /// 1. First escape HTML special characters (&, <, >)
     /// 2. Then convert markdown patterns to HTML with proper closing tags
+    #[cfg(test)]
     fn markdown_to_html(text: &str) -> String {
+        Self::render_html(text, DEFAULT_MARKDOWN_DEPTH, true)
+    }
+
+    /// Convert a whole response to Telegram HTML
+    ///
+    /// A response that is entirely one fenced block is sent as a code block
+    /// with its language tag, unless it is a markdown fence and
+    /// `unwrap_markdown` is set, in which case its body is rendered as prose.
+    /// Anything else goes through the markdown conversion, so fences
+    /// embedded in prose do not swallow the prose formatting.
+    fn render_html(text: &str, max_depth: usize, unwrap_markdown: bool) -> String {
+        let Some((lang, body)) = whole_fence(text) else {
+            return Self::markdown_to_html_with_depth(text, max_depth);
+        };
+        if unwrap_markdown && MARKDOWN_FENCE_LANGS.contains(&lang.to_lowercase().as_str()) {
+            return Self::markdown_to_html_with_depth(body, max_depth);
+        }
+
+        let body = body
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        if lang.is_empty() {
+            format!("<pre>{}</pre>", body)
+        } else {
+            format!(
+                "<pre><code class=\"language-{}\">{}</code></pre>",
+                lang.replace(|c: char| !c.is_alphanumeric() && !"+-#._".contains(c), ""),
+                body
+            )
+        }
+    }
+
+    /// Convert markdown to Telegram HTML, nesting emphasis at most `max_depth` deep
+    fn markdown_to_html_with_depth(text: &str, max_depth: usize) -> String {
         // Step 1: Escape HTML special characters
         let escaped = text
             .replace('&', "&amp;")
@@ -41,17 +242,23 @@ impl TelegramChannel {
             .replace('>', "&gt;");
 
         // Step 2: Convert markdown to HTML
-        Self::convert_markdown(&escaped)
+        Self::convert_markdown(&escaped, max_depth)
     }
 
     /// Convert markdown patterns to HTML after escaping
     ///
     /// Processing order matters:
     /// - Code blocks (```) must be processed before inline code (`)
-    /// - Bold (**) must be processed before italic (*)
-    fn convert_markdown(text: &str) -> String {
+    /// - A marker run closes an open italic before toggling bold, so
+    ///   `***` ends `**bold *italic***` correctly
+    ///
+    /// Open emphasis is tracked on a stack, so the output is always balanced:
+    /// closing an outer span closes and reopens the spans inside it, and
+    /// anything still open at the end of input is closed.
+    fn convert_markdown(text: &str, max_depth: usize) -> String {
         let mut result = String::with_capacity(text.len() * 2);
         let mut chars = text.chars().peekable();
+        let mut open: Vec<Emphasis> = Vec::new();
 
         while let Some(ch) = chars.next() {
             match ch {
@@ -72,24 +279,22 @@ impl TelegramChannel {
                         Self::process_inline_code(&mut chars, &mut result);
                     }
                 }
-                '*' => {
-                    // Check for bold (**)
-                    if chars.peek() == Some(&'*') {
+                '*' | '_' => {
+                    let mut run = 1;
+                    while chars.peek() == Some(&ch) {
                         chars.next();
-                        Self::process_bold(&mut chars, &mut result, "**");
-                    } else {
-                        // Italic
-                        Self::process_italic(&mut chars, &mut result, "*");
+                        run += 1;
                     }
-                }
-                '_' => {
-                    // Check for bold (__)
-                    if chars.peek() == Some(&'_') {
-                        chars.next();
-                        Self::process_bold(&mut chars, &mut result, "__");
-                    } else {
-                        // Italic
-                        Self::process_italic(&mut chars, &mut result, "_");
+                    if run % 2 == 1 && open.last() == Some(&Emphasis::Italic(ch)) {
+                        Self::toggle(Emphasis::Italic(ch), &mut open, &mut result, max_depth);
+                        run -= 1;
+                    }
+                    while run >= 2 {
+                        Self::toggle(Emphasis::Bold(ch), &mut open, &mut result, max_depth);
+                        run -= 2;
+                    }
+                    if run == 1 {
+                        Self::toggle(Emphasis::Italic(ch), &mut open, &mut result, max_depth);
                     }
                 }
                 _ => {
@@ -98,9 +303,34 @@ impl TelegramChannel {
             }
         }
 
+        while let Some(emphasis) = open.pop() {
+            emphasis.close(&mut result);
+        }
+
         result
     }
 
+    /// Close `marker` if it is open, otherwise open it (or keep it literal past `max_depth`)
+    fn toggle(marker: Emphasis, open: &mut Vec<Emphasis>, result: &mut String, max_depth: usize) {
+        if let Some(pos) = open.iter().rposition(|m| *m == marker) {
+            let inner = open.split_off(pos + 1);
+            for emphasis in inner.iter().rev() {
+                emphasis.close(result);
+            }
+            open.pop();
+            marker.close(result);
+            for emphasis in inner {
+                emphasis.open(result);
+                open.push(emphasis);
+            }
+        } else if open.len() < max_depth {
+            marker.open(result);
+            open.push(marker);
+        } else {
+            result.push_str(&marker.literal());
+        }
+    }
+
     fn process_code_block(chars: &mut std::iter::Peekable<std::str::Chars>, result: &mut String) {
         let mut content = String::new();
         let mut backtick_count = 0;
@@ -151,66 +381,6 @@ impl TelegramChannel {
         result.push_str(&content);
         result.push_str("</code>");
     }
-
-    fn process_bold(
-        chars: &mut std::iter::Peekable<std::str::Chars>,
-        result: &mut String,
-        closer: &str,
-    ) {
-        let mut content = String::new();
-        let first_char = closer.chars().next().unwrap();
-
-        while let Some(ch) = chars.next() {
-            if ch == first_char && chars.peek() == Some(&first_char) {
-                chars.next();
-                break;
-            }
-            // Handle nested italic within bold
-            if (ch == '*' || ch == '_') && chars.peek() != Some(&first_char) {
-                content.push_str("<i>");
-                let close_char = ch;
-                while let Some(inner_ch) = chars.next() {
-                    if inner_ch == close_char {
-                        break;
-                    }
-                    // Check for nested bold closing while in italic
-                    if inner_ch == first_char && chars.peek() == Some(&first_char) {
-                        // Put back the characters and exit italic
-                        content.push(inner_ch);
-                        break;
-                    }
-                    content.push(inner_ch);
-                }
-                content.push_str("</i>");
-            } else {
-                content.push(ch);
-            }
-        }
-
-        result.push_str("<b>");
-        result.push_str(&content);
-        result.push_str("</b>");
-    }
-
-    fn process_italic(
-        chars: &mut std::iter::Peekable<std::str::Chars>,
-        result: &mut String,
-        closer: &str,
-    ) {
-        let mut content = String::new();
-        let close_char = closer.chars().next().unwrap();
-
-        for ch in chars.by_ref() {
-            if ch == close_char {
-                break;
-            }
-            content.push(ch);
-        }
-
-        result.push_str("<i>");
-        result.push_str(&content);
-        result.push_str("</i>");
-    }
 }
 
 #[async_trait]
@@ -227,40 +397,29 @@ impl Channel for TelegramChannel {
         info!("Starting Telegram channel");
 
         let bot = Bot::new(&self.config.token);
-        let bus = self.bus.clone();
-        let allow_from = self.config.allow_from.clone();
-
-        teloxide::repl(bot, move |msg: Message, _bot: Bot| {
-            let bus = bus.clone();
-            let allow_from = allow_from.clone();
-
-            async move {
-                if let Some(text) = msg.text() {
-                    let user = msg.from();
-                    let chat_id = msg.chat.id;
-
-                    // Check if allowed
-                    let sender_id = user.map(|u| u.id.to_string()).unwrap_or_default();
-                    if !allow_from.is_empty() && !allow_from.contains(&sender_id) {
-                        debug!("Ignoring message from unauthorized user: {}", sender_id);
-                        return Ok(());
-                    }
-
-                    let inbound = InboundMessage::new(
-                        "telegram",
-                        sender_id,
-                        chat_id.to_string(),
-                        text.to_string(),
-                    );
-
-                    if let Err(e) = bus.publish_inbound(inbound) {
-                        error!("Failed to publish message: {}", e);
-                    }
-                }
-                Ok(())
-            }
-        })
-        .await;
+        let gate = Arc::new(TelegramChannel::new(self.config.clone(), self.bus.clone()));
+
+        let handler = dptree::entry()
+            .branch(Update::filter_message().endpoint(
+                |msg: Message, gate: Arc<TelegramChannel>| async move {
+                    gate.relay(&msg, false).await;
+                    respond(())
+                },
+            ))
+            .branch(Update::filter_edited_message().endpoint(
+                |msg: Message, gate: Arc<TelegramChannel>| async move {
+                    gate.relay(&msg, true).await;
+                    respond(())
+                },
+            ));
+
+        Dispatcher::builder(bot, handler)
+            .dependencies(dptree::deps![gate])
+            .default_handler(|_update| Box::pin(async {}))
+            .enable_ctrlc_handler()
+            .build()
+            .dispatch()
+            .await;
 
         Ok(())
     }
@@ -276,7 +435,21 @@ impl Channel for TelegramChannel {
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let bot = Bot::new(&self.config.token);
         let chat_id: i64 = msg.chat_id.parse()?;
-        let html_content = Self::markdown_to_html(&msg.content);
+        let html_content = Self::render_html(
+            &msg.content,
+            self.markdown_depth,
+            self.unwrap_markdown_fences,
+        );
+
+        let delay = self
+            .throttle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .reserve(&msg.chat_id, Instant::now());
+        if !delay.is_zero() {
+            debug!("◆ THROTTLING SEND TO {} FOR {:?}", msg.chat_id, delay);
+            tokio::time::sleep(delay).await;
+        }
 
         bot.send_message(ChatId(chat_id), html_content)
             .parse_mode(ParseMode::Html)
@@ -296,6 +469,7 @@ impl Channel for TelegramChannel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use opensam_bus::{InboundPolicy, REPLY_TO_ID_KEY};
 
     /// Helper function to create a mock MessageBus for testing
     fn create_mock_bus() -> MessageBus {
@@ -525,6 +699,107 @@ mod tests {
         );
     }
 
+    /// Assert every opened tag is closed in order
+    fn assert_balanced(html: &str) {
+        let mut stack = Vec::new();
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            let end = rest[start..].find('>').unwrap() + start;
+            let tag = &rest[start + 1..end];
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(
+                    stack.pop(),
+                    Some(name),
+                    "mismatched </{}> in {}",
+                    name,
+                    html
+                );
+            } else {
+                stack.push(tag);
+            }
+            rest = &rest[end + 1..];
+        }
+        assert!(stack.is_empty(), "unclosed {:?} in {}", stack, html);
+    }
+
+    #[test]
+    fn test_markdown_to_html_deep_nesting() {
+        let result = TelegramChannel::markdown_to_html("**a *b _c_ b* a**");
+        assert_eq!(result, "<b>a <i>b <i>c</i> b</i> a</b>");
+        assert_balanced(&result);
+    }
+
+    #[test]
+    fn test_markdown_to_html_depth_limit_keeps_markers() {
+        let result = TelegramChannel::markdown_to_html_with_depth("**a *b _c_ b* a**", 2);
+        assert_eq!(result, "<b>a <i>b _c_ b</i> a</b>");
+        assert_balanced(&result);
+    }
+
+    #[test]
+    fn test_whole_answer_fence_renders_as_code() {
+        let input = "```rust\nfn main() {\n    println!(\"<hi>\");\n}\n```";
+        let result = TelegramChannel::markdown_to_html(input);
+        assert_eq!(
+            result,
+            "<pre><code class=\"language-rust\">fn main() {\n    println!(\"&lt;hi&gt;\");\n}</code></pre>"
+        );
+
+        let untagged = TelegramChannel::markdown_to_html("\n```\n**not bold**\n```\n");
+        assert_eq!(untagged, "<pre>**not bold**</pre>");
+    }
+
+    #[test]
+    fn test_whole_answer_markdown_fence_is_unwrapped() {
+        let input = "```markdown\n**Summary**\n\n- _done_\n```";
+        assert_eq!(
+            TelegramChannel::markdown_to_html(input),
+            "<b>Summary</b>\n\n- <i>done</i>"
+        );
+        assert_eq!(
+            TelegramChannel::render_html(input, DEFAULT_MARKDOWN_DEPTH, false),
+            "<pre><code class=\"language-markdown\">**Summary**\n\n- _done_</code></pre>"
+        );
+    }
+
+    #[test]
+    fn test_embedded_fence_keeps_prose_formatting() {
+        let input = "Try **this**:\n```\nls -la\n```\nThen _rerun_.";
+        let result = TelegramChannel::markdown_to_html(input);
+        assert_eq!(
+            result,
+            "Try <b>this</b>:\n<pre>\nls -la\n</pre>\nThen <i>rerun</i>."
+        );
+
+        // Two blocks are not one whole-answer fence
+        let two = "```\na\n```\n```\nb\n```";
+        assert!(whole_fence(two).is_none());
+        assert_eq!(
+            TelegramChannel::markdown_to_html(two)
+                .matches("<pre>")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_markdown_to_html_interleaved_emphasis() {
+        let result = TelegramChannel::markdown_to_html("**a *b** c*");
+        assert_eq!(result, "<b>a <i>b</i></b><i> c</i>");
+        assert_balanced(&result);
+    }
+
+    #[test]
+    fn test_markdown_to_html_unclosed_markers() {
+        for input in ["**bold *italic", "*a __b _c", "__x** y*", "***", "a_b_c_d"] {
+            assert_balanced(&TelegramChannel::markdown_to_html(input));
+        }
+        assert_eq!(
+            TelegramChannel::markdown_to_html("**bold *italic"),
+            "<b>bold <i>italic</i></b>"
+        );
+    }
+
     // =========================================================================
     // is_allowed Tests
     // =========================================================================
@@ -605,6 +880,116 @@ mod tests {
         assert!(!channel.is_allowed("1234567890"));
     }
 
+    #[test]
+    fn test_admit_rejected_sender_increments_counter() {
+        let config = TelegramConfig {
+            enabled: true,
+            token: "token".to_string(),
+            allow_from: vec!["admin".to_string()],
+        };
+        let bus = create_mock_bus();
+        let channel = TelegramChannel::new(config, bus.clone());
+
+        assert!(!channel.admit("intruder"));
+        assert!(!channel.admit("intruder"));
+
+        assert_eq!(bus.metrics().dropped_unauthorized("telegram"), 2);
+    }
+
+    #[test]
+    fn test_admit_allowed_sender_does_not_increment_counter() {
+        let config = TelegramConfig {
+            enabled: true,
+            token: "token".to_string(),
+            allow_from: vec!["admin".to_string()],
+        };
+        let bus = create_mock_bus();
+        let channel = TelegramChannel::new(config, bus.clone());
+
+        assert!(channel.admit("admin"));
+
+        assert_eq!(bus.metrics().dropped_unauthorized("telegram"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_global_block_overrides_channel_allow_list() {
+        let config = TelegramConfig {
+            enabled: true,
+            token: "token".to_string(),
+            allow_from: vec!["42".to_string()],
+        };
+        let (bus, mut in_rx, _out_rx) = MessageBus::channels();
+        let bus = bus.with_inbound_policy(InboundPolicy::new().block_sender("42"));
+        let channel = TelegramChannel::new(config, bus.clone());
+
+        assert!(channel.admit("42"), "channel allow-list permits the sender");
+        bus.publish_inbound(InboundMessage::new("telegram", "42", "-1001", "hi"))
+            .unwrap();
+
+        assert!(in_rx.try_recv().is_err());
+        assert_eq!(bus.metrics().dropped_unauthorized("telegram"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_allowed_sender_passes_both_gates() {
+        let config = TelegramConfig {
+            enabled: true,
+            token: "token".to_string(),
+            allow_from: vec!["7".to_string()],
+        };
+        let (bus, mut in_rx, _out_rx) = MessageBus::channels();
+        let bus = bus.with_inbound_policy(InboundPolicy::new().block_sender("42"));
+        let channel = TelegramChannel::new(config, bus.clone());
+
+        assert!(channel.admit("7"));
+        bus.publish_inbound(InboundMessage::new("telegram", "7", "-1001", "hi"))
+            .unwrap();
+
+        assert_eq!(in_rx.try_recv().unwrap().sender_id, "7");
+        assert_eq!(bus.metrics().dropped_unauthorized("telegram"), 0);
+    }
+
+    #[test]
+    fn test_edited_to_inbound_marks_edit() {
+        let edited_at = Local::now();
+        let inbound = edited_to_inbound("42", -1001, 77, "corrected text", edited_at);
+
+        assert_eq!(inbound.channel, "telegram");
+        assert_eq!(inbound.sender_id, "42");
+        assert_eq!(inbound.chat_id, "-1001");
+        assert_eq!(inbound.content, "corrected text");
+        assert_eq!(inbound.timestamp, edited_at);
+        assert_eq!(inbound.metadata[EDITED_KEY].as_bool(), Some(true));
+        assert_eq!(inbound.metadata[ORIGINAL_MESSAGE_ID_KEY].as_i64(), Some(77));
+    }
+
+    #[test]
+    fn test_reply_context_captured_in_metadata() {
+        let inbound = InboundMessage::new("telegram", "42", "-1001", "yes, that one");
+        let inbound = with_reply_context(inbound, 55, Some("Deploy on Friday?"), Some("Ops"));
+
+        assert_eq!(inbound.metadata[REPLY_TO_ID_KEY].as_i64(), Some(55));
+        assert_eq!(inbound.replied_text(), Some("Deploy on Friday?"));
+        assert_eq!(inbound.replied_sender(), Some("Ops"));
+    }
+
+    #[test]
+    fn test_reply_context_without_text_keeps_id() {
+        let inbound = InboundMessage::new("telegram", "42", "-1001", "this photo");
+        let inbound = with_reply_context(inbound, 56, None, None);
+
+        assert_eq!(inbound.metadata[REPLY_TO_ID_KEY].as_i64(), Some(56));
+        assert_eq!(inbound.replied_text(), None);
+    }
+
+    #[test]
+    fn test_edited_to_inbound_shares_session_with_original() {
+        let original = InboundMessage::new("telegram", "42", "-1001", "typo");
+        let edit = edited_to_inbound("42", -1001, 77, "fixed", Local::now());
+
+        assert_eq!(edit.session_key(), original.session_key());
+    }
+
     // =========================================================================
     // Async Method Tests (basic smoke tests)
     // =========================================================================
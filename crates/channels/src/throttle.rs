@@ -0,0 +1,194 @@
+//! Outbound send throttling
+//!
+//! Token buckets, kept as GCRA arrival times, limit sends both per chat and
+//! across the whole channel. `reserve` books a slot and returns how long to
+//! wait before using it, so callers only need to sleep.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Telegram's documented global limit
+pub const DEFAULT_GLOBAL_PER_SEC: f64 = 30.0;
+
+/// Telegram's documented per-chat limit
+pub const DEFAULT_PER_CHAT_PER_SEC: f64 = 1.0;
+
+/// One rate limit with a burst allowance
+#[derive(Debug, Clone)]
+struct Bucket {
+    interval: Duration,
+    tolerance: Duration,
+    /// Earliest time the bucket is completely refilled
+    tat: Option<Instant>,
+}
+
+impl Bucket {
+    /// `burst` sends may go back to back before spacing applies
+    fn new(per_sec: f64, burst: u32) -> Self {
+        let interval = Duration::from_secs_f64(1.0 / per_sec);
+        Self {
+            interval,
+            tolerance: interval * burst.saturating_sub(1),
+            tat: None,
+        }
+    }
+
+    /// Earliest instant at or after `now` this bucket admits a send
+    fn allowed_at(&self, now: Instant) -> Instant {
+        match self.tat {
+            Some(tat) => now.max(tat.checked_sub(self.tolerance).unwrap_or(now)),
+            None => now,
+        }
+    }
+
+    /// Whether the bucket has refilled by `now`, making it the same as a new one
+    fn is_full(&self, now: Instant) -> bool {
+        self.tat.is_none_or(|tat| tat <= now)
+    }
+
+    /// Record a send at `at`
+    fn consume(&mut self, at: Instant) {
+        let tat = self.tat.map_or(at, |tat| tat.max(at));
+        self.tat = Some(tat + self.interval);
+    }
+}
+
+/// Per-chat and global send limits
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    global: Bucket,
+    per_chat_per_sec: f64,
+    chats: HashMap<String, Bucket>,
+}
+
+impl Throttle {
+    /// Limit to `global_per_sec` overall and `per_chat_per_sec` per chat
+    ///
+    /// The global limit allows a one-second burst; chats get none.
+    /// Non-positive rates fall back to the defaults.
+    pub fn new(global_per_sec: f64, per_chat_per_sec: f64) -> Self {
+        let global_per_sec = positive_or(global_per_sec, DEFAULT_GLOBAL_PER_SEC);
+        Self {
+            global: Bucket::new(global_per_sec, global_per_sec.floor().max(1.0) as u32),
+            per_chat_per_sec: positive_or(per_chat_per_sec, DEFAULT_PER_CHAT_PER_SEC),
+            chats: HashMap::new(),
+        }
+    }
+
+    /// Book the next send to `chat_id`, returning the delay before it may go
+    ///
+    /// Chats whose buckets have refilled are forgotten, so idle chats do not
+    /// accumulate.
+    pub fn reserve(&mut self, chat_id: &str, now: Instant) -> Duration {
+        self.chats.retain(|_, bucket| !bucket.is_full(now));
+        let per_chat = self.per_chat_per_sec;
+        let chat = self
+            .chats
+            .entry(chat_id.to_string())
+            .or_insert_with(|| Bucket::new(per_chat, 1));
+        let at = chat.allowed_at(now).max(self.global.allowed_at(now));
+        chat.consume(at);
+        self.global.consume(at);
+        at - now
+    }
+
+    /// Chats with a bucket still refilling
+    pub fn tracked_chats(&self) -> usize {
+        self.chats.len()
+    }
+}
+
+impl Default for Throttle {
+    fn default() -> Self {
+        Self::new(DEFAULT_GLOBAL_PER_SEC, DEFAULT_PER_CHAT_PER_SEC)
+    }
+}
+
+fn positive_or(value: f64, default: f64) -> f64 {
+    if value.is_finite() && value > 0.0 {
+        value
+    } else {
+        default
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(d: Duration) -> u128 {
+        d.as_millis()
+    }
+
+    #[test]
+    fn test_burst_to_one_chat_is_spaced_per_chat() {
+        let mut throttle = Throttle::new(30.0, 1.0);
+        let now = Instant::now();
+
+        let delays: Vec<u128> = (0..3).map(|_| ms(throttle.reserve("42", now))).collect();
+
+        assert_eq!(delays, vec![0, 1000, 2000]);
+    }
+
+    #[test]
+    fn test_different_chats_share_global_burst() {
+        let mut throttle = Throttle::new(30.0, 1.0);
+        let now = Instant::now();
+
+        for chat in 0..30 {
+            assert_eq!(throttle.reserve(&chat.to_string(), now), Duration::ZERO);
+        }
+        // The 31st send has to wait for the global bucket to drip
+        let delay = throttle.reserve("30", now);
+        assert!(delay > Duration::ZERO && ms(delay) <= 34, "{:?}", delay);
+    }
+
+    #[test]
+    fn test_global_limit_slows_chat_limit() {
+        let mut throttle = Throttle::new(2.0, 10.0);
+        let now = Instant::now();
+
+        let delays: Vec<u128> = (0..4).map(|_| ms(throttle.reserve("42", now))).collect();
+
+        // Two-message global burst, then one every 500ms
+        assert_eq!(delays[0], 0);
+        assert_eq!(delays[1], 100);
+        assert_eq!(delays[2], 500);
+        assert_eq!(delays[3], 1000);
+    }
+
+    #[test]
+    fn test_waiting_refills_buckets() {
+        let mut throttle = Throttle::new(30.0, 1.0);
+        let now = Instant::now();
+
+        assert_eq!(throttle.reserve("42", now), Duration::ZERO);
+        let later = now + Duration::from_secs(2);
+        assert_eq!(throttle.reserve("42", later), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_refilled_chats_are_evicted() {
+        let mut throttle = Throttle::new(30.0, 1.0);
+        let now = Instant::now();
+
+        for chat in 0..10 {
+            throttle.reserve(&chat.to_string(), now);
+        }
+        assert_eq!(throttle.tracked_chats(), 10);
+
+        let later = now + Duration::from_secs(2);
+        assert_eq!(throttle.reserve("42", later), Duration::ZERO);
+        assert_eq!(throttle.tracked_chats(), 1);
+        assert_eq!(ms(throttle.reserve("42", later)), 1000);
+    }
+
+    #[test]
+    fn test_invalid_rates_fall_back_to_defaults() {
+        let mut throttle = Throttle::new(0.0, -1.0);
+        let now = Instant::now();
+
+        assert_eq!(throttle.reserve("42", now), Duration::ZERO);
+        assert_eq!(ms(throttle.reserve("42", now)), 1000);
+    }
+}
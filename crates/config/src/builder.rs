@@ -0,0 +1,216 @@
+//! Validated construction of operative defaults
+
+use crate::{ConfigError, OperativeDefaults, Result};
+
+/// Largest frequency or presence penalty, either sign, providers accept
+pub const MAX_PENALTY: f32 = 2.0;
+
+/// Highest sampling temperature providers accept
+pub const MAX_TEMPERATURE: f32 = 2.0;
+
+impl OperativeDefaults {
+    /// Start from the defaults; `build` checks every range
+    pub fn builder() -> OperativeDefaultsBuilder {
+        OperativeDefaultsBuilder::default()
+    }
+
+    /// Check that every value is in range
+    pub fn validate(&self) -> Result<()> {
+        if self.model.trim().is_empty() {
+            return Err(invalid("model", "must not be empty"));
+        }
+        if self.max_tokens == 0 {
+            return Err(invalid("max_tokens", "must be at least 1"));
+        }
+        check_temperature("temperature", self.temperature)?;
+        check_temperature("max_temperature", self.max_temperature)?;
+        if self.max_temperature < self.temperature {
+            return Err(invalid(
+                "max_temperature",
+                format!(
+                    "{} is below temperature {}",
+                    self.max_temperature, self.temperature
+                ),
+            ));
+        }
+        if self.stall_temperature_step.is_nan() || self.stall_temperature_step < 0.0 {
+            return Err(invalid(
+                "stall_temperature_step",
+                format!("{} must not be negative", self.stall_temperature_step),
+            ));
+        }
+        if self.max_tool_iterations == 0 {
+            return Err(invalid("max_tool_iterations", "must be at least 1"));
+        }
+        if self.session_max_messages == 0 {
+            return Err(invalid("session_max_messages", "must be at least 1"));
+        }
+        if self.session_context_window == 0 {
+            return Err(invalid("session_context_window", "must be at least 1"));
+        }
+        if self.session_save_attempts == 0 {
+            return Err(invalid("session_save_attempts", "must be at least 1"));
+        }
+        if let Some(threshold) = self.compact_threshold {
+            if threshold.is_nan() || threshold <= 0.0 || threshold > 1.0 {
+                return Err(invalid(
+                    "compact_threshold",
+                    format!("{} is outside (0, 1]", threshold),
+                ));
+            }
+        }
+        if let Some(top_p) = self.top_p {
+            if top_p.is_nan() || top_p <= 0.0 || top_p > 1.0 {
+                return Err(invalid("top_p", format!("{} is outside (0, 1]", top_p)));
+            }
+        }
+        check_penalty("frequency_penalty", self.frequency_penalty)?;
+        check_penalty("presence_penalty", self.presence_penalty)?;
+        if self.timeout_s == 0 {
+            return Err(invalid("timeout_s", "must be at least 1"));
+        }
+        if self.max_output_chars == Some(0) {
+            return Err(invalid("max_output_chars", "must be at least 1 when set"));
+        }
+        Ok(())
+    }
+}
+
+/// Builder for `OperativeDefaults` that rejects out-of-range values
+#[derive(Debug, Clone, Default)]
+pub struct OperativeDefaultsBuilder {
+    defaults: OperativeDefaults,
+}
+
+impl From<OperativeDefaults> for OperativeDefaultsBuilder {
+    /// Start from existing values, e.g. a loaded config
+    fn from(defaults: OperativeDefaults) -> Self {
+        Self { defaults }
+    }
+}
+
+impl OperativeDefaultsBuilder {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.defaults.name = name.into();
+        self
+    }
+
+    pub fn workspace(mut self, workspace: impl Into<String>) -> Self {
+        self.defaults.workspace = workspace.into();
+        self
+    }
+
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.defaults.model = model.into();
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.defaults.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.defaults.temperature = temperature;
+        self
+    }
+
+    pub fn max_temperature(mut self, max_temperature: f32) -> Self {
+        self.defaults.max_temperature = max_temperature;
+        self
+    }
+
+    pub fn stall_temperature_step(mut self, step: f32) -> Self {
+        self.defaults.stall_temperature_step = step;
+        self
+    }
+
+    pub fn max_tool_iterations(mut self, iterations: u32) -> Self {
+        self.defaults.max_tool_iterations = iterations;
+        self
+    }
+
+    pub fn session_max_messages(mut self, max_messages: usize) -> Self {
+        self.defaults.session_max_messages = max_messages;
+        self
+    }
+
+    pub fn session_context_window(mut self, context_window: usize) -> Self {
+        self.defaults.session_context_window = context_window;
+        self
+    }
+
+    pub fn session_save_attempts(mut self, attempts: u32) -> Self {
+        self.defaults.session_save_attempts = attempts;
+        self
+    }
+
+    pub fn compact_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.defaults.compact_threshold = threshold;
+        self
+    }
+
+    pub fn max_output_chars(mut self, max_chars: Option<usize>) -> Self {
+        self.defaults.max_output_chars = max_chars;
+        self
+    }
+
+    pub fn top_p(mut self, top_p: Option<f32>) -> Self {
+        self.defaults.top_p = top_p;
+        self
+    }
+
+    pub fn frequency_penalty(mut self, penalty: Option<f32>) -> Self {
+        self.defaults.frequency_penalty = penalty;
+        self
+    }
+
+    pub fn presence_penalty(mut self, penalty: Option<f32>) -> Self {
+        self.defaults.presence_penalty = penalty;
+        self
+    }
+
+    pub fn stop(mut self, stop: Vec<String>) -> Self {
+        self.defaults.stop = stop;
+        self
+    }
+
+    pub fn timeout_s(mut self, timeout_s: u64) -> Self {
+        self.defaults.timeout_s = timeout_s;
+        self
+    }
+
+    /// Validate and return the defaults
+    pub fn build(self) -> Result<OperativeDefaults> {
+        self.defaults.validate()?;
+        Ok(self.defaults)
+    }
+}
+
+fn check_temperature(field: &'static str, value: f32) -> Result<()> {
+    if (0.0..=MAX_TEMPERATURE).contains(&value) {
+        Ok(())
+    } else {
+        Err(invalid(
+            field,
+            format!("{} is outside 0.0..={}", value, MAX_TEMPERATURE),
+        ))
+    }
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> ConfigError {
+    ConfigError::Invalid {
+        field,
+        reason: reason.into(),
+    }
+}
+
+fn check_penalty(field: &'static str, value: Option<f32>) -> Result<()> {
+    match value {
+        Some(penalty) if !(-MAX_PENALTY..=MAX_PENALTY).contains(&penalty) => Err(invalid(
+            field,
+            format!("{} is outside -{1}..={1}", penalty, MAX_PENALTY),
+        )),
+        _ => Ok(()),
+    }
+}
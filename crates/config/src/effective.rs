@@ -0,0 +1,90 @@
+//! Effective configuration
+//!
+//! Environment overrides layered on top of the config file, and a masked
+//! copy of the result that is safe to print.
+
+use crate::{Config, ProviderConfig};
+
+/// Overrides `operative.defaults.model`
+pub const ENV_MODEL: &str = "OPENSAM_MODEL";
+/// Overrides `operative.defaults.workspace`
+pub const ENV_WORKSPACE: &str = "OPENSAM_WORKSPACE";
+/// Overrides `soliton.openrouter.api_key`
+pub const ENV_OPENROUTER_API_KEY: &str = "OPENROUTER_API_KEY";
+/// Overrides `frequency.telegram.token`
+pub const ENV_TELEGRAM_TOKEN: &str = "OPENSAM_TELEGRAM_TOKEN";
+/// Overrides `toolkit.web.search.api_key`
+pub const ENV_BRAVE_API_KEY: &str = "BRAVE_API_KEY";
+
+/// Every variable consulted by `apply_env_overrides`
+pub const ENV_OVERRIDES: &[&str] = &[
+    ENV_MODEL,
+    ENV_WORKSPACE,
+    ENV_OPENROUTER_API_KEY,
+    ENV_TELEGRAM_TOKEN,
+    ENV_BRAVE_API_KEY,
+];
+
+/// Placeholder shown instead of a configured secret
+pub const MASKED: &str = "********";
+
+impl Config {
+    /// Load the config file and apply environment overrides
+    pub async fn load_effective() -> crate::Result<Self> {
+        let mut config = Self::load().await?;
+        config.apply_env_overrides();
+        Ok(config)
+    }
+
+    /// Apply overrides from the process environment
+    pub fn apply_env_overrides(&mut self) {
+        self.apply_overrides(|name| std::env::var(name).ok());
+    }
+
+    /// Apply overrides from `lookup`; empty values are ignored
+    pub fn apply_overrides(&mut self, lookup: impl Fn(&str) -> Option<String>) {
+        let get = |name: &str| lookup(name).filter(|v| !v.is_empty());
+
+        if let Some(model) = get(ENV_MODEL) {
+            self.operative.defaults.model = model;
+        }
+        if let Some(workspace) = get(ENV_WORKSPACE) {
+            self.operative.defaults.workspace = workspace;
+        }
+        if let Some(key) = get(ENV_OPENROUTER_API_KEY) {
+            self.providers.openrouter.api_key = key;
+        }
+        if let Some(token) = get(ENV_TELEGRAM_TOKEN) {
+            self.frequency.telegram.token = token;
+        }
+        if let Some(key) = get(ENV_BRAVE_API_KEY) {
+            self.toolkit.web.search.api_key = key;
+        }
+    }
+
+    /// Copy of the config with secrets masked
+    ///
+    /// Unset secrets stay empty so it is still visible which ones are missing.
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        for provider in config.providers.all_mut() {
+            mask_provider(provider);
+        }
+        mask(&mut config.frequency.telegram.token);
+        mask(&mut config.toolkit.web.search.api_key);
+        config
+    }
+}
+
+fn mask_provider(provider: &mut ProviderConfig) {
+    mask(&mut provider.api_key);
+    for value in provider.extra_headers.values_mut() {
+        mask(value);
+    }
+}
+
+fn mask(secret: &mut String) {
+    if !secret.is_empty() {
+        *secret = MASKED.to_string();
+    }
+}
@@ -0,0 +1,94 @@
+//! Migration from the original flat config layout
+//!
+//! Early builds stored `{"api_key", "default_model", "workspace"}` at the
+//! top level; the nested schema moved them under `soliton` and
+//! `operative.defaults`.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::{Config, Result};
+
+/// Top-level keys of the flat layout
+pub const LEGACY_KEYS: [&str; 4] = ["api_key", "api_base", "default_model", "workspace"];
+
+impl Config {
+    /// Whether `value` uses the flat layout
+    pub fn is_legacy(value: &Value) -> bool {
+        value
+            .as_object()
+            .is_some_and(|obj| LEGACY_KEYS.iter().any(|key| obj.contains_key(*key)))
+    }
+
+    /// Map a flat config into the nested structure
+    ///
+    /// Legacy fields take precedence; any nested sections already present
+    /// are kept, and fail the mapping when they do not parse.
+    pub fn from_legacy(value: Value) -> Result<Config> {
+        let Value::Object(mut obj) = value else {
+            return Ok(Config::default());
+        };
+        let mut take = |key: &str| match obj.remove(key) {
+            Some(Value::String(s)) if !s.is_empty() => Some(s),
+            _ => None,
+        };
+        let api_key = take("api_key");
+        let api_base = take("api_base");
+        let model = take("default_model");
+        let workspace = take("workspace");
+
+        let mut config: Config = serde_json::from_value(Value::Object(obj))?;
+        if let Some(key) = api_key {
+            config.providers.openrouter.api_key = key;
+        }
+        if api_base.is_some() {
+            config.providers.openrouter.api_base = api_base;
+        }
+        if let Some(model) = model {
+            config.operative.defaults.model = model;
+        }
+        if let Some(workspace) = workspace {
+            config.operative.defaults.workspace = workspace;
+        }
+        Ok(config)
+    }
+
+    /// Parse config JSON, migrating the flat layout when detected
+    pub fn from_json(content: &str) -> Result<Config> {
+        let value: Value = serde_json::from_str(content)?;
+        if Self::is_legacy(&value) {
+            warn!("◆ LEGACY INTEL FORMAT DETECTED; RUN `sam config migrate`");
+            return Self::from_legacy(value);
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Where [`Config::migrate_file`] keeps the original of `path`
+    pub fn legacy_backup_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".bak");
+        path.with_file_name(name)
+    }
+
+    /// Rewrite a flat config file in the nested schema
+    ///
+    /// The original is copied to [`Config::legacy_backup_path`] first, and
+    /// nothing is written when it cannot be mapped. Returns `false` when the
+    /// file is missing or already current.
+    pub async fn migrate_file(path: &Path) -> Result<bool> {
+        if !path.exists() {
+            return Ok(false);
+        }
+        let content = tokio::fs::read_to_string(path).await?;
+        let value: Value = serde_json::from_str(&content)?;
+        if !Self::is_legacy(&value) {
+            return Ok(false);
+        }
+        let config = Self::from_legacy(value)?;
+        tokio::fs::copy(path, Self::legacy_backup_path(path)).await?;
+        config.save_to(path).await?;
+        info!("◆ INTEL MIGRATED AT {:?}", path);
+        Ok(true)
+    }
+}
@@ -3,13 +3,24 @@
 //! Handles loading and saving mission parameters from encrypted storage.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 use tracing::{debug, info, warn};
 
+pub mod builder;
+pub mod effective;
+pub mod legacy;
+pub mod logging;
 pub mod paths;
+pub mod provider;
+pub mod secrets;
+pub mod workspace;
 
+pub use builder::OperativeDefaultsBuilder;
 pub use paths::{config_path, data_dir, workspace_path};
+pub use provider::ProviderKind;
+pub use workspace::WorkspaceFallback;
 
 /// Errors in configuration systems
 #[derive(Error, Debug)]
@@ -22,6 +33,18 @@ pub enum ConfigError {
 
     #[error("INTEL NOT FOUND: {0}")]
     NotFound(PathBuf),
+
+    #[error("SECRET {reference} UNAVAILABLE: {reason}")]
+    Secret { reference: String, reason: String },
+
+    #[error("INVALID PARAMETER {field}: {reason}")]
+    Invalid { field: &'static str, reason: String },
+
+    #[error("WORKSPACE {path} NOT WRITABLE: {source}")]
+    WorkspaceUnwritable {
+        path: PathBuf,
+        source: std::io::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, ConfigError>;
@@ -29,10 +52,26 @@ pub type Result<T> = std::result::Result<T, ConfigError>;
 /// SOLITON network configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProviderConfig {
+    /// The key, or an `env:VAR` / `file:/path` reference resolved at load
     #[serde(default)]
     pub api_key: String,
+    /// Reference `api_key` was resolved from, written back on save
+    #[serde(skip)]
+    pub api_key_ref: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_base: Option<String>,
+    /// Extra HTTP headers sent with every request
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_headers: HashMap<String, String>,
+    /// Model used for embeddings, instead of the provider default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    /// Header carrying a unique id per chat request; empty disables it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id_header: Option<String>,
+    /// Chat model this entry serves as a fallback node, instead of the default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
 }
 
 /// All SOLITON network nodes
@@ -46,6 +85,24 @@ pub struct SolitonConfig {
     pub openrouter: ProviderConfig,
     #[serde(default)]
     pub vllm: ProviderConfig,
+    /// Regex patterns redacted from prompts and replies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redaction: Vec<String>,
+    /// Backoff for rate-limited and failed requests
+    #[serde(default)]
+    pub retry: opensam_provider::RetryConfig,
+}
+
+impl SolitonConfig {
+    /// Every provider entry
+    pub fn all_mut(&mut self) -> [&mut ProviderConfig; 4] {
+        [
+            &mut self.anthropic,
+            &mut self.openai,
+            &mut self.openrouter,
+            &mut self.vllm,
+        ]
+    }
 }
 
 /// WhatsApp frequency
@@ -74,7 +131,7 @@ fn default_bridge_url() -> String {
 }
 
 /// Telegram frequency
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelegramConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -82,6 +139,44 @@ pub struct TelegramConfig {
     pub token: String,
     #[serde(default)]
     pub allow_from: Vec<String>,
+    /// Outbound sends per second across all chats
+    #[serde(default = "default_telegram_global_per_sec")]
+    pub global_per_sec: f64,
+    /// Outbound sends per second to any one chat
+    #[serde(default = "default_telegram_per_chat_per_sec")]
+    pub per_chat_per_sec: f64,
+    /// Emphasis nesting rendered in outgoing markdown before markers stay literal
+    #[serde(default = "default_telegram_markdown_depth")]
+    pub markdown_depth: usize,
+    /// Render a reply wrapped entirely in a ```markdown fence as prose
+    #[serde(default = "default_true")]
+    pub unwrap_markdown_fences: bool,
+}
+
+impl Default for TelegramConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token: String::new(),
+            allow_from: Vec::new(),
+            global_per_sec: default_telegram_global_per_sec(),
+            per_chat_per_sec: default_telegram_per_chat_per_sec(),
+            markdown_depth: default_telegram_markdown_depth(),
+            unwrap_markdown_fences: true,
+        }
+    }
+}
+
+fn default_telegram_global_per_sec() -> f64 {
+    30.0
+}
+
+fn default_telegram_per_chat_per_sec() -> f64 {
+    1.0
+}
+
+fn default_telegram_markdown_depth() -> usize {
+    4
 }
 
 /// All frequency configurations
@@ -93,36 +188,161 @@ pub struct FrequencyConfig {
     pub telegram: TelegramConfig,
 }
 
+/// Overview of one configured frequency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelSummary {
+    pub name: String,
+    pub enabled: bool,
+    /// Number of allow-listed senders; zero admits anyone
+    pub allowed: usize,
+}
+
+impl FrequencyConfig {
+    /// Summaries of every frequency, sorted by name
+    ///
+    /// Built from the serialized config, so a new frequency with `enabled`
+    /// and `allow_from` fields is listed without changes here.
+    pub fn summaries(&self) -> Vec<ChannelSummary> {
+        let Ok(serde_json::Value::Object(channels)) = serde_json::to_value(self) else {
+            return Vec::new();
+        };
+        let mut summaries: Vec<ChannelSummary> = channels
+            .into_iter()
+            .map(|(name, channel)| ChannelSummary {
+                enabled: channel["enabled"].as_bool().unwrap_or(false),
+                allowed: channel["allow_from"].as_array().map_or(0, |a| a.len()),
+                name,
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.name.cmp(&b.name));
+        summaries
+    }
+}
+
 /// Default operative parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OperativeDefaults {
+    /// Assistant name substituted for `{{name}}` in workspace templates
+    #[serde(default = "default_name")]
+    pub name: String,
     #[serde(default = "default_workspace")]
     pub workspace: String,
+    /// Behavior when the workspace is not writable at startup
+    #[serde(default)]
+    pub workspace_fallback: WorkspaceFallback,
     #[serde(default = "default_model")]
     pub model: String,
     #[serde(default = "default_max_tokens")]
     pub max_tokens: u32,
+    /// Smallest reply budget left after clamping max_tokens to the context
+    #[serde(default = "default_min_max_tokens")]
+    pub min_max_tokens: u32,
+    /// Context window per model id; max_tokens is clamped to fit for these
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub model_context_lengths: HashMap<String, usize>,
     #[serde(default = "default_temperature")]
     pub temperature: f32,
+    /// Temperature added per stalled tool-loop iteration (0 disables)
+    #[serde(default)]
+    pub stall_temperature_step: f32,
+    /// Ceiling for the raised temperature
+    #[serde(default = "default_max_temperature")]
+    pub max_temperature: f32,
+    /// Nucleus sampling cutoff in (0, 1]; the node's default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Penalty in -2.0..=2.0 on tokens by how often they already appeared
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    /// Penalty in -2.0..=2.0 on tokens that already appeared at all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Sequences that end generation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
     #[serde(default = "default_max_iterations")]
     pub max_tool_iterations: u32,
     #[serde(default = "default_session_max_messages")]
     pub session_max_messages: usize,
+    /// Recent messages sent to the model, independent of storage
+    #[serde(default = "default_session_context_window")]
+    pub session_context_window: usize,
+    /// Attempts per session save before giving up until the next turn
+    #[serde(default = "default_session_save_attempts")]
+    pub session_save_attempts: u32,
+    /// Save changed sessions every this many seconds, between turns too
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_flush_interval_s: Option<u64>,
+    /// Summarize older messages once history fills this fraction of the context window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compact_threshold: Option<f32>,
+    /// Trim replies longer than this many characters (sessions keep them whole)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_chars: Option<usize>,
+    /// Append a token/cost footer to replies
+    #[serde(default)]
+    pub usage_footer: bool,
+    /// Price per 1000 tokens used for the footer's cost estimate
+    #[serde(default)]
+    pub cost_per_1k_tokens: f64,
+    /// Price per 1000 tokens by model, overriding `cost_per_1k_tokens`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub model_prices: HashMap<String, f64>,
+    /// Remove text between the reasoning markers from replies
+    #[serde(default)]
+    pub strip_reasoning: bool,
+    #[serde(default = "default_reasoning_open")]
+    pub reasoning_open: String,
+    #[serde(default = "default_reasoning_close")]
+    pub reasoning_close: String,
+    /// Seconds before a request to the SOLITON node is abandoned
+    #[serde(default = "default_timeout_s")]
+    pub timeout_s: u64,
+    /// User-facing error message overrides, keyed by error kind
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub error_messages: HashMap<String, String>,
 }
 
 impl Default for OperativeDefaults {
     fn default() -> Self {
         Self {
+            name: default_name(),
             workspace: default_workspace(),
+            workspace_fallback: WorkspaceFallback::default(),
             model: default_model(),
             max_tokens: default_max_tokens(),
+            min_max_tokens: default_min_max_tokens(),
+            model_context_lengths: HashMap::new(),
             temperature: default_temperature(),
+            stall_temperature_step: 0.0,
+            max_temperature: default_max_temperature(),
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            stop: Vec::new(),
             max_tool_iterations: default_max_iterations(),
             session_max_messages: default_session_max_messages(),
+            session_context_window: default_session_context_window(),
+            session_save_attempts: default_session_save_attempts(),
+            session_flush_interval_s: None,
+            compact_threshold: None,
+            max_output_chars: None,
+            usage_footer: false,
+            cost_per_1k_tokens: 0.0,
+            model_prices: HashMap::new(),
+            strip_reasoning: false,
+            reasoning_open: default_reasoning_open(),
+            reasoning_close: default_reasoning_close(),
+            timeout_s: default_timeout_s(),
+            error_messages: HashMap::new(),
         }
     }
 }
 
+fn default_name() -> String {
+    "OpenSAM".to_string()
+}
+
 fn default_workspace() -> String {
     "~/.opensam/ops".to_string()
 }
@@ -135,10 +355,18 @@ fn default_max_tokens() -> u32 {
     8192
 }
 
+fn default_min_max_tokens() -> u32 {
+    256
+}
+
 fn default_temperature() -> f32 {
     0.7
 }
 
+fn default_max_temperature() -> f32 {
+    1.2
+}
+
 fn default_max_iterations() -> u32 {
     20
 }
@@ -147,6 +375,26 @@ fn default_session_max_messages() -> usize {
     100
 }
 
+fn default_session_context_window() -> usize {
+    20
+}
+
+fn default_session_save_attempts() -> u32 {
+    3
+}
+
+fn default_timeout_s() -> u64 {
+    opensam_provider::openrouter::DEFAULT_TIMEOUT.as_secs()
+}
+
+fn default_reasoning_open() -> String {
+    "<think>".to_string()
+}
+
+fn default_reasoning_close() -> String {
+    "</think>".to_string()
+}
+
 /// Operative configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct OperativeConfig {
@@ -176,18 +424,148 @@ impl Default for WebSearchConfig {
     }
 }
 
+/// Web fetch TOOLKIT configuration
+///
+/// Internal addresses are always refused unless listed in `allow_hosts`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebFetchConfig {
+    /// When non-empty, the only hosts web_fetch may request
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_hosts: Vec<String>,
+    /// Hosts web_fetch must never request
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny_hosts: Vec<String>,
+    /// Bytes downloaded per fetch before the body is cut off
+    #[serde(default = "default_fetch_max_bytes")]
+    pub max_bytes: usize,
+}
+
+impl Default for WebFetchConfig {
+    fn default() -> Self {
+        Self {
+            allow_hosts: Vec::new(),
+            deny_hosts: Vec::new(),
+            max_bytes: default_fetch_max_bytes(),
+        }
+    }
+}
+
+fn default_fetch_max_bytes() -> usize {
+    2_000_000
+}
+
 /// Web TOOLKIT configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WebToolkitConfig {
     #[serde(default)]
     pub search: WebSearchConfig,
+    #[serde(default)]
+    pub fetch: WebFetchConfig,
 }
 
-/// TOOLKIT configuration
+/// Post-processing applied to a tool result before the model sees it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ToolResultFormat {
+    /// Wrap the result as "tool X returned:\n..."
+    #[serde(default)]
+    pub envelope: bool,
+    /// Re-indent results that parse as JSON
+    #[serde(default)]
+    pub pretty_json: bool,
+}
+
+/// Tool result formatting, with optional per-tool overrides
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ToolResultsConfig {
+    #[serde(default)]
+    pub default: ToolResultFormat,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tools: HashMap<String, ToolResultFormat>,
+}
+
+impl ToolResultsConfig {
+    /// Format settings for a tool, falling back to the default
+    pub fn format_for(&self, tool: &str) -> ToolResultFormat {
+        self.tools.get(tool).copied().unwrap_or(self.default)
+    }
+}
+
+/// Memory TOOLKIT configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryToolkitConfig {
+    /// Register the memory tool so the agent can record facts
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Register the embedding-based memory search tool
+    #[serde(default)]
+    pub search: bool,
+    /// Snippets the search returns by default
+    #[serde(default = "default_memory_search_top_k")]
+    pub search_top_k: usize,
+    /// Workspace-relative files searched besides MEMORY.md
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search_files: Vec<String>,
+}
+
+fn default_memory_search_top_k() -> usize {
+    3
+}
+
+impl Default for MemoryToolkitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            search: false,
+            search_top_k: default_memory_search_top_k(),
+            search_files: Vec::new(),
+        }
+    }
+}
+
+/// Clock TOOLKIT configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeToolkitConfig {
+    /// Register the current-time tool
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl Default for TimeToolkitConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// TOOLKIT configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolkitConfig {
     #[serde(default)]
     pub web: WebToolkitConfig,
+    #[serde(default)]
+    pub results: ToolResultsConfig,
+    #[serde(default)]
+    pub memory: MemoryToolkitConfig,
+    #[serde(default)]
+    pub time: TimeToolkitConfig,
+    /// Check tool-call arguments against the tool schema before executing
+    #[serde(default = "default_true")]
+    pub validate_arguments: bool,
+}
+
+impl Default for ToolkitConfig {
+    fn default() -> Self {
+        Self {
+            web: WebToolkitConfig::default(),
+            results: ToolResultsConfig::default(),
+            memory: MemoryToolkitConfig::default(),
+            time: TimeToolkitConfig::default(),
+            validate_arguments: true,
+        }
+    }
 }
 
 /// Gateway deployment configuration
@@ -197,6 +575,53 @@ pub struct DeployConfig {
     pub host: String,
     #[serde(default = "default_port")]
     pub port: u16,
+    /// Agent turns processed in parallel (turns within a session stay ordered)
+    #[serde(default = "default_max_concurrent_turns")]
+    pub max_concurrent_turns: usize,
+    /// Scheduled jobs run in parallel; the rest wait their turn
+    #[serde(default = "default_max_concurrent_jobs")]
+    pub max_concurrent_jobs: usize,
+    /// Previous versions of the job store kept as `cron.json.1`..`N` (0 keeps none)
+    #[serde(default)]
+    pub cron_backups: usize,
+    /// Drop inbound messages older than this many seconds (unset keeps all)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_message_age_s: Option<u64>,
+    /// Tell the sender when their message was dropped as stale
+    #[serde(default)]
+    pub reply_to_stale: bool,
+    /// Content given to caption-less media, `{n}` being the attachment count
+    /// (unset uses the bus default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_prompt: Option<String>,
+    /// Merge consecutive text replies to one chat sent within this many
+    /// milliseconds (unset sends each separately)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outbound_batch_ms: Option<u64>,
+    /// Tell the sender when their turn failed
+    #[serde(default = "default_true")]
+    pub notify_errors: bool,
+    /// Inbound messages queued before channels must wait; unbounded if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inbound_capacity: Option<usize>,
+    /// Cancel a session's in-flight turn when a newer message arrives for it
+    ///
+    /// Off by default: the superseded turn is dropped mid-flight, so a tool
+    /// call it started may be left half done.
+    #[serde(default)]
+    pub cancel_superseded_turns: bool,
+    /// Check every tool at startup and log the ones that fail
+    #[serde(default = "default_true")]
+    pub self_test: bool,
+    /// Seconds each task gets to finish after a shutdown signal
+    #[serde(default = "default_shutdown_timeout_s")]
+    pub shutdown_timeout_s: u64,
+    /// Seconds between writes of the telemetry snapshot read by `freq status`
+    #[serde(default = "default_metrics_interval_s")]
+    pub metrics_interval_s: u64,
+    /// Cross-channel inbound rules applied on top of per-channel allow-lists
+    #[serde(default)]
+    pub inbound: InboundPolicyConfig,
 }
 
 impl Default for DeployConfig {
@@ -204,10 +629,40 @@ impl Default for DeployConfig {
         Self {
             host: default_host(),
             port: default_port(),
+            max_concurrent_turns: default_max_concurrent_turns(),
+            max_concurrent_jobs: default_max_concurrent_jobs(),
+            cron_backups: 0,
+            max_message_age_s: None,
+            reply_to_stale: false,
+            attachment_prompt: None,
+            outbound_batch_ms: None,
+            notify_errors: true,
+            inbound_capacity: None,
+            cancel_superseded_turns: false,
+            self_test: true,
+            shutdown_timeout_s: default_shutdown_timeout_s(),
+            metrics_interval_s: default_metrics_interval_s(),
+            inbound: InboundPolicyConfig::default(),
         }
     }
 }
 
+/// Global inbound rules, enforced by the bus for every channel
+///
+/// Sender entries are bare ids (any channel) or `channel:id`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct InboundPolicyConfig {
+    /// Senders rejected on every channel
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_senders: Vec<String>,
+    /// Channels whose messages are rejected outright
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_channels: Vec<String>,
+    /// When set, only these senders are admitted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_senders: Vec<String>,
+}
+
 fn default_host() -> String {
     "0.0.0.0".to_string()
 }
@@ -216,6 +671,30 @@ fn default_port() -> u16 {
     18789
 }
 
+fn default_max_concurrent_turns() -> usize {
+    4
+}
+
+fn default_max_concurrent_jobs() -> usize {
+    1
+}
+
+fn default_shutdown_timeout_s() -> u64 {
+    5
+}
+
+fn default_metrics_interval_s() -> u64 {
+    30
+}
+
+/// Periodic wake-up configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HeartbeatConfig {
+    /// File that records every heartbeat that took action; relative to the workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_log: Option<String>,
+}
+
 /// Root mission parameters
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
@@ -229,6 +708,11 @@ pub struct Config {
     pub deploy: DeployConfig,
     #[serde(default)]
     pub toolkit: ToolkitConfig,
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig,
+    /// Per-module log levels, e.g. "opensam_agent=debug,teloxide=warn"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_filter: Option<String>,
 }
 
 impl Config {
@@ -238,8 +722,34 @@ impl Config {
         Self::load_from(&path).await
     }
 
+    /// Whether this is a fresh install: no config file has been written yet
+    pub fn is_first_run() -> bool {
+        Self::is_first_run_at(&config_path())
+    }
+
+    /// Whether no config file exists at `path`
+    pub fn is_first_run_at(path: &Path) -> bool {
+        !path.exists()
+    }
+
     /// Load from specific location
     pub async fn load_from(path: &Path) -> Result<Self> {
+        let mut config = Self::load_unresolved_from(path).await?;
+        config.resolve_secrets()?;
+        Ok(config)
+    }
+
+    /// Load from default location, keeping `env:`/`file:` references as written
+    pub async fn load_unresolved() -> Result<Self> {
+        let path = config_path();
+        Self::load_unresolved_from(&path).await
+    }
+
+    /// Load from specific location without resolving secret references
+    ///
+    /// Suited to rewriting the file: references survive a later save even
+    /// when the keys they name are not available right now.
+    pub async fn load_unresolved_from(path: &Path) -> Result<Self> {
         if !path.exists() {
             info!("◆ NO INTEL FOUND AT {:?}, USING DEFAULTS", path);
             return Ok(Config::default());
@@ -247,8 +757,19 @@ impl Config {
 
         debug!("◆ DECRYPTING INTEL FROM {:?}", path);
         let content = tokio::fs::read_to_string(path).await?;
-        let config: Config = serde_json::from_str(&content)?;
-        Ok(config)
+        Config::from_json(&content)
+    }
+
+    /// Replace `env:`/`file:` api key references with the keys they name
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        for provider in self.providers.all_mut() {
+            if secrets::is_reference(&provider.api_key) {
+                let reference = std::mem::take(&mut provider.api_key);
+                provider.api_key = secrets::resolve(&reference)?;
+                provider.api_key_ref = Some(reference);
+            }
+        }
+        Ok(())
     }
 
     /// Save mission parameters
@@ -265,7 +786,15 @@ impl Config {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let content = serde_json::to_string_pretty(self)?;
+        // Keep secret references on disk rather than the keys they resolved to
+        let mut stored = self.clone();
+        for provider in stored.providers.all_mut() {
+            if let Some(reference) = provider.api_key_ref.take() {
+                provider.api_key = reference;
+            }
+        }
+
+        let content = serde_json::to_string_pretty(&stored)?;
         tokio::fs::write(path, content).await?;
         Ok(())
     }
@@ -285,6 +814,25 @@ impl Config {
         PathBuf::from(path)
     }
 
+    /// Workspace path after checking it is writable
+    ///
+    /// Applies `workspace_fallback` when it is not.
+    pub async fn ensure_workspace(&self) -> Result<PathBuf> {
+        workspace::ensure_writable(
+            &self.workspace_path(),
+            self.operative.defaults.workspace_fallback,
+        )
+        .await
+    }
+
+    /// Heartbeat action log path, resolved against the workspace
+    pub fn heartbeat_action_log(&self) -> Option<PathBuf> {
+        self.heartbeat
+            .action_log
+            .as_ref()
+            .map(|path| self.workspace_path().join(path))
+    }
+
     /// Get SOLITON access key
     pub fn api_key(&self) -> Option<String> {
         let key = self.providers.openrouter.api_key.clone();
@@ -310,6 +858,21 @@ impl Config {
         None
     }
 
+    /// Get extra headers for the provider whose API key is in use
+    pub fn extra_headers(&self) -> HashMap<String, String> {
+        let providers = &self.providers;
+        [
+            &providers.openrouter,
+            &providers.anthropic,
+            &providers.openai,
+            &providers.vllm,
+        ]
+        .into_iter()
+        .find(|p| !p.api_key.is_empty())
+        .map(|p| p.extra_headers.clone())
+        .unwrap_or_default()
+    }
+
     /// Get SOLITON frequency
     pub fn api_base(&self) -> Option<String> {
         if !self.providers.openrouter.api_key.is_empty() {
@@ -355,6 +918,30 @@ impl Config {
         self.operative.defaults.session_max_messages
     }
 
+    /// Get session context window
+    pub fn session_context_window(&self) -> usize {
+        self.operative.defaults.session_context_window
+    }
+
+    /// Get session save attempts
+    pub fn session_save_attempts(&self) -> u32 {
+        self.operative.defaults.session_save_attempts
+    }
+
+    /// Grace period for each task during gateway shutdown
+    pub fn shutdown_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.deploy.shutdown_timeout_s)
+    }
+
+    /// Periodic session flush interval, if enabled
+    pub fn session_flush_interval(&self) -> Option<std::time::Duration> {
+        self.operative
+            .defaults
+            .session_flush_interval_s
+            .filter(|&secs| secs > 0)
+            .map(std::time::Duration::from_secs)
+    }
+
     /// Get web search max results from toolkit config
     pub fn web_search_max_results(&self) -> u32 {
         self.toolkit.web.search.max_results
@@ -0,0 +1,28 @@
+//! Log filter selection
+
+/// Filter used when nothing else is configured
+pub const DEFAULT_LOG_FILTER: &str = "error";
+
+/// Filter used by `deploy --verbose`
+pub const VERBOSE_LOG_FILTER: &str = "debug";
+
+/// Final tracing filter directives
+///
+/// A non-empty `RUST_LOG` wins outright. Otherwise the base level is
+/// `debug` with `--verbose` and `error` without, followed by the config's
+/// `log_filter` so its per-module levels still apply on top.
+pub fn log_filter(config_filter: Option<&str>, rust_log: Option<&str>, verbose: bool) -> String {
+    if let Some(env) = rust_log.map(str::trim).filter(|v| !v.is_empty()) {
+        return env.to_string();
+    }
+
+    let base = if verbose {
+        VERBOSE_LOG_FILTER
+    } else {
+        DEFAULT_LOG_FILTER
+    };
+    match config_filter.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(filter) => format!("{},{}", base, filter),
+        None => base.to_string(),
+    }
+}
@@ -34,6 +34,11 @@ pub fn media_dir() -> PathBuf {
     data_dir().join("intel")
 }
 
+/// Gateway telemetry snapshot
+pub fn metrics_path() -> PathBuf {
+    data_dir().join("metrics.json")
+}
+
 /// Ensure directory exists
 pub async fn ensure_dir(path: &PathBuf) -> std::io::Result<()> {
     tokio::fs::create_dir_all(path).await
@@ -0,0 +1,148 @@
+//! SOLITON node selection - building the provider for the active key
+
+use opensam_provider::openrouter::OpenRouterProvider;
+use opensam_provider::{FallbackProvider, Provider, RedactingProvider, RetryConfig};
+use std::time::Duration;
+use tracing::debug;
+
+use crate::{Config, ConfigError, ProviderConfig, Result};
+
+/// Anthropic's OpenAI-compatible endpoint, used until a native provider exists
+pub const ANTHROPIC_API_BASE: &str = "https://api.anthropic.com/v1";
+
+/// Provider entries, in the order their keys are tried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    OpenRouter,
+    Anthropic,
+    OpenAi,
+    Vllm,
+}
+
+impl ProviderKind {
+    /// Config key of this entry under `soliton`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenRouter => "openrouter",
+            ProviderKind::Anthropic => "anthropic",
+            ProviderKind::OpenAi => "openai",
+            ProviderKind::Vllm => "vllm",
+        }
+    }
+}
+
+impl Config {
+    /// Provider entries with an API key, in the order they are tried
+    pub fn configured_providers(&self) -> Vec<(ProviderKind, &ProviderConfig)> {
+        let providers = &self.providers;
+        [
+            (ProviderKind::OpenRouter, &providers.openrouter),
+            (ProviderKind::Anthropic, &providers.anthropic),
+            (ProviderKind::OpenAi, &providers.openai),
+            (ProviderKind::Vllm, &providers.vllm),
+        ]
+        .into_iter()
+        .filter(|(_, p)| !p.api_key.is_empty())
+        .collect()
+    }
+
+    /// First provider entry with an API key
+    pub fn active_provider(&self) -> Option<(ProviderKind, &ProviderConfig)> {
+        self.configured_providers().into_iter().next()
+    }
+
+    /// Build the provider for the configured keys, with redaction applied
+    ///
+    /// One key gives a single node. Several keys give a fallback chain in
+    /// key order; every node but the last skips its own retries so a failing
+    /// node hands over to the next one straight away.
+    pub fn build_provider(&self) -> Result<Box<dyn Provider>> {
+        let entries = self.configured_providers();
+        if entries.is_empty() {
+            return Err(ConfigError::Invalid {
+                field: "soliton",
+                reason: "no provider has an api_key".to_string(),
+            });
+        }
+
+        let last = entries.len() - 1;
+        let mut nodes = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, (kind, entry))| {
+                let retry = if index == last {
+                    self.providers.retry
+                } else {
+                    RetryConfig::disabled()
+                };
+                self.build_node(kind, entry, retry)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let provider: Box<dyn Provider> = if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            Box::new(FallbackProvider::new(nodes))
+        };
+
+        let provider = RedactingProvider::from_patterns(provider, &self.providers.redaction)
+            .map_err(|e| ConfigError::Invalid {
+                field: "soliton.redaction",
+                reason: e.to_string(),
+            })?;
+        Ok(Box::new(provider))
+    }
+
+    /// Node for one provider entry
+    ///
+    /// Every entry currently speaks the OpenAI-compatible protocol; the
+    /// entry decides the endpoint.
+    fn build_node(
+        &self,
+        kind: ProviderKind,
+        entry: &ProviderConfig,
+        retry: RetryConfig,
+    ) -> Result<Box<dyn Provider>> {
+        let api_base = match kind {
+            ProviderKind::OpenRouter => self.api_base(),
+            ProviderKind::Anthropic => entry
+                .api_base
+                .clone()
+                .or_else(|| Some(ANTHROPIC_API_BASE.to_string())),
+            ProviderKind::OpenAi => entry.api_base.clone(),
+            ProviderKind::Vllm => match entry.api_base.as_deref() {
+                Some(base) if !base.is_empty() => Some(base.to_string()),
+                _ => {
+                    return Err(ConfigError::Invalid {
+                        field: "soliton.vllm.api_base",
+                        reason: "required for vLLM".to_string(),
+                    })
+                }
+            },
+        };
+        debug!("◆ SOLITON NODE: {} ({:?})", kind.as_str(), api_base);
+
+        let model = entry.model.clone().unwrap_or_else(|| self.default_model());
+        let mut provider = OpenRouterProvider::new(entry.api_key.clone(), api_base, Some(model))
+            .with_extra_headers(entry.extra_headers.clone())
+            .map_err(|e| ConfigError::Invalid {
+                field: "extra_headers",
+                reason: e.to_string(),
+            })?;
+        provider = provider
+            .with_retry(retry)
+            .with_timeout(Duration::from_secs(self.operative.defaults.timeout_s));
+        if let Some(model) = &entry.embedding_model {
+            provider = provider.with_embedding_model(model.clone());
+        }
+        if let Some(header) = &entry.request_id_header {
+            provider =
+                provider
+                    .with_request_id_header(header)
+                    .map_err(|e| ConfigError::Invalid {
+                        field: "request_id_header",
+                        reason: e.to_string(),
+                    })?;
+        }
+        Ok(Box::new(provider))
+    }
+}
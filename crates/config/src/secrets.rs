@@ -0,0 +1,49 @@
+//! Secret references - keys kept outside config.json
+//!
+//! An api key of `env:VAR` is read from the environment and `file:/path`
+//! from a file (trimmed). Any other value is the key itself.
+
+use crate::{ConfigError, Result};
+use std::path::Path;
+
+/// Prefix for keys read from an environment variable
+pub const ENV_PREFIX: &str = "env:";
+
+/// Prefix for keys read from a file
+pub const FILE_PREFIX: &str = "file:";
+
+/// Whether `value` points at a secret rather than holding it
+pub fn is_reference(value: &str) -> bool {
+    value.starts_with(ENV_PREFIX) || value.starts_with(FILE_PREFIX)
+}
+
+/// Resolve a secret reference against the process environment
+pub fn resolve(value: &str) -> Result<String> {
+    resolve_with(value, |name| std::env::var(name).ok())
+}
+
+/// Resolve a secret reference, reading variables through `lookup`
+pub fn resolve_with(value: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    if let Some(name) = value.strip_prefix(ENV_PREFIX) {
+        return lookup(name).filter(|v| !v.is_empty()).ok_or_else(|| {
+            secret_error(value, format!("environment variable {} is not set", name))
+        });
+    }
+    if let Some(path) = value.strip_prefix(FILE_PREFIX) {
+        let content = std::fs::read_to_string(Path::new(path))
+            .map_err(|e| secret_error(value, e.to_string()))?;
+        let key = content.trim();
+        if key.is_empty() {
+            return Err(secret_error(value, "file is empty"));
+        }
+        return Ok(key.to_string());
+    }
+    Ok(value.to_string())
+}
+
+fn secret_error(reference: &str, reason: impl Into<String>) -> ConfigError {
+    ConfigError::Secret {
+        reference: reference.to_string(),
+        reason: reason.into(),
+    }
+}
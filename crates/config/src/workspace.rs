@@ -0,0 +1,53 @@
+//! Operations theater checks
+
+use crate::{ConfigError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const PROBE_FILE: &str = ".opensam-write-probe";
+
+/// What to do when the workspace cannot be written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceFallback {
+    /// Stop with an error naming the path
+    #[default]
+    Fail,
+    /// Continue in a directory under the system temp dir
+    TempDir,
+}
+
+/// Create the workspace if needed and confirm a file can be written there
+pub async fn check_writable(path: &Path) -> std::io::Result<()> {
+    tokio::fs::create_dir_all(path).await?;
+    let probe = path.join(PROBE_FILE);
+    tokio::fs::write(&probe, b"").await?;
+    tokio::fs::remove_file(&probe).await
+}
+
+/// Return a writable workspace, applying `fallback` when `path` is not
+pub async fn ensure_writable(path: &Path, fallback: WorkspaceFallback) -> Result<PathBuf> {
+    let source = match check_writable(path).await {
+        Ok(()) => return Ok(path.to_path_buf()),
+        Err(e) => e,
+    };
+
+    match fallback {
+        WorkspaceFallback::Fail => Err(ConfigError::WorkspaceUnwritable {
+            path: path.to_path_buf(),
+            source,
+        }),
+        WorkspaceFallback::TempDir => {
+            let temp = std::env::temp_dir().join("opensam-ops");
+            warn!(
+                "◆ WORKSPACE {} UNWRITABLE ({}) - FALLING BACK TO {}",
+                path.display(),
+                source,
+                temp.display()
+            );
+            check_writable(&temp).await?;
+            Ok(temp)
+        }
+    }
+}
@@ -207,6 +207,7 @@ fn test_provider_config_roundtrip() {
     let provider = ProviderConfig {
         api_key: "test-key".to_string(),
         api_base: Some("https://api.example.com".to_string()),
+        ..Default::default()
     };
 
     let json = serde_json::to_string(&provider).expect("Failed to serialize");
@@ -225,6 +226,7 @@ fn test_provider_config_none_base_roundtrip() {
     let provider = ProviderConfig {
         api_key: "test-key".to_string(),
         api_base: None,
+        ..Default::default()
     };
 
     let json = serde_json::to_string(&provider).expect("Failed to serialize");
@@ -261,3 +263,29 @@ fn test_api_base_vllm_empty_base() {
     // Empty api_base is treated as None (invalid/unset)
     assert_eq!(config.api_base(), None);
 }
+
+/// Test extra headers come from the provider whose key is in use
+#[test]
+fn test_extra_headers_follow_active_provider() {
+    let json = r#"{
+        "soliton": {
+            "openai": {
+                "api_key": "openai-key",
+                "extra_headers": {"OpenAI-Organization": "org-1"}
+            },
+            "vllm": {
+                "extra_headers": {"X-Unused": "nope"}
+            }
+        }
+    }"#;
+
+    let config: Config = serde_json::from_str(json).expect("Failed to parse");
+    let headers = config.extra_headers();
+
+    assert_eq!(headers.len(), 1);
+    assert_eq!(
+        headers.get("OpenAI-Organization"),
+        Some(&"org-1".to_string())
+    );
+    assert!(Config::default().extra_headers().is_empty());
+}
@@ -0,0 +1,144 @@
+//! Tests for the validated OperativeDefaults builder
+
+use opensam_config::{ConfigError, OperativeDefaults, OperativeDefaultsBuilder};
+
+fn rejected_field(builder: OperativeDefaultsBuilder) -> &'static str {
+    match builder.build() {
+        Err(ConfigError::Invalid { field, .. }) => field,
+        other => panic!("expected an invalid parameter, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_builder_defaults_are_valid() {
+    let defaults = OperativeDefaults::builder().build().unwrap();
+    assert_eq!(defaults.model, OperativeDefaults::default().model);
+}
+
+#[test]
+fn test_builder_sets_valid_values() {
+    let defaults = OperativeDefaults::builder()
+        .name("Snake")
+        .model("openai/gpt-4o")
+        .max_tokens(2048)
+        .temperature(0.2)
+        .max_temperature(1.0)
+        .max_tool_iterations(8)
+        .max_output_chars(Some(500))
+        .compact_threshold(Some(0.8))
+        .timeout_s(30)
+        .top_p(Some(0.9))
+        .frequency_penalty(Some(-2.0))
+        .presence_penalty(Some(0.5))
+        .stop(vec!["END".to_string()])
+        .build()
+        .unwrap();
+
+    assert_eq!(defaults.name, "Snake");
+    assert_eq!(defaults.model, "openai/gpt-4o");
+    assert_eq!(defaults.max_tokens, 2048);
+    assert_eq!(defaults.temperature, 0.2);
+    assert_eq!(defaults.max_temperature, 1.0);
+    assert_eq!(defaults.max_tool_iterations, 8);
+    assert_eq!(defaults.max_output_chars, Some(500));
+    assert_eq!(defaults.compact_threshold, Some(0.8));
+    assert_eq!(defaults.timeout_s, 30);
+    assert_eq!(defaults.top_p, Some(0.9));
+    assert_eq!(defaults.frequency_penalty, Some(-2.0));
+    assert_eq!(defaults.presence_penalty, Some(0.5));
+    assert_eq!(defaults.stop, vec!["END".to_string()]);
+}
+
+#[test]
+fn test_builder_rejects_out_of_range_values() {
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().temperature(2.5)),
+        "temperature"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().temperature(-0.1)),
+        "temperature"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().temperature(f32::NAN)),
+        "temperature"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().max_tokens(0)),
+        "max_tokens"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().model("  ")),
+        "model"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().max_tool_iterations(0)),
+        "max_tool_iterations"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().session_save_attempts(0)),
+        "session_save_attempts"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().max_output_chars(Some(0))),
+        "max_output_chars"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().compact_threshold(Some(1.5))),
+        "compact_threshold"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().compact_threshold(Some(0.0))),
+        "compact_threshold"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().timeout_s(0)),
+        "timeout_s"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().top_p(Some(0.0))),
+        "top_p"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().top_p(Some(1.5))),
+        "top_p"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().frequency_penalty(Some(2.5))),
+        "frequency_penalty"
+    );
+    assert_eq!(
+        rejected_field(OperativeDefaults::builder().presence_penalty(Some(f32::NAN))),
+        "presence_penalty"
+    );
+}
+
+#[test]
+fn test_builder_rejects_max_temperature_below_temperature() {
+    let err = OperativeDefaults::builder()
+        .temperature(1.0)
+        .max_temperature(0.5)
+        .build()
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "INVALID PARAMETER max_temperature: 0.5 is below temperature 1"
+    );
+}
+
+#[test]
+fn test_builder_from_existing_keeps_other_fields() {
+    let existing = OperativeDefaults {
+        name: "Otacon".to_string(),
+        ..Default::default()
+    };
+
+    let defaults = OperativeDefaultsBuilder::from(existing)
+        .model("meta/llama")
+        .build()
+        .unwrap();
+
+    assert_eq!(defaults.name, "Otacon");
+    assert_eq!(defaults.model, "meta/llama");
+}
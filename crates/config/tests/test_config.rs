@@ -1,9 +1,9 @@
 //! Tests for Config serialization, deserialization, and core functionality
 
 use opensam_config::{
-    Config, DeployConfig, FrequencyConfig, OperativeConfig, OperativeDefaults, ProviderConfig,
-    SolitonConfig, TelegramConfig, ToolkitConfig, WebSearchConfig, WebToolkitConfig,
-    WhatsAppConfig,
+    Config, DeployConfig, FrequencyConfig, InboundPolicyConfig, OperativeConfig, OperativeDefaults,
+    ProviderConfig, SolitonConfig, TelegramConfig, ToolkitConfig, WebSearchConfig,
+    WebToolkitConfig, WhatsAppConfig,
 };
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -86,6 +86,18 @@ fn test_telegram_config_defaults() {
     assert!(!telegram.enabled);
     assert!(telegram.token.is_empty());
     assert!(telegram.allow_from.is_empty());
+    assert_eq!(telegram.global_per_sec, 30.0);
+    assert_eq!(telegram.per_chat_per_sec, 1.0);
+}
+
+/// Test Telegram send rates parse, defaulting when absent
+#[test]
+fn test_telegram_throttle_rates_deserialization() {
+    let json = r#"{"frequency": {"telegram": {"per_chat_per_sec": 0.5}}}"#;
+    let config: Config = serde_json::from_str(json).expect("Failed to deserialize");
+
+    assert_eq!(config.frequency.telegram.per_chat_per_sec, 0.5);
+    assert_eq!(config.frequency.telegram.global_per_sec, 30.0);
 }
 
 /// Test FrequencyConfig defaults
@@ -100,6 +112,7 @@ fn test_frequency_config_defaults() {
 #[test]
 fn test_operative_defaults() {
     let defaults = OperativeDefaults::default();
+    assert_eq!(defaults.name, "OpenSAM");
     assert_eq!(defaults.workspace, "~/.opensam/ops");
     assert_eq!(defaults.model, "anthropic/claude-sonnet-4");
     assert_eq!(defaults.max_tokens, 8192);
@@ -142,6 +155,65 @@ fn test_deploy_config_defaults() {
     let deploy = DeployConfig::default();
     assert_eq!(deploy.host, "0.0.0.0");
     assert_eq!(deploy.port, 18789);
+    assert_eq!(deploy.max_concurrent_turns, 4);
+    assert!(!deploy.cancel_superseded_turns);
+    assert_eq!(deploy.max_message_age_s, None);
+    assert!(!deploy.reply_to_stale);
+    assert_eq!(deploy.inbound, InboundPolicyConfig::default());
+    assert_eq!(deploy.shutdown_timeout_s, 5);
+}
+
+/// Test the request timeout default comes from the provider crate
+#[test]
+fn test_request_timeout_default_matches_provider() {
+    let config: Config = serde_json::from_str("{}").expect("Failed to deserialize");
+
+    assert_eq!(
+        config.operative.defaults.timeout_s,
+        opensam_provider::openrouter::DEFAULT_TIMEOUT.as_secs()
+    );
+    assert_eq!(
+        OperativeDefaults::default().timeout_s,
+        opensam_provider::openrouter::DEFAULT_TIMEOUT.as_secs()
+    );
+}
+
+/// Test the shutdown timeout is read from the deploy section
+#[test]
+fn test_deploy_shutdown_timeout_deserialization() {
+    let json = r#"{"deploy": {"shutdown_timeout_s": 30}}"#;
+    let config: Config = serde_json::from_str(json).expect("Failed to deserialize");
+
+    assert_eq!(config.deploy.shutdown_timeout_s, 30);
+    assert_eq!(
+        config.shutdown_timeout(),
+        std::time::Duration::from_secs(30)
+    );
+    assert_eq!(
+        Config::default().shutdown_timeout(),
+        std::time::Duration::from_secs(5)
+    );
+}
+
+/// Test inbound policy parses from the deploy section
+#[test]
+fn test_deploy_inbound_policy_deserialization() {
+    let json = r#"{
+        "deploy": {
+            "inbound": {
+                "block_senders": ["42", "telegram:99"],
+                "block_channels": ["whatsapp"]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(json).expect("Failed to deserialize");
+
+    assert_eq!(
+        config.deploy.inbound.block_senders,
+        vec!["42", "telegram:99"]
+    );
+    assert_eq!(config.deploy.inbound.block_channels, vec!["whatsapp"]);
+    assert!(config.deploy.inbound.allow_senders.is_empty());
 }
 
 /// Test Config serialization to JSON
@@ -453,6 +525,7 @@ fn test_provider_config_includes_some_api_base() {
     let provider = ProviderConfig {
         api_key: "key".to_string(),
         api_base: Some("https://api.example.com".to_string()),
+        ..Default::default()
     };
     let json = serde_json::to_string(&provider).expect("Failed to serialize");
 
@@ -484,3 +557,19 @@ fn test_complex_config_serialization() {
     let reparsed: Config = serde_json::from_str(&output).expect("Failed to re-deserialize");
     assert_eq!(reparsed.providers.vllm.api_key, "vllm-key");
 }
+
+/// Test that channel summaries cover every frequency
+#[test]
+fn test_frequency_summaries_cover_all_channels() {
+    let mut frequency = FrequencyConfig::default();
+    frequency.telegram.enabled = true;
+    frequency.telegram.allow_from = vec!["1".to_string()];
+
+    let summaries = frequency.summaries();
+    let names: Vec<&str> = summaries.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["telegram", "whatsapp"]);
+    assert!(summaries[0].enabled);
+    assert_eq!(summaries[0].allowed, 1);
+    assert!(!summaries[1].enabled);
+    assert_eq!(summaries[1].allowed, 0);
+}
@@ -0,0 +1,56 @@
+//! Tests for environment overrides and secret masking
+
+use opensam_config::effective::{ENV_MODEL, ENV_OPENROUTER_API_KEY, ENV_TELEGRAM_TOKEN, MASKED};
+use opensam_config::Config;
+use std::collections::HashMap;
+
+fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let map: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |name| map.get(name).cloned()
+}
+
+#[test]
+fn test_apply_overrides_replaces_values() {
+    let mut config = Config::default();
+    config.apply_overrides(lookup(&[
+        (ENV_MODEL, "env/model"),
+        (ENV_OPENROUTER_API_KEY, "sk-env"),
+    ]));
+
+    assert_eq!(config.operative.defaults.model, "env/model");
+    assert_eq!(config.providers.openrouter.api_key, "sk-env");
+}
+
+#[test]
+fn test_apply_overrides_ignores_empty_values() {
+    let mut config = Config::default();
+    let model = config.operative.defaults.model.clone();
+    config.apply_overrides(lookup(&[(ENV_MODEL, "")]));
+
+    assert_eq!(config.operative.defaults.model, model);
+}
+
+#[test]
+fn test_redacted_masks_set_secrets_only() {
+    let mut config = Config::default();
+    config.apply_overrides(lookup(&[
+        (ENV_OPENROUTER_API_KEY, "sk-secret"),
+        (ENV_TELEGRAM_TOKEN, "123:abc"),
+    ]));
+    config
+        .providers
+        .openrouter
+        .extra_headers
+        .insert("X-Key".to_string(), "hidden".to_string());
+
+    let redacted = config.redacted();
+    assert_eq!(redacted.providers.openrouter.api_key, MASKED);
+    assert_eq!(redacted.frequency.telegram.token, MASKED);
+    assert_eq!(redacted.providers.openrouter.extra_headers["X-Key"], MASKED);
+    assert!(redacted.providers.anthropic.api_key.is_empty());
+    // The original is untouched
+    assert_eq!(config.providers.openrouter.api_key, "sk-secret");
+}
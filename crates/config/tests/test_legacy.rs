@@ -0,0 +1,103 @@
+//! Tests for migrating the flat legacy config layout
+
+use opensam_config::Config;
+use serde_json::json;
+use tempfile::TempDir;
+
+#[test]
+fn test_is_legacy_detects_flat_layout() {
+    assert!(Config::is_legacy(&json!({"api_key": "k"})));
+    assert!(Config::is_legacy(&json!({"workspace": "ws"})));
+    assert!(!Config::is_legacy(&json!({"soliton": {}})));
+    assert!(!Config::is_legacy(&json!([])));
+}
+
+#[test]
+fn test_from_legacy_maps_fields() {
+    let config = Config::from_legacy(json!({
+        "api_key": "test-api-key",
+        "default_model": "test/model",
+        "workspace": "workspace"
+    }))
+    .unwrap();
+
+    assert_eq!(config.providers.openrouter.api_key, "test-api-key");
+    assert_eq!(config.operative.defaults.model, "test/model");
+    assert_eq!(config.operative.defaults.workspace, "workspace");
+    assert_eq!(config.api_key().as_deref(), Some("test-api-key"));
+}
+
+#[test]
+fn test_from_legacy_keeps_nested_sections() {
+    let config = Config::from_legacy(json!({
+        "api_key": "flat-key",
+        "deploy": {"port": 9000}
+    }))
+    .unwrap();
+
+    assert_eq!(config.providers.openrouter.api_key, "flat-key");
+    assert_eq!(config.deploy.port, 9000);
+}
+
+#[tokio::test]
+async fn test_load_from_maps_legacy_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("config.json");
+    std::fs::write(
+        &path,
+        r#"{"api_key": "test-api-key", "default_model": "test/model", "workspace": "ws"}"#,
+    )
+    .unwrap();
+
+    let config = Config::load_from(&path).await.unwrap();
+
+    assert_eq!(config.providers.openrouter.api_key, "test-api-key");
+    assert_eq!(config.operative.defaults.model, "test/model");
+    assert_eq!(config.operative.defaults.workspace, "ws");
+}
+
+#[tokio::test]
+async fn test_migrate_file_rewrites_nested() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("config.json");
+    std::fs::write(&path, r#"{"api_key": "k", "default_model": "m"}"#).unwrap();
+
+    assert!(Config::migrate_file(&path).await.unwrap());
+
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(!Config::is_legacy(&written));
+    assert_eq!(written["soliton"]["openrouter"]["api_key"], "k");
+    assert_eq!(written["operative"]["defaults"]["model"], "m");
+
+    let original = std::fs::read_to_string(Config::legacy_backup_path(&path)).unwrap();
+    assert_eq!(original, r#"{"api_key": "k", "default_model": "m"}"#);
+
+    assert!(
+        !Config::migrate_file(&path).await.unwrap(),
+        "already current"
+    );
+}
+
+#[test]
+fn test_from_legacy_rejects_unreadable_nested_sections() {
+    let result = Config::from_legacy(json!({
+        "api_key": "flat-key",
+        "deploy": {"port": "not-a-port"}
+    }));
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_migrate_file_leaves_unreadable_file_untouched() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("config.json");
+    let content = r#"{"api_key": "k", "deploy": {"port": "not-a-port"}}"#;
+    std::fs::write(&path, content).unwrap();
+
+    assert!(Config::migrate_file(&path).await.is_err());
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+    assert!(!Config::legacy_backup_path(&path).exists());
+}
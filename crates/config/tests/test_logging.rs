@@ -0,0 +1,42 @@
+//! Tests for log filter precedence
+
+use opensam_config::logging::{log_filter, DEFAULT_LOG_FILTER};
+use opensam_config::Config;
+
+#[test]
+fn test_default_filter_without_settings() {
+    assert_eq!(log_filter(None, None, false), DEFAULT_LOG_FILTER);
+    assert_eq!(log_filter(Some("  "), Some(""), false), DEFAULT_LOG_FILTER);
+}
+
+#[test]
+fn test_verbose_raises_base_level() {
+    assert_eq!(log_filter(None, None, true), "debug");
+}
+
+#[test]
+fn test_config_filter_layers_on_base_level() {
+    let config = Some("opensam_agent=debug,teloxide=warn");
+    assert_eq!(
+        log_filter(config, None, false),
+        "error,opensam_agent=debug,teloxide=warn"
+    );
+    assert_eq!(
+        log_filter(config, None, true),
+        "debug,opensam_agent=debug,teloxide=warn"
+    );
+}
+
+#[test]
+fn test_rust_log_overrides_everything() {
+    assert_eq!(
+        log_filter(Some("teloxide=warn"), Some("trace"), true),
+        "trace"
+    );
+}
+
+#[test]
+fn test_log_filter_config_field() {
+    let config: Config = serde_json::from_str(r#"{"log_filter": "opensam_agent=debug"}"#).unwrap();
+    assert_eq!(config.log_filter.as_deref(), Some("opensam_agent=debug"));
+}
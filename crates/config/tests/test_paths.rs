@@ -212,3 +212,14 @@ fn test_all_dirs_under_opensam() {
     assert!(cron_dir().starts_with(&data));
     assert!(media_dir().starts_with(&data));
 }
+
+/// First run is detected from the config file alone
+#[test]
+fn test_is_first_run_at() {
+    let dir = temp_dir();
+    let path = dir.path().join("config.json");
+    assert!(opensam_config::Config::is_first_run_at(&path));
+
+    std::fs::write(&path, "{}").unwrap();
+    assert!(!opensam_config::Config::is_first_run_at(&path));
+}
@@ -0,0 +1,220 @@
+//! Tests for choosing the provider implementation from config
+
+use opensam_config::{Config, ConfigError, ProviderKind};
+use opensam_provider::{ChatParams, Message, Provider};
+
+fn config(json: serde_json::Value) -> Config {
+    serde_json::from_value(json).expect("Failed to deserialize")
+}
+
+#[test]
+fn test_openrouter_config_builds_openrouter_provider() {
+    let config = config(serde_json::json!({
+        "soliton": {"openrouter": {"api_key": "sk-or-test"}}
+    }));
+
+    let (kind, _) = config.active_provider().unwrap();
+    assert_eq!(kind, ProviderKind::OpenRouter);
+
+    let provider = config.build_provider().unwrap();
+    assert_eq!(provider.name(), "openrouter");
+    assert_eq!(provider.default_model(), config.default_model());
+}
+
+#[test]
+fn test_vllm_config_builds_openai_compatible_provider() {
+    let config = config(serde_json::json!({
+        "soliton": {"vllm": {"api_key": "local", "api_base": "http://gpu-box:8000/v1"}}
+    }));
+
+    let (kind, entry) = config.active_provider().unwrap();
+    assert_eq!(kind, ProviderKind::Vllm);
+    assert_eq!(entry.api_base.as_deref(), Some("http://gpu-box:8000/v1"));
+
+    let provider = config.build_provider().unwrap();
+    assert_eq!(provider.name(), "openai-compatible");
+}
+
+#[tokio::test]
+async fn test_vllm_provider_sends_to_custom_base() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_header("authorization", "Bearer local")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            serde_json::json!({
+                "choices": [{"message": {"content": "ok"}, "finish_reason": "stop"}]
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let config = config(serde_json::json!({
+        "soliton": {"vllm": {"api_key": "local", "api_base": format!("{}/v1", server.url())}}
+    }));
+
+    let provider = config.build_provider().unwrap();
+    let response = provider
+        .chat(ChatParams {
+            model: "local/model".to_string(),
+            messages: vec![Message::user("Hello")],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("ok"));
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_vllm_without_base_is_rejected() {
+    let config = config(serde_json::json!({
+        "soliton": {"vllm": {"api_key": "local"}}
+    }));
+
+    match config.build_provider() {
+        Err(ConfigError::Invalid { field, .. }) => assert_eq!(field, "soliton.vllm.api_base"),
+        other => panic!("expected invalid vllm base, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_openrouter_key_takes_priority() {
+    let config = config(serde_json::json!({
+        "soliton": {
+            "openrouter": {"api_key": "sk-or-test"},
+            "vllm": {"api_key": "local", "api_base": "http://gpu-box:8000/v1"}
+        }
+    }));
+
+    assert_eq!(
+        config.active_provider().unwrap().0,
+        ProviderKind::OpenRouter
+    );
+}
+
+#[test]
+fn test_no_key_has_no_provider() {
+    let config = Config::default();
+
+    assert!(config.active_provider().is_none());
+    assert!(config.build_provider().is_err());
+}
+
+#[test]
+fn test_several_keys_build_a_fallback_chain() {
+    let config = config(serde_json::json!({
+        "soliton": {
+            "openrouter": {"api_key": "sk-or-test"},
+            "vllm": {"api_key": "local", "api_base": "http://gpu-box:8000/v1"}
+        }
+    }));
+
+    assert_eq!(config.configured_providers().len(), 2);
+    let provider = config.build_provider().unwrap();
+    assert_eq!(provider.name(), "fallback");
+    assert_eq!(provider.default_model(), config.default_model());
+}
+
+#[tokio::test]
+async fn test_chain_fails_over_without_retrying_the_first_node() {
+    let mut primary = mockito::Server::new_async().await;
+    let down = primary
+        .mock("POST", "/v1/chat/completions")
+        .with_status(503)
+        .with_body("down")
+        .expect(1)
+        .create_async()
+        .await;
+    let mut backup = mockito::Server::new_async().await;
+    let ok = backup
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            serde_json::json!({
+                "choices": [{"message": {"content": "from backup"}, "finish_reason": "stop"}]
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let config = config(serde_json::json!({
+        "soliton": {
+            "openrouter": {"api_key": "sk-or-test", "api_base": format!("{}/v1", primary.url())},
+            "vllm": {"api_key": "local", "api_base": format!("{}/v1", backup.url())}
+        }
+    }));
+
+    let provider = config.build_provider().unwrap();
+    let response = provider
+        .chat(ChatParams {
+            model: "local/model".to_string(),
+            messages: vec![Message::user("Hello")],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("from backup"));
+    down.assert_async().await;
+    ok.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_chain_asks_each_node_for_its_own_model() {
+    let mut primary = mockito::Server::new_async().await;
+    let down = primary
+        .mock("POST", "/v1/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(
+            serde_json::json!({"model": "anthropic/claude-sonnet-4"}),
+        ))
+        .with_status(503)
+        .with_body("down")
+        .expect(1)
+        .create_async()
+        .await;
+    let mut backup = mockito::Server::new_async().await;
+    let ok = backup
+        .mock("POST", "/v1/chat/completions")
+        .match_body(mockito::Matcher::PartialJson(
+            serde_json::json!({"model": "local/model"}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            serde_json::json!({
+                "choices": [{"message": {"content": "from backup"}, "finish_reason": "stop"}]
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let config = config(serde_json::json!({
+        "soliton": {
+            "openrouter": {"api_key": "sk-or-test", "api_base": format!("{}/v1", primary.url())},
+            "vllm": {
+                "api_key": "local",
+                "api_base": format!("{}/v1", backup.url()),
+                "model": "local/model"
+            }
+        }
+    }));
+
+    let provider = config.build_provider().unwrap();
+    let response = provider
+        .chat(ChatParams {
+            model: "anthropic/claude-sonnet-4".to_string(),
+            messages: vec![Message::user("Hello")],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("from backup"));
+    down.assert_async().await;
+    ok.assert_async().await;
+}
@@ -0,0 +1,112 @@
+//! Tests for env:/file: api key references
+
+use opensam_config::secrets::{is_reference, resolve, resolve_with};
+use opensam_config::{Config, ConfigError};
+
+#[test]
+fn test_env_reference_resolves_from_environment() {
+    let key = resolve_with("env:OPENSAM_TEST_KEY", |name| {
+        (name == "OPENSAM_TEST_KEY").then(|| "sk-from-env".to_string())
+    })
+    .unwrap();
+    assert_eq!(key, "sk-from-env");
+}
+
+#[test]
+fn test_env_reference_reads_process_environment() {
+    std::env::set_var("OPENSAM_SECRETS_TEST_KEY", "sk-process");
+    assert_eq!(
+        resolve("env:OPENSAM_SECRETS_TEST_KEY").unwrap(),
+        "sk-process"
+    );
+}
+
+#[test]
+fn test_file_reference_reads_trimmed_contents() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("key");
+    std::fs::write(&path, "  sk-from-file\n").unwrap();
+
+    let key = resolve(&format!("file:{}", path.display())).unwrap();
+    assert_eq!(key, "sk-from-file");
+}
+
+#[test]
+fn test_literal_key_passes_through() {
+    assert!(!is_reference("sk-or-v1-abc"));
+    assert_eq!(resolve("sk-or-v1-abc").unwrap(), "sk-or-v1-abc");
+}
+
+#[test]
+fn test_missing_sources_are_clear_errors() {
+    let err = resolve_with("env:NOPE", |_| None).unwrap_err();
+    assert!(matches!(err, ConfigError::Secret { .. }));
+    assert_eq!(
+        err.to_string(),
+        "SECRET env:NOPE UNAVAILABLE: environment variable NOPE is not set"
+    );
+
+    let err = resolve("file:/nonexistent/opensam/key").unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("SECRET file:/nonexistent/opensam/key UNAVAILABLE"));
+}
+
+#[tokio::test]
+async fn test_load_resolves_reference_and_save_keeps_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let key_path = dir.path().join("openrouter.key");
+    std::fs::write(&key_path, "sk-secret\n").unwrap();
+    let reference = format!("file:{}", key_path.display());
+    let config_path = dir.path().join("config.json");
+    std::fs::write(
+        &config_path,
+        serde_json::json!({"soliton": {"openrouter": {"api_key": reference}}}).to_string(),
+    )
+    .unwrap();
+
+    let config = Config::load_from(&config_path).await.unwrap();
+    assert_eq!(config.api_key().as_deref(), Some("sk-secret"));
+
+    config.save_to(&config_path).await.unwrap();
+    let stored = std::fs::read_to_string(&config_path).unwrap();
+    assert!(stored.contains(&reference));
+    assert!(!stored.contains("sk-secret"));
+}
+
+#[tokio::test]
+async fn test_load_fails_for_missing_reference() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.json");
+    std::fs::write(
+        &config_path,
+        r#"{"soliton": {"openai": {"api_key": "file:/nonexistent/opensam/key"}}}"#,
+    )
+    .unwrap();
+
+    let err = Config::load_from(&config_path).await.unwrap_err();
+    assert!(matches!(err, ConfigError::Secret { .. }));
+}
+
+#[tokio::test]
+async fn test_load_unresolved_keeps_missing_reference_through_save() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.json");
+    std::fs::write(
+        &config_path,
+        r#"{"soliton": {"openai": {"api_key": "file:/nonexistent/opensam/key"}}}"#,
+    )
+    .unwrap();
+
+    let mut config = Config::load_unresolved_from(&config_path).await.unwrap();
+    assert_eq!(
+        config.providers.openai.api_key,
+        "file:/nonexistent/opensam/key"
+    );
+
+    config.providers.openrouter.api_key = "sk-new".to_string();
+    config.save_to(&config_path).await.unwrap();
+    let stored = std::fs::read_to_string(&config_path).unwrap();
+    assert!(stored.contains("file:/nonexistent/opensam/key"));
+    assert!(stored.contains("sk-new"));
+}
@@ -0,0 +1,77 @@
+//! Tests for workspace writability checks
+
+use opensam_config::workspace::{check_writable, ensure_writable};
+use opensam_config::{Config, ConfigError, WorkspaceFallback};
+
+/// A workspace nested under a regular file can never be created
+fn unwritable_path(dir: &tempfile::TempDir) -> std::path::PathBuf {
+    let blocker = dir.path().join("not-a-dir");
+    std::fs::write(&blocker, "file").unwrap();
+    blocker.join("ops")
+}
+
+#[tokio::test]
+async fn test_writable_workspace_is_created() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ops");
+
+    let result = ensure_writable(&path, WorkspaceFallback::Fail)
+        .await
+        .unwrap();
+
+    assert_eq!(result, path);
+    assert!(path.is_dir());
+    assert_eq!(std::fs::read_dir(&path).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn test_unwritable_workspace_detected() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(check_writable(&unwritable_path(&dir)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_unwritable_workspace_fails_fast() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = unwritable_path(&dir);
+
+    let err = ensure_writable(&path, WorkspaceFallback::Fail)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, ConfigError::WorkspaceUnwritable { .. }));
+    assert!(err.to_string().contains(&path.display().to_string()));
+}
+
+#[tokio::test]
+async fn test_unwritable_workspace_falls_back_to_temp() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = unwritable_path(&dir);
+
+    let result = ensure_writable(&path, WorkspaceFallback::TempDir)
+        .await
+        .unwrap();
+
+    assert!(result.starts_with(std::env::temp_dir()));
+    assert!(result.is_dir());
+}
+
+#[tokio::test]
+async fn test_config_workspace_fallback_setting() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config: Config = serde_json::from_value(serde_json::json!({
+        "operative": {"defaults": {"workspace_fallback": "temp_dir"}}
+    }))
+    .unwrap();
+    assert_eq!(
+        config.operative.defaults.workspace_fallback,
+        WorkspaceFallback::TempDir
+    );
+
+    config.operative.defaults.workspace = unwritable_path(&dir).display().to_string();
+    let workspace = config.ensure_workspace().await.unwrap();
+    assert!(workspace.starts_with(std::env::temp_dir()));
+
+    config.operative.defaults.workspace_fallback = WorkspaceFallback::Fail;
+    assert!(config.ensure_workspace().await.is_err());
+}
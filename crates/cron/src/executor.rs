@@ -0,0 +1,357 @@
+//! Job execution with per-job limits
+//!
+//! The executor runs the task for each due job and records how it went, so a
+//! single stuck run can't hold up the scheduler.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+use crate::{CronService, Job};
+
+/// Boxed future returned by a job handler
+pub type JobFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+type JobHandler = Arc<dyn Fn(Job) -> JobFuture + Send + Sync>;
+
+/// Jobs run at once unless configured otherwise
+pub const DEFAULT_MAX_CONCURRENT: usize = 1;
+
+/// Result of a single job run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobOutcome {
+    /// The task completed
+    Success,
+    /// The task returned an error
+    Failed(String),
+    /// The task exceeded the job's `timeout_ms` and was cancelled
+    TimedOut { after_ms: i64 },
+}
+
+impl JobOutcome {
+    /// Status recorded in `JobState::last_status`
+    pub fn status(&self) -> &'static str {
+        match self {
+            JobOutcome::Success => "success",
+            JobOutcome::Failed(_) => "failed",
+            JobOutcome::TimedOut { .. } => "timeout",
+        }
+    }
+
+    /// Error recorded in `JobState::last_error`
+    pub fn error(&self) -> Option<String> {
+        match self {
+            JobOutcome::Success => None,
+            JobOutcome::Failed(e) => Some(e.clone()),
+            JobOutcome::TimedOut { after_ms } => Some(format!("timed out after {}ms", after_ms)),
+        }
+    }
+}
+
+/// Runs scheduled jobs through a handler
+#[derive(Clone)]
+pub struct CronExecutor {
+    handler: JobHandler,
+    permits: Arc<Semaphore>,
+    max_concurrent: usize,
+}
+
+impl CronExecutor {
+    /// Create an executor that runs each job with `handler`
+    pub fn new<F, Fut>(handler: F) -> Self
+    where
+        F: Fn(Job) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        Self {
+            handler: Arc::new(move |job| Box::pin(handler(job))),
+            permits: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT)),
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+        }
+    }
+
+    /// Limit how many jobs run at once (at least one)
+    pub fn with_max_concurrent(mut self, max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        self.permits = Arc::new(Semaphore::new(max_concurrent));
+        self.max_concurrent = max_concurrent;
+        self
+    }
+
+    /// Jobs allowed to run at once
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Run one job, cancelling it if it exceeds its timeout
+    ///
+    /// Waits for a free slot when `max_concurrent` jobs are already running.
+    pub async fn execute(&self, job: &Job) -> JobOutcome {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("executor semaphore is never closed");
+        self.run_job(job).await
+    }
+
+    async fn run_job(&self, job: &Job) -> JobOutcome {
+        let task = (self.handler)(job.clone());
+        let result = match job.timeout() {
+            Some(limit) => match tokio::time::timeout(limit, task).await {
+                Ok(result) => result,
+                Err(_) => {
+                    warn!("◆ Job {} timed out after {:?}", job.id, limit);
+                    return JobOutcome::TimedOut {
+                        after_ms: limit.as_millis() as i64,
+                    };
+                }
+            },
+            None => task.await,
+        };
+
+        match result {
+            Ok(()) => JobOutcome::Success,
+            Err(e) => {
+                warn!("◆ Job {} failed: {}", job.id, e);
+                JobOutcome::Failed(e)
+            }
+        }
+    }
+
+    /// Run every due job in `service` and record the outcomes
+    ///
+    /// Up to `max_concurrent` jobs run in parallel; the rest start in order
+    /// as slots free up. Outcomes are returned in due order.
+    pub async fn run_due(&self, service: &mut CronService) -> Vec<(String, JobOutcome)> {
+        let due: Vec<Job> = service.get_due_jobs().into_iter().cloned().collect();
+        let mut runs = JoinSet::new();
+
+        for (index, job) in due.iter().cloned().enumerate() {
+            let permit = Arc::clone(&self.permits)
+                .acquire_owned()
+                .await
+                .expect("executor semaphore is never closed");
+            let executor = self.clone();
+            info!("◆ Running job {} ({})", job.id, job.name);
+            runs.spawn(async move {
+                let outcome = executor.run_job(&job).await;
+                drop(permit);
+                (index, outcome)
+            });
+        }
+
+        let mut finished = Vec::with_capacity(due.len());
+        while let Some(result) = runs.join_next().await {
+            match result {
+                Ok(run) => finished.push(run),
+                Err(e) => warn!("◆ Job run panicked: {}", e),
+            }
+        }
+        finished.sort_by_key(|(index, _)| *index);
+
+        let mut outcomes = Vec::with_capacity(finished.len());
+        for (index, outcome) in finished {
+            let job = &due[index];
+            service
+                .update_after_run(&job.id, outcome.status(), outcome.error().as_deref())
+                .await;
+            outcomes.push((job.id.clone(), outcome));
+        }
+
+        outcomes
+    }
+}
+
+impl std::fmt::Debug for CronExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CronExecutor")
+            .field("max_concurrent", &self.max_concurrent)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Convert a job timeout to a duration, ignoring non-positive values
+pub(crate) fn timeout_from_ms(timeout_ms: Option<i64>) -> Option<Duration> {
+    timeout_ms
+        .filter(|ms| *ms > 0)
+        .map(|ms| Duration::from_millis(ms as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Payload, Schedule};
+    use tempfile::TempDir;
+
+    fn slow_or_fast_executor() -> CronExecutor {
+        CronExecutor::new(|job: Job| async move {
+            if job.payload.message == "slow" {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_slow_task_times_out() {
+        let job = Job::recurring("slow", 60_000, Payload::new("slow")).with_timeout_ms(50);
+
+        let outcome = slow_or_fast_executor().execute(&job).await;
+
+        assert_eq!(outcome, JobOutcome::TimedOut { after_ms: 50 });
+        assert_eq!(outcome.status(), "timeout");
+        assert_eq!(outcome.error().as_deref(), Some("timed out after 50ms"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_fast_task_completes() {
+        let job = Job::recurring("fast", 60_000, Payload::new("fast")).with_timeout_ms(1_000);
+
+        let outcome = slow_or_fast_executor().execute(&job).await;
+
+        assert_eq!(outcome, JobOutcome::Success);
+        assert_eq!(outcome.error(), None);
+    }
+
+    #[tokio::test]
+    async fn test_execute_failure_is_recorded() {
+        let executor = CronExecutor::new(|_job: Job| async { Err("no signal".to_string()) });
+        let job = Job::recurring("broken", 60_000, Payload::new("x"));
+
+        let outcome = executor.execute(&job).await;
+
+        assert_eq!(outcome, JobOutcome::Failed("no signal".to_string()));
+        assert_eq!(outcome.status(), "failed");
+    }
+
+    #[tokio::test]
+    async fn test_run_due_records_timeout_status() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut service = CronService::new(temp_dir.path().join("cron.json"));
+        let slow = Job::new(
+            "slow",
+            Schedule::Every { every_ms: 60_000 },
+            Payload::new("slow"),
+        )
+        .with_timeout_ms(50);
+        let fast = Job::new(
+            "fast",
+            Schedule::Every { every_ms: 60_000 },
+            Payload::new("fast"),
+        )
+        .with_timeout_ms(1_000);
+        let (slow_id, fast_id) = (slow.id.clone(), fast.id.clone());
+        service.add_job(slow).await;
+        service.add_job(fast).await;
+        for job in &mut service.store_mut().jobs {
+            job.state.next_run_at_ms = Some(0);
+        }
+
+        let outcomes = slow_or_fast_executor().run_due(&mut service).await;
+        assert_eq!(outcomes.len(), 2);
+
+        let slow = service.store().find_job(&slow_id).unwrap();
+        assert_eq!(slow.state.last_status.as_deref(), Some("timeout"));
+        assert_eq!(
+            slow.state.last_error.as_deref(),
+            Some("timed out after 50ms")
+        );
+
+        let fast = service.store().find_job(&fast_id).unwrap();
+        assert_eq!(fast.state.last_status.as_deref(), Some("success"));
+        assert_eq!(fast.state.last_error, None);
+    }
+
+    async fn due_service(temp_dir: &TempDir, messages: &[&str]) -> CronService {
+        let mut service = CronService::new(temp_dir.path().join("cron.json"));
+        for message in messages {
+            let job = Job::new(
+                *message,
+                Schedule::Every { every_ms: 60_000 },
+                Payload::new(*message),
+            );
+            service.add_job(job).await;
+        }
+        for job in &mut service.store_mut().jobs {
+            job.state.next_run_at_ms = Some(0);
+        }
+        service
+    }
+
+    #[tokio::test]
+    async fn test_limit_of_one_runs_jobs_one_after_another() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut service = due_service(&temp_dir, &["first", "second"]).await;
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let executor = CronExecutor::new({
+            let events = Arc::clone(&events);
+            move |job: Job| {
+                let events = Arc::clone(&events);
+                async move {
+                    events.lock().unwrap().push(format!("start {}", job.name));
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    events.lock().unwrap().push(format!("end {}", job.name));
+                    Ok(())
+                }
+            }
+        })
+        .with_max_concurrent(1);
+
+        let outcomes = executor.run_due(&mut service).await;
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|(_, o)| *o == JobOutcome::Success));
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["start first", "end first", "start second", "end second"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_higher_limit_runs_jobs_in_parallel() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut service = due_service(&temp_dir, &["a", "b"]).await;
+        let running = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let executor = CronExecutor::new({
+            let (running, peak) = (Arc::clone(&running), Arc::clone(&peak));
+            move |_job: Job| {
+                let (running, peak) = (Arc::clone(&running), Arc::clone(&peak));
+                async move {
+                    use std::sync::atomic::Ordering;
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            }
+        })
+        .with_max_concurrent(2);
+
+        let outcomes = executor.run_due(&mut service).await;
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_max_concurrent_is_at_least_one() {
+        let executor = CronExecutor::new(|_job: Job| async { Ok(()) }).with_max_concurrent(0);
+        assert_eq!(executor.max_concurrent(), 1);
+    }
+
+    #[test]
+    fn test_non_positive_timeout_is_ignored() {
+        assert_eq!(timeout_from_ms(None), None);
+        assert_eq!(timeout_from_ms(Some(0)), None);
+        assert_eq!(timeout_from_ms(Some(-5)), None);
+        assert_eq!(timeout_from_ms(Some(250)), Some(Duration::from_millis(250)));
+    }
+}
@@ -1,13 +1,51 @@
 //! Cron service for scheduled tasks
 
-use chrono::Local;
+use chrono::{Local, TimeZone};
 use serde::{Deserialize, Serialize};
 
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
-use tracing::{debug, info};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+pub mod executor;
+
+pub use executor::{CronExecutor, JobOutcome};
+
+/// Why a schedule cannot be used
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("cron expression is empty")]
+    Empty,
+    #[error("cron expression needs 5 fields (minute hour day month weekday), got {0}")]
+    FieldCount(usize),
+    #[error("invalid {field} field '{value}'")]
+    InvalidField { field: &'static str, value: String },
+    #[error("{field} value {value} is outside {min}-{max}")]
+    OutOfRange {
+        field: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+    #[error("interval must be positive, got {0}ms")]
+    NonPositiveInterval(i64),
+}
+
+/// Cron fields in order, with their allowed ranges
+const CRON_FIELDS: [(&str, u32, u32); 5] = [
+    ("minute", 0, 59),
+    ("hour", 0, 23),
+    ("day", 1, 31),
+    ("month", 1, 12),
+    ("weekday", 0, 6),
+];
+
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
 /// Cron job schedule
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
@@ -23,6 +61,241 @@ pub enum Schedule {
     Cron { expr: String },
 }
 
+impl Schedule {
+    /// Check the schedule can be computed
+    ///
+    /// Cron expressions must have five fields of numbers, `*`, `*/step`,
+    /// `a-b` ranges, or comma lists, each within the field's range (weekdays
+    /// may also be named, `MON`..`SUN`).
+    pub fn validate(&self) -> Result<(), ScheduleError> {
+        match self {
+            Schedule::At { .. } => Ok(()),
+            Schedule::Every { every_ms } if *every_ms <= 0 => {
+                Err(ScheduleError::NonPositiveInterval(*every_ms))
+            }
+            Schedule::Every { .. } => Ok(()),
+            Schedule::Cron { expr } => validate_cron(expr),
+        }
+    }
+
+    /// Human-readable summary of the schedule
+    ///
+    /// Common cron patterns are rendered in plain words ("daily at midnight",
+    /// "every 15 minutes"); anything else falls back to the raw expression.
+    pub fn describe(&self) -> String {
+        match self {
+            Schedule::At { at_ms } => match chrono::DateTime::from_timestamp_millis(*at_ms) {
+                Some(at) => format!(
+                    "once at {}",
+                    at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+                ),
+                None => format!("once at {}ms", at_ms),
+            },
+            Schedule::Every { every_ms } => describe_interval(*every_ms),
+            Schedule::Cron { expr } => {
+                describe_cron(expr).unwrap_or_else(|| format!("cron: {}", expr))
+            }
+        }
+    }
+}
+
+fn validate_cron(expr: &str) -> Result<(), ScheduleError> {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    if fields.is_empty() {
+        return Err(ScheduleError::Empty);
+    }
+    if fields.len() != CRON_FIELDS.len() {
+        return Err(ScheduleError::FieldCount(fields.len()));
+    }
+    for (value, (field, min, max)) in fields.into_iter().zip(CRON_FIELDS) {
+        for part in value.split(',') {
+            validate_cron_part(part, field, min, max).map_err(|e| match e {
+                ScheduleError::InvalidField { field, .. } => ScheduleError::InvalidField {
+                    field,
+                    value: value.to_string(),
+                },
+                other => other,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_cron_part(
+    part: &str,
+    field: &'static str,
+    min: u32,
+    max: u32,
+) -> Result<(), ScheduleError> {
+    let invalid = || ScheduleError::InvalidField {
+        field,
+        value: part.to_string(),
+    };
+    let value = |s: &str| -> Result<u32, ScheduleError> {
+        let n = if field == "weekday" {
+            WEEKDAY_NAMES
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(s))
+                .map(|i| i as u32)
+        } else {
+            None
+        };
+        let n = match n {
+            Some(n) => n,
+            None => s.parse::<u32>().map_err(|_| invalid())?,
+        };
+        if n < min || n > max {
+            return Err(ScheduleError::OutOfRange {
+                field,
+                value: n,
+                min,
+                max,
+            });
+        }
+        Ok(n)
+    };
+
+    if part == "*" {
+        return Ok(());
+    }
+    if let Some(step) = part.strip_prefix("*/") {
+        let step: u32 = step.parse().map_err(|_| invalid())?;
+        if step == 0 || step > max {
+            return Err(ScheduleError::OutOfRange {
+                field,
+                value: step,
+                min: 1,
+                max,
+            });
+        }
+        return Ok(());
+    }
+    if let Some((start, end)) = part.split_once('-') {
+        if value(start)? > value(end)? {
+            return Err(invalid());
+        }
+        return Ok(());
+    }
+    value(part).map(|_| ())
+}
+
+/// Describe a fixed interval in the largest whole unit
+fn describe_interval(every_ms: i64) -> String {
+    const UNITS: [(i64, &str, &str); 4] = [
+        (86_400_000, "day", "d"),
+        (3_600_000, "hour", "h"),
+        (60_000, "minute", "m"),
+        (1_000, "second", "s"),
+    ];
+
+    // A whole number of the largest fitting unit reads as "every 15 minutes"
+    match UNITS.iter().find(|(unit_ms, _, _)| every_ms >= *unit_ms) {
+        Some((unit_ms, unit, _)) if every_ms % unit_ms == 0 => {
+            return plural_every(every_ms / unit_ms, unit)
+        }
+        None => return plural_every(every_ms, "millisecond"),
+        Some(_) => {}
+    }
+
+    // Mixed units read as a compact duration, e.g. "every 2h 30m"
+    let mut rest = every_ms;
+    let mut parts = Vec::new();
+    for (unit_ms, _, short) in UNITS {
+        if rest >= unit_ms {
+            parts.push(format!("{}{}", rest / unit_ms, short));
+            rest %= unit_ms;
+        }
+    }
+    if rest > 0 {
+        parts.push(format!("{}ms", rest));
+    }
+    format!("every {}", parts.join(" "))
+}
+
+fn plural_every(count: i64, unit: &str) -> String {
+    if count == 1 {
+        format!("every {}", unit)
+    } else {
+        format!("every {} {}s", count, unit)
+    }
+}
+
+/// Describe well-known five-field cron patterns, `None` for anything else
+fn describe_cron(expr: &str) -> Option<String> {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let [minute, hour, dom, month, dow] = fields.as_slice() else {
+        return None;
+    };
+    if *month != "*" {
+        return None;
+    }
+
+    let step = |field: &str| field.strip_prefix("*/").and_then(|n| n.parse::<u32>().ok());
+    let number = |field: &str| field.parse::<u32>().ok();
+
+    match (*minute, *hour, *dom, *dow) {
+        ("*", "*", "*", "*") => Some("every minute".to_string()),
+        (m, "*", "*", "*") => {
+            if let Some(n) = step(m) {
+                Some(plural_every(n as i64, "minute"))
+            } else {
+                match number(m)? {
+                    0 => Some("every hour".to_string()),
+                    m => Some(format!("every hour at minute {}", m)),
+                }
+            }
+        }
+        (m, h, "*", "*") if step(h).is_some() => {
+            let n = step(h)?;
+            match number(m)? {
+                0 => Some(plural_every(n as i64, "hour")),
+                m => Some(format!(
+                    "{} at minute {}",
+                    plural_every(n as i64, "hour"),
+                    m
+                )),
+            }
+        }
+        (m, h, "*", "*") => Some(format!("daily at {}", time_of_day(number(h)?, number(m)?)?)),
+        (m, h, "*", d) => {
+            let at = time_of_day(number(h)?, number(m)?)?;
+            match d {
+                "1-5" | "MON-FRI" | "mon-fri" => Some(format!("weekdays at {}", at)),
+                "0,6" | "6,0" | "SAT,SUN" | "sat,sun" => Some(format!("weekends at {}", at)),
+                d => Some(format!("weekly on {} at {}", weekday_name(number(d)?)?, at)),
+            }
+        }
+        (m, h, d, "*") => {
+            let at = time_of_day(number(h)?, number(m)?)?;
+            Some(format!("monthly on day {} at {}", number(d)?, at))
+        }
+        _ => None,
+    }
+}
+
+fn time_of_day(hour: u32, minute: u32) -> Option<String> {
+    match (hour, minute) {
+        (0, 0) => Some("midnight".to_string()),
+        (12, 0) => Some("noon".to_string()),
+        (h, m) if h < 24 && m < 60 => Some(format!("{:02}:{:02}", h, m)),
+        _ => None,
+    }
+}
+
+fn weekday_name(day: u32) -> Option<&'static str> {
+    const DAYS: [&str; 7] = [
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+    ];
+    // Both 0 and 7 mean Sunday
+    DAYS.get((day % 7) as usize).filter(|_| day <= 7).copied()
+}
+
 /// Cron job payload
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Payload {
@@ -82,6 +355,25 @@ pub struct JobState {
     /// Last error message
     #[serde(default)]
     pub last_error: Option<String>,
+    /// Retries used since the last successful run
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub retry_attempts: u32,
+    /// Missed runs still queued by `CatchUp::RunAll`
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub pending_runs: u32,
+    /// Runs recorded by `update_after_run`
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub run_count: u64,
+    /// Runs that finished with status `success`
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub success_count: u64,
+    /// Runs that failed or timed out
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub failure_count: u64,
+}
+
+fn is_zero<T: Default + PartialEq>(n: &T) -> bool {
+    *n == T::default()
 }
 
 impl JobState {
@@ -97,6 +389,63 @@ impl JobState {
             last_run_at_ms: None,
             last_status: None,
             last_error: None,
+            retry_attempts: 0,
+            pending_runs: 0,
+            run_count: 0,
+            success_count: 0,
+            failure_count: 0,
+        }
+    }
+}
+
+/// Near-term retries for a failed run
+///
+/// A failed run is retried after `backoff_ms`, up to `max_retries` times in
+/// a row; after that the job goes back to its normal schedule.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub backoff_ms: i64,
+}
+
+/// Most missed runs counted (and queued) for one job
+pub const MAX_CATCH_UP_RUNS: u32 = 100;
+
+/// What to do about runs missed while the gateway was down
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CatchUp {
+    /// Drop missed runs and wait for the next scheduled time
+    Skip,
+    /// Run once for all missed runs
+    #[default]
+    RunOnce,
+    /// Run once per missed run, back to back
+    RunAll,
+}
+
+impl CatchUp {
+    fn is_default(&self) -> bool {
+        *self == CatchUp::default()
+    }
+}
+
+/// Why a job is not enabled
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseReason {
+    /// Disabled by an operator; can be enabled again
+    Paused,
+    /// A one-shot job that has already run
+    Completed,
+}
+
+impl PauseReason {
+    /// Display label
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PauseReason::Paused => "paused",
+            PauseReason::Completed => "completed",
         }
     }
 }
@@ -111,6 +460,9 @@ pub struct Job {
     /// Whether the job is enabled
     #[serde(default = "default_true")]
     pub enabled: bool,
+    /// Why the job is disabled, if it is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pause_reason: Option<PauseReason>,
     /// Schedule
     pub schedule: Schedule,
     /// Payload
@@ -125,6 +477,15 @@ pub struct Job {
     /// Delete after one run
     #[serde(default)]
     pub delete_after_run: bool,
+    /// Cancel a run that takes longer than this (ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<i64>,
+    /// Retry failed runs sooner than the next scheduled time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicy>,
+    /// Handling of runs missed during downtime
+    #[serde(default, skip_serializing_if = "CatchUp::is_default")]
+    pub catch_up: CatchUp,
 }
 
 fn default_true() -> bool {
@@ -139,15 +500,42 @@ impl Job {
             id: Uuid::new_v4().to_string()[..8].to_string(),
             name: name.into(),
             enabled: true,
+            pause_reason: None,
             schedule,
             payload,
             state: JobState::default(),
             created_at_ms: now,
             updated_at_ms: now,
             delete_after_run: false,
+            timeout_ms: None,
+            retry_policy: None,
+            catch_up: CatchUp::default(),
         }
     }
 
+    /// Handle missed runs per `catch_up`
+    pub fn with_catch_up(mut self, catch_up: CatchUp) -> Self {
+        self.catch_up = catch_up;
+        self
+    }
+
+    /// Retry failed runs per `policy`
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Set the run timeout
+    pub fn with_timeout_ms(mut self, timeout_ms: i64) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// Run timeout, if a positive one is set
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        executor::timeout_from_ms(self.timeout_ms)
+    }
+
     /// Create a one-shot job that runs at a specific time
     pub fn one_shot(
         name: impl Into<String>,
@@ -179,17 +567,88 @@ impl Job {
             }
             Schedule::Every { every_ms } => Some(now + every_ms),
             Schedule::Cron { expr } => {
-                // Parse cron expression and get next occurrence
-                // For simplicity, using a basic implementation
-                if let Ok(schedule) = cron_parser::parse(expr, Local::now()) {
-                    Some(schedule.timestamp_millis())
-                } else {
-                    None
+                // cron-parser panics on some malformed input, so only
+                // validated expressions reach it
+                if let Err(e) = self.schedule.validate() {
+                    debug!("Invalid schedule for job {}: {}", self.id, e);
+                    return None;
+                }
+                cron_parser::parse(expr, Local::now())
+                    .ok()
+                    .map(|next| next.timestamp_millis())
+            }
+        }
+    }
+
+    /// The next `count` run times, without touching state
+    ///
+    /// `Every` schedules step from the pending next run (or now); `At`
+    /// yields at most one time.
+    pub fn next_runs(&self, count: usize) -> Vec<i64> {
+        if count == 0 {
+            return Vec::new();
+        }
+
+        match &self.schedule {
+            Schedule::At { .. } => self.compute_next_run().into_iter().collect(),
+            Schedule::Every { every_ms } => {
+                let first = self
+                    .state
+                    .next_run_at_ms
+                    .unwrap_or_else(|| Local::now().timestamp_millis() + every_ms);
+                (0..count as i64).map(|i| first + i * every_ms).collect()
+            }
+            Schedule::Cron { expr } => {
+                if self.schedule.validate().is_err() {
+                    return Vec::new();
+                }
+                let mut runs = Vec::with_capacity(count);
+                let mut after = Local::now();
+                while runs.len() < count {
+                    let Ok(next) = cron_parser::parse(expr, after) else {
+                        break;
+                    };
+                    runs.push(next.timestamp_millis());
+                    after = next;
                 }
+                runs
             }
         }
     }
 
+    /// Scheduled runs between the pending next run and `now_ms`, inclusive
+    ///
+    /// Zero when the next run is still ahead; capped at `MAX_CATCH_UP_RUNS`.
+    pub fn missed_runs(&self, now_ms: i64) -> u32 {
+        let Some(next) = self.state.next_run_at_ms.filter(|next| *next <= now_ms) else {
+            return 0;
+        };
+
+        let missed = match &self.schedule {
+            Schedule::At { .. } => 1,
+            Schedule::Every { every_ms } if *every_ms > 0 => 1 + (now_ms - next) / every_ms,
+            Schedule::Every { .. } => 1,
+            Schedule::Cron { expr } => {
+                let mut count = 1;
+                let mut after = Local.timestamp_millis_opt(next).single();
+                while let Some(from) = after {
+                    if count >= MAX_CATCH_UP_RUNS as i64 || self.schedule.validate().is_err() {
+                        break;
+                    }
+                    match cron_parser::parse(expr, from) {
+                        Ok(run) if run.timestamp_millis() <= now_ms => {
+                            count += 1;
+                            after = Some(run);
+                        }
+                        _ => break,
+                    }
+                }
+                count
+            }
+        };
+        missed.clamp(0, MAX_CATCH_UP_RUNS as i64) as u32
+    }
+
     /// Check if job is due to run
     pub fn is_due(&self) -> bool {
         if !self.enabled {
@@ -211,9 +670,35 @@ impl Job {
             .unwrap_or(false)
     }
 
+    /// Why the job is not enabled, `None` while it is
+    ///
+    /// Stores written before the reason was recorded are inferred: a one-shot
+    /// that has run is completed, anything else is paused.
+    pub fn pause_reason(&self) -> Option<PauseReason> {
+        if self.enabled {
+            return None;
+        }
+        self.pause_reason.or_else(|| {
+            let ran = self.state.last_run_at_ms.is_some();
+            if ran && matches!(self.schedule, Schedule::At { .. }) {
+                Some(PauseReason::Completed)
+            } else {
+                Some(PauseReason::Paused)
+            }
+        })
+    }
+
+    /// Status label: "enabled", "paused" or "completed"
+    pub fn status(&self) -> &'static str {
+        self.pause_reason().map_or("enabled", |r| r.as_str())
+    }
+
     /// Set enabled state
+    ///
+    /// Disabling marks the job as paused by an operator.
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
+        self.pause_reason = (!enabled).then_some(PauseReason::Paused);
         if enabled {
             self.state.next_run_at_ms = self.compute_next_run();
         } else {
@@ -285,10 +770,47 @@ impl JobStore {
     }
 }
 
+/// Read and parse a job store file
+async fn read_store(path: &Path) -> std::io::Result<JobStore> {
+    let content = tokio::fs::read_to_string(path).await?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Write `content` to a sibling temp file, then rename it over `path`
+///
+/// Readers see either the old file or the new one, never a partial write.
+async fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".tmp-{}", std::process::id()));
+    let tmp = PathBuf::from(tmp);
+
+    let result = async {
+        let mut file = tokio::fs::File::create(&tmp).await?;
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await?;
+        drop(file);
+        tokio::fs::rename(&tmp, path).await
+    }
+    .await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp).await;
+    }
+    result
+}
+
+/// Modification time of `path`, or `None` if it cannot be read
+async fn modified_at(path: &Path) -> Option<SystemTime> {
+    tokio::fs::metadata(path).await.ok()?.modified().ok()
+}
+
 /// Cron service for managing scheduled tasks
 pub struct CronService {
     store_path: PathBuf,
     store: JobStore,
+    /// Rotating copies of the store kept before each edit (0 keeps none)
+    backups: usize,
+    /// Store modification time as of the last load or save
+    synced_at: Mutex<Option<SystemTime>>,
 }
 
 impl CronService {
@@ -297,29 +819,155 @@ impl CronService {
         let store_path = store_path.as_ref().to_path_buf();
         let store = JobStore::new();
 
-        Self { store_path, store }
+        Self {
+            store_path,
+            store,
+            backups: 0,
+            synced_at: Mutex::new(None),
+        }
+    }
+
+    /// Keep the last `backups` versions of the store as `<store>.1`..`<store>.N`
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    /// Path of backup `n` (1 is the most recent)
+    pub fn backup_path(&self, n: usize) -> PathBuf {
+        let mut path = self.store_path.clone().into_os_string();
+        path.push(format!(".{}", n));
+        PathBuf::from(path)
+    }
+
+    /// Shift existing backups up one slot and copy the current store into `.1`
+    ///
+    /// Saves that don't change the store leave the backups alone.
+    async fn rotate_backups(&self, next: &str) -> std::io::Result<()> {
+        if self.backups == 0 || !self.store_path.exists() {
+            return Ok(());
+        }
+        if tokio::fs::read_to_string(&self.store_path)
+            .await
+            .ok()
+            .as_deref()
+            == Some(next)
+        {
+            return Ok(());
+        }
+        let oldest = self.backup_path(self.backups);
+        if oldest.exists() {
+            tokio::fs::remove_file(&oldest).await?;
+        }
+        for n in (1..self.backups).rev() {
+            let from = self.backup_path(n);
+            if from.exists() {
+                tokio::fs::rename(&from, self.backup_path(n + 1)).await?;
+            }
+        }
+        tokio::fs::copy(&self.store_path, self.backup_path(1)).await?;
+        Ok(())
+    }
+
+    /// Replace the active store with backup `n` and load it
+    pub async fn restore_backup(&mut self, n: usize) -> std::io::Result<()> {
+        let backup = self.backup_path(n);
+        if n == 0 || !backup.exists() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no backup at {}", backup.display()),
+            ));
+        }
+        let content = tokio::fs::read_to_string(&backup).await?;
+        let store: JobStore = serde_json::from_str(&content)?;
+        write_atomic(&self.store_path, &content).await?;
+        self.mark_synced().await;
+        info!("◆ TIMELINE RESTORED FROM {}", backup.display());
+        self.store = store;
+        Ok(())
+    }
+
+    /// Remember the store's current modification time as seen
+    async fn mark_synced(&self) {
+        let modified = modified_at(&self.store_path).await;
+        *self.synced_at.lock().unwrap_or_else(|e| e.into_inner()) = modified;
+    }
+
+    /// Load jobs again if the store changed on disk since the last load or save
+    ///
+    /// Returns whether the store was reloaded.
+    pub async fn reload_if_changed(&mut self) -> std::io::Result<bool> {
+        let modified = modified_at(&self.store_path).await;
+        if modified == *self.synced_at.lock().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(false);
+        }
+        self.load().await?;
+        Ok(true)
     }
 
     /// Load jobs from disk
+    ///
+    /// If the store cannot be read or parsed, the most recent backup that
+    /// parses is loaded instead and written back as the store.
     pub async fn load(&mut self) -> std::io::Result<()> {
         if !self.store_path.exists() {
             return Ok(());
         }
 
-        let content = tokio::fs::read_to_string(&self.store_path).await?;
-        self.store = serde_json::from_str(&content)?;
-        info!("Loaded {} cron jobs", self.store.jobs.len());
-        Ok(())
+        let modified = modified_at(&self.store_path).await;
+        let error = match read_store(&self.store_path).await {
+            Ok(store) => {
+                *self.synced_at.lock().unwrap_or_else(|e| e.into_inner()) = modified;
+                self.store = store;
+                info!("Loaded {} cron jobs", self.store.jobs.len());
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+
+        let mut n = 1;
+        while self.backup_path(n).exists() {
+            let backup = self.backup_path(n);
+            if let Ok(store) = read_store(&backup).await {
+                warn!(
+                    "◆ TIMELINE CORRUPTED ({}), RECOVERED FROM {}",
+                    error,
+                    backup.display()
+                );
+                write_atomic(&self.store_path, &tokio::fs::read_to_string(&backup).await?).await?;
+                self.mark_synced().await;
+                self.store = store;
+                return Ok(());
+            }
+            n += 1;
+        }
+        Err(error)
     }
 
-    /// Save jobs to disk
+    /// Save jobs to disk after an edit, rotating backups first
     pub async fn save(&self) -> std::io::Result<()> {
+        self.write_store(true).await
+    }
+
+    /// Save run state to disk, leaving the backups alone
+    ///
+    /// Used after runs and catch-up so backups hold the user's edits rather
+    /// than every tick.
+    async fn save_state(&self) -> std::io::Result<()> {
+        self.write_store(false).await
+    }
+
+    async fn write_store(&self, rotate: bool) -> std::io::Result<()> {
         if let Some(parent) = self.store_path.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
 
         let content = serde_json::to_string_pretty(&self.store)?;
-        tokio::fs::write(&self.store_path, content).await?;
+        if rotate {
+            self.rotate_backups(&content).await?;
+        }
+        write_atomic(&self.store_path, &content).await?;
+        self.mark_synced().await;
         debug!("Saved {} cron jobs", self.store.jobs.len());
         Ok(())
     }
@@ -355,50 +1003,137 @@ impl CronService {
     /// Enable/disable a job
     pub async fn enable_job(&mut self, id: &str, enabled: bool) -> Option<Job> {
         let job_index = self.store.jobs.iter().position(|j| j.id == id)?;
-        {
-            let job = &mut self.store.jobs[job_index];
-            job.enabled = enabled;
-            if enabled {
-                job.state.next_run_at_ms = job.compute_next_run();
-            } else {
-                job.state.next_run_at_ms = None;
-            }
-            job.updated_at_ms = Local::now().timestamp_millis();
-        }
+        self.store.jobs[job_index].set_enabled(enabled);
         let job = self.store.jobs[job_index].clone();
         let _ = self.save().await;
         Some(job)
     }
 
+    /// Replace a job's schedule, keeping its ID and run history
+    ///
+    /// Returns `None` if the job is missing or the schedule fails
+    /// validation; the job is left untouched in either case. Pending
+    /// catch-up runs and retries belong to the old cadence and are dropped.
+    pub async fn update_schedule(&mut self, id: &str, schedule: Schedule) -> Option<Job> {
+        schedule.validate().ok()?;
+        let job = self.store.find_job_mut(id)?;
+        job.schedule = schedule;
+        job.state.pending_runs = 0;
+        job.state.retry_attempts = 0;
+        if job.enabled {
+            job.state.next_run_at_ms = job.compute_next_run();
+        }
+        job.updated_at_ms = Local::now().timestamp_millis();
+        let job = job.clone();
+        let _ = self.save().await;
+        Some(job)
+    }
+
     /// Get due jobs
     pub fn get_due_jobs(&self) -> Vec<&Job> {
         self.store.jobs.iter().filter(|j| j.is_due()).collect()
     }
 
     /// Update job after execution
+    ///
+    /// Edits saved to the store while the job ran are loaded first, so the
+    /// run state is written on top of them instead of over them.
     pub async fn update_after_run(&mut self, id: &str, status: &str, error: Option<&str>) {
+        if let Err(e) = self.reload_if_changed().await {
+            warn!("◆ Failed to reload scheduled jobs: {}", e);
+        }
         let now = Local::now().timestamp_millis();
 
         if let Some(job) = self.store.jobs.iter_mut().find(|j| j.id == id) {
             job.state.last_run_at_ms = Some(now);
             job.state.last_status = Some(status.to_string());
             job.state.last_error = error.map(|e| e.to_string());
+            job.state.run_count += 1;
+            // Anything but success, including a timeout, counts as a failure
+            let failed = status != "success";
+            if failed {
+                job.state.failure_count += 1;
+            } else {
+                job.state.success_count += 1;
+            }
             job.updated_at_ms = now;
 
+            // A failure with retries left comes back soon instead of next period
+            let retry = job
+                .retry_policy
+                .filter(|policy| failed && job.state.retry_attempts < policy.max_retries);
+            if let Some(policy) = retry {
+                job.state.retry_attempts += 1;
+                job.state.next_run_at_ms = Some(now + policy.backoff_ms);
+                info!(
+                    "◆ Retrying job {} in {}ms (attempt {}/{})",
+                    job.id, policy.backoff_ms, job.state.retry_attempts, policy.max_retries
+                );
+                let _ = self.save_state().await;
+                return;
+            }
+            job.state.retry_attempts = 0;
+
+            // Missed runs queued by reconcile go out back to back
+            if job.state.pending_runs > 0 {
+                job.state.pending_runs -= 1;
+                job.state.next_run_at_ms = Some(now);
+                let _ = self.save_state().await;
+                return;
+            }
+
             // Compute next run
             if matches!(job.schedule, Schedule::At { .. }) {
                 if job.delete_after_run {
                     self.store.jobs.retain(|j| j.id != id);
                 } else {
                     job.enabled = false;
+                    job.pause_reason = Some(PauseReason::Completed);
                     job.state.next_run_at_ms = None;
                 }
             } else {
                 job.state.next_run_at_ms = job.compute_next_run();
             }
 
-            let _ = self.save().await;
+            let _ = self.save_state().await;
+        }
+    }
+
+    /// Apply each job's catch-up policy to runs missed during downtime
+    ///
+    /// Call once after loading, before running due jobs. Returns how many
+    /// jobs had missed runs.
+    pub async fn reconcile(&mut self) -> usize {
+        let now = Local::now().timestamp_millis();
+        let mut reconciled = 0;
+
+        for job in self.store.jobs.iter_mut().filter(|j| j.enabled) {
+            if matches!(job.schedule, Schedule::At { .. }) {
+                continue;
+            }
+            let missed = job.missed_runs(now);
+            if missed == 0 {
+                continue;
+            }
+            reconciled += 1;
+            info!(
+                "◆ Job {} missed {} run(s), catching up: {:?}",
+                job.id, missed, job.catch_up
+            );
+            match job.catch_up {
+                CatchUp::Skip => {
+                    job.state.pending_runs = 0;
+                    job.state.next_run_at_ms = job.compute_next_run();
+                }
+                CatchUp::RunOnce => job.state.pending_runs = 0,
+                CatchUp::RunAll => job.state.pending_runs = missed - 1,
+            }
+        }
+
+        if reconciled > 0 {
+            let _ = self.save_state().await;
         }
+        reconciled
     }
 
     /// Get a reference to the store
@@ -492,6 +1227,77 @@ mod tests {
         assert!(matches!(cron, Schedule::Cron { expr } if expr == "0 0 * * *"));
     }
 
+    #[test]
+    fn test_schedule_describe_cron_patterns() {
+        let cases = [
+            ("0 0 * * *", "daily at midnight"),
+            ("0 12 * * *", "daily at noon"),
+            ("30 9 * * *", "daily at 09:30"),
+            ("*/15 * * * *", "every 15 minutes"),
+            ("* * * * *", "every minute"),
+            ("0 * * * *", "every hour"),
+            ("5 * * * *", "every hour at minute 5"),
+            ("0 */6 * * *", "every 6 hours"),
+            ("0 9 * * 1-5", "weekdays at 09:00"),
+            ("0 18 * * 5", "weekly on Friday at 18:00"),
+            ("0 0 1 * *", "monthly on day 1 at midnight"),
+        ];
+
+        for (expr, expected) in cases {
+            let schedule = Schedule::Cron {
+                expr: expr.to_string(),
+            };
+            assert_eq!(schedule.describe(), expected, "expr: {}", expr);
+        }
+    }
+
+    #[test]
+    fn test_schedule_describe_cron_fallback() {
+        for expr in ["0 0 1 1 *", "1,2,3 4-6 * * *", "not a cron", "0 25 * * *"] {
+            let schedule = Schedule::Cron {
+                expr: expr.to_string(),
+            };
+            assert_eq!(schedule.describe(), format!("cron: {}", expr));
+        }
+    }
+
+    #[test]
+    fn test_schedule_describe_every() {
+        let cases = [
+            (1_000, "every second"),
+            (30_000, "every 30 seconds"),
+            (60_000, "every minute"),
+            (900_000, "every 15 minutes"),
+            (3_600_000, "every hour"),
+            (7_200_000, "every 2 hours"),
+            (86_400_000, "every day"),
+            (250, "every 250 milliseconds"),
+            (90_000, "every 1m 30s"),
+            (1_500, "every 1s 500ms"),
+            (9_000_000, "every 2h 30m"),
+            (93_784_000, "every 1d 2h 3m 4s"),
+        ];
+
+        for (every_ms, expected) in cases {
+            assert_eq!(Schedule::Every { every_ms }.describe(), expected);
+        }
+    }
+
+    #[test]
+    fn test_schedule_describe_at() {
+        let at_ms = 1_700_000_000_000i64;
+        let expected = chrono::DateTime::from_timestamp_millis(at_ms)
+            .unwrap()
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+
+        assert_eq!(
+            Schedule::At { at_ms }.describe(),
+            format!("once at {}", expected)
+        );
+    }
+
     // ============ Payload Tests ============
 
     #[test]
@@ -570,6 +1376,11 @@ mod tests {
             last_run_at_ms: Some(1_699_999_000_000),
             last_status: Some("success".to_string()),
             last_error: Some("error msg".to_string()),
+            retry_attempts: 2,
+            pending_runs: 0,
+            run_count: 4,
+            success_count: 3,
+            failure_count: 1,
         };
 
         let json = serde_json::to_string(&state).unwrap();
@@ -706,18 +1517,119 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "index out of bounds")]
     fn test_compute_next_run_cron_invalid() {
-        // cron-parser library panics on invalid expressions
-        let job = Job::new(
-            "test",
-            Schedule::Cron {
-                expr: "invalid".to_string(),
-            },
-            Payload::new("msg"),
+        for expr in ["invalid", "", "* * *", "*/0 * * * *", "61 * * * *"] {
+            let job = Job::new(
+                "test",
+                Schedule::Cron {
+                    expr: expr.to_string(),
+                },
+                Payload::new("msg"),
+            );
+
+            assert_eq!(job.compute_next_run(), None, "{:?}", expr);
+        }
+    }
+
+    fn cron(expr: &str) -> Schedule {
+        Schedule::Cron {
+            expr: expr.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_common_expressions() {
+        for expr in [
+            "* * * * *",
+            "*/15 * * * *",
+            "0 9 * * MON-FRI",
+            "30 8,12,18 1-15 * 0",
+            "0 0 1 1 *",
+        ] {
+            assert_eq!(cron(expr).validate(), Ok(()), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_expression() {
+        assert_eq!(cron("").validate(), Err(ScheduleError::Empty));
+        assert_eq!(cron("   ").validate(), Err(ScheduleError::Empty));
+    }
+
+    #[test]
+    fn test_validate_rejects_wrong_field_count() {
+        assert_eq!(
+            cron("invalid").validate(),
+            Err(ScheduleError::FieldCount(1))
         );
+        assert_eq!(
+            cron("* * * *").validate(),
+            Err(ScheduleError::FieldCount(4))
+        );
+        assert_eq!(
+            cron("* * * * * *").validate(),
+            Err(ScheduleError::FieldCount(6))
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_values() {
+        assert!(matches!(
+            cron("60 * * * *").validate(),
+            Err(ScheduleError::OutOfRange {
+                field: "minute",
+                value: 60,
+                ..
+            })
+        ));
+        assert!(matches!(
+            cron("0 24 * * *").validate(),
+            Err(ScheduleError::OutOfRange { field: "hour", .. })
+        ));
+        assert!(matches!(
+            cron("0 0 0 * *").validate(),
+            Err(ScheduleError::OutOfRange {
+                field: "day",
+                value: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            cron("0 0 * 13 *").validate(),
+            Err(ScheduleError::OutOfRange { field: "month", .. })
+        ));
+        assert!(matches!(
+            cron("*/0 * * * *").validate(),
+            Err(ScheduleError::OutOfRange { .. })
+        ));
+    }
 
-        let _next_run = job.compute_next_run();
+    #[test]
+    fn test_validate_rejects_malformed_fields() {
+        for expr in [
+            "a * * * *",
+            "1-2-3 * * * *",
+            "5-1 * * * *",
+            "0 0 * * FUNDAY",
+        ] {
+            assert!(
+                matches!(
+                    cron(expr).validate(),
+                    Err(ScheduleError::InvalidField { .. })
+                ),
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_every_interval() {
+        assert_eq!(Schedule::Every { every_ms: 1000 }.validate(), Ok(()));
+        assert_eq!(
+            Schedule::Every { every_ms: 0 }.validate(),
+            Err(ScheduleError::NonPositiveInterval(0))
+        );
     }
 
     // ============ Job.is_due() Tests ============
@@ -1018,7 +1930,257 @@ mod tests {
         assert!(service.store().is_empty());
     }
 
-    #[tokio::test]
+    fn job_names(path: &std::path::Path) -> Vec<String> {
+        let store: JobStore =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        store.jobs.into_iter().map(|j| j.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_rotates_backups() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let mut service = CronService::new(&store_path).with_backups(2);
+
+        for name in ["a", "b", "c", "d"] {
+            service.store_mut().add_job(Job::new(
+                name,
+                Schedule::Every { every_ms: 5000 },
+                Payload::new("msg"),
+            ));
+            service.save().await.unwrap();
+        }
+
+        assert_eq!(job_names(&store_path), vec!["a", "b", "c", "d"]);
+        assert_eq!(job_names(&service.backup_path(1)), vec!["a", "b", "c"]);
+        assert_eq!(job_names(&service.backup_path(2)), vec!["a", "b"]);
+        assert!(!service.backup_path(3).exists());
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_no_backups_by_default() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let service = CronService::new(&store_path);
+
+        service.save().await.unwrap();
+        service.save().await.unwrap();
+
+        assert!(!service.backup_path(1).exists());
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_unchanged_save_keeps_backups() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let mut service = CronService::new(&store_path).with_backups(2);
+
+        service.save().await.unwrap();
+        service.store_mut().add_job(Job::new(
+            "a",
+            Schedule::Every { every_ms: 5000 },
+            Payload::new("msg"),
+        ));
+        service.save().await.unwrap();
+        service.save().await.unwrap();
+
+        assert!(job_names(&service.backup_path(1)).is_empty());
+        assert!(!service.backup_path(2).exists());
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_run_state_saves_keep_backups() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let mut service = CronService::new(&store_path).with_backups(2);
+
+        let first = Job::new("a", Schedule::Every { every_ms: 5000 }, Payload::new("msg"));
+        let id = first.id.clone();
+        service.add_job(first).await;
+        service
+            .add_job(Job::new(
+                "b",
+                Schedule::Every { every_ms: 5000 },
+                Payload::new("msg"),
+            ))
+            .await;
+        for _ in 0..3 {
+            service.update_after_run(&id, "success", None).await;
+        }
+
+        assert_eq!(job_names(&service.backup_path(1)), vec!["a"]);
+        assert!(!service.backup_path(2).exists());
+        assert_eq!(service.store().jobs[0].state.run_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_reloads_only_after_outside_edit() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let mut gateway = CronService::new(&store_path);
+        assert!(!gateway.reload_if_changed().await.unwrap());
+
+        gateway
+            .add_job(Job::new(
+                "a",
+                Schedule::Every { every_ms: 5000 },
+                Payload::new("msg"),
+            ))
+            .await;
+        assert!(!gateway.reload_if_changed().await.unwrap());
+
+        let mut cli = CronService::new(&store_path);
+        cli.load().await.unwrap();
+        cli.add_job(Job::new(
+            "b",
+            Schedule::Every { every_ms: 5000 },
+            Payload::new("msg"),
+        ))
+        .await;
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::options()
+            .append(true)
+            .open(&store_path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        assert!(gateway.reload_if_changed().await.unwrap());
+        assert_eq!(gateway.store().len(), 2);
+        assert!(!gateway.reload_if_changed().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_run_state_keeps_edits_made_during_run() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let mut gateway = CronService::new(&store_path);
+        let job = Job::new("a", Schedule::Every { every_ms: 5000 }, Payload::new("msg"));
+        let id = job.id.clone();
+        gateway.add_job(job).await;
+
+        // `sam schedule add` while job "a" is running
+        let mut cli = CronService::new(&store_path);
+        cli.load().await.unwrap();
+        cli.add_job(Job::new(
+            "b",
+            Schedule::Every { every_ms: 5000 },
+            Payload::new("msg"),
+        ))
+        .await;
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::options()
+            .append(true)
+            .open(&store_path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        gateway.update_after_run(&id, "success", None).await;
+
+        assert_eq!(job_names(&store_path), vec!["a", "b"]);
+        assert_eq!(gateway.store().jobs[0].state.run_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_restore_backup() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let mut service = CronService::new(&store_path).with_backups(3);
+
+        for name in ["a", "b", "c"] {
+            service.store_mut().add_job(Job::new(
+                name,
+                Schedule::Every { every_ms: 5000 },
+                Payload::new("msg"),
+            ));
+            service.save().await.unwrap();
+        }
+
+        service.restore_backup(2).await.unwrap();
+
+        assert_eq!(job_names(&store_path), vec!["a"]);
+        assert_eq!(service.store().len(), 1);
+        let err = service.restore_backup(9).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_load_recovers_truncated_store_from_backup() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let mut service = CronService::new(&store_path).with_backups(2);
+        for name in ["a", "b"] {
+            service
+                .add_job(Job::recurring(name, 5000, Payload::new("msg")))
+                .await;
+        }
+
+        // Simulate a crash halfway through writing the store
+        let content = std::fs::read_to_string(&store_path).unwrap();
+        std::fs::write(&store_path, &content[..content.len() / 2]).unwrap();
+
+        let mut reloaded = CronService::new(&store_path).with_backups(2);
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.store().len(), 1);
+        // The recovered store is written back in place
+        assert_eq!(job_names(&store_path), vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_load_without_backup_reports_corruption() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        std::fs::write(&store_path, "{\"version\": 1, \"jobs\": [").unwrap();
+
+        let mut service = CronService::new(&store_path);
+        assert!(service.load().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_save_never_exposes_partial_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let mut service = CronService::new(&store_path);
+        for i in 0..200 {
+            service.store_mut().add_job(Job::recurring(
+                format!("job-{}", i),
+                5000,
+                Payload::new("x".repeat(200)),
+            ));
+        }
+        service.save().await.unwrap();
+
+        let done = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let reader = {
+            let (path, done) = (store_path.clone(), done.clone());
+            std::thread::spawn(move || {
+                let mut reads = 0;
+                while !done.load(std::sync::atomic::Ordering::SeqCst) {
+                    let content = std::fs::read_to_string(&path).unwrap();
+                    serde_json::from_str::<JobStore>(&content).expect("partial store observed");
+                    reads += 1;
+                }
+                reads
+            })
+        };
+
+        for _ in 0..30 {
+            service.store_mut().jobs.rotate_left(1);
+            service.save().await.unwrap();
+        }
+        done.store(true, std::sync::atomic::Ordering::SeqCst);
+
+        assert!(reader.join().unwrap() > 0);
+        let leftovers: Vec<_> = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[tokio::test]
     async fn test_cron_service_add_job() {
         let temp_dir = tempfile::tempdir().unwrap();
         let store_path = temp_dir.path().join("cron.json");
@@ -1130,6 +2292,86 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[tokio::test]
+    async fn test_cron_service_update_schedule_every_to_cron() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+
+        let mut service = CronService::new(&store_path);
+        let job = Job::new(
+            "test",
+            Schedule::Every {
+                every_ms: 365 * 24 * 3_600_000,
+            },
+            Payload::new("msg"),
+        );
+        let id = job.id.clone();
+        let created_at = job.created_at_ms;
+        service.add_job(job).await;
+        service.store_mut().jobs[0].state.last_status = Some("ok".to_string());
+        service.store_mut().jobs[0].updated_at_ms = 0;
+
+        let updated = service
+            .update_schedule(
+                &id,
+                Schedule::Cron {
+                    expr: "* * * * *".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated.id, id);
+        assert_eq!(updated.created_at_ms, created_at);
+        assert_eq!(updated.state.last_status.as_deref(), Some("ok"));
+        assert!(updated.updated_at_ms > 0);
+        // Within a minute rather than a year out
+        let next = updated.state.next_run_at_ms.unwrap();
+        assert!(next - Local::now().timestamp_millis() <= 60_000);
+
+        // Persisted
+        let mut reloaded = CronService::new(&store_path);
+        reloaded.load().await.unwrap();
+        let job = reloaded.store().find_job(&id).unwrap();
+        assert_eq!(
+            job.schedule,
+            Schedule::Cron {
+                expr: "* * * * *".to_string()
+            }
+        );
+        assert_eq!(job.state.next_run_at_ms, Some(next));
+    }
+
+    #[tokio::test]
+    async fn test_cron_service_update_schedule_rejects_invalid() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+
+        let mut service = CronService::new(&store_path);
+        let job = Job::new(
+            "test",
+            Schedule::Every { every_ms: 5000 },
+            Payload::new("msg"),
+        );
+        let id = job.id.clone();
+        service.add_job(job).await;
+        let before = service.store().find_job(&id).unwrap().clone();
+
+        let bad_cron = Schedule::Cron {
+            expr: "61 * * * *".to_string(),
+        };
+        assert!(service.update_schedule(&id, bad_cron).await.is_none());
+        let bad_every = Schedule::Every { every_ms: 0 };
+        assert!(service.update_schedule(&id, bad_every).await.is_none());
+        assert_eq!(service.store().find_job(&id), Some(&before));
+
+        let valid = Schedule::Every { every_ms: 1000 };
+        assert!(service
+            .update_schedule("nonexistent", valid)
+            .await
+            .is_none());
+    }
+
     #[tokio::test]
     async fn test_cron_service_get_due_jobs() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1189,6 +2431,239 @@ mod tests {
         assert!(job.state.next_run_at_ms.unwrap() >= original_next_run.unwrap());
     }
 
+    #[test]
+    fn test_next_runs_every_is_evenly_spaced() {
+        let mut job = Job::recurring("tick", 60_000, Payload::new("msg"));
+        job.state.next_run_at_ms = Some(1_700_000_000_000);
+
+        let runs = job.next_runs(5);
+        assert_eq!(runs.len(), 5);
+        assert_eq!(runs[0], 1_700_000_000_000);
+        for pair in runs.windows(2) {
+            assert_eq!(pair[1] - pair[0], 60_000);
+        }
+        assert_eq!(job.state.next_run_at_ms, Some(1_700_000_000_000));
+    }
+
+    #[test]
+    fn test_next_runs_cron_increases() {
+        let job = Job::new(
+            "quarterly",
+            Schedule::Cron {
+                expr: "*/15 * * * *".to_string(),
+            },
+            Payload::new("msg"),
+        );
+
+        let runs = job.next_runs(6);
+        assert_eq!(runs.len(), 6);
+        assert!(runs[0] > Local::now().timestamp_millis() - 1000);
+        for pair in runs.windows(2) {
+            assert_eq!(pair[1] - pair[0], 15 * 60_000);
+        }
+    }
+
+    #[test]
+    fn test_next_runs_at_and_invalid() {
+        let future = Local::now().timestamp_millis() + 60_000;
+        let job = Job::one_shot("once", future, Payload::new("msg"), false);
+        assert_eq!(job.next_runs(3), vec![future]);
+
+        let past = Job::one_shot("gone", 1_000, Payload::new("msg"), false);
+        assert!(past.next_runs(3).is_empty());
+
+        let bad = Job::new(
+            "bad",
+            Schedule::Cron {
+                expr: "not a cron".to_string(),
+            },
+            Payload::new("msg"),
+        );
+        assert!(bad.next_runs(3).is_empty());
+        assert!(job.next_runs(0).is_empty());
+    }
+
+    const HOUR_MS: i64 = 3_600_000;
+
+    /// Hourly job whose next run was due six hours ago, minus a minute
+    async fn service_after_gap(catch_up: CatchUp) -> (tempfile::TempDir, CronService, String) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut service = CronService::new(temp_dir.path().join("cron.json"));
+        let job = Job::recurring("backup", HOUR_MS, Payload::new("msg")).with_catch_up(catch_up);
+        let id = job.id.clone();
+        service.add_job(job).await;
+        let now = Local::now().timestamp_millis();
+        service
+            .store_mut()
+            .find_job_mut(&id)
+            .unwrap()
+            .state
+            .next_run_at_ms = Some(now - 6 * HOUR_MS + 60_000);
+        (temp_dir, service, id)
+    }
+
+    #[tokio::test]
+    async fn test_missed_runs_counts_intervals() {
+        let (_dir, service, id) = service_after_gap(CatchUp::RunOnce).await;
+        let job = service.store().find_job(&id).unwrap();
+        let now = Local::now().timestamp_millis();
+
+        assert_eq!(job.missed_runs(now), 6);
+        assert_eq!(job.missed_runs(now - 6 * HOUR_MS), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_skip_drops_missed_runs() {
+        let (_dir, mut service, id) = service_after_gap(CatchUp::Skip).await;
+
+        assert_eq!(service.reconcile().await, 1);
+        assert!(service.get_due_jobs().is_empty());
+        let job = service.store().find_job(&id).unwrap();
+        assert!(job.state.next_run_at_ms.unwrap() > Local::now().timestamp_millis());
+        assert_eq!(job.state.pending_runs, 0);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_run_once_fires_a_single_time() {
+        let (_dir, mut service, id) = service_after_gap(CatchUp::RunOnce).await;
+
+        assert_eq!(service.reconcile().await, 1);
+        assert_eq!(service.get_due_jobs().len(), 1);
+
+        service.update_after_run(&id, "success", None).await;
+        assert!(service.get_due_jobs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_run_all_fires_every_missed_run() {
+        let (_dir, mut service, id) = service_after_gap(CatchUp::RunAll).await;
+
+        assert_eq!(service.reconcile().await, 1);
+        assert_eq!(service.store().find_job(&id).unwrap().state.pending_runs, 5);
+
+        let mut runs = 0;
+        while !service.get_due_jobs().is_empty() {
+            service.update_after_run(&id, "success", None).await;
+            runs += 1;
+            assert!(runs <= 6, "catch-up never ended");
+        }
+        assert_eq!(runs, 6);
+        let job = service.store().find_job(&id).unwrap();
+        assert!(job.state.next_run_at_ms.unwrap() > Local::now().timestamp_millis());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_ignores_jobs_not_yet_due() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut service = CronService::new(temp_dir.path().join("cron.json"));
+        service
+            .add_job(Job::recurring("later", HOUR_MS, Payload::new("msg")))
+            .await;
+
+        assert_eq!(service.reconcile().await, 0);
+    }
+
+    /// Run `update_after_run` and return the next run relative to the call
+    async fn next_run_after(service: &mut CronService, id: &str, status: &str) -> (i64, i64) {
+        let before = Local::now().timestamp_millis();
+        service.update_after_run(id, status, None).await;
+        let after = Local::now().timestamp_millis();
+        let next = service
+            .store()
+            .find_job(id)
+            .unwrap()
+            .state
+            .next_run_at_ms
+            .unwrap();
+        (next - after, next - before)
+    }
+
+    #[tokio::test]
+    async fn test_update_after_run_retries_failed_job() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut service = CronService::new(temp_dir.path().join("cron.json"));
+        let job = Job::recurring("flaky", 3_600_000, Payload::new("msg")).with_retry_policy(
+            RetryPolicy {
+                max_retries: 3,
+                backoff_ms: 30_000,
+            },
+        );
+        let id = job.id.clone();
+        service.add_job(job).await;
+
+        // Two failures retry after the backoff, not the hourly period
+        for attempt in 1..=2 {
+            let (min, max) = next_run_after(&mut service, &id, "failed").await;
+            assert!(min <= 30_000 && max >= 30_000, "{}..{}", min, max);
+            assert_eq!(
+                service.store().find_job(&id).unwrap().state.retry_attempts,
+                attempt
+            );
+        }
+
+        // Success resets the counter and returns to the schedule
+        let (min, _) = next_run_after(&mut service, &id, "success").await;
+        assert!(min > 3_000_000);
+        assert_eq!(
+            service.store().find_job(&id).unwrap().state.retry_attempts,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_after_run_retries_timed_out_job() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut service = CronService::new(temp_dir.path().join("cron.json"));
+        let job =
+            Job::recurring("slow", 3_600_000, Payload::new("msg")).with_retry_policy(RetryPolicy {
+                max_retries: 2,
+                backoff_ms: 20_000,
+            });
+        let id = job.id.clone();
+        service.add_job(job).await;
+
+        let (min, max) = next_run_after(&mut service, &id, "timeout").await;
+        assert!(min <= 20_000 && max >= 20_000, "{}..{}", min, max);
+        let state = &service.store().find_job(&id).unwrap().state;
+        assert_eq!(state.retry_attempts, 1);
+        assert_eq!(state.failure_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_after_run_exhausted_retries_fall_back_to_schedule() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut service = CronService::new(temp_dir.path().join("cron.json"));
+        let job = Job::recurring("broken", 3_600_000, Payload::new("msg")).with_retry_policy(
+            RetryPolicy {
+                max_retries: 1,
+                backoff_ms: 10_000,
+            },
+        );
+        let id = job.id.clone();
+        service.add_job(job).await;
+
+        let (_, max) = next_run_after(&mut service, &id, "failed").await;
+        assert!(max <= 10_000 + 5);
+        let (min, _) = next_run_after(&mut service, &id, "failed").await;
+        assert!(min > 3_000_000);
+        assert_eq!(
+            service.store().find_job(&id).unwrap().state.retry_attempts,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_after_run_without_policy_does_not_retry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut service = CronService::new(temp_dir.path().join("cron.json"));
+        let job = Job::recurring("plain", 3_600_000, Payload::new("msg"));
+        let id = job.id.clone();
+        service.add_job(job).await;
+
+        let (min, _) = next_run_after(&mut service, &id, "failed").await;
+        assert!(min > 3_000_000);
+    }
+
     #[tokio::test]
     async fn test_cron_service_update_after_run_one_shot_keep() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1206,6 +2681,40 @@ mod tests {
         let job = &service.store().jobs[0];
         assert!(!job.enabled);
         assert!(job.state.next_run_at_ms.is_none());
+        assert_eq!(job.pause_reason(), Some(PauseReason::Completed));
+        assert_eq!(job.status(), "completed");
+        assert!(service.get_due_jobs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_job_reports_paused() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+
+        let mut service = CronService::new(&store_path);
+        let mut job = Job::recurring("paused", 1000, Payload::new("msg"));
+        let id = job.id.clone();
+        job.state.next_run_at_ms = Some(0);
+        service.store_mut().jobs.push(job);
+        assert_eq!(service.get_due_jobs().len(), 1);
+
+        let job = service.enable_job(&id, false).await.unwrap();
+        assert_eq!(job.status(), "paused");
+        assert!(service.get_due_jobs().is_empty());
+
+        let job = service.enable_job(&id, true).await.unwrap();
+        assert_eq!(job.status(), "enabled");
+        assert_eq!(job.pause_reason(), None);
+    }
+
+    #[test]
+    fn test_pause_reason_inferred_for_legacy_jobs() {
+        let mut one_shot = Job::one_shot("done", 0, Payload::new("msg"), false);
+        one_shot.enabled = false;
+        assert_eq!(one_shot.status(), "paused");
+
+        one_shot.state.last_run_at_ms = Some(1);
+        assert_eq!(one_shot.status(), "completed");
     }
 
     #[tokio::test]
@@ -1250,6 +2759,44 @@ mod tests {
         assert_eq!(job.state.last_error, Some("error message".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_cron_service_update_after_run_tallies() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+
+        let mut service = CronService::new(&store_path);
+        let job = Job::new(
+            "test",
+            Schedule::Every { every_ms: 5000 },
+            Payload::new("msg"),
+        );
+        let id = job.id.clone();
+        service.add_job(job).await;
+
+        service.update_after_run(&id, "success", None).await;
+        service
+            .update_after_run(&id, "failed", Some("node down"))
+            .await;
+        service.update_after_run(&id, "success", None).await;
+
+        let mut reloaded = CronService::new(&store_path);
+        reloaded.load().await.unwrap();
+        let state = &reloaded.store().find_job(&id).unwrap().state;
+        assert_eq!(state.run_count, 3);
+        assert_eq!(state.success_count, 2);
+        assert_eq!(state.failure_count, 1);
+    }
+
+    #[test]
+    fn test_job_state_without_tallies_loads() {
+        let state: JobState = serde_json::from_str(r#"{"next_run_at_ms": 1000}"#).unwrap();
+        assert_eq!(state.run_count, 0);
+        assert_eq!(state.success_count, 0);
+        assert_eq!(state.failure_count, 0);
+        // Zero tallies stay out of the store
+        assert!(!serde_json::to_string(&state).unwrap().contains("run_count"));
+    }
+
     #[tokio::test]
     async fn test_cron_service_update_after_run_nonexistent() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1,8 +1,9 @@
 //! Heartbeat service for periodic agent wake-up
 
 use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
 use tokio::time::{interval, Duration};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 const DEFAULT_INTERVAL_S: u64 = 30 * 60; // 30 minutes
 const HEARTBEAT_PROMPT: &str = "Read HEARTBEAT.md in your workspace (if it exists).
@@ -16,6 +17,7 @@ pub struct HeartbeatService {
     workspace: PathBuf,
     interval_s: u64,
     enabled: bool,
+    action_log: Option<PathBuf>,
 }
 
 impl HeartbeatService {
@@ -25,9 +27,16 @@ impl HeartbeatService {
             workspace: workspace.as_ref().to_path_buf(),
             interval_s: interval_s.unwrap_or(DEFAULT_INTERVAL_S),
             enabled,
+            action_log: None,
         }
     }
 
+    /// Append every non-OK heartbeat response to `path` for auditing
+    pub fn with_action_log(mut self, path: impl Into<PathBuf>) -> Self {
+        self.action_log = Some(path.into());
+        self
+    }
+
     /// Check if HEARTBEAT.md has actionable content
     async fn has_actionable_content(&self) -> bool {
         let path = self.workspace.join("HEARTBEAT.md");
@@ -68,19 +77,61 @@ impl HeartbeatService {
 
         loop {
             interval.tick().await;
+            self.beat(&mut on_heartbeat).await;
+        }
+    }
 
-            if self.has_actionable_content().await {
-                info!("Heartbeat: checking for tasks...");
-                let response = on_heartbeat(HEARTBEAT_PROMPT.to_string()).await;
-
-                if response.to_uppercase().contains(HEARTBEAT_OK_TOKEN) {
-                    debug!("Heartbeat: OK (no action needed)");
-                } else {
-                    info!("Heartbeat: completed task");
-                }
-            } else {
-                debug!("Heartbeat: no tasks (HEARTBEAT.md empty)");
-            }
+    /// Run a single heartbeat, returning the response when one was requested
+    pub async fn beat<F, Fut>(&self, on_heartbeat: &mut F) -> Option<String>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: std::future::Future<Output = String>,
+    {
+        if !self.has_actionable_content().await {
+            debug!("Heartbeat: no tasks (HEARTBEAT.md empty)");
+            return None;
         }
+
+        info!("Heartbeat: checking for tasks...");
+        let response = on_heartbeat(HEARTBEAT_PROMPT.to_string()).await;
+
+        if response.to_uppercase().contains(HEARTBEAT_OK_TOKEN) {
+            debug!("Heartbeat: OK (no action needed)");
+        } else {
+            info!("Heartbeat: completed task");
+            self.record_action(&response).await;
+        }
+        Some(response)
+    }
+
+    /// Append a timestamped response to the action log, if configured
+    async fn record_action(&self, response: &str) {
+        let Some(path) = &self.action_log else {
+            return;
+        };
+        let entry = format!(
+            "## {}\n\n{}\n\n",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            response.trim()
+        );
+        if let Err(e) = append(path, &entry).await {
+            warn!(
+                "Heartbeat: failed to write action log {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+async fn append(path: &Path, entry: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
     }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(entry.as_bytes()).await
 }
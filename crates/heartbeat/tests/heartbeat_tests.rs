@@ -836,3 +836,75 @@ Run the above code.
     // Cleanup
     fs::remove_dir_all(&temp_dir).await.ok();
 }
+
+// ============================================================================
+// Action Log Tests
+// ============================================================================
+
+async fn workspace_with_task(name: &str) -> std::path::PathBuf {
+    let temp_dir = std::env::temp_dir().join(name);
+    fs::remove_dir_all(&temp_dir).await.ok();
+    fs::create_dir_all(&temp_dir).await.unwrap();
+    fs::write(temp_dir.join("HEARTBEAT.md"), "Send the daily report\n")
+        .await
+        .unwrap();
+    temp_dir
+}
+
+#[tokio::test]
+async fn test_ok_response_writes_no_action_log() {
+    let temp_dir = workspace_with_task("opensam_test_action_log_ok").await;
+    let log = temp_dir.join("actions.md");
+    let service = HeartbeatService::new(&temp_dir, None, true).with_action_log(&log);
+
+    let response = service
+        .beat(&mut |_prompt| async { "HEARTBEAT_OK".to_string() })
+        .await;
+
+    assert_eq!(response.as_deref(), Some("HEARTBEAT_OK"));
+    assert!(!log.exists());
+
+    fs::remove_dir_all(&temp_dir).await.ok();
+}
+
+#[tokio::test]
+async fn test_action_response_is_appended_with_timestamp() {
+    let temp_dir = workspace_with_task("opensam_test_action_log_append").await;
+    let log = temp_dir.join("logs").join("actions.md");
+    let service = HeartbeatService::new(&temp_dir, None, true).with_action_log(&log);
+
+    let before = chrono::Utc::now();
+    service
+        .beat(&mut |_prompt| async { "Sent the daily report".to_string() })
+        .await;
+    service
+        .beat(&mut |_prompt| async { "Archived old notes".to_string() })
+        .await;
+
+    let content = fs::read_to_string(&log).await.unwrap();
+    let entries: Vec<&str> = content.split("## ").filter(|e| !e.is_empty()).collect();
+    assert_eq!(entries.len(), 2);
+    assert!(entries[0].contains("Sent the daily report"));
+    assert!(entries[1].contains("Archived old notes"));
+
+    let stamp = entries[0].lines().next().unwrap();
+    let stamp = chrono::DateTime::parse_from_rfc3339(stamp).unwrap();
+    assert!(stamp.timestamp() >= before.timestamp());
+
+    fs::remove_dir_all(&temp_dir).await.ok();
+}
+
+#[tokio::test]
+async fn test_no_tasks_skips_callback() {
+    let temp_dir = std::env::temp_dir().join("opensam_test_action_log_idle");
+    fs::create_dir_all(&temp_dir).await.unwrap();
+    fs::remove_file(temp_dir.join("HEARTBEAT.md")).await.ok();
+    let service = HeartbeatService::new(&temp_dir, None, true);
+
+    let response = service
+        .beat(&mut |_prompt| async { panic!("callback should not run") })
+        .await;
+
+    assert_eq!(response, None);
+    fs::remove_dir_all(&temp_dir).await.ok();
+}
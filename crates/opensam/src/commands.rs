@@ -1,19 +1,23 @@
 //! OpenSAM command implementations
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use serde::Deserialize;
 use std::io::Write;
-use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
-use opensam_agent::AgentLoop;
-use opensam_bus::{InboundMessage, MessageBus, OutboundDispatcher};
-use opensam_channels::{Channel, TelegramChannel};
-use opensam_config::{self, Config, ProviderConfig, TelegramConfig};
-use opensam_cron::{CronService, Job, Payload, Schedule};
-use opensam_provider::openrouter::OpenRouterProvider;
+use opensam_agent::{AgentLoop, TurnResult, TurnScheduler, UsageTracker};
+use opensam_bus::{
+    BusMetrics, GatewayStats, GatewayStatsSnapshot, InboundMessage, InboundPolicy, InboundQueue,
+    MessageBus, MetricsSnapshot, OutboundDispatcher, OutboundMessage, DEFAULT_ATTACHMENT_PROMPT,
+};
+use opensam_channels::{send_test_message, Channel, TelegramChannel, Throttle};
+use opensam_config::{
+    self, Config, InboundPolicyConfig, OperativeDefaultsBuilder, ProviderConfig, TelegramConfig,
+};
+use opensam_cron::{CronExecutor, CronService, Job, Payload, Schedule};
+use opensam_provider::{OpenRouterProvider, Provider};
 
 /// Get path to cron job store
 fn cron_store_path() -> std::path::PathBuf {
@@ -22,10 +26,15 @@ fn cron_store_path() -> std::path::PathBuf {
         .join("cron.json")
 }
 
+/// Cron service over the job store, with the configured backup rotation
+async fn cron_service() -> Result<CronService> {
+    let config = Config::load_effective().await?;
+    Ok(CronService::new(cron_store_path()).with_backups(config.deploy.cron_backups))
+}
+
 /// List scheduled jobs
 pub async fn schedule_list_command(all: bool) -> Result<()> {
-    let store_path = cron_store_path();
-    let mut service = CronService::new(&store_path);
+    let mut service = cron_service().await?;
     service.load().await?;
 
     let jobs = service.list_jobs(all);
@@ -35,18 +44,19 @@ pub async fn schedule_list_command(all: bool) -> Result<()> {
     } else {
         println!("Scheduled jobs:");
         for job in jobs {
-            let status = if job.enabled { "enabled" } else { "disabled" };
             println!(
                 "  {} - {} ({}, {})",
                 job.id,
                 job.name,
-                status,
-                match &job.schedule {
-                    Schedule::Every { every_ms } => format!("every {}s", every_ms / 1000),
-                    Schedule::Cron { expr } => format!("cron: {}", expr),
-                    Schedule::At { at_ms } => format!("at: {}", at_ms),
-                }
+                job.status(),
+                job.schedule.describe()
             );
+            if all {
+                println!(
+                    "      runs: {} ({} ok, {} failed)",
+                    job.state.run_count, job.state.success_count, job.state.failure_count
+                );
+            }
         }
     }
 
@@ -59,11 +69,26 @@ pub async fn schedule_add_command(
     message: String,
     every: Option<u64>,
     cron: Option<String>,
+    timeout_ms: Option<i64>,
 ) -> Result<()> {
-    let store_path = cron_store_path();
-    let mut service = CronService::new(&store_path);
+    let mut service = cron_service().await?;
     service.load().await?;
 
+    let schedule = schedule_from_args(every, cron)?;
+
+    let payload = Payload::new(message);
+    let mut job = Job::new(name, schedule, payload);
+    job.timeout_ms = timeout_ms;
+
+    service.add_job(job).await;
+    service.save().await?;
+
+    println!("✓ Job added");
+    Ok(())
+}
+
+/// Schedule from `--every` seconds or a `--cron` expression, validated
+fn schedule_from_args(every: Option<u64>, cron: Option<String>) -> Result<Schedule> {
     let schedule = if let Some(seconds) = every {
         Schedule::Every {
             every_ms: (seconds * 1000) as i64,
@@ -73,21 +98,34 @@ pub async fn schedule_add_command(
     } else {
         anyhow::bail!("Either --every or --cron must be specified");
     };
+    if let Err(e) = schedule.validate() {
+        println!("✗ Invalid schedule: {}", e);
+        anyhow::bail!("invalid schedule: {}", e);
+    }
+    Ok(schedule)
+}
 
-    let payload = Payload::new(message);
-    let job = Job::new(name, schedule, payload);
+/// Change the schedule of an existing job
+pub async fn schedule_update_command(
+    id: String,
+    every: Option<u64>,
+    cron: Option<String>,
+) -> Result<()> {
+    let schedule = schedule_from_args(every, cron)?;
+    let mut service = cron_service().await?;
+    service.load().await?;
 
-    service.add_job(job).await;
-    service.save().await?;
+    match service.update_schedule(&id, schedule).await {
+        Some(job) => println!("✓ Job {} now runs {}", job.id, job.schedule.describe()),
+        None => println!("✗ Job {} not found", id),
+    }
 
-    println!("✓ Job added");
     Ok(())
 }
 
 /// Remove a scheduled job
 pub async fn schedule_remove_command(id: String) -> Result<()> {
-    let store_path = cron_store_path();
-    let mut service = CronService::new(&store_path);
+    let mut service = cron_service().await?;
     service.load().await?;
 
     if service.remove_job(&id).await {
@@ -99,9 +137,117 @@ pub async fn schedule_remove_command(id: String) -> Result<()> {
     Ok(())
 }
 
+/// Enable or pause a scheduled job
+pub async fn schedule_enable_command(id: String, enabled: bool) -> Result<()> {
+    let mut service = cron_service().await?;
+    service.load().await?;
+
+    match service.enable_job(&id, enabled).await {
+        Some(job) => println!("✓ Job {} {}", job.id, job.status()),
+        None => println!("✗ Job {} not found", id),
+    }
+
+    Ok(())
+}
+
+/// Replace the job store with one of its rotated backups
+pub async fn schedule_restore_command(n: usize) -> Result<()> {
+    // The active store may be the thing being recovered from, so don't load it
+    let mut service = cron_service().await?;
+    service.restore_backup(n).await?;
+    println!(
+        "◆ Restored job store from backup {} ({} jobs)",
+        n,
+        service.store().len()
+    );
+    Ok(())
+}
+
+/// Show full details of a scheduled job
+pub async fn schedule_show_command(id: String) -> Result<()> {
+    let mut service = cron_service().await?;
+    service.load().await?;
+
+    let Some(job) = service.store().find_job(&id) else {
+        println!("✗ Job {} not found", id);
+        return Ok(());
+    };
+
+    println!("◆ Job {}", job.id);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Name: {}", job.name);
+    println!("Schedule: {}", job.schedule.describe());
+    println!("Enabled: {}", if job.enabled { "yes" } else { "no" });
+    println!("Status: {}", job.status());
+    println!("Next run: {}", format_job_time(job.state.next_run_at_ms));
+    println!("Last run: {}", format_job_time(job.state.last_run_at_ms));
+    println!(
+        "Last status: {}",
+        job.state.last_status.as_deref().unwrap_or("-")
+    );
+    println!(
+        "Last error: {}",
+        job.state.last_error.as_deref().unwrap_or("-")
+    );
+    if let Some(timeout_ms) = job.timeout_ms {
+        println!("Timeout: {}ms", timeout_ms);
+    }
+    if job.delete_after_run {
+        println!("Delete after run: yes");
+    }
+    println!("Payload:");
+    println!("  Message: {}", job.payload.message);
+    println!(
+        "  Deliver: {}",
+        if job.payload.deliver { "yes" } else { "no" }
+    );
+    println!(
+        "  Channel: {}",
+        job.payload.channel.as_deref().unwrap_or("-")
+    );
+    println!("  To: {}", job.payload.to.as_deref().unwrap_or("-"));
+    println!("Created: {}", format_job_time(Some(job.created_at_ms)));
+    println!("Updated: {}", format_job_time(Some(job.updated_at_ms)));
+
+    Ok(())
+}
+
+/// Print the next `count` run times of a job
+pub async fn schedule_preview_command(id: String, count: usize) -> Result<()> {
+    let mut service = cron_service().await?;
+    service.load().await?;
+
+    let Some(job) = service.store().find_job(&id) else {
+        println!("✗ Job {} not found", id);
+        return Ok(());
+    };
+
+    let runs = job.next_runs(count);
+    println!("◆ Next runs of {} ({})", job.name, job.schedule.describe());
+    if runs.is_empty() {
+        println!("  none");
+    }
+    for run in runs {
+        println!("  {}", format_job_time(Some(run)));
+    }
+
+    Ok(())
+}
+
+/// Format a job timestamp (ms since epoch) in local time
+fn format_job_time(ms: Option<i64>) -> String {
+    ms.and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| "never".to_string())
+}
+
 /// Show frequency/channel status
 pub async fn freq_status_command() -> Result<()> {
-    let config = Config::load().await?;
+    let config = Config::load_effective().await?;
 
     println!("◆ Channel Status");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -125,9 +271,93 @@ pub async fn freq_status_command() -> Result<()> {
     };
     println!("  Allowed users: {}", allowed);
 
+    // Counters are persisted by a running or previously run gateway
+    let metrics = MetricsSnapshot::load(opensam_config::paths::metrics_path()).unwrap_or_default();
+    let dropped = metrics
+        .inbound_dropped_unauthorized
+        .get("telegram")
+        .copied()
+        .unwrap_or(0);
+    println!("  Dropped (unauthorized): {}", dropped);
+    let traffic = metrics
+        .gateway
+        .channels
+        .get("telegram")
+        .copied()
+        .unwrap_or_default();
+    println!(
+        "  Messages: {} in / {} out",
+        traffic.inbound, traffic.outbound
+    );
+
     Ok(())
 }
 
+/// List every configured channel
+pub async fn freq_list_command() -> Result<()> {
+    let config = Config::load_effective().await?;
+
+    println!("◆ Channels");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    for channel in config.frequency.summaries() {
+        let allowed = if channel.allowed == 0 {
+            "any sender".to_string()
+        } else {
+            format!("{} allowed", channel.allowed)
+        };
+        println!(
+            "  {} - {} ({})",
+            channel.name,
+            if channel.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            allowed
+        );
+    }
+
+    Ok(())
+}
+
+/// Send a test message through a configured channel
+pub async fn freq_test_command(channel: String, to: String, message: String) -> Result<()> {
+    let config = Config::load_effective().await?;
+
+    let sender: Box<dyn Channel> = match channel.as_str() {
+        "telegram" => {
+            let tg = &config.frequency.telegram;
+            if tg.token.is_empty() {
+                anyhow::bail!("Telegram token is not configured");
+            }
+            let tg_config = opensam_channels::telegram::TelegramConfig {
+                enabled: tg.enabled,
+                token: tg.token.clone(),
+                allow_from: tg.allow_from.clone(),
+            };
+            let (bus, _in_rx, _out_rx) = MessageBus::channels();
+            Box::new(
+                TelegramChannel::new(tg_config, bus)
+                    .with_markdown_depth(tg.markdown_depth)
+                    .with_unwrap_markdown_fences(tg.unwrap_markdown_fences),
+            )
+        }
+        other => anyhow::bail!("Unknown channel: {}", other),
+    };
+
+    println!("◆ Sending test message via {} to {}", channel, to);
+    match send_test_message(sender.as_ref(), &to, &message).await {
+        Ok(_) => {
+            println!("[OK] Message delivered");
+            Ok(())
+        }
+        Err(e) => {
+            println!("[FAILED] {}", e);
+            Err(anyhow::anyhow!("Test message failed: {}", e))
+        }
+    }
+}
+
 /// Read line from stdin
 fn read_line() -> String {
     let mut input = String::new();
@@ -344,24 +574,28 @@ pub async fn setup_command() -> Result<()> {
     print!("Creating config... ");
     std::io::stdout().flush()?;
 
-    // Load existing config or create new one
+    // Load existing config or create new one; secret references are kept
+    // unresolved so other providers' `env:`/`file:` keys survive the save
     let config_path = opensam_config::config_path();
-    let mut config = if config_path.exists() {
-        Config::load().await.unwrap_or_default()
-    } else {
-        Config::default()
-    };
+    let mut config = Config::load_unresolved().await?;
 
     // Update configuration
     config.providers.openrouter = ProviderConfig {
         api_key,
         api_base: Some("https://openrouter.ai/api/v1".to_string()),
+        extra_headers: config.providers.openrouter.extra_headers.clone(),
+        embedding_model: config.providers.openrouter.embedding_model.clone(),
+        request_id_header: config.providers.openrouter.request_id_header.clone(),
+        ..Default::default()
     };
-    config.operative.defaults.model = model_id;
+    config.operative.defaults = OperativeDefaultsBuilder::from(config.operative.defaults)
+        .model(model_id)
+        .build()?;
     config.frequency.telegram = TelegramConfig {
         enabled: enable_telegram,
         token: tg_token,
         allow_from: tg_allow_from,
+        ..config.frequency.telegram
     };
 
     // Ensure config directory exists
@@ -460,35 +694,34 @@ async fn create_template(dir: &std::path::Path, filename: &str, content: &str) -
 }
 
 /// Chat with the agent
-pub async fn engage_command(message: Option<String>, _session: String) -> Result<()> {
-    let config = Config::load().await?;
-
-    let api_key = config
-        .api_key()
-        .context("No API key configured. Set one in ~/.opensam/config.json")?;
-    let api_base = config.api_base();
-    let model = config.default_model();
-
-    let provider = OpenRouterProvider::new(api_key, api_base, Some(model));
+pub async fn engage_command(message: Option<String>, session: String) -> Result<()> {
+    let config = Config::load_effective().await?;
+    if !ensure_ready(&config) {
+        return Ok(());
+    }
+    let provider = config.build_provider()?;
     let (bus, _in_rx, _out_rx) = MessageBus::channels();
 
-    let agent = AgentLoop::with_config(
+    let workspace = config.ensure_workspace().await?;
+    let mut agent = AgentLoop::with_config(
         bus.clone(),
         provider,
-        config.workspace_path(),
+        workspace,
         config.default_model(),
         20,
         config.brave_api_key(),
         &config,
     );
+    agent.enable_memory_search(&config);
 
     if let Some(msg) = message {
-        let inbound = InboundMessage::new("field", "user", "direct", msg);
+        let inbound = InboundMessage::new("field", "user", session.as_str(), msg);
         if let Some(response) = agent.process_message(inbound).await {
             println!("\n◆ {}", response.content);
         }
     } else {
         println!("◆ Interactive mode (type 'exit' to quit)");
+        println!("Session: {}", session);
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
         loop {
@@ -506,7 +739,7 @@ pub async fn engage_command(message: Option<String>, _session: String) -> Result
                 break;
             }
 
-            let inbound = InboundMessage::new("field", "user", "direct", input.to_string());
+            let inbound = InboundMessage::new("field", "user", session.as_str(), input.to_string());
             if let Some(response) = agent.process_message(inbound).await {
                 println!("\n◆ {}\n", response.content);
             }
@@ -516,17 +749,194 @@ pub async fn engage_command(message: Option<String>, _session: String) -> Result
     Ok(())
 }
 
+/// Print the request `engage` would send for `session`, without calling the model
+pub async fn dump_context_command(message: Option<String>, session: String) -> Result<()> {
+    let config = Config::load_effective().await?;
+    if !ensure_ready(&config) {
+        return Ok(());
+    }
+    let provider = config.build_provider()?;
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+
+    let workspace = config.ensure_workspace().await?;
+    let mut agent = AgentLoop::with_config(
+        bus.clone(),
+        provider,
+        workspace,
+        config.default_model(),
+        20,
+        config.brave_api_key(),
+        &config,
+    );
+    agent.enable_memory_search(&config);
+
+    let inbound = InboundMessage::new("field", "user", session.as_str(), "");
+    let session_key = inbound.session_key();
+    let params = agent.preview_params(&session_key, message.as_deref()).await;
+
+    println!("◆ Context for {}", session_key);
+    println!("Model: {}", params.model);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    for (i, msg) in params.messages.iter().enumerate() {
+        println!("[{}] {}", i, msg.role);
+        if let Some(content) = &msg.content {
+            println!("{}", content);
+        }
+        if let Some(calls) = &msg.tool_calls {
+            for call in calls {
+                println!("→ {}({})", call.function.name, call.function.arguments);
+            }
+        }
+        println!();
+    }
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    let tools: Vec<&str> = params
+        .tools
+        .iter()
+        .map(|t| t.function.name.as_str())
+        .collect();
+    println!("Tools ({}): {}", tools.len(), tools.join(", "));
+
+    Ok(())
+}
+
+/// Shared pre-check for commands that need a configured provider
+///
+/// Prints setup guidance and returns false when the agent cannot run.
+fn ensure_ready(config: &Config) -> bool {
+    if config.has_api_key() {
+        return true;
+    }
+    print_setup_guidance();
+    false
+}
+
+/// Explain how to configure a provider when no API key is set
+fn print_setup_guidance() {
+    println!("◆ No API key configured");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    if Config::is_first_run() {
+        println!(
+            "No config found at {}.",
+            opensam_config::config_path().display()
+        );
+        println!("Run `sam init` to create the config and workspace,");
+        println!("then `sam setup` to choose a provider and model.");
+    } else {
+        println!("Run `sam setup` to choose a provider and model,");
+        println!(
+            "or add an API key to {}",
+            opensam_config::config_path().display()
+        );
+    }
+}
+
+/// Reply sent for messages dropped as stale
+const STALE_MESSAGE_REPLY: &str = "This message is stale and was not processed.";
+
+/// Wait up to `timeout` for a task to stop; true when it finished cleanly
+async fn join_within(
+    label: &str,
+    handle: tokio::task::JoinHandle<()>,
+    timeout: std::time::Duration,
+) -> bool {
+    match tokio::time::timeout(timeout, handle).await {
+        Ok(Ok(())) => {
+            info!("◆ {} completed gracefully", label);
+            true
+        }
+        Ok(Err(e)) => {
+            warn!("◆ {} panicked: {}", label, e);
+            false
+        }
+        Err(_) => {
+            warn!("◆ {} shutdown timed out", label);
+            false
+        }
+    }
+}
+
+/// Translate configured inbound rules into the bus policy
+fn inbound_policy(config: &InboundPolicyConfig) -> InboundPolicy {
+    let policy = config
+        .block_senders
+        .iter()
+        .fold(InboundPolicy::new(), |p, s| p.block_sender(s.as_str()));
+    let policy = config
+        .block_channels
+        .iter()
+        .fold(policy, |p, c| p.block_channel(c.as_str()));
+    config
+        .allow_senders
+        .iter()
+        .fold(policy, |p, s| p.allow_sender(s.as_str()))
+}
+
+/// Bus counters merged with gateway traffic, as persisted for `freq status`
+fn telemetry_snapshot(metrics: &BusMetrics, stats: &GatewayStats) -> MetricsSnapshot {
+    MetricsSnapshot {
+        gateway: stats.snapshot(),
+        ..metrics.snapshot()
+    }
+}
+
+/// Print gateway traffic counters
+fn print_gateway_stats(stats: &GatewayStatsSnapshot) {
+    println!(
+        "  Turns: {} (avg {} ms)",
+        stats.turns,
+        stats.average_latency_ms()
+    );
+    println!("  Tool executions: {}", stats.tool_executions);
+    println!("  Provider errors: {}", stats.provider_errors);
+    let mut channels: Vec<_> = stats.channels.iter().collect();
+    channels.sort_by(|a, b| a.0.cmp(b.0));
+    for (channel, traffic) in channels {
+        println!(
+            "  {}: {} in / {} out",
+            channel, traffic.inbound, traffic.outbound
+        );
+    }
+}
+
+/// Run a scheduled job as one agent turn
+///
+/// The reply goes to the job's channel and recipient when it asks for
+/// delivery. A failed turn is returned as an error so the run counts as a
+/// failure and the job's retry policy applies.
+async fn run_cron_job<P: Provider + 'static>(
+    agent: &AgentLoop<P>,
+    bus: &MessageBus,
+    job: &Job,
+) -> std::result::Result<(), String> {
+    let session_key = format!("cron:{}", job.id);
+    let msg = InboundMessage::new("cli", "cron", session_key, &job.payload.message);
+    let reply = match agent.process_turn(msg).await {
+        TurnResult::Reply(reply) => reply,
+        TurnResult::NoReply => return Ok(()),
+        TurnResult::Error(e) => return Err(e.to_string()),
+    };
+    if job.payload.deliver {
+        if let (Some(channel), Some(to)) = (&job.payload.channel, &job.payload.to) {
+            bus.publish_outbound(OutboundMessage::new(channel, to, reply.content))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
 /// Start gateway server
 pub async fn deploy_command() -> Result<()> {
-    // Telemetry: Track start time and message count
+    // Telemetry: Track start time; traffic counters live on the bus
     let start_time = std::time::Instant::now();
-    let message_count = Arc::new(AtomicU64::new(0));
-    let message_count_for_inbound = Arc::clone(&message_count);
 
     println!("◆ Starting OpenSAM gateway");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-    let config = Config::load().await?;
+    let config = Config::load_effective().await?;
+    if !ensure_ready(&config) {
+        anyhow::bail!("no provider configured");
+    }
 
     // Telemetry: Log enabled channels
     info!(
@@ -539,21 +949,48 @@ pub async fn deploy_command() -> Result<()> {
         config.web_search_max_results()
     );
 
-    let api_key = config.api_key().context("No API key configured")?;
-    let api_base = config.api_base();
-
-    let provider = OpenRouterProvider::new(api_key, api_base, Some(config.default_model()));
-    let (bus, mut in_rx, out_rx) = MessageBus::channels();
+    anyhow::ensure!(config.has_api_key(), "No API key configured");
+    let provider = config.build_provider()?;
+    info!("◆ SOLITON NODE: {}", provider.name());
+    let (bus, mut in_rx, out_rx) = match config.deploy.inbound_capacity {
+        Some(capacity) => {
+            let (bus, in_rx, out_rx) = MessageBus::bounded(capacity);
+            info!("◆ Inbound queue bounded at {} messages", capacity);
+            (bus, InboundQueue::from(in_rx), out_rx)
+        }
+        None => {
+            let (bus, in_rx, out_rx) = MessageBus::channels();
+            (bus, InboundQueue::from(in_rx), out_rx)
+        }
+    };
+    let bus = bus.with_inbound_policy(inbound_policy(&config.deploy.inbound));
+    if !bus.inbound_policy().is_open() {
+        info!("◆ INBOUND POLICY ACTIVE");
+    }
 
-    let agent = AgentLoop::with_config(
+    let workspace = config.ensure_workspace().await?;
+    let mut agent = AgentLoop::with_config(
         bus.clone(),
         provider,
-        config.workspace_path(),
+        workspace,
         config.default_model(),
         20,
         config.brave_api_key(),
         &config,
     );
+    agent.enable_memory_search(&config);
+
+    if config.deploy.self_test {
+        let results = agent.tools().self_test();
+        let mut ok = 0;
+        for (name, result) in &results {
+            match result {
+                Ok(()) => ok += 1,
+                Err(e) => warn!("◆ TOOLKIT SELF-TEST FAILED: {}: {}", name, e),
+            }
+        }
+        info!("◆ Toolkit self-test: {}/{} tools ok", ok, results.len());
+    }
 
     // Create channel for coordinating shutdown
     let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
@@ -591,8 +1028,33 @@ pub async fn deploy_command() -> Result<()> {
     // ========================================
     // 2. Inbound processing loop
     // ========================================
-    let agent_for_inbound = agent;
+    let sessions = agent.sessions().clone();
+    let flush_task = config.session_flush_interval().map(|interval| {
+        info!("◆ Flushing sessions every {:?}", interval);
+        sessions.spawn_flush(interval)
+    });
+    let agent_for_inbound = Arc::new(agent);
+    let agent_for_cron = Arc::clone(&agent_for_inbound);
     let bus_for_inbound = bus.clone();
+    let scheduler = TurnScheduler::new(config.deploy.max_concurrent_turns)
+        .with_cancel_superseded(config.deploy.cancel_superseded_turns);
+    info!(
+        "◆ Processing up to {} turns concurrently",
+        scheduler.max_concurrent()
+    );
+    let turns = scheduler.clone();
+
+    let max_message_age = config
+        .deploy
+        .max_message_age_s
+        .map(std::time::Duration::from_secs);
+    let reply_to_stale = config.deploy.reply_to_stale;
+    let notify_errors = config.deploy.notify_errors;
+    let attachment_prompt = config
+        .deploy
+        .attachment_prompt
+        .clone()
+        .unwrap_or_else(|| DEFAULT_ATTACHMENT_PROMPT.to_string());
 
     let inbound_task = tokio::spawn(async move {
         info!("◆ Inbound processing loop started");
@@ -602,23 +1064,65 @@ pub async fn deploy_command() -> Result<()> {
                 msg = in_rx.recv() => {
                     match msg {
                         Some(inbound) => {
-                            // Telemetry: Track message count
-                            message_count_for_inbound.fetch_add(1, Ordering::SeqCst);
+                            if let Some(max_age) = max_message_age {
+                                if inbound.is_stale(max_age, chrono::Local::now()) {
+                                    warn!(
+                                        "◆ Dropping stale message from {} sent at {}",
+                                        inbound.sender_id, inbound.timestamp
+                                    );
+                                    if reply_to_stale {
+                                        let notice = OutboundMessage::new(
+                                            &inbound.channel,
+                                            &inbound.chat_id,
+                                            STALE_MESSAGE_REPLY,
+                                        );
+                                        if let Err(e) = bus_for_inbound.publish_outbound(notice) {
+                                            error!("Failed to publish stale notice: {}", e);
+                                        }
+                                    }
+                                    continue;
+                                }
+                            }
+
+                            let sender_id = inbound.sender_id.clone();
+                            let Some(inbound) = inbound.normalize(&attachment_prompt) else {
+                                debug!("◆ Dropping empty message from {}", sender_id);
+                                continue;
+                            };
 
                             debug!("Processing inbound message from {}", inbound.sender_id);
 
-                            // Process the message through the agent
-                            match agent_for_inbound.process_message(inbound.clone()).await {
-                                Some(response) => {
-                                    // Publish the response to outbound queue
-                                    if let Err(e) = bus_for_inbound.publish_outbound(response) {
-                                        error!("Failed to publish outbound message: {}", e);
+                            // Turns for the same session run in order; others run in parallel
+                            let agent = Arc::clone(&agent_for_inbound);
+                            let bus = bus_for_inbound.clone();
+                            scheduler.spawn(inbound.session_key(), async move {
+                                let started = std::time::Instant::now();
+                                let result = agent.process_turn(inbound.clone()).await;
+                                bus.stats().record_turn(started.elapsed());
+                                let response = match result {
+                                    TurnResult::Reply(response) => response,
+                                    TurnResult::NoReply => {
+                                        debug!("No response from agent for message from {}", inbound.sender_id);
+                                        return;
                                     }
+                                    TurnResult::Error(e) => {
+                                        error!(
+                                            "◆ TURN FAILED FOR {} ({}): {}",
+                                            inbound.session_key(),
+                                            e.kind(),
+                                            e
+                                        );
+                                        if !notify_errors {
+                                            return;
+                                        }
+                                        agent.error_notice(&inbound.channel, &inbound.chat_id, &e)
+                                    }
+                                };
+                                // Publish the response to outbound queue
+                                if let Err(e) = bus.publish_outbound(response) {
+                                    error!("Failed to publish outbound message: {}", e);
                                 }
-                                None => {
-                                    debug!("No response from agent for message from {}", inbound.sender_id);
-                                }
-                            }
+                            });
                         }
                         None => {
                             info!("◆ Inbound channel closed, shutting down processing loop");
@@ -639,7 +1143,10 @@ pub async fn deploy_command() -> Result<()> {
     // ========================================
     // 3. Outbound dispatcher
     // ========================================
-    let mut dispatcher = OutboundDispatcher::new(out_rx);
+    let mut dispatcher = OutboundDispatcher::new(out_rx).with_stats(bus.stats().clone());
+    if let Some(ms) = config.deploy.outbound_batch_ms {
+        dispatcher = dispatcher.with_batching(std::time::Duration::from_millis(ms));
+    }
 
     // Register Telegram handler if enabled
     if config.frequency.telegram.enabled && !config.frequency.telegram.token.is_empty() {
@@ -648,34 +1155,115 @@ pub async fn deploy_command() -> Result<()> {
             token: config.frequency.telegram.token.clone(),
             allow_from: config.frequency.telegram.allow_from.clone(),
         };
-
-        dispatcher.on_channel("telegram", move |msg| {
+        // One throttle for every send, so bursts are spaced across handler calls
+        let throttle = Arc::new(std::sync::Mutex::new(Throttle::new(
+            config.frequency.telegram.global_per_sec,
+            config.frequency.telegram.per_chat_per_sec,
+        )));
+        let markdown_depth = config.frequency.telegram.markdown_depth;
+        let unwrap_markdown_fences = config.frequency.telegram.unwrap_markdown_fences;
+
+        dispatcher.try_on_channel("telegram", move |msg| {
             let tg_config = tg_config.clone();
+            let throttle = Arc::clone(&throttle);
             tokio::spawn(async move {
                 let bus = MessageBus::new(
                     tokio::sync::mpsc::unbounded_channel().0,
                     tokio::sync::mpsc::unbounded_channel().0,
                 );
-                let channel = TelegramChannel::new(tg_config, bus);
+                let channel = TelegramChannel::new(tg_config, bus)
+                    .with_throttle(throttle)
+                    .with_markdown_depth(markdown_depth)
+                    .with_unwrap_markdown_fences(unwrap_markdown_fences);
                 if let Err(e) = channel.send(&msg).await {
                     error!("Failed to send message via Telegram: {}", e);
                 }
             });
-        });
+        })?;
     }
 
+    let routes = dispatcher.registered_channels();
+    let dispatcher_stop = dispatcher.stop_handle();
+    info!("◆ Outbound routes: {:?}", routes);
+
     let dispatcher_task = tokio::spawn(async move {
         info!("◆ Outbound dispatcher started");
         dispatcher.run().await;
         info!("◆ Outbound dispatcher stopped");
     });
 
+    // ========================================
+    // Scheduled jobs
+    // ========================================
+    let executor = {
+        let agent = Arc::clone(&agent_for_cron);
+        let bus = bus.clone();
+        CronExecutor::new(move |job: Job| {
+            let agent = Arc::clone(&agent);
+            let bus = bus.clone();
+            async move { run_cron_job(&agent, &bus, &job).await }
+        })
+        .with_max_concurrent(config.deploy.max_concurrent_jobs)
+    };
+    let cron_backups = config.deploy.cron_backups;
+    let cron_task = tokio::spawn(async move {
+        let mut service = CronService::new(cron_store_path()).with_backups(cron_backups);
+        match service.load().await {
+            Ok(()) => {
+                let caught_up = service.reconcile().await;
+                if caught_up > 0 {
+                    info!("◆ Reconciled {} job(s) with missed runs", caught_up);
+                }
+            }
+            Err(e) => warn!("◆ Failed to load scheduled jobs: {}", e),
+        }
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            // Pick up edits from `sam schedule` without rereading every tick
+            if let Err(e) = service.reload_if_changed().await {
+                warn!("◆ Failed to load scheduled jobs: {}", e);
+                continue;
+            }
+            executor.run_due(&mut service).await;
+        }
+    });
+
+    // Persist bus counters so `freq status` can report them
+    let metrics = bus.metrics().clone();
+    let stats = bus.stats().clone();
+    let metrics_interval = config.deploy.metrics_interval_s.max(1);
+    let metrics_task = tokio::spawn({
+        let metrics = metrics.clone();
+        let stats = stats.clone();
+        async move {
+            let mut interval =
+                tokio::time::interval(tokio::time::Duration::from_secs(metrics_interval));
+            loop {
+                interval.tick().await;
+                if let Err(e) =
+                    telemetry_snapshot(&metrics, &stats).save(opensam_config::paths::metrics_path())
+                {
+                    warn!("◆ Failed to persist metrics: {}", e);
+                }
+            }
+        }
+    });
+
     // ========================================
     // 4. Main service loop with graceful shutdown
     // ========================================
     info!("◆ Gateway active");
     println!("◆ Gateway active");
     println!("Channels: telegram={}", config.frequency.telegram.enabled);
+    println!(
+        "Routes: {}",
+        if routes.is_empty() {
+            "none".to_string()
+        } else {
+            routes.join(", ")
+        }
+    );
     println!("Waiting for connections...");
     println!("Press Ctrl+C to stop");
 
@@ -700,36 +1288,40 @@ pub async fn deploy_command() -> Result<()> {
     drop(bus);
 
     // Wait for all tasks to complete (with timeout)
-    let shutdown_timeout = tokio::time::Duration::from_secs(5);
+    let shutdown_timeout = config.shutdown_timeout();
 
     info!("◆ Waiting for tasks to complete...");
 
-    // Wait for inbound task
-    match tokio::time::timeout(shutdown_timeout, inbound_task).await {
-        Ok(Ok(())) => info!("◆ Inbound task completed gracefully"),
-        Ok(Err(e)) => warn!("◆ Inbound task panicked: {}", e),
-        Err(_) => warn!("◆ Inbound task shutdown timed out"),
-    }
+    join_within("Inbound task", inbound_task, shutdown_timeout).await;
 
-    // Wait for dispatcher task
-    match tokio::time::timeout(shutdown_timeout, dispatcher_task).await {
-        Ok(Ok(())) => info!("◆ Dispatcher task completed gracefully"),
-        Ok(Err(e)) => warn!("◆ Dispatcher task panicked: {}", e),
-        Err(_) => warn!("◆ Dispatcher task shutdown timed out"),
+    // Let in-flight turns publish their replies before routing stops
+    if turns.drain(shutdown_timeout).await {
+        info!("◆ In-flight turns completed gracefully");
+    } else {
+        warn!("◆ In-flight turns shutdown timed out");
     }
 
-    // Wait for channel tasks
+    // Stop routing replies; the dispatcher releases its handlers on exit
+    dispatcher_stop.stop();
+    join_within("Dispatcher task", dispatcher_task, shutdown_timeout).await;
     for (i, handle) in channel_handles.into_iter().enumerate() {
-        match tokio::time::timeout(shutdown_timeout, handle).await {
-            Ok(Ok(())) => info!("◆ Channel task {} completed gracefully", i),
-            Ok(Err(e)) => warn!("◆ Channel task {} panicked: {}", i, e),
-            Err(_) => warn!("◆ Channel task {} shutdown timed out", i),
-        }
+        join_within(&format!("Channel task {}", i), handle, shutdown_timeout).await;
+    }
+
+    cron_task.abort();
+    metrics_task.abort();
+    if let Some(task) = flush_task {
+        task.abort();
+        sessions.flush().await;
+    }
+    let snapshot = telemetry_snapshot(&metrics, &stats);
+    if let Err(e) = snapshot.save(opensam_config::paths::metrics_path()) {
+        warn!("◆ Failed to persist metrics: {}", e);
     }
 
     // Telemetry: Calculate uptime and log summary
     let elapsed = start_time.elapsed();
-    let processed = message_count.load(Ordering::SeqCst);
+    let processed = snapshot.gateway.inbound_total();
 
     info!(
         "◆ Gateway ran for {:?}, processed {} messages",
@@ -737,11 +1329,143 @@ pub async fn deploy_command() -> Result<()> {
     );
     println!("◆ Gateway ran for {:?}", elapsed);
     println!("◆ Processed {} messages", processed);
+    print_gateway_stats(&snapshot.gateway);
     println!("◆ Gateway shutdown complete");
 
     Ok(())
 }
 
+/// Print the configuration with secrets masked
+///
+/// `effective` layers environment overrides on top of the file.
+pub async fn config_show_command(effective: bool) -> Result<()> {
+    let config = if effective {
+        Config::load_effective().await?
+    } else {
+        Config::load().await?
+    };
+
+    println!("{}", serde_json::to_string_pretty(&config.redacted())?);
+    Ok(())
+}
+
+/// Set or clear the system prompt suffix of a session
+///
+/// Edits the saved session on disk. A running gateway holds its sessions in
+/// memory and overwrites the file on its next save, so stop it first.
+pub async fn session_set_suffix_command(key: String, text: String) -> Result<()> {
+    let config = Config::load_effective().await?;
+    let mut manager =
+        opensam_agent::session_manager(&config, opensam_agent::default_sessions_dir());
+    let session = manager.get_or_create(&key).await;
+    anyhow::ensure!(
+        session.set_system_suffix(&text),
+        "session {} has no room for more metadata",
+        key
+    );
+    let session = session.clone();
+    manager.save(&session).await?;
+
+    match session.system_suffix() {
+        Some(_) => println!("✓ System prompt suffix set for {}", key),
+        None => println!("✓ System prompt suffix cleared for {}", key),
+    }
+    Ok(())
+}
+
+/// Show token usage and estimated cost per session
+pub async fn session_usage_command(key: Option<String>) -> Result<()> {
+    let config = Config::load_effective().await?;
+    let mut manager =
+        opensam_agent::session_manager(&config, opensam_agent::default_sessions_dir());
+    let keys = match key {
+        Some(key) => vec![key],
+        None => manager.list().await,
+    };
+
+    let mut tracker = UsageTracker::from_config(&config);
+    for key in &keys {
+        let session = manager.get_or_create(key).await;
+        tracker.record_session(session);
+    }
+
+    println!("◆ Token Usage");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    let sessions: Vec<String> = tracker.sessions().map(str::to_string).collect();
+    if sessions.is_empty() {
+        println!("No usage recorded");
+        return Ok(());
+    }
+    for key in &sessions {
+        let usage = tracker.session_usage(key);
+        println!(
+            "  {} - {} tokens ({} prompt / {} completion), ~${:.4}",
+            key,
+            usage.total_tokens,
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            tracker.session_cost(key)
+        );
+    }
+    let total = tracker.total_usage();
+    println!(
+        "Total: {} tokens, ~${:.4}",
+        total.total_tokens,
+        tracker.total_cost()
+    );
+    Ok(())
+}
+
+/// Rewrite a flat legacy config file in the nested schema
+pub async fn config_migrate_command() -> Result<()> {
+    let path = opensam_config::config_path();
+    if Config::migrate_file(&path).await? {
+        println!("◆ Config migrated: {}", path.display());
+        println!(
+            "◆ Original kept at: {}",
+            Config::legacy_backup_path(&path).display()
+        );
+    } else {
+        println!("◆ Config already current: {}", path.display());
+    }
+    Ok(())
+}
+
+/// List the tools the configured agent can call, or dump their schema as JSON
+pub async fn tools_command(json: bool) -> Result<()> {
+    let config = Config::load_effective().await?;
+    // Listing never calls the model, so an unconfigured node will do
+    let provider = config
+        .build_provider()
+        .unwrap_or_else(|_| Box::new(OpenRouterProvider::new(String::new(), None, None)));
+    let (bus, _in_rx, _out_rx) = MessageBus::channels();
+    let mut agent = AgentLoop::with_config(
+        bus,
+        provider,
+        config.workspace_path(),
+        config.default_model(),
+        20,
+        config.brave_api_key(),
+        &config,
+    );
+    agent.enable_memory_search(&config);
+    let registry = agent.tools();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&registry.export_schema())?
+        );
+        return Ok(());
+    }
+
+    println!("◆ TOOLKIT");
+    for tool in registry.definitions() {
+        println!("  {} - {}", tool.function.name, tool.function.description);
+    }
+    Ok(())
+}
+
 /// Show status
 pub async fn status_command() -> Result<()> {
     let config_path = opensam_config::config_path();
@@ -750,6 +1474,10 @@ pub async fn status_command() -> Result<()> {
     println!("◆ OpenSAM System Status");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
+    if Config::is_first_run() {
+        println!("First run: run `sam init`, then `sam setup`\n");
+    }
+
     println!(
         "Config:   {} {}",
         config_path.display(),
@@ -770,7 +1498,7 @@ pub async fn status_command() -> Result<()> {
     );
 
     if config_path.exists() {
-        let config = Config::load().await?;
+        let config = Config::load_effective().await?;
         println!("Model:     {}", config.default_model());
         println!(
             "API Key:   {}",
@@ -811,7 +1539,7 @@ You are a helpful AI assistant running in a terminal. Be concise and practical.
 
 const PERSONA_MD: &str = r#"# Persona
 
-Name: OpenSAM
+Name: {{name}}
 Type: Terminal AI Agent
 
 ## Traits
@@ -853,3 +1581,25 @@ Important information that persists across sessions.
 
 (Important things to remember)
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_join_within_finishes_inside_window() {
+        let handle = tokio::spawn(async {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+        });
+        assert!(join_within("stub", handle, Duration::from_secs(5)).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_join_within_times_out_past_window() {
+        let handle = tokio::spawn(async {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        });
+        assert!(!join_within("stub", handle, Duration::from_secs(5)).await);
+    }
+}
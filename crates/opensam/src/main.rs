@@ -6,8 +6,12 @@ use tracing::error;
 mod commands;
 
 use commands::{
-    deploy_command, engage_command, freq_status_command, init_command, schedule_add_command,
-    schedule_list_command, schedule_remove_command, setup_command, status_command,
+    config_migrate_command, config_show_command, deploy_command, dump_context_command,
+    engage_command, freq_list_command, freq_status_command, freq_test_command, init_command,
+    schedule_add_command, schedule_enable_command, schedule_list_command, schedule_preview_command,
+    schedule_remove_command, schedule_restore_command, schedule_show_command,
+    schedule_update_command, session_set_suffix_command, session_usage_command, setup_command,
+    status_command, tools_command,
 };
 
 /// OpenSAM - AI agent for your terminal
@@ -32,6 +36,9 @@ enum Commands {
         /// Session ID
         #[arg(short, long, default_value = "default")]
         session: String,
+        /// Print the prompt and tools the agent would send, then exit
+        #[arg(long)]
+        dump_context: bool,
     },
     /// Start gateway server
     Deploy {
@@ -46,13 +53,41 @@ enum Commands {
         #[command(subcommand)]
         command: ScheduleCommands,
     },
+    /// Manage conversation sessions
+    Session {
+        #[command(subcommand)]
+        command: SessionCommands,
+    },
     /// Manage channels
     Freq {
         #[command(subcommand)]
         command: FreqCommands,
     },
+    /// List the agent's tools
+    Tools {
+        /// Print the full tool schema as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Interactive setup wizard
     Setup,
+    /// Inspect configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the configuration as JSON with secrets masked
+    Show {
+        /// Include environment overrides and defaults
+        #[arg(long)]
+        effective: bool,
+    },
+    /// Rewrite a flat legacy config in the current nested schema
+    Migrate,
 }
 
 #[derive(Subcommand)]
@@ -72,26 +107,94 @@ enum ScheduleCommands {
         every: Option<u64>,
         #[arg(short, long)]
         cron: Option<String>,
+        /// Cancel a run that takes longer than this many milliseconds
+        #[arg(long)]
+        timeout_ms: Option<i64>,
+    },
+    /// Change a job's schedule, keeping its ID and run history
+    Update {
+        id: String,
+        #[arg(short, long)]
+        every: Option<u64>,
+        #[arg(short, long)]
+        cron: Option<String>,
     },
     /// Remove a job
     Remove { id: String },
+    /// Resume a paused job
+    Enable { id: String },
+    /// Pause a job without removing it
+    Disable { id: String },
+    /// Show full details of a job
+    Show { id: String },
+    /// Print the next run times of a job
+    Preview {
+        id: String,
+        /// How many run times to print
+        #[arg(short, long, default_value_t = 5)]
+        count: usize,
+    },
+    /// Replace the job store with backup N (1 is the most recent)
+    Restore { n: usize },
+}
+
+#[derive(Subcommand)]
+enum SessionCommands {
+    /// Append text to a session's system prompt (empty text removes it)
+    ///
+    /// Edits the saved session on disk; stop a running gateway first, or its
+    /// next save overwrites the change.
+    SetSuffix {
+        /// Session key, e.g. telegram:12345 or field:default
+        key: String,
+        /// Text appended after the assembled system prompt
+        text: String,
+    },
+    /// Show token usage and estimated cost per session
+    Usage {
+        /// Session key; every session when omitted
+        key: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
 enum FreqCommands {
     /// Show channel status
     Status,
+    /// List all configured channels
+    List,
+    /// Send a test message through a channel
+    Test {
+        /// Channel to test
+        #[arg(short, long, default_value = "telegram")]
+        channel: String,
+        /// Recipient chat ID
+        #[arg(short, long)]
+        to: String,
+        /// Message text
+        #[arg(short, long, default_value = "◆ OpenSAM frequency test")]
+        message: String,
+    },
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
 
-    // Initialize tracing based on verbose flag in Deploy command
-    if matches!(cli.command, Commands::Deploy { verbose: true, .. }) {
-        tracing_subscriber::fmt().with_env_filter("debug").init();
-    } else {
-        tracing_subscriber::fmt::init();
+    // Initialize tracing from RUST_LOG, the verbose flag and the config's log_filter
+    let verbose = matches!(cli.command, Commands::Deploy { verbose: true, .. });
+    let loaded = opensam_config::Config::load().await;
+    let config_filter = loaded
+        .as_ref()
+        .ok()
+        .and_then(|config| config.log_filter.clone());
+    let rust_log = std::env::var("RUST_LOG").ok();
+    let filter =
+        opensam_config::logging::log_filter(config_filter.as_deref(), rust_log.as_deref(), verbose);
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+    // Reported only now so the error reaches the subscriber
+    if let Err(e) = &loaded {
+        error!("Config load failed, log_filter ignored: {}", e);
     }
 
     match cli.command {
@@ -101,8 +204,17 @@ async fn main() {
                 std::process::exit(1);
             }
         }
-        Commands::Engage { message, session } => {
-            if let Err(e) = engage_command(message, session).await {
+        Commands::Engage {
+            message,
+            session,
+            dump_context,
+        } => {
+            let result = if dump_context {
+                dump_context_command(message, session).await
+            } else {
+                engage_command(message, session).await
+            };
+            if let Err(e) = result {
                 error!("Error: {}", e);
                 std::process::exit(1);
             }
@@ -131,18 +243,55 @@ async fn main() {
                 message,
                 every,
                 cron,
+                timeout_ms,
             } => {
-                if let Err(e) = schedule_add_command(name, message, every, cron).await {
+                if let Err(e) = schedule_add_command(name, message, every, cron, timeout_ms).await {
                     error!("Schedule add failed: {}", e);
                     std::process::exit(1);
                 }
             }
+            ScheduleCommands::Update { id, every, cron } => {
+                if let Err(e) = schedule_update_command(id, every, cron).await {
+                    error!("Schedule update failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            ScheduleCommands::Show { id } => {
+                if let Err(e) = schedule_show_command(id).await {
+                    error!("Schedule show failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            ScheduleCommands::Preview { id, count } => {
+                if let Err(e) = schedule_preview_command(id, count).await {
+                    error!("Schedule preview failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            ScheduleCommands::Restore { n } => {
+                if let Err(e) = schedule_restore_command(n).await {
+                    error!("Schedule restore failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
             ScheduleCommands::Remove { id } => {
                 if let Err(e) = schedule_remove_command(id).await {
                     error!("Schedule remove failed: {}", e);
                     std::process::exit(1);
                 }
             }
+            ScheduleCommands::Enable { id } => {
+                if let Err(e) = schedule_enable_command(id, true).await {
+                    error!("Schedule enable failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            ScheduleCommands::Disable { id } => {
+                if let Err(e) = schedule_enable_command(id, false).await {
+                    error!("Schedule disable failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
         },
         Commands::Freq { command } => match command {
             FreqCommands::Status => {
@@ -151,6 +300,22 @@ async fn main() {
                     std::process::exit(1);
                 }
             }
+            FreqCommands::List => {
+                if let Err(e) = freq_list_command().await {
+                    error!("Freq list failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            FreqCommands::Test {
+                channel,
+                to,
+                message,
+            } => {
+                if let Err(e) = freq_test_command(channel, to, message).await {
+                    error!("Freq test failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
         },
         Commands::Setup => {
             if let Err(e) = setup_command().await {
@@ -158,5 +323,39 @@ async fn main() {
                 std::process::exit(1);
             }
         }
+        Commands::Tools { json } => {
+            if let Err(e) = tools_command(json).await {
+                error!("Tools failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Session { command } => match command {
+            SessionCommands::SetSuffix { key, text } => {
+                if let Err(e) = session_set_suffix_command(key, text).await {
+                    error!("Session set-suffix failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            SessionCommands::Usage { key } => {
+                if let Err(e) = session_usage_command(key).await {
+                    error!("Session usage failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
+        Commands::Config { command } => match command {
+            ConfigCommands::Show { effective } => {
+                if let Err(e) = config_show_command(effective).await {
+                    error!("Config show failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            ConfigCommands::Migrate => {
+                if let Err(e) = config_migrate_command().await {
+                    error!("Config migrate failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
     }
 }
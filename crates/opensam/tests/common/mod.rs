@@ -2,7 +2,10 @@
 #![allow(dead_code)]
 
 use assert_cmd::Command;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use tempfile::{tempdir, TempDir};
 
 /// Path to the opensam binary
@@ -48,6 +51,16 @@ impl TestEnv {
         let mut cmd = Command::new(env!("CARGO_BIN_EXE_opensam"));
         cmd.env("HOME", self.temp_dir.path());
         cmd.env("XDG_CONFIG_HOME", &self.config_dir);
+        // Keep the developer's environment from overriding test configs
+        for var in [
+            "OPENSAM_MODEL",
+            "OPENSAM_WORKSPACE",
+            "OPENROUTER_API_KEY",
+            "OPENSAM_TELEGRAM_TOKEN",
+            "BRAVE_API_KEY",
+        ] {
+            cmd.env_remove(var);
+        }
         cmd
     }
 
@@ -63,8 +76,98 @@ impl TestEnv {
     }
 }
 
+impl TestEnv {
+    /// Create a config pointing the OpenRouter provider at `api_base`
+    pub fn create_config_with_api_base(&self, api_base: &str) -> anyhow::Result<()> {
+        let config = serde_json::json!({
+            "soliton": {
+                "openrouter": {
+                    "api_key": "sk-or-test",
+                    "api_base": api_base
+                }
+            },
+            "operative": {
+                "defaults": {
+                    "workspace": self.workspace_dir.to_string_lossy()
+                }
+            }
+        });
+        std::fs::write(
+            self.config_file("config.json"),
+            serde_json::to_string_pretty(&config)?,
+        )?;
+        Ok(())
+    }
+}
+
 impl Default for TestEnv {
     fn default() -> Self {
         Self::new().expect("Failed to create test environment")
     }
 }
+
+/// Minimal OpenAI-compatible chat server for CLI tests
+///
+/// Answers every request with `reply` and records request bodies.
+pub struct MockLlmServer {
+    pub api_base: String,
+    requests: Arc<Mutex<Vec<serde_json::Value>>>,
+}
+
+impl MockLlmServer {
+    /// Start the server on an ephemeral port
+    pub fn start(reply: &str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind mock server");
+        let api_base = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+        let body = serde_json::json!({
+            "choices": [{
+                "message": {"role": "assistant", "content": reply},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2}
+        })
+        .to_string();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+                    let lower = line.to_ascii_lowercase();
+                    if let Some(value) = lower.strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+
+                let mut request = vec![0; content_length];
+                if reader.read_exact(&mut request).is_ok() {
+                    if let Ok(json) = serde_json::from_slice(&request) {
+                        recorded.lock().unwrap().push(json);
+                    }
+                }
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        Self { api_base, requests }
+    }
+
+    /// Request bodies received so far
+    pub fn requests(&self) -> Vec<serde_json::Value> {
+        self.requests.lock().unwrap().clone()
+    }
+}
@@ -2,7 +2,7 @@
 
 mod common;
 
-use common::TestEnv;
+use common::{MockLlmServer, TestEnv};
 use predicates::prelude::*;
 use std::fs;
 
@@ -121,23 +121,56 @@ fn test_status_shows_missing() {
     );
 }
 
+#[test]
+fn test_first_run_detected_until_init() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+
+    env.command()
+        .arg("status")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("First run"));
+
+    env.command().arg("init").assert().success();
+
+    env.command()
+        .arg("status")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("First run").not());
+}
+
+#[test]
+fn test_first_run_engage_points_to_init() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+
+    env.command()
+        .args(["engage", "-m", "Hello"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("sam init"))
+        .stdout(predicate::str::contains("sam setup"));
+}
+
 // ============================================================================
 // Engage command tests (with mocked/missing provider)
 // ============================================================================
 
 #[test]
-fn test_engage_without_config_fails() {
+fn test_engage_without_config_shows_setup_guidance() {
     let env = TestEnv::new().expect("Failed to create test environment");
 
     let mut cmd = env.command();
     cmd.args(["engage", "-m", "Hello"]);
 
-    // The error may be in stdout or stderr and contains "API key"
-    cmd.assert().failure();
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No API key configured"))
+        .stdout(predicate::str::contains("sam setup"));
 }
 
 #[test]
-fn test_engage_without_api_key_fails() {
+fn test_engage_without_api_key_shows_setup_guidance() {
     let env = TestEnv::new().expect("Failed to create test environment");
 
     // Create config without API key
@@ -150,19 +183,220 @@ fn test_engage_without_api_key_fails() {
     let mut cmd = env.command();
     cmd.args(["engage", "-m", "Hello"]);
 
-    cmd.assert().failure();
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("sam setup"))
+        .stdout(predicate::str::contains("config.json"));
+}
+
+#[test]
+fn test_engage_interactive_without_api_key_does_not_prompt() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+
+    let mut cmd = env.command();
+    cmd.arg("engage");
+    cmd.write_stdin("");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("sam setup"))
+        .stdout(predicate::str::contains("Interactive mode").not());
 }
 
 #[test]
 fn test_engage_with_empty_message() {
     let env = TestEnv::new().expect("Failed to create test environment");
-    env.create_config().expect("Failed to create config");
+    let config = serde_json::json!({"operative": {"defaults": {"model": "test/model"}}});
+    fs::write(env.config_file("config.json"), config.to_string()).unwrap();
 
     let mut cmd = env.command();
     cmd.args(["engage", "-m", ""]);
 
-    // Empty message should still be processed (will fail on provider)
-    cmd.assert().failure();
+    // The config carries no provider key, so engage explains setup
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("sam setup"));
+}
+
+/// User messages sent to the model in a recorded chat request
+fn user_messages(request: &serde_json::Value) -> Vec<String> {
+    request["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|m| m["role"] == "user")
+        .filter_map(|m| m["content"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn test_engage_same_session_shares_history() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let server = MockLlmServer::start("Copy that");
+    env.create_config_with_api_base(&server.api_base)
+        .expect("Failed to create config");
+
+    for message in ["Remember the codeword", "What was the codeword?"] {
+        env.command()
+            .args(["engage", "-s", "mission", "-m", message])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Copy that"));
+    }
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        user_messages(&requests[1]),
+        vec!["Remember the codeword", "What was the codeword?"]
+    );
+}
+
+#[test]
+fn test_engage_dump_context_shows_prompt_and_history() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let server = MockLlmServer::start("Copy that");
+    env.create_config_with_api_base(&server.api_base)
+        .expect("Failed to create config");
+    fs::create_dir_all(env.workspace_file("")).unwrap();
+    fs::write(
+        env.workspace_file("DIRECTIVE.md"),
+        "Infiltrate Shadow Moses quietly.",
+    )
+    .unwrap();
+
+    env.command()
+        .args(["engage", "-s", "mission", "-m", "Remember the codeword"])
+        .assert()
+        .success();
+
+    env.command()
+        .args(["engage", "-s", "mission", "--dump-context", "-m", "Next?"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("◆ Context for field:mission"))
+        .stdout(predicate::str::contains("Infiltrate Shadow Moses quietly."))
+        .stdout(predicate::str::contains("Remember the codeword"))
+        .stdout(predicate::str::contains("Copy that"))
+        .stdout(predicate::str::contains("Next?"))
+        .stdout(predicate::str::contains("read_file"));
+
+    // Dumping never calls the model or records the message
+    assert_eq!(server.requests().len(), 1);
+    env.command()
+        .args(["engage", "-s", "mission", "--dump-context"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Next?").not());
+}
+
+#[test]
+fn test_session_suffix_applies_to_that_session_only() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let server = MockLlmServer::start("Copy that");
+    env.create_config_with_api_base(&server.api_base)
+        .expect("Failed to create config");
+
+    env.command()
+        .args([
+            "session",
+            "set-suffix",
+            "field:alpha",
+            "Sprint goal: ship v2",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("suffix set for field:alpha"));
+
+    env.command()
+        .args(["engage", "-s", "alpha", "--dump-context"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Sprint goal: ship v2"));
+    env.command()
+        .args(["engage", "-s", "bravo", "--dump-context"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Sprint goal").not());
+
+    env.command()
+        .args(["engage", "-s", "alpha", "-m", "Status?"])
+        .assert()
+        .success();
+    let requests = server.requests();
+    let system = requests[0]["messages"][0]["content"].as_str().unwrap();
+    assert!(system.ends_with("Sprint goal: ship v2"));
+
+    env.command()
+        .args(["session", "set-suffix", "field:alpha", ""])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cleared"));
+    env.command()
+        .args(["engage", "-s", "alpha", "--dump-context"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Sprint goal").not());
+}
+
+#[test]
+fn test_session_usage_totals_engaged_turns() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let server = MockLlmServer::start("Copy that");
+    env.create_config_with_api_base(&server.api_base)
+        .expect("Failed to create config");
+
+    env.command()
+        .args(["session", "usage"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No usage recorded"));
+
+    for _ in 0..2 {
+        env.command()
+            .args(["engage", "-s", "alpha", "-m", "Status?"])
+            .assert()
+            .success();
+    }
+    env.command()
+        .args(["engage", "-s", "bravo", "-m", "Status?"])
+        .assert()
+        .success();
+
+    env.command()
+        .args(["session", "usage"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("field:alpha - 4 tokens"))
+        .stdout(predicate::str::contains("field:bravo - 2 tokens"))
+        .stdout(predicate::str::contains("Total: 6 tokens"));
+    env.command()
+        .args(["session", "usage", "field:bravo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("field:alpha").not())
+        .stdout(predicate::str::contains("Total: 2 tokens"));
+}
+
+#[test]
+fn test_engage_different_sessions_are_isolated() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let server = MockLlmServer::start("Copy that");
+    env.create_config_with_api_base(&server.api_base)
+        .expect("Failed to create config");
+
+    env.command()
+        .args(["engage", "-s", "alpha", "-m", "Alpha intel"])
+        .assert()
+        .success();
+    env.command()
+        .args(["engage", "-s", "bravo", "-m", "Bravo intel"])
+        .assert()
+        .success();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(user_messages(&requests[1]), vec!["Bravo intel"]);
 }
 
 // ============================================================================
@@ -239,6 +473,286 @@ fn test_schedule_add_outputs() {
         .stdout(predicate::str::contains("Job added"));
 }
 
+#[test]
+fn test_schedule_add_rejects_invalid_cron() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+
+    env.command()
+        .args([
+            "schedule", "add", "-n", "bad-job", "-m", "Test", "-c", "garbage",
+        ])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "✗ Invalid schedule: cron expression needs 5 fields",
+        ));
+
+    env.command()
+        .args(["schedule", "list", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bad-job").not());
+}
+
+#[test]
+fn test_schedule_list_describes_schedule() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+
+    env.command()
+        .args([
+            "schedule",
+            "add",
+            "-n",
+            "nightly",
+            "-m",
+            "Report",
+            "-c",
+            "0 0 * * *",
+        ])
+        .assert()
+        .success();
+
+    env.command()
+        .args(["schedule", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("daily at midnight"));
+}
+
+#[test]
+fn test_schedule_show_prints_job_details() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let timeline = env.config_file("timeline");
+    fs::create_dir_all(&timeline).expect("Failed to create timeline dir");
+    let store = serde_json::json!({
+        "version": 1,
+        "jobs": [{
+            "id": "abc12345",
+            "name": "nightly-report",
+            "enabled": true,
+            "schedule": {"kind": "cron", "expr": "0 0 * * *"},
+            "payload": {"message": "Compile the report"},
+            "state": {
+                "next_run_at_ms": null,
+                "last_run_at_ms": 1700000000000i64,
+                "last_status": "timeout",
+                "last_error": "timed out after 50ms"
+            },
+            "created_at_ms": 1700000000000i64,
+            "updated_at_ms": 1700000000000i64
+        }]
+    });
+    fs::write(timeline.join("cron.json"), store.to_string()).expect("Failed to write store");
+
+    env.command()
+        .args(["schedule", "show", "abc12345"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("nightly-report"))
+        .stdout(predicate::str::contains("Schedule: daily at midnight"))
+        .stdout(predicate::str::contains("Enabled: yes"))
+        .stdout(predicate::str::contains("Next run: never"))
+        .stdout(predicate::str::contains("Last status: timeout"))
+        .stdout(predicate::str::contains("Last error: timed out after 50ms"))
+        .stdout(predicate::str::contains("Message: Compile the report"));
+}
+
+#[test]
+fn test_schedule_list_distinguishes_paused_and_completed() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let timeline = env.config_file("timeline");
+    fs::create_dir_all(&timeline).expect("Failed to create timeline dir");
+    let store = serde_json::json!({
+        "version": 1,
+        "jobs": [{
+            "id": "once0001",
+            "name": "reminder",
+            "enabled": false,
+            "pause_reason": "completed",
+            "schedule": {"kind": "at", "at_ms": 1700000000000i64},
+            "payload": {"message": "Ping"},
+            "created_at_ms": 1700000000000i64,
+            "updated_at_ms": 1700000000000i64
+        }, {
+            "id": "every001",
+            "name": "heartbeat",
+            "enabled": true,
+            "schedule": {"kind": "every", "every_ms": 60000},
+            "payload": {"message": "Check"},
+            "created_at_ms": 1700000000000i64,
+            "updated_at_ms": 1700000000000i64
+        }]
+    });
+    fs::write(timeline.join("cron.json"), store.to_string()).expect("Failed to write store");
+
+    env.command()
+        .args(["schedule", "disable", "every001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Job every001 paused"));
+
+    env.command()
+        .args(["schedule", "list", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("reminder (completed"))
+        .stdout(predicate::str::contains("heartbeat (paused"));
+
+    env.command()
+        .args(["schedule", "enable", "every001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Job every001 enabled"));
+}
+
+#[test]
+fn test_schedule_restore_swaps_in_backup() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let config = serde_json::json!({"deploy": {"cron_backups": 2}});
+    fs::write(env.config_file("config.json"), config.to_string()).unwrap();
+
+    for name in ["first", "second"] {
+        env.command()
+            .args(["schedule", "add", "-n", name, "-m", "Report", "-e", "60"])
+            .assert()
+            .success();
+    }
+
+    // Backup 1 was taken just before "second" was saved
+    env.command()
+        .args(["schedule", "restore", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("backup 1 (1 jobs)"));
+
+    env.command()
+        .args(["schedule", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("first"))
+        .stdout(predicate::str::contains("second").not());
+
+    env.command()
+        .args(["schedule", "restore", "5"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_schedule_preview_prints_run_times() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let timeline = env.config_file("timeline");
+    fs::create_dir_all(&timeline).expect("Failed to create timeline dir");
+    let store = serde_json::json!({
+        "version": 1,
+        "jobs": [{
+            "id": "abc12345",
+            "name": "hourly-check",
+            "enabled": true,
+            "schedule": {"kind": "cron", "expr": "0 * * * *"},
+            "payload": {"message": "Check in"},
+            "state": {},
+            "created_at_ms": 1700000000000i64,
+            "updated_at_ms": 1700000000000i64
+        }]
+    });
+    fs::write(timeline.join("cron.json"), store.to_string()).expect("Failed to write store");
+
+    let output = env
+        .command()
+        .args(["schedule", "preview", "abc12345", "--count", "3"])
+        .output()
+        .expect("Failed to run preview");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Next runs of hourly-check"));
+    let times: Vec<&str> = stdout.lines().skip(1).map(str::trim).collect();
+    assert_eq!(times.len(), 3);
+    assert!(times.iter().all(|t| t.ends_with(":00:00")));
+}
+
+#[test]
+fn test_schedule_update_changes_cadence() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let timeline = env.config_file("timeline");
+    fs::create_dir_all(&timeline).expect("Failed to create timeline dir");
+    let store = serde_json::json!({
+        "version": 1,
+        "jobs": [{
+            "id": "abc12345",
+            "name": "hourly-check",
+            "enabled": true,
+            "schedule": {"kind": "every", "every_ms": 3600000},
+            "payload": {"message": "Check in"},
+            "state": {"last_status": "ok"},
+            "created_at_ms": 1700000000000i64,
+            "updated_at_ms": 1700000000000i64
+        }]
+    });
+    fs::write(timeline.join("cron.json"), store.to_string()).expect("Failed to write store");
+
+    env.command()
+        .args(["schedule", "update", "abc12345", "--cron", "0 9 * * *"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Job abc12345 now runs daily at 09:00",
+        ));
+
+    let saved: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(timeline.join("cron.json")).expect("Failed to read store"),
+    )
+    .expect("Store is not JSON");
+    let job = &saved["jobs"][0];
+    assert_eq!(job["schedule"]["expr"], "0 9 * * *");
+    assert_eq!(job["state"]["last_status"], "ok");
+    assert!(job["updated_at_ms"].as_i64().unwrap() > 1700000000000);
+
+    env.command()
+        .args(["schedule", "update", "abc12345", "--cron", "0 25 * * *"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Invalid schedule"));
+}
+
+#[test]
+fn test_schedule_list_all_shows_run_tallies() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let timeline = env.config_file("timeline");
+    fs::create_dir_all(&timeline).expect("Failed to create timeline dir");
+    let store = serde_json::json!({
+        "version": 1,
+        "jobs": [{
+            "id": "abc12345",
+            "name": "hourly-check",
+            "enabled": true,
+            "schedule": {"kind": "every", "every_ms": 3600000},
+            "payload": {"message": "Check in"},
+            "state": {"run_count": 3, "success_count": 2, "failure_count": 1},
+            "created_at_ms": 1700000000000i64,
+            "updated_at_ms": 1700000000000i64
+        }]
+    });
+    fs::write(timeline.join("cron.json"), store.to_string()).expect("Failed to write store");
+
+    env.command()
+        .args(["schedule", "list", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("runs: 3 (2 ok, 1 failed)"));
+}
+
+#[test]
+fn test_schedule_show_unknown_job() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+
+    env.command()
+        .args(["schedule", "show", "missing1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Job missing1 not found"));
+}
+
 #[test]
 fn test_schedule_remove_outputs() {
     let env = TestEnv::new().expect("Failed to create test environment");
@@ -270,6 +784,43 @@ fn test_freq_status_outputs() {
         .stdout(predicate::str::contains("Channel Status"));
 }
 
+#[test]
+fn test_freq_status_shows_dropped_unauthorized() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    env.create_config().expect("Failed to create config");
+    fs::write(
+        env.config_file("metrics.json"),
+        r#"{"inbound_dropped_unauthorized": {"telegram": 7}}"#,
+    )
+    .expect("Failed to write metrics");
+
+    env.command()
+        .args(["freq", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Dropped (unauthorized): 7"));
+}
+
+#[test]
+fn test_freq_test_without_token_fails() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+
+    env.command()
+        .args(["freq", "test", "--to", "12345"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_freq_test_unknown_channel_fails() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+
+    env.command()
+        .args(["freq", "test", "--channel", "pigeon", "--to", "12345"])
+        .assert()
+        .failure();
+}
+
 // ============================================================================
 // Command error handling tests
 // ============================================================================
@@ -310,7 +861,227 @@ fn test_invalid_config_json() {
     fs::write(env.config_file("config.json"), "{invalid json}").expect("Failed to write config");
 
     let mut cmd = env.command();
-    cmd.arg("status");
+    cmd.arg("status").env_remove("RUST_LOG");
 
-    cmd.assert().failure();
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Config load failed"));
+}
+
+#[test]
+fn test_freq_list_shows_all_channels() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let config = serde_json::json!({
+        "frequency": {
+            "telegram": {"enabled": true, "token": "123:abc", "allow_from": ["1", "2"]},
+            "whatsapp": {"enabled": false}
+        }
+    });
+    fs::write(env.config_file("config.json"), config.to_string()).unwrap();
+
+    env.command()
+        .args(["freq", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("telegram - enabled (2 allowed)"))
+        .stdout(predicate::str::contains("whatsapp - disabled (any sender)"));
+}
+
+#[test]
+fn test_tools_json_exports_schema() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+
+    let output = env.command().args(["tools", "--json"]).output().unwrap();
+    assert!(output.status.success());
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(schema["read_file"]["parameters"]["properties"]["path"].is_object());
+    assert!(schema["exec"]["description"].is_string());
+}
+
+#[test]
+fn test_tools_follow_toolkit_config() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let config = serde_json::json!({
+        "toolkit": {
+            "time": {"enabled": false},
+            "memory": {"search": true}
+        }
+    });
+    fs::write(env.config_file("config.json"), config.to_string()).unwrap();
+
+    let output = env.command().args(["tools", "--json"]).output().unwrap();
+    assert!(output.status.success());
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(schema.get("current_time").is_none());
+    assert!(schema["recall"].is_object());
+    assert!(schema["remember"].is_object());
+}
+
+/// Test config show --effective applies environment overrides
+#[test]
+fn test_config_show_effective_applies_env() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    env.create_config_with_api_base("http://127.0.0.1:1")
+        .expect("Failed to create config");
+
+    let mut cmd = env.command();
+    cmd.args(["config", "show", "--effective"])
+        .env("OPENSAM_MODEL", "env/model");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("env/model"));
+
+    let mut cmd = env.command();
+    cmd.args(["config", "show"])
+        .env("OPENSAM_MODEL", "env/model");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("env/model").not());
+}
+
+/// Test config show masks secrets
+#[test]
+fn test_config_show_masks_secrets() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let config = serde_json::json!({
+        "soliton": {"openrouter": {"api_key": "sk-or-secret"}},
+        "frequency": {"telegram": {"token": "123:tg-secret"}}
+    });
+    fs::write(env.config_file("config.json"), config.to_string()).unwrap();
+
+    let mut cmd = env.command();
+    cmd.args(["config", "show", "--effective"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("********"))
+        .stdout(predicate::str::contains("sk-or-secret").not())
+        .stdout(predicate::str::contains("tg-secret").not());
+}
+
+/// Test config migrate rewrites the flat fixture layout
+#[test]
+fn test_config_migrate_rewrites_legacy_config() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    env.create_config().expect("Failed to create config");
+
+    let mut cmd = env.command();
+    cmd.args(["config", "migrate"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Config migrated"));
+
+    let written: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(env.config_file("config.json")).unwrap()).unwrap();
+    assert_eq!(written["soliton"]["openrouter"]["api_key"], "test-api-key");
+    assert_eq!(written["operative"]["defaults"]["model"], "test/model");
+    assert!(written.get("api_key").is_none());
+
+    let mut cmd = env.command();
+    cmd.args(["config", "migrate"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("already current"));
+}
+
+/// Gateway config whose provider refuses every connection, without retries
+fn write_unreachable_provider_config(env: &TestEnv) {
+    let dead = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let api_base = format!("http://{}", dead.local_addr().unwrap());
+    drop(dead);
+    let config = serde_json::json!({
+        "soliton": {
+            "openrouter": {"api_key": "sk-or-test", "api_base": api_base},
+            "retry": {"max_retries": 0, "base_delay_ms": 1, "respect_retry_after": false}
+        },
+        "operative": {"defaults": {"workspace": env.workspace_dir.to_string_lossy()}}
+    });
+    fs::write(env.config_file("config.json"), config.to_string()).unwrap();
+}
+
+/// Store with one job that is already due
+fn write_due_job(env: &TestEnv, job: serde_json::Value) {
+    let timeline = env.config_file("timeline");
+    fs::create_dir_all(&timeline).unwrap();
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut job = job;
+    job["schedule"] = serde_json::json!({"kind": "every", "every_ms": 3_600_000});
+    job["payload"] = serde_json::json!({"message": "Report"});
+    job["state"] = serde_json::json!({"next_run_at_ms": now - 1000});
+    job["created_at_ms"] = serde_json::json!(now);
+    job["updated_at_ms"] = serde_json::json!(now);
+    let store = serde_json::json!({"version": 1, "jobs": [job]});
+    fs::write(timeline.join("cron.json"), store.to_string()).unwrap();
+}
+
+/// Run the gateway briefly, then return the stored job state
+fn deploy_and_read_job(env: &TestEnv) -> serde_json::Value {
+    env.command()
+        .arg("deploy")
+        .timeout(std::time::Duration::from_secs(4))
+        .output()
+        .expect("Failed to execute");
+    let store: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(env.config_file("timeline").join("cron.json")).unwrap(),
+    )
+    .unwrap();
+    store["jobs"][0].clone()
+}
+
+#[test]
+fn test_deploy_retries_job_whose_turn_fails() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    write_unreachable_provider_config(&env);
+    write_due_job(
+        &env,
+        serde_json::json!({
+            "id": "flaky001",
+            "name": "report",
+            "retry_policy": {"max_retries": 2, "backoff_ms": 600000}
+        }),
+    );
+
+    let before = chrono::Utc::now().timestamp_millis();
+    let job = deploy_and_read_job(&env);
+
+    assert_eq!(job["state"]["last_status"], "failed", "{}", job);
+    assert_eq!(job["state"]["retry_attempts"], 1, "{}", job);
+    let next = job["state"]["next_run_at_ms"].as_i64().unwrap();
+    assert!(next >= before + 600000, "{}", job);
+}
+
+#[test]
+fn test_deploy_counts_failed_turns() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    write_unreachable_provider_config(&env);
+    write_due_job(
+        &env,
+        serde_json::json!({"id": "fail0001", "name": "report"}),
+    );
+
+    let job = deploy_and_read_job(&env);
+
+    assert_eq!(job["state"]["run_count"], 1, "{}", job);
+    assert_eq!(job["state"]["failure_count"], 1, "{}", job);
+    assert!(job["state"]["success_count"].is_null(), "{}", job);
+}
+
+#[test]
+fn test_deploy_counts_successful_turns() {
+    let env = TestEnv::new().expect("Failed to create test environment");
+    let server = MockLlmServer::start("Report filed");
+    env.create_config_with_api_base(&server.api_base)
+        .expect("Failed to create config");
+    write_due_job(
+        &env,
+        serde_json::json!({"id": "okay0001", "name": "report"}),
+    );
+
+    let job = deploy_and_read_job(&env);
+
+    assert_eq!(job["state"]["last_status"], "success", "{}", job);
+    assert_eq!(job["state"]["success_count"], 1, "{}", job);
+    // Zero counters are left out of the store
+    assert!(job["state"]["failure_count"].is_null(), "{}", job);
 }
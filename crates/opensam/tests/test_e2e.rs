@@ -237,8 +237,23 @@ fn test_all_commands_have_help() {
         vec!["schedule", "list", "--help"],
         vec!["schedule", "add", "--help"],
         vec!["schedule", "remove", "--help"],
+        vec!["schedule", "show", "--help"],
+        vec!["schedule", "preview", "--help"],
+        vec!["schedule", "update", "--help"],
+        vec!["schedule", "restore", "--help"],
+        vec!["schedule", "enable", "--help"],
+        vec!["schedule", "disable", "--help"],
+        vec!["session", "--help"],
+        vec!["session", "set-suffix", "--help"],
+        vec!["session", "usage", "--help"],
         vec!["freq", "--help"],
         vec!["freq", "status", "--help"],
+        vec!["freq", "list", "--help"],
+        vec!["tools", "--help"],
+        vec!["freq", "test", "--help"],
+        vec!["config", "--help"],
+        vec!["config", "show", "--help"],
+        vec!["config", "migrate", "--help"],
     ];
 
     for cmd_args in commands {
@@ -0,0 +1,186 @@
+//! SOLITON Backoff
+//!
+//! Exponential backoff with jitter, shared by anything that retries.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Exponential backoff policy
+///
+/// Delays start at `base`, grow by `multiplier` per attempt and never exceed
+/// `max`. `jitter` is the fraction (0.0..=1.0) by which each delay is randomly
+/// spread in either direction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backoff {
+    pub base: Duration,
+    pub max: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl Backoff {
+    /// Create a policy doubling from `base` up to `max`, without jitter
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            multiplier: 2.0,
+            jitter: 0.0,
+        }
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Delay before retry `attempt` (0-based), without jitter
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(attempt.min(i32::MAX as u32) as i32);
+        let secs = (self.base.as_secs_f64() * factor).min(self.max.as_secs_f64());
+        Duration::from_secs_f64(secs)
+    }
+
+    /// Infinite iterator of delays; combine with `take` to bound retries
+    pub fn iter(&self) -> BackoffIter {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        self.iter_seeded(seed)
+    }
+
+    /// Iterator with a fixed jitter seed, for reproducible sequences
+    pub fn iter_seeded(&self, seed: u64) -> BackoffIter {
+        BackoffIter {
+            policy: self.clone(),
+            attempt: 0,
+            // xorshift must not start at zero
+            state: seed | 1,
+        }
+    }
+}
+
+impl IntoIterator for &Backoff {
+    type Item = Duration;
+    type IntoIter = BackoffIter;
+
+    fn into_iter(self) -> BackoffIter {
+        self.iter()
+    }
+}
+
+/// Delays produced by a [`Backoff`] policy
+#[derive(Debug, Clone)]
+pub struct BackoffIter {
+    policy: Backoff,
+    attempt: u32,
+    state: u64,
+}
+
+impl BackoffIter {
+    /// Uniform sample in [0, 1)
+    fn next_unit(&mut self) -> f64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Iterator for BackoffIter {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let delay = self.policy.delay(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+
+        if self.policy.jitter <= 0.0 {
+            return Some(delay);
+        }
+
+        let spread = 1.0 - self.policy.jitter + 2.0 * self.policy.jitter * self.next_unit();
+        let secs = (delay.as_secs_f64() * spread).min(self.policy.max.as_secs_f64());
+        Some(Duration::from_secs_f64(secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_by_multiplier() {
+        let backoff =
+            Backoff::new(Duration::from_millis(100), Duration::from_secs(60)).with_multiplier(3.0);
+        let delays: Vec<_> = backoff.iter().take(4).collect();
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_millis(100),
+                Duration::from_millis(300),
+                Duration::from_millis(900),
+                Duration::from_millis(2700),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backoff_capped_at_max() {
+        let backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+        let delays: Vec<_> = backoff.iter().take(6).collect();
+
+        assert_eq!(delays[2], Duration::from_secs(4));
+        assert!(delays[3..].iter().all(|d| *d == Duration::from_secs(5)));
+        assert_eq!(backoff.delay(1000), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_backoff_jitter_within_bounds() {
+        let backoff =
+            Backoff::new(Duration::from_millis(100), Duration::from_secs(10)).with_jitter(0.5);
+
+        for seed in 0..50 {
+            for (attempt, delay) in backoff.iter_seeded(seed).take(8).enumerate() {
+                let nominal = backoff.delay(attempt as u32).as_secs_f64();
+                let secs = delay.as_secs_f64();
+                assert!(secs >= nominal * 0.5 - 1e-9, "{} < {}", secs, nominal * 0.5);
+                assert!(secs <= (nominal * 1.5).min(10.0) + 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_backoff_jitter_varies() {
+        let backoff = Backoff::default();
+        let a: Vec<_> = backoff.iter_seeded(1).take(5).collect();
+        let b: Vec<_> = backoff.iter_seeded(2).take(5).collect();
+        assert_ne!(a, b);
+        assert_eq!(a, backoff.iter_seeded(1).take(5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_backoff_jitter_clamped() {
+        let backoff = Backoff::default().with_jitter(3.0);
+        assert_eq!(backoff.jitter, 1.0);
+    }
+}
@@ -0,0 +1,137 @@
+//! SOLITON Fallback Chain
+//!
+//! Fails over to the next node when one is rate limited or down.
+
+use crate::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{debug, warn};
+
+const NONE_ANSWERED: usize = usize::MAX;
+
+/// SOLITON node that tries an ordered list of providers
+///
+/// Each provider is tried in turn while the error is retriable (see
+/// [`ProviderError::is_retriable`]); the first success is returned. Any
+/// other error is returned straight away, as is the last node's error once
+/// the chain is exhausted.
+///
+/// The first node is asked for the requested model; later nodes are asked
+/// for their own [`Provider::default_model`], since a model name one
+/// provider accepts is usually unknown to the next.
+pub struct FallbackProvider {
+    nodes: Vec<Box<dyn Provider>>,
+    answered: AtomicUsize,
+}
+
+impl FallbackProvider {
+    pub fn new(nodes: Vec<Box<dyn Provider>>) -> Self {
+        Self {
+            nodes,
+            answered: AtomicUsize::new(NONE_ANSWERED),
+        }
+    }
+
+    /// Providers in the order they are tried
+    pub fn nodes(&self) -> &[Box<dyn Provider>] {
+        &self.nodes
+    }
+
+    /// Index of the node that served the last successful request
+    pub fn last_answered(&self) -> Option<usize> {
+        match self.answered.load(Ordering::Relaxed) {
+            NONE_ANSWERED => None,
+            index => Some(index),
+        }
+    }
+
+    fn no_nodes() -> ProviderError {
+        ProviderError::Unsupported("FALLBACK CHAIN HAS NO NODES".to_string())
+    }
+
+    fn record(&self, index: usize) {
+        self.answered.store(index, Ordering::Relaxed);
+        if index > 0 {
+            let node = &self.nodes[index];
+            debug!(
+                "◆ SOLITON FALLBACK: NODE {} ({} / {}) ANSWERED",
+                index,
+                node.name(),
+                node.default_model()
+            );
+        }
+    }
+
+    fn fail_over(&self, index: usize, error: &ProviderError) {
+        warn!(
+            "◆ SOLITON NODE {} ({}) FAILED: {} - FALLING BACK",
+            index,
+            self.nodes[index].name(),
+            error
+        );
+    }
+}
+
+#[async_trait]
+impl Provider for FallbackProvider {
+    async fn chat(&self, params: ChatParams) -> Result<ChatResponse> {
+        let mut last_error = None;
+        for (index, node) in self.nodes.iter().enumerate() {
+            let mut params = params.clone();
+            if index > 0 {
+                params.model = node.default_model();
+            }
+            match node.chat(params).await {
+                Ok(response) => {
+                    self.record(index);
+                    return Ok(response);
+                }
+                Err(e) if e.is_retriable() && index + 1 < self.nodes.len() => {
+                    self.fail_over(index, &e);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(Self::no_nodes))
+    }
+
+    fn default_model(&self) -> String {
+        self.nodes
+            .first()
+            .map(|node| node.default_model())
+            .unwrap_or_default()
+    }
+
+    fn is_configured(&self) -> bool {
+        self.nodes.iter().any(|node| node.is_configured())
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.nodes
+            .first()
+            .map(|node| node.capabilities())
+            .unwrap_or_default()
+    }
+
+    fn name(&self) -> &str {
+        "fallback"
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut last_error = None;
+        for (index, node) in self.nodes.iter().enumerate() {
+            match node.embed(texts).await {
+                Ok(vectors) => {
+                    self.record(index);
+                    return Ok(vectors);
+                }
+                Err(e) if e.is_retriable() && index + 1 < self.nodes.len() => {
+                    self.fail_over(index, &e);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(Self::no_nodes))
+    }
+}
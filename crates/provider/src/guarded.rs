@@ -0,0 +1,149 @@
+//! SOLITON Guarded Node
+//!
+//! Pre-flight prompt size checks in front of another provider.
+
+use crate::*;
+use tracing::{info, warn};
+
+/// Rough characters-per-token ratio used for estimates
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Fixed per-message overhead for role and framing
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Estimate the prompt token count of a message list
+///
+/// This is a cheap heuristic (about four characters per token), not a real
+/// tokenizer, so the ceiling should leave some headroom.
+pub fn estimate_tokens(messages: &[Message]) -> usize {
+    messages.iter().map(estimate_message_tokens).sum()
+}
+
+fn estimate_message_tokens(message: &Message) -> usize {
+    let mut chars = message.content.as_deref().map(str::len).unwrap_or(0);
+    if let Some(tool_calls) = &message.tool_calls {
+        chars += tool_calls
+            .iter()
+            .map(|call| call.function.name.len() + call.function.arguments.to_string().len())
+            .sum::<usize>();
+    }
+    chars.div_ceil(CHARS_PER_TOKEN) + MESSAGE_OVERHEAD_TOKENS
+}
+
+fn estimate_tool_tokens(tools: &[Tool]) -> usize {
+    tools
+        .iter()
+        .map(|tool| {
+            serde_json::to_string(tool)
+                .map(|s| s.len())
+                .unwrap_or(0)
+                .div_ceil(CHARS_PER_TOKEN)
+        })
+        .sum()
+}
+
+/// SOLITON node that caps prompt size before forwarding
+///
+/// When the estimated prompt exceeds `max_prompt_tokens`, the oldest
+/// non-system messages are dropped (the latest message is always kept).
+/// If the prompt is still over budget, `ProviderError::PromptTooLarge` is
+/// returned without calling the inner provider.
+pub struct GuardedProvider<P: Provider> {
+    inner: P,
+    max_prompt_tokens: usize,
+}
+
+impl<P: Provider> GuardedProvider<P> {
+    pub fn new(inner: P, max_prompt_tokens: usize) -> Self {
+        Self {
+            inner,
+            max_prompt_tokens,
+        }
+    }
+
+    /// Configured prompt ceiling in estimated tokens
+    pub fn max_prompt_tokens(&self) -> usize {
+        self.max_prompt_tokens
+    }
+
+    /// Wrapped provider
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Trim `params.messages` to fit the ceiling
+    fn guard(&self, params: &mut ChatParams) -> Result<()> {
+        let tool_tokens = estimate_tool_tokens(&params.tools);
+        let original = tool_tokens + estimate_tokens(&params.messages);
+        if original <= self.max_prompt_tokens {
+            return Ok(());
+        }
+
+        let mut dropped = 0;
+        let mut estimated = original;
+        while estimated > self.max_prompt_tokens {
+            let Some(oldest) = params
+                .messages
+                .iter()
+                .position(|m| m.role != "system")
+                .filter(|&i| i + 1 < params.messages.len())
+            else {
+                break;
+            };
+
+            params.messages.remove(oldest);
+            dropped += 1;
+            // Tool results without their assistant call are rejected upstream
+            while oldest + 1 < params.messages.len() && params.messages[oldest].role == "tool" {
+                params.messages.remove(oldest);
+                dropped += 1;
+            }
+            estimated = tool_tokens + estimate_tokens(&params.messages);
+        }
+
+        if estimated > self.max_prompt_tokens {
+            warn!(
+                "◆ PROMPT TOO LARGE: ~{} TOKENS AFTER TRIMMING (LIMIT {})",
+                estimated, self.max_prompt_tokens
+            );
+            return Err(ProviderError::PromptTooLarge {
+                estimated,
+                limit: self.max_prompt_tokens,
+            });
+        }
+
+        info!(
+            "◆ PROMPT TRIMMED: DROPPED {} MESSAGES, ~{} -> ~{} TOKENS (LIMIT {})",
+            dropped, original, estimated, self.max_prompt_tokens
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P: Provider> Provider for GuardedProvider<P> {
+    async fn chat(&self, mut params: ChatParams) -> Result<ChatResponse> {
+        self.guard(&mut params)?;
+        self.inner.chat(params).await
+    }
+
+    fn default_model(&self) -> String {
+        self.inner.default_model()
+    }
+
+    fn is_configured(&self) -> bool {
+        self.inner.is_configured()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.inner.embed(texts).await
+    }
+}
@@ -9,9 +9,22 @@ use serde_json::Value;
 use thiserror::Error;
 use tracing::{debug, trace};
 
+pub mod backoff;
+pub mod fallback;
+pub mod guarded;
+pub mod log_redaction;
 pub mod openrouter;
+pub mod record_replay;
+pub mod redacting;
+pub mod retry;
 
+pub use backoff::{Backoff, BackoffIter};
+pub use fallback::FallbackProvider;
+pub use guarded::{estimate_tokens, GuardedProvider};
 pub use openrouter::OpenRouterProvider;
+pub use record_replay::{Cassette, Interaction, RecordReplayProvider, RecordedRequest};
+pub use redacting::RedactingProvider;
+pub use retry::RetryConfig;
 
 /// SOLITON network errors
 #[derive(Error, Debug)]
@@ -28,11 +41,87 @@ pub enum ProviderError {
     #[error("ACCESS DENIED: NO API KEY")]
     NoApiKey,
 
-    #[error("CORRUPTED RESPONSE")]
-    InvalidResponse,
+    #[error("CORRUPTED RESPONSE: {0}")]
+    InvalidResponse(String),
 
     #[error("RATE LIMITED - RETREAT")]
     RateLimited,
+
+    #[error("NODE DOWN ({status}): {message}")]
+    Unavailable { status: u16, message: String },
+
+    #[error("NODE TIMED OUT AFTER {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("INVALID HEADER: {0}")]
+    InvalidHeader(String),
+
+    #[error("PAYLOAD TOO LARGE: ~{estimated} TOKENS (LIMIT {limit})")]
+    PromptTooLarge { estimated: usize, limit: usize },
+
+    #[error("CASSETTE ERROR: {0}")]
+    Cassette(String),
+
+    #[error("UNSUPPORTED: {0}")]
+    Unsupported(String),
+
+    /// Any of the above, from the chat request with this id
+    #[error("{source} [request {request_id}]")]
+    Tagged {
+        request_id: String,
+        source: Box<ProviderError>,
+    },
+}
+
+impl ProviderError {
+    /// Stable machine-readable name for the error variant
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ProviderError::Request(_) => "request",
+            ProviderError::Json(_) => "json",
+            ProviderError::Api(_) => "api",
+            ProviderError::NoApiKey => "no_api_key",
+            ProviderError::InvalidResponse(_) => "invalid_response",
+            ProviderError::RateLimited => "rate_limited",
+            ProviderError::Unavailable { .. } => "unavailable",
+            ProviderError::Timeout(_) => "timeout",
+            ProviderError::InvalidHeader(_) => "invalid_header",
+            ProviderError::PromptTooLarge { .. } => "prompt_too_large",
+            ProviderError::Cassette(_) => "cassette",
+            ProviderError::Unsupported(_) => "unsupported",
+            ProviderError::Tagged { source, .. } => source.kind(),
+        }
+    }
+
+    /// Id of the request that failed, when the node tagged one
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            ProviderError::Tagged { request_id, .. } => Some(request_id),
+            _ => None,
+        }
+    }
+
+    /// The error without its request id tag
+    pub fn untagged(&self) -> &ProviderError {
+        match self {
+            ProviderError::Tagged { source, .. } => source.untagged(),
+            other => other,
+        }
+    }
+
+    /// Whether another node might succeed where this one failed
+    ///
+    /// True for rate limits, 5xx responses, timeouts and transport failures;
+    /// a request the node rejected outright would be rejected anywhere.
+    pub fn is_retriable(&self) -> bool {
+        matches!(
+            self.untagged(),
+            ProviderError::Request(_)
+                | ProviderError::RateLimited
+                | ProviderError::Unavailable { .. }
+                | ProviderError::Timeout(_)
+        )
+    }
 }
 
 pub type Result<T> = std::result::Result<T, ProviderError>;
@@ -55,6 +144,9 @@ pub struct ChatResponse {
     pub finish_reason: String,
     #[serde(default)]
     pub usage: Usage,
+    /// Model that actually produced the response, when the node reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
 }
 
 impl ChatResponse {
@@ -62,11 +154,18 @@ impl ChatResponse {
         !self.tool_calls.is_empty()
     }
 
+    /// Record the model that served the response
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
     pub fn text(content: impl Into<String>) -> Self {
         Self {
             content: Some(content.into()),
             tool_calls: Vec::new(),
             finish_reason: "stop".to_string(),
+            model: None,
             usage: Usage::default(),
         }
     }
@@ -76,6 +175,7 @@ impl ChatResponse {
             content: Some(message.into()),
             tool_calls: Vec::new(),
             finish_reason: "error".to_string(),
+            model: None,
             usage: Usage::default(),
         }
     }
@@ -89,6 +189,17 @@ pub struct Usage {
     pub total_tokens: u32,
 }
 
+impl Usage {
+    /// Add another response's usage to this total
+    pub fn add(&mut self, other: &Usage) {
+        self.prompt_tokens = self.prompt_tokens.saturating_add(other.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(other.completion_tokens);
+        self.total_tokens = self.total_tokens.saturating_add(other.total_tokens);
+    }
+}
+
 /// Transmission log entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
@@ -194,9 +305,16 @@ impl Tool {
                 name: name.into(),
                 description: description.into(),
                 parameters,
+                strict: false,
             },
         }
     }
+
+    /// Ask the node to guarantee calls match the schema exactly
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.function.strict = strict;
+        self
+    }
 }
 
 /// Function schema
@@ -205,6 +323,40 @@ pub struct FunctionDef {
     pub name: String,
     pub description: String,
     pub parameters: Value,
+    /// Strict schema adherence; off unless requested
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict: bool,
+}
+
+impl FunctionDef {
+    /// Parameters as sent to the node
+    ///
+    /// Strict mode requires `additionalProperties: false` on every object
+    /// schema, so it is added wherever it is missing.
+    pub fn request_parameters(&self) -> Value {
+        let mut parameters = self.parameters.clone();
+        if self.strict {
+            close_objects(&mut parameters);
+        }
+        parameters
+    }
+}
+
+/// Set `additionalProperties: false` on object schemas that don't say otherwise
+fn close_objects(schema: &mut Value) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+    if obj.get("type").and_then(Value::as_str) == Some("object") {
+        obj.entry("additionalProperties")
+            .or_insert(Value::Bool(false));
+    }
+    if let Some(properties) = obj.get_mut("properties").and_then(Value::as_object_mut) {
+        properties.values_mut().for_each(close_objects);
+    }
+    if let Some(items) = obj.get_mut("items") {
+        close_objects(items);
+    }
 }
 
 /// Transmission parameters
@@ -216,6 +368,12 @@ pub struct ChatParams {
     pub max_tokens: u32,
     pub temperature: f32,
     pub tool_choice: ToolChoice,
+    /// Nucleus sampling cutoff; the node's default when unset
+    pub top_p: Option<f32>,
+    pub frequency_penalty: Option<f32>,
+    pub presence_penalty: Option<f32>,
+    /// Sequences that end generation; none when empty
+    pub stop: Vec<String>,
 }
 
 impl Default for ChatParams {
@@ -227,6 +385,10 @@ impl Default for ChatParams {
             max_tokens: 4096,
             temperature: 0.7,
             tool_choice: ToolChoice::Auto,
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            stop: Vec::new(),
         }
     }
 }
@@ -235,16 +397,128 @@ impl Default for ChatParams {
 #[derive(Debug, Clone)]
 pub enum ToolChoice {
     Auto,
+    /// Force a call to the named tool
     Required(String),
+    /// Force a call to any tool
+    Any,
     None,
 }
 
+/// Features a provider supports beyond plain chat
+///
+/// Tool calling is part of the `ChatParams` contract, so it defaults on;
+/// the optional extras default off until a provider opts in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProviderCapabilities {
+    /// Incremental streaming of responses
+    pub streaming: bool,
+    /// Tool definitions and tool calls
+    pub tools: bool,
+    /// Image inputs
+    pub vision: bool,
+    /// Structured JSON output mode
+    pub json_mode: bool,
+}
+
+impl Default for ProviderCapabilities {
+    fn default() -> Self {
+        Self {
+            streaming: false,
+            tools: true,
+            vision: false,
+            json_mode: false,
+        }
+    }
+}
+
 /// SOLITON network node
 #[async_trait]
 pub trait Provider: Send + Sync {
     async fn chat(&self, params: ChatParams) -> Result<ChatResponse>;
     fn default_model(&self) -> String;
     fn is_configured(&self) -> bool;
+
+    /// What this provider supports
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities::default()
+    }
+
+    /// Short name of the backing implementation, for logs
+    fn name(&self) -> &str {
+        "provider"
+    }
+
+    /// One embedding vector per input text, in input order
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let _ = texts;
+        Err(ProviderError::Unsupported(format!(
+            "{} HAS NO EMBEDDINGS",
+            self.name()
+        )))
+    }
+}
+
+#[async_trait]
+impl<P: Provider + ?Sized> Provider for Box<P> {
+    async fn chat(&self, params: ChatParams) -> Result<ChatResponse> {
+        (**self).chat(params).await
+    }
+
+    fn default_model(&self) -> String {
+        (**self).default_model()
+    }
+
+    fn is_configured(&self) -> bool {
+        (**self).is_configured()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        (**self).capabilities()
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        (**self).embed(texts).await
+    }
+}
+
+/// Cosine similarity of two vectors
+///
+/// Returns 0.0 when the lengths differ or either vector is all zeros.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Collapse runs of identical consecutive system messages into one
+///
+/// Distinct system messages, and identical ones separated by other roles,
+/// are kept.
+pub fn dedupe_system_messages(messages: &[Message]) -> Vec<&Message> {
+    let mut kept: Vec<&Message> = Vec::with_capacity(messages.len());
+    for message in messages {
+        let duplicate = kept.last().is_some_and(|prev| {
+            message.role == "system" && prev.role == "system" && prev.content == message.content
+        });
+        if !duplicate {
+            kept.push(message);
+        }
+    }
+    kept
 }
 
 /// Build JSON schema
@@ -287,13 +561,51 @@ mod tests {
         let err = ProviderError::Api("test error".to_string());
         assert_eq!(err.to_string(), "NODE REJECTED: test error");
 
-        let err = ProviderError::InvalidResponse;
-        assert_eq!(err.to_string(), "CORRUPTED RESPONSE");
+        let err = ProviderError::InvalidResponse("no choices".to_string());
+        assert_eq!(err.to_string(), "CORRUPTED RESPONSE: no choices");
 
         let err = ProviderError::RateLimited;
         assert_eq!(err.to_string(), "RATE LIMITED - RETREAT");
     }
 
+    #[test]
+    fn test_provider_error_kind() {
+        assert_eq!(ProviderError::NoApiKey.kind(), "no_api_key");
+        assert_eq!(ProviderError::Api("x".to_string()).kind(), "api");
+        assert_eq!(
+            ProviderError::InvalidResponse(String::new()).kind(),
+            "invalid_response"
+        );
+        assert_eq!(ProviderError::RateLimited.kind(), "rate_limited");
+        assert_eq!(
+            ProviderError::Timeout(std::time::Duration::from_secs(1)).kind(),
+            "timeout"
+        );
+        assert_eq!(
+            ProviderError::Unavailable {
+                status: 503,
+                message: String::new()
+            }
+            .kind(),
+            "unavailable"
+        );
+        assert_eq!(
+            ProviderError::InvalidHeader("x".to_string()).kind(),
+            "invalid_header"
+        );
+        assert_eq!(
+            ProviderError::PromptTooLarge {
+                estimated: 10,
+                limit: 5
+            }
+            .kind(),
+            "prompt_too_large"
+        );
+
+        let json_err = serde_json::from_str::<Value>("{").unwrap_err();
+        assert_eq!(ProviderError::Json(json_err).kind(), "json");
+    }
+
     #[test]
     fn test_provider_error_from_reqwest() {
         // Note: We can't easily create a reqwest::Error, but we can verify the From trait exists
@@ -333,6 +645,7 @@ mod tests {
                 arguments: json!({}),
             }],
             finish_reason: "tool_calls".to_string(),
+            model: None,
             usage: Usage::default(),
         };
         assert!(response_with_tools.has_tool_calls());
@@ -346,8 +659,81 @@ mod tests {
         assert_eq!(response.usage.total_tokens, 0);
     }
 
+    // ========== System Message Dedup Tests ==========
+
+    #[test]
+    fn test_dedupe_identical_leading_system_messages() {
+        let messages = vec![
+            Message::system("You are helpful"),
+            Message::system("You are helpful"),
+            Message::user("Hello"),
+        ];
+
+        let kept = dedupe_system_messages(&messages);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].role, "system");
+        assert_eq!(kept[1].role, "user");
+    }
+
+    #[test]
+    fn test_dedupe_keeps_distinct_and_separated_system_messages() {
+        let messages = vec![
+            Message::system("You are helpful"),
+            Message::system("Reply in French"),
+            Message::user("Hello"),
+            Message::system("Reply in French"),
+        ];
+
+        assert_eq!(dedupe_system_messages(&messages).len(), 4);
+    }
+
+    // ========== Embedding Tests ==========
+
+    #[test]
+    fn test_cosine_similarity_ranks_obvious_match_highest() {
+        let query = [0.9, 0.1, 0.0];
+        let candidates = [
+            ("weather", vec![0.0, 0.2, 0.9]),
+            ("match", vec![0.85, 0.15, 0.05]),
+            ("noise", vec![-0.5, 0.5, 0.1]),
+        ];
+
+        let best = candidates
+            .iter()
+            .max_by(|a, b| {
+                cosine_similarity(&query, &a.1).total_cmp(&cosine_similarity(&query, &b.1))
+            })
+            .unwrap();
+        assert_eq!(best.0, "match");
+    }
+
+    #[test]
+    fn test_cosine_similarity_edge_cases() {
+        assert!((cosine_similarity(&[1.0, 2.0], &[2.0, 4.0]) - 1.0).abs() < 1e-6);
+        assert!((cosine_similarity(&[1.0, 0.0], &[-1.0, 0.0]) + 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+    }
+
     // ========== Usage Tests ==========
 
+    #[test]
+    fn test_usage_add_accumulates() {
+        let mut total = Usage::default();
+        let turn = Usage {
+            prompt_tokens: 10,
+            completion_tokens: 5,
+            total_tokens: 15,
+        };
+        total.add(&turn);
+        total.add(&turn);
+
+        assert_eq!(total.prompt_tokens, 20);
+        assert_eq!(total.completion_tokens, 10);
+        assert_eq!(total.total_tokens, 30);
+    }
+
     #[test]
     fn test_usage_default() {
         let usage = Usage::default();
@@ -493,6 +879,7 @@ mod tests {
             max_tokens: 2048,
             temperature: 0.5,
             tool_choice: ToolChoice::Required("test_tool".to_string()),
+            ..Default::default()
         };
 
         assert_eq!(params.model, "gpt-4");
@@ -632,6 +1019,7 @@ mod tests {
                 arguments: json!({"location": "NYC"}),
             }],
             finish_reason: "tool_calls".to_string(),
+            model: None,
             usage: Usage {
                 prompt_tokens: 10,
                 completion_tokens: 20,
@@ -687,9 +1075,54 @@ mod tests {
                 "type": "object",
                 "properties": {}
             }),
+            strict: false,
         };
 
         assert_eq!(func_def.name, "calculate");
         assert_eq!(func_def.description, "Perform a calculation");
     }
+
+    #[test]
+    fn test_strict_tool_closes_object_schemas() {
+        let tool = Tool::new(
+            "save",
+            "Save items",
+            json!({
+                "type": "object",
+                "properties": {
+                    "items": {
+                        "type": "array",
+                        "items": {"type": "object", "properties": {"id": {"type": "string"}}}
+                    },
+                    "meta": {"type": "object", "additionalProperties": true}
+                }
+            }),
+        )
+        .with_strict(true);
+
+        let parameters = tool.function.request_parameters();
+        assert_eq!(parameters["additionalProperties"], false);
+        assert_eq!(
+            parameters["properties"]["items"]["items"]["additionalProperties"],
+            false
+        );
+        // An explicit setting is left alone
+        assert_eq!(
+            parameters["properties"]["meta"]["additionalProperties"],
+            true
+        );
+
+        let serialized = serde_json::to_value(&tool).unwrap();
+        assert_eq!(serialized["function"]["strict"], true);
+    }
+
+    #[test]
+    fn test_non_strict_tool_is_unchanged() {
+        let schema = json!({"type": "object", "properties": {"q": {"type": "string"}}});
+        let tool = Tool::new("search", "Search", schema.clone());
+
+        assert_eq!(tool.function.request_parameters(), schema);
+        let serialized = serde_json::to_value(&tool).unwrap();
+        assert!(serialized["function"].get("strict").is_none());
+    }
 }
@@ -0,0 +1,117 @@
+//! SOLITON Request Log Redaction
+//!
+//! Request bodies are logged for debugging, but inline media would flood the
+//! log with base64. Media values are replaced by a short size summary; the
+//! JSON shape and all text are kept.
+
+use serde_json::Value;
+
+/// Default length above which base64-looking strings are summarized
+pub const DEFAULT_MAX_INLINE_BYTES: usize = 1024;
+
+/// Copy of `body` with inline media summarized for logging
+///
+/// Base64 `data:` URLs become `[image, N bytes]` (or `[media, N bytes]` for
+/// non-image types). Other strings longer than `max_inline` that consist only
+/// of base64 characters become `[data, N bytes]`. Text with whitespace or
+/// punctuation is never touched.
+pub fn summarize_media(body: &Value, max_inline: usize) -> Value {
+    match body {
+        Value::String(s) => {
+            Value::String(summarize_string(s, max_inline).unwrap_or_else(|| s.clone()))
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| summarize_media(item, max_inline))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), summarize_media(v, max_inline)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn summarize_string(s: &str, max_inline: usize) -> Option<String> {
+    if let Some(rest) = s.strip_prefix("data:") {
+        let (mime, data) = rest.split_once(";base64,")?;
+        let kind = if mime.starts_with("image/") {
+            "image"
+        } else {
+            "media"
+        };
+        return Some(format!("[{}, {} bytes]", kind, decoded_len(data)));
+    }
+    if s.len() > max_inline && looks_base64(s) {
+        return Some(format!("[data, {} bytes]", decoded_len(s)));
+    }
+    None
+}
+
+fn looks_base64(s: &str) -> bool {
+    s.bytes()
+        .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=' | b'-' | b'_'))
+}
+
+/// Size of the data a base64 string encodes
+fn decoded_len(data: &str) -> usize {
+    let padding = data.bytes().rev().take_while(|&b| b == b'=').count();
+    (data.len() * 3 / 4).saturating_sub(padding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_image_data_url_summarized() {
+        let body = json!({
+            "messages": [{
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "What is in this picture?"},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,AAAABBBB"}}
+                ]
+            }]
+        });
+
+        let logged = summarize_media(&body, DEFAULT_MAX_INLINE_BYTES);
+        let parts = &logged["messages"][0]["content"];
+
+        assert_eq!(parts[0]["text"], "What is in this picture?");
+        assert_eq!(parts[1]["image_url"]["url"], "[image, 6 bytes]");
+        assert_eq!(parts[1]["type"], "image_url");
+    }
+
+    #[test]
+    fn test_long_text_kept() {
+        let text = "word ".repeat(1000);
+        let body = json!({"messages": [{"role": "user", "content": text}]});
+
+        let logged = summarize_media(&body, 16);
+        assert_eq!(logged, body);
+    }
+
+    #[test]
+    fn test_large_raw_base64_summarized() {
+        let body = json!({"data": "QUJD".repeat(100), "short": "QUJD"});
+
+        let logged = summarize_media(&body, 64);
+        assert_eq!(logged["data"], "[data, 300 bytes]");
+        assert_eq!(logged["short"], "QUJD");
+    }
+
+    #[test]
+    fn test_output_is_valid_json() {
+        let body = json!({"content": [{"image_url": {"url": "data:audio/wav;base64,UklGRg=="}}]});
+
+        let logged = summarize_media(&body, DEFAULT_MAX_INLINE_BYTES);
+        let text = serde_json::to_string(&logged).unwrap();
+        let parsed: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed["content"][0]["image_url"]["url"], "[media, 4 bytes]");
+    }
+}
@@ -3,8 +3,21 @@
 //! OpenRouter/OpenAI-compatible network access.
 
 use crate::*;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Client;
 use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{warn, Instrument};
+
+/// Embedding model used when none is configured
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// Longest a request may take when no timeout is configured
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Header carrying the per-call request id unless configured otherwise
+pub const DEFAULT_REQUEST_ID_HEADER: &str = "X-Request-Id";
 
 /// SOLITON OpenRouter node
 pub struct OpenRouterProvider {
@@ -12,7 +25,12 @@ pub struct OpenRouterProvider {
     api_key: String,
     api_base: String,
     default_model: String,
-    #[allow(dead_code)]
+    embedding_model: String,
+    extra_headers: HeaderMap,
+    request_id_header: Option<HeaderName>,
+    retry: RetryConfig,
+    timeout: Duration,
+    log_inline_limit: usize,
     is_openrouter: bool,
 }
 
@@ -45,21 +63,196 @@ impl OpenRouterProvider {
             }
         });
 
+        let embedding_model = if is_openrouter {
+            format!("openai/{}", DEFAULT_EMBEDDING_MODEL)
+        } else {
+            DEFAULT_EMBEDDING_MODEL.to_string()
+        };
+
         Self {
-            client: Client::new(),
+            client: build_client(DEFAULT_TIMEOUT),
             api_key,
             api_base,
             default_model,
+            embedding_model,
+            extra_headers: HeaderMap::new(),
+            request_id_header: HeaderName::from_bytes(DEFAULT_REQUEST_ID_HEADER.as_bytes()).ok(),
+            retry: RetryConfig::default(),
+            timeout: DEFAULT_TIMEOUT,
+            log_inline_limit: log_redaction::DEFAULT_MAX_INLINE_BYTES,
             is_openrouter,
         }
     }
 
+    /// Add headers sent with every request
+    ///
+    /// Fails with `ProviderError::InvalidHeader` if a name or value is not a
+    /// valid HTTP header.
+    pub fn with_extra_headers(mut self, headers: HashMap<String, String>) -> Result<Self> {
+        for (name, value) in headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| ProviderError::InvalidHeader(name.clone()))?;
+            let header_value = HeaderValue::from_str(&value)
+                .map_err(|_| ProviderError::InvalidHeader(name.clone()))?;
+            self.extra_headers.insert(header_name, header_value);
+        }
+        Ok(self)
+    }
+
+    /// Header each chat request's id is sent in; empty disables it
+    ///
+    /// Fails with `ProviderError::InvalidHeader` if `name` is not a valid
+    /// header name.
+    pub fn with_request_id_header(mut self, name: &str) -> Result<Self> {
+        self.request_id_header = if name.is_empty() {
+            None
+        } else {
+            Some(
+                HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|_| ProviderError::InvalidHeader(name.to_string()))?,
+            )
+        };
+        Ok(self)
+    }
+
+    /// Header each chat request's id is sent in, if any
+    pub fn request_id_header(&self) -> Option<&HeaderName> {
+        self.request_id_header.as_ref()
+    }
+
+    /// Give up on a request that takes longer than `timeout`
+    ///
+    /// Each retry attempt gets the full timeout again.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = build_client(timeout);
+        self.timeout = timeout;
+        self
+    }
+
+    /// Per-request timeout
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Retry rate-limited and failed chat requests with backoff
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Retry policy for chat requests
+    pub fn retry(&self) -> &RetryConfig {
+        &self.retry
+    }
+
+    /// Model used by `embed`
+    pub fn with_embedding_model(mut self, model: impl Into<String>) -> Self {
+        self.embedding_model = model.into();
+        self
+    }
+
+    /// Model used by `embed`
+    pub fn embedding_model(&self) -> &str {
+        &self.embedding_model
+    }
+
+    /// Summarize base64 strings longer than `bytes` in logged requests
+    pub fn with_log_inline_limit(mut self, bytes: usize) -> Self {
+        self.log_inline_limit = bytes;
+        self
+    }
+
+    /// Base URL requests are sent to
+    pub fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    /// Extra headers applied to every request
+    pub fn extra_headers(&self) -> &HeaderMap {
+        &self.extra_headers
+    }
+
+    /// Report an elapsed timeout as `ProviderError::Timeout`
+    fn classify_timeout(&self, error: ProviderError) -> ProviderError {
+        match error {
+            ProviderError::Request(e) if e.is_timeout() => ProviderError::Timeout(self.timeout),
+            other => other,
+        }
+    }
+
+    /// Send one chat request tagged with `request_id`
+    async fn send_chat(&self, params: ChatParams, request_id: &str) -> Result<ChatResponse> {
+        trace!("◆ ESTABLISHING SOLITON UPLINK TO {}", self.api_base);
+
+        let mut request_headers = HeaderMap::new();
+        if let Some(name) = &self.request_id_header {
+            // A UUID is always a valid header value
+            if let Ok(value) = HeaderValue::from_str(request_id) {
+                request_headers.insert(name.clone(), value);
+            }
+        }
+
+        let url = format!("{}/chat/completions", self.api_base);
+        let body = self.build_request(&params);
+        if tracing::enabled!(tracing::Level::TRACE) {
+            trace!(
+                "◆ SOLITON REQUEST: {}",
+                log_redaction::summarize_media(&body, self.log_inline_limit)
+            );
+        }
+
+        let mut attempt = 0;
+        let response = loop {
+            let response = self
+                .client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .header("Content-Type", "application/json")
+                .headers(self.extra_headers.clone())
+                .headers(request_headers.clone())
+                .json(&body)
+                .send()
+                .await?;
+
+            let status = response.status();
+            if !RetryConfig::is_retryable(status) || attempt >= self.retry.retries() {
+                break response;
+            }
+            let delay = self.retry.delay(attempt, response.headers());
+            attempt += 1;
+            warn!(
+                "◆ SOLITON {} - RETRY {}/{} IN {:?}",
+                status,
+                attempt,
+                self.retry.retries(),
+                delay
+            );
+            tokio::time::sleep(delay).await;
+        };
+
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            return Err(status_error(status, &text));
+        }
+        let json: serde_json::Value = serde_json::from_str(&text)?;
+
+        debug!(
+            "◆ SOLITON RESPONSE: {} TOOL CALLS",
+            json["choices"][0]["message"]["tool_calls"]
+                .as_array()
+                .map(|v| v.len())
+                .unwrap_or(0)
+        );
+
+        self.parse_response(json)
+    }
+
     fn build_request(&self, params: &ChatParams) -> serde_json::Value {
         let model = params.model.clone();
 
-        let messages: Vec<serde_json::Value> = params
-            .messages
-            .iter()
+        let messages: Vec<serde_json::Value> = crate::dedupe_system_messages(&params.messages)
+            .into_iter()
             .map(|m| {
                 let mut obj = json!({ "role": &m.role });
                 if let Some(content) = &m.content {
@@ -85,19 +278,36 @@ impl OpenRouterProvider {
             "temperature": params.temperature,
         });
 
+        // Optional sampling knobs are sent only when set
+        if let Some(top_p) = params.top_p {
+            body["top_p"] = json!(top_p);
+        }
+        if let Some(penalty) = params.frequency_penalty {
+            body["frequency_penalty"] = json!(penalty);
+        }
+        if let Some(penalty) = params.presence_penalty {
+            body["presence_penalty"] = json!(penalty);
+        }
+        if !params.stop.is_empty() {
+            body["stop"] = json!(params.stop);
+        }
+
+        // APIs reject a tool_choice without tools, so an empty tool set
+        // omits both whatever tool_choice says
         if !params.tools.is_empty() {
             let tools: Vec<serde_json::Value> = params
                 .tools
                 .iter()
                 .map(|t| {
-                    json!({
-                        "type": "function",
-                        "function": {
-                            "name": &t.function.name,
-                            "description": &t.function.description,
-                            "parameters": &t.function.parameters
-                        }
-                    })
+                    let mut function = json!({
+                        "name": &t.function.name,
+                        "description": &t.function.description,
+                        "parameters": t.function.request_parameters()
+                    });
+                    if t.function.strict {
+                        function["strict"] = json!(true);
+                    }
+                    json!({ "type": "function", "function": function })
                 })
                 .collect();
 
@@ -107,6 +317,7 @@ impl OpenRouterProvider {
                 ToolChoice::Required(name) => {
                     json!({"type": "function", "function": {"name": name}})
                 }
+                ToolChoice::Any => json!("required"),
                 ToolChoice::None => json!("none"),
             };
         }
@@ -117,8 +328,25 @@ impl OpenRouterProvider {
     fn parse_response(&self, json: serde_json::Value) -> Result<ChatResponse> {
         let choice = json["choices"]
             .get(0)
-            .ok_or(ProviderError::InvalidResponse)?;
-        let message = &choice["message"];
+            .ok_or_else(|| ProviderError::InvalidResponse("no choices".to_string()))?;
+        // Streaming-style `delta` choices, or malformed ones, carry no message
+        let message = choice
+            .get("message")
+            .filter(|m| m.is_object())
+            .ok_or_else(|| {
+                let keys: Vec<&str> = choice
+                    .as_object()
+                    .map(|o| o.keys().map(String::as_str).collect())
+                    .unwrap_or_default();
+                ProviderError::InvalidResponse(format!(
+                    "choice has no message object (keys: {})",
+                    if keys.is_empty() {
+                        "none".to_string()
+                    } else {
+                        keys.join(", ")
+                    }
+                ))
+            })?;
         let content = message["content"].as_str().map(|s| s.to_string());
         let finish_reason = choice["finish_reason"]
             .as_str()
@@ -129,27 +357,20 @@ impl OpenRouterProvider {
         if let Some(calls) = message["tool_calls"].as_array() {
             for call in calls {
                 let function = &call["function"];
-                let args = function["arguments"]
-                    .as_str()
-                    .and_then(|s| serde_json::from_str(s).ok())
-                    .unwrap_or_else(|| function["arguments"].clone());
-
                 tool_calls.push(ToolCall {
                     id: call["id"].as_str().unwrap_or("").to_string(),
                     name: function["name"].as_str().unwrap_or("").to_string(),
-                    arguments: args,
+                    arguments: parse_arguments(&function["arguments"]),
                 });
             }
         }
 
-        let usage = if let Some(usage) = json["usage"].as_object() {
-            Usage {
-                prompt_tokens: usage["prompt_tokens"].as_u64().unwrap_or(0) as u32,
-                completion_tokens: usage["completion_tokens"].as_u64().unwrap_or(0) as u32,
-                total_tokens: usage["total_tokens"].as_u64().unwrap_or(0) as u32,
-            }
-        } else {
-            Usage::default()
+        // Nodes may send a partial or empty usage object
+        let tokens = |key: &str| json["usage"][key].as_u64().unwrap_or(0) as u32;
+        let usage = Usage {
+            prompt_tokens: tokens("prompt_tokens"),
+            completion_tokens: tokens("completion_tokens"),
+            total_tokens: tokens("total_tokens"),
         };
 
         Ok(ChatResponse {
@@ -157,50 +378,161 @@ impl OpenRouterProvider {
             tool_calls,
             finish_reason,
             usage,
+            model: json["model"].as_str().map(|s| s.to_string()),
         })
     }
 }
 
+/// Tool call arguments, whether sent as a JSON string or an object
+///
+/// A string that is not valid JSON is kept as the raw string.
+fn parse_arguments(arguments: &serde_json::Value) -> serde_json::Value {
+    match arguments {
+        serde_json::Value::String(raw) => {
+            serde_json::from_str(raw).unwrap_or_else(|_| arguments.clone())
+        }
+        other => other.clone(),
+    }
+}
+
+/// HTTP client that abandons requests after `timeout`
+///
+/// If the client cannot be built, a default one without the timeout is used
+/// and the failure is logged.
+fn build_client(timeout: Duration) -> Client {
+    Client::builder()
+        .timeout(timeout)
+        .build()
+        .unwrap_or_else(|e| {
+            warn!(
+                "◆ HTTP CLIENT SETUP FAILED ({}) - REQUESTS WILL NOT TIME OUT AFTER {:?}",
+                e, timeout
+            );
+            Client::new()
+        })
+}
+
+/// Tag any error with the id of the request that hit it
+/// Error for a non-success response, whatever its body holds
+///
+/// The status decides the kind; a JSON body only supplies the message, so
+/// an HTML error page from a proxy is still a rate limit or an outage.
+fn status_error(status: reqwest::StatusCode, body: &str) -> ProviderError {
+    if status.as_u16() == 429 {
+        return ProviderError::RateLimited;
+    }
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|json| json["error"]["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "UNKNOWN ERROR".to_string());
+    if status.is_server_error() {
+        return ProviderError::Unavailable {
+            status: status.as_u16(),
+            message,
+        };
+    }
+    ProviderError::Api(message)
+}
+
+fn tag_request_id(error: ProviderError, request_id: &str) -> ProviderError {
+    ProviderError::Tagged {
+        request_id: request_id.to_string(),
+        source: Box::new(error),
+    }
+}
+
+/// Vectors from an `/embeddings` response, ordered by `index`
+fn parse_embeddings(json: serde_json::Value) -> Result<Vec<Vec<f32>>> {
+    let data = json["data"]
+        .as_array()
+        .ok_or_else(|| ProviderError::InvalidResponse("no embedding data".to_string()))?;
+    let mut indexed = data
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let index = item["index"].as_u64().map(|n| n as usize).unwrap_or(i);
+            let vector = item["embedding"]
+                .as_array()
+                .ok_or_else(|| {
+                    ProviderError::InvalidResponse(format!("item {} has no embedding", i))
+                })?
+                .iter()
+                .map(|v| v.as_f64().map(|f| f as f32))
+                .collect::<Option<Vec<f32>>>()
+                .ok_or_else(|| {
+                    ProviderError::InvalidResponse(format!(
+                        "item {} has a non-numeric embedding",
+                        i
+                    ))
+                })?;
+            Ok((index, vector))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    indexed.sort_by_key(|(index, _)| *index);
+    Ok(indexed.into_iter().map(|(_, vector)| vector).collect())
+}
+
 #[async_trait::async_trait]
 impl Provider for OpenRouterProvider {
     async fn chat(&self, params: ChatParams) -> Result<ChatResponse> {
-        trace!("◆ ESTABLISHING SOLITON UPLINK TO {}", self.api_base);
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let span = tracing::debug_span!("soliton_chat", request_id = %request_id);
+        self.send_chat(params, &request_id)
+            .instrument(span)
+            .await
+            .map_err(|e| {
+                let e = self.classify_timeout(e);
+                debug!("◆ SOLITON REQUEST {} FAILED: {}", request_id, e);
+                tag_request_id(e, &request_id)
+            })
+    }
 
-        let url = format!("{}/chat/completions", self.api_base);
-        let body = self.build_request(&params);
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = format!("{}/embeddings", self.api_base);
+        let body = json!({
+            "model": self.embedding_model,
+            "input": texts,
+        });
+        trace!(
+            "◆ EMBEDDING {} TEXTS WITH {}",
+            texts.len(),
+            self.embedding_model
+        );
 
         let response = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
+            .headers(self.extra_headers.clone())
             .json(&body)
             .send()
-            .await?;
+            .await
+            .map_err(|e| self.classify_timeout(e.into()))?;
 
         let status = response.status();
-        let json: serde_json::Value = response.json().await?;
-
+        let text = response
+            .text()
+            .await
+            .map_err(|e| self.classify_timeout(e.into()))?;
         if !status.is_success() {
-            let error = json["error"]["message"]
-                .as_str()
-                .unwrap_or("UNKNOWN ERROR")
-                .to_string();
-            if status.as_u16() == 429 {
-                return Err(ProviderError::RateLimited);
-            }
-            return Err(ProviderError::Api(error));
+            return Err(status_error(status, &text));
         }
-
-        debug!(
-            "◆ SOLITON RESPONSE: {} TOOL CALLS",
-            json["choices"][0]["message"]["tool_calls"]
-                .as_array()
-                .map(|v| v.len())
-                .unwrap_or(0)
-        );
-
-        self.parse_response(json)
+        let json: serde_json::Value = serde_json::from_str(&text)?;
+
+        let vectors = parse_embeddings(json)?;
+        if vectors.len() != texts.len() {
+            return Err(ProviderError::InvalidResponse(format!(
+                "{} embeddings for {} texts",
+                vectors.len(),
+                texts.len()
+            )));
+        }
+        Ok(vectors)
     }
 
     fn default_model(&self) -> String {
@@ -210,6 +542,25 @@ impl Provider for OpenRouterProvider {
     fn is_configured(&self) -> bool {
         !self.api_key.is_empty()
     }
+
+    /// Tools are sent as OpenAI-style functions. Requests are not streamed,
+    /// message content is text only and no response format is set.
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            streaming: false,
+            tools: true,
+            vision: false,
+            json_mode: false,
+        }
+    }
+
+    fn name(&self) -> &str {
+        if self.is_openrouter {
+            "openrouter"
+        } else {
+            "openai-compatible"
+        }
+    }
 }
 
 #[cfg(test)]
@@ -217,6 +568,17 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_capabilities_report_tool_support() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let caps = provider.capabilities();
+
+        assert!(caps.tools);
+        assert!(!caps.streaming);
+        assert!(!caps.vision);
+        assert!(!caps.json_mode);
+    }
+
     // ========== OpenRouterProvider Construction Tests ==========
 
     #[test]
@@ -295,6 +657,43 @@ mod tests {
         assert!(!provider.is_configured());
     }
 
+    #[test]
+    fn test_openrouter_provider_extra_headers() {
+        let mut headers = HashMap::new();
+        headers.insert("X-Org-Id".to_string(), "org-42".to_string());
+        headers.insert("x-project".to_string(), "alpha".to_string());
+
+        let provider = OpenRouterProvider::new("sk-test", None, None)
+            .with_extra_headers(headers)
+            .unwrap();
+
+        assert_eq!(provider.extra_headers().len(), 2);
+        assert_eq!(provider.extra_headers()["x-org-id"], "org-42");
+        assert_eq!(provider.extra_headers()["x-project"], "alpha");
+    }
+
+    #[test]
+    fn test_openrouter_provider_invalid_header_name() {
+        let mut headers = HashMap::new();
+        headers.insert("Bad Header".to_string(), "value".to_string());
+
+        let result = OpenRouterProvider::new("sk-test", None, None).with_extra_headers(headers);
+
+        match result {
+            Err(ProviderError::InvalidHeader(name)) => assert_eq!(name, "Bad Header"),
+            _ => panic!("Expected InvalidHeader error"),
+        }
+    }
+
+    #[test]
+    fn test_openrouter_provider_invalid_header_value() {
+        let mut headers = HashMap::new();
+        headers.insert("X-Token".to_string(), "line\nbreak".to_string());
+
+        let result = OpenRouterProvider::new("sk-test", None, None).with_extra_headers(headers);
+        assert!(matches!(result, Err(ProviderError::InvalidHeader(_))));
+    }
+
     // ========== build_request Tests ==========
 
     #[test]
@@ -307,6 +706,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.5,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -323,6 +723,69 @@ mod tests {
         assert_eq!(messages[0]["content"], "Hello");
     }
 
+    #[test]
+    fn test_build_request_omits_unset_sampling() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let request = provider.build_request(&ChatParams::default());
+
+        for field in ["top_p", "frequency_penalty", "presence_penalty", "stop"] {
+            assert!(request.get(field).is_none(), "{} sent unset", field);
+        }
+    }
+
+    #[test]
+    fn test_build_request_includes_each_sampling_field() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let cases: Vec<(&str, ChatParams, serde_json::Value)> = vec![
+            (
+                "top_p",
+                ChatParams {
+                    top_p: Some(0.5),
+                    ..Default::default()
+                },
+                json!(0.5),
+            ),
+            (
+                "frequency_penalty",
+                ChatParams {
+                    frequency_penalty: Some(-1.5),
+                    ..Default::default()
+                },
+                json!(-1.5),
+            ),
+            (
+                "presence_penalty",
+                ChatParams {
+                    presence_penalty: Some(0.25),
+                    ..Default::default()
+                },
+                json!(0.25),
+            ),
+            (
+                "stop",
+                ChatParams {
+                    stop: vec!["END".to_string(), "\n\n".to_string()],
+                    ..Default::default()
+                },
+                json!(["END", "\n\n"]),
+            ),
+        ];
+
+        for (field, params, expected) in cases {
+            let request = provider.build_request(&params);
+            assert_eq!(request[field], expected, "{}", field);
+            let others = ["top_p", "frequency_penalty", "presence_penalty", "stop"];
+            for other in others.iter().filter(|other| **other != field) {
+                assert!(
+                    request.get(*other).is_none(),
+                    "{} sent with {}",
+                    other,
+                    field
+                );
+            }
+        }
+    }
+
     #[test]
     #[ignore = "test has a bug"]
     fn test_build_request_with_openrouter_prefix() {
@@ -334,6 +797,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.5,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -350,6 +814,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.5,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -371,6 +836,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.7,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -384,6 +850,26 @@ mod tests {
         assert_eq!(messages[2]["content"], "Hi there");
     }
 
+    #[test]
+    fn test_build_request_collapses_duplicate_system_messages() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let params = ChatParams {
+            model: "gpt-4".to_string(),
+            messages: vec![
+                Message::system("You are helpful"),
+                Message::system("You are helpful"),
+                Message::user("Hello"),
+            ],
+            ..Default::default()
+        };
+
+        let request = provider.build_request(&params);
+        let messages = request["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["content"], "You are helpful");
+        assert_eq!(messages[1]["role"], "user");
+    }
+
     #[test]
     fn test_build_request_tool_message() {
         let provider = OpenRouterProvider::new("sk-test", None, None);
@@ -394,6 +880,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.7,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -423,6 +910,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.7,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -439,6 +927,59 @@ mod tests {
         assert_eq!(request["tool_choice"], "auto");
     }
 
+    #[test]
+    fn test_build_request_without_tools_omits_tool_choice() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        for tool_choice in [
+            ToolChoice::Auto,
+            ToolChoice::Any,
+            ToolChoice::None,
+            ToolChoice::Required("get_weather".to_string()),
+        ] {
+            let params = ChatParams {
+                model: "gpt-4".to_string(),
+                messages: vec![Message::user("Hello")],
+                tools: vec![],
+                tool_choice,
+                ..Default::default()
+            };
+
+            let request = provider.build_request(&params);
+            assert!(request.get("tools").is_none());
+            assert!(request.get("tool_choice").is_none());
+        }
+    }
+
+    #[test]
+    fn test_build_request_strict_tool() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let schema = json!({
+            "type": "object",
+            "properties": {"location": {"type": "string"}},
+            "required": ["location"]
+        });
+        let params = ChatParams {
+            model: "gpt-4".to_string(),
+            messages: vec![Message::user("What's the weather?")],
+            tools: vec![
+                Tool::new("get_weather", "Get weather", schema.clone()).with_strict(true),
+                Tool::new("get_time", "Get time", schema.clone()),
+            ],
+            ..Default::default()
+        };
+
+        let request = provider.build_request(&params);
+        let tools = request["tools"].as_array().unwrap();
+
+        assert_eq!(tools[0]["function"]["strict"], true);
+        assert_eq!(
+            tools[0]["function"]["parameters"]["additionalProperties"],
+            false
+        );
+        assert!(tools[1]["function"].get("strict").is_none());
+        assert_eq!(tools[1]["function"]["parameters"], schema);
+    }
+
     #[test]
     fn test_build_request_with_tools_required_choice() {
         let provider = OpenRouterProvider::new("sk-test", None, None);
@@ -449,6 +990,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.7,
             tool_choice: ToolChoice::Required("get_weather".to_string()),
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -458,6 +1000,26 @@ mod tests {
         assert_eq!(tool_choice["function"]["name"], "get_weather");
     }
 
+    #[test]
+    fn test_build_request_with_tools_any_choice() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let params = ChatParams {
+            model: "gpt-4".to_string(),
+            messages: vec![Message::user("What's the weather?")],
+            tools: vec![Tool::new("get_weather", "Get weather", json!({}))],
+            max_tokens: 1024,
+            temperature: 0.7,
+            tool_choice: ToolChoice::Any,
+            ..Default::default()
+        };
+
+        let request = provider.build_request(&params);
+        assert_eq!(request["tool_choice"], "required");
+
+        let body = serde_json::to_string(&request).unwrap();
+        assert!(body.contains(r#""tool_choice":"required""#));
+    }
+
     #[test]
     fn test_build_request_with_tools_none_choice() {
         let provider = OpenRouterProvider::new("sk-test", None, None);
@@ -468,6 +1030,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.7,
             tool_choice: ToolChoice::None,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -488,6 +1051,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.7,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -515,6 +1079,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.7,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
@@ -589,7 +1154,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "test has a bug"]
     fn test_parse_response_multiple_tool_calls() {
         let provider = OpenRouterProvider::new("sk-test", None, None);
         let response_json = json!({
@@ -622,12 +1186,13 @@ mod tests {
         assert_eq!(response.tool_calls.len(), 2);
         assert_eq!(response.tool_calls[0].id, "call_1");
         assert_eq!(response.tool_calls[0].name, "tool1");
+        assert_eq!(response.tool_calls[0].arguments, json!({"arg1": "val1"}));
         assert_eq!(response.tool_calls[1].id, "call_2");
         assert_eq!(response.tool_calls[1].name, "tool2");
+        assert_eq!(response.tool_calls[1].arguments, json!({"arg2": 42}));
     }
 
     #[test]
-    #[ignore = "test has a bug"]
     fn test_parse_response_arguments_as_object() {
         // Some APIs return arguments as an object instead of a string
         let provider = OpenRouterProvider::new("sk-test", None, None);
@@ -652,7 +1217,62 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "test has a bug"]
+    fn test_parse_response_mixed_argument_forms() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let response_json = json!({
+            "choices": [{
+                "message": {
+                    "tool_calls": [
+                        {
+                            "id": "call_1",
+                            "function": {
+                                "name": "read_file",
+                                "arguments": "{\"path\": \"notes.md\"}"
+                            }
+                        },
+                        {
+                            "id": "call_2",
+                            "function": {
+                                "name": "web_search",
+                                "arguments": {"query": "weather", "count": 3}
+                            }
+                        },
+                        {
+                            "id": "call_3",
+                            "function": {
+                                "name": "exec",
+                                "arguments": "{not json"
+                            }
+                        }
+                    ]
+                },
+                "finish_reason": "tool_calls"
+            }]
+        });
+
+        let response = provider.parse_response(response_json).unwrap();
+
+        let calls: Vec<_> = response
+            .tool_calls
+            .iter()
+            .map(|c| (c.id.as_str(), c.name.as_str(), c.arguments.clone()))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("call_1", "read_file", json!({"path": "notes.md"})),
+                (
+                    "call_2",
+                    "web_search",
+                    json!({"query": "weather", "count": 3})
+                ),
+                ("call_3", "exec", json!("{not json")),
+            ]
+        );
+        assert_eq!(response.finish_reason, "tool_calls");
+    }
+
+    #[test]
     fn test_parse_response_invalid_json_arguments() {
         let provider = OpenRouterProvider::new("sk-test", None, None);
         let response_json = json!({
@@ -677,7 +1297,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "test has a bug"]
     fn test_parse_response_missing_content() {
         let provider = OpenRouterProvider::new("sk-test", None, None);
         let response_json = json!({
@@ -695,7 +1314,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "test has a bug"]
     fn test_parse_response_default_finish_reason() {
         let provider = OpenRouterProvider::new("sk-test", None, None);
         let response_json = json!({
@@ -727,6 +1345,49 @@ mod tests {
         assert_eq!(response.usage.prompt_tokens, 0);
         assert_eq!(response.usage.completion_tokens, 0);
         assert_eq!(response.usage.total_tokens, 0);
+        assert_eq!(response.model, None);
+    }
+
+    #[test]
+    fn test_parse_response_reports_served_model() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let response_json = json!({
+            "model": "openai/gpt-4o-mini",
+            "choices": [{
+                "message": { "content": "Hello" },
+                "finish_reason": "stop"
+            }]
+        });
+
+        let response = provider.parse_response(response_json).unwrap();
+        assert_eq!(response.model.as_deref(), Some("openai/gpt-4o-mini"));
+    }
+
+    #[test]
+    fn test_parse_response_delta_only_choice_is_invalid() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let response_json = json!({
+            "choices": [{
+                "delta": { "content": "Hel" },
+                "finish_reason": null
+            }]
+        });
+
+        let err = provider.parse_response(response_json).unwrap_err();
+        assert_eq!(err.kind(), "invalid_response");
+        assert!(err.to_string().contains("no message object"));
+        assert!(err.to_string().contains("delta"));
+    }
+
+    #[test]
+    fn test_parse_response_non_object_message_is_invalid() {
+        let provider = OpenRouterProvider::new("sk-test", None, None);
+        let response_json = json!({
+            "choices": [{ "message": "Hello" }]
+        });
+
+        let result = provider.parse_response(response_json);
+        assert!(matches!(result, Err(ProviderError::InvalidResponse(_))));
     }
 
     #[test]
@@ -738,7 +1399,7 @@ mod tests {
         });
 
         let result = provider.parse_response(response_json);
-        assert!(matches!(result, Err(ProviderError::InvalidResponse)));
+        assert!(matches!(result, Err(ProviderError::InvalidResponse(_))));
     }
 
     #[test]
@@ -749,11 +1410,10 @@ mod tests {
         });
 
         let result = provider.parse_response(response_json);
-        assert!(matches!(result, Err(ProviderError::InvalidResponse)));
+        assert!(matches!(result, Err(ProviderError::InvalidResponse(_))));
     }
 
     #[test]
-    #[ignore = "test has a bug"]
     fn test_parse_response_missing_tool_call_fields() {
         let provider = OpenRouterProvider::new("sk-test", None, None);
         let response_json = json!({
@@ -775,6 +1435,44 @@ mod tests {
 
     // ========== Integration-style Tests ==========
 
+    #[test]
+    fn test_parse_embeddings_orders_by_index() {
+        // Captured /embeddings response, truncated to 3 dimensions
+        let json = json!({
+            "object": "list",
+            "data": [
+                {"object": "embedding", "index": 1, "embedding": [0.5, -0.25, 0.0]},
+                {"object": "embedding", "index": 0, "embedding": [0.0123, 0.456, -0.789]}
+            ],
+            "model": "text-embedding-3-small",
+            "usage": {"prompt_tokens": 8, "total_tokens": 8}
+        });
+
+        let vectors = parse_embeddings(json).unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[0], vec![0.0123, 0.456, -0.789]);
+        assert_eq!(vectors[1], vec![0.5, -0.25, 0.0]);
+    }
+
+    #[test]
+    fn test_parse_embeddings_rejects_malformed() {
+        assert!(parse_embeddings(json!({"error": "nope"})).is_err());
+        assert!(parse_embeddings(json!({"data": [{"embedding": ["x"]}]})).is_err());
+    }
+
+    #[test]
+    fn test_embedding_model_defaults() {
+        let openrouter = OpenRouterProvider::new("sk-or-test", None, None);
+        assert_eq!(
+            openrouter.embedding_model(),
+            "openai/text-embedding-3-small"
+        );
+        let openai = OpenRouterProvider::new("sk-test", None, None);
+        assert_eq!(openai.embedding_model(), DEFAULT_EMBEDDING_MODEL);
+        let custom = openai.with_embedding_model("nomic-embed-text");
+        assert_eq!(custom.embedding_model(), "nomic-embed-text");
+    }
+
     #[test]
     fn test_full_request_response_cycle() {
         let provider = OpenRouterProvider::new("sk-test", None, None);
@@ -800,6 +1498,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.7,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let request = provider.build_request(&params);
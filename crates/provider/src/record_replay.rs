@@ -0,0 +1,220 @@
+//! SOLITON Record/Replay Node
+//!
+//! Golden transcripts for agent tests: record a live provider once, then
+//! replay the cassette without touching the network.
+
+use crate::*;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, info};
+
+/// Serializable form of `ChatParams`, used to match replayed requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub model: String,
+    pub messages: Value,
+    pub tools: Value,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    pub tool_choice: String,
+    /// Sampling fields; absent from cassettes recorded before they existed
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    #[serde(default)]
+    pub frequency_penalty: Option<f32>,
+    #[serde(default)]
+    pub presence_penalty: Option<f32>,
+    #[serde(default)]
+    pub stop: Vec<String>,
+}
+
+impl RecordedRequest {
+    pub fn from_params(params: &ChatParams) -> Self {
+        Self {
+            model: params.model.clone(),
+            messages: serde_json::to_value(&params.messages).unwrap_or(Value::Null),
+            tools: serde_json::to_value(&params.tools).unwrap_or(Value::Null),
+            max_tokens: params.max_tokens,
+            temperature: params.temperature,
+            tool_choice: format!("{:?}", params.tool_choice),
+            top_p: params.top_p,
+            frequency_penalty: params.frequency_penalty,
+            presence_penalty: params.presence_penalty,
+            stop: params.stop.clone(),
+        }
+    }
+}
+
+/// One recorded exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: ChatResponse,
+}
+
+/// Recorded exchanges, in call order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ProviderError::Cassette(format!("CANNOT READ {}: {}", path.display(), e))
+        })?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                ProviderError::Cassette(format!("CANNOT CREATE {}: {}", parent.display(), e))
+            })?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .map_err(|e| ProviderError::Cassette(format!("CANNOT WRITE {}: {}", path.display(), e)))
+    }
+}
+
+/// SOLITON node that records to, or replays from, a cassette file
+///
+/// In record mode every call is proxied to the inner provider and the
+/// `(request, response)` pair is appended to the cassette on disk. In replay
+/// mode each request is answered by the first unused interaction with an
+/// identical request; anything else is a `ProviderError::Cassette`.
+pub struct RecordReplayProvider {
+    /// Live provider; `None` in replay mode
+    inner: Option<Box<dyn Provider>>,
+    cassette: Mutex<Cassette>,
+    /// Replay flags marking interactions already served
+    used: Mutex<Vec<bool>>,
+    path: PathBuf,
+}
+
+impl RecordReplayProvider {
+    /// Proxy `inner`, writing a fresh cassette to `path`
+    pub fn record(inner: impl Provider + 'static, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner: Some(Box::new(inner)),
+            cassette: Mutex::new(Cassette::default()),
+            used: Mutex::new(Vec::new()),
+            path: path.into(),
+        }
+    }
+
+    /// Serve responses from the cassette at `path`
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let cassette = Cassette::load(&path)?;
+        info!(
+            "◆ REPLAYING {} INTERACTIONS FROM {}",
+            cassette.interactions.len(),
+            path.display()
+        );
+        Ok(Self {
+            inner: None,
+            used: Mutex::new(vec![false; cassette.interactions.len()]),
+            cassette: Mutex::new(cassette),
+            path,
+        })
+    }
+
+    /// Cassette file location
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether calls are served from the cassette
+    pub fn is_replay(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    /// Interactions recorded or loaded so far
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.cassette.lock().unwrap().interactions.clone()
+    }
+
+    fn serve(&self, request: &RecordedRequest) -> Result<ChatResponse> {
+        let cassette = self.cassette.lock().unwrap();
+        let mut used = self.used.lock().unwrap();
+        let found = cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .position(|(i, interaction)| !used[i] && interaction.request == *request);
+
+        match found {
+            Some(i) => {
+                used[i] = true;
+                debug!("◆ REPLAYED INTERACTION {}", i);
+                Ok(cassette.interactions[i].response.clone())
+            }
+            None => Err(ProviderError::Cassette(format!(
+                "NO RECORDED RESPONSE FOR REQUEST TO {} ({} MESSAGES)",
+                request.model,
+                request.messages.as_array().map(Vec::len).unwrap_or(0)
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for RecordReplayProvider {
+    async fn chat(&self, params: ChatParams) -> Result<ChatResponse> {
+        let request = RecordedRequest::from_params(&params);
+        let Some(inner) = &self.inner else {
+            return self.serve(&request);
+        };
+
+        let response = inner.chat(params).await?;
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(Interaction {
+            request,
+            response: response.clone(),
+        });
+        cassette.save(&self.path)?;
+        Ok(response)
+    }
+
+    fn default_model(&self) -> String {
+        match &self.inner {
+            Some(inner) => inner.default_model(),
+            None => self
+                .cassette
+                .lock()
+                .unwrap()
+                .interactions
+                .first()
+                .map(|i| i.request.model.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn is_configured(&self) -> bool {
+        self.inner
+            .as_ref()
+            .is_none_or(|inner| inner.is_configured())
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner
+            .as_ref()
+            .map(|inner| inner.capabilities())
+            .unwrap_or_default()
+    }
+
+    fn name(&self) -> &str {
+        "record-replay"
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        match &self.inner {
+            Some(inner) => inner.embed(texts).await,
+            None => Err(ProviderError::Unsupported(
+                "EMBEDDINGS ARE NOT RECORDED".to_string(),
+            )),
+        }
+    }
+}
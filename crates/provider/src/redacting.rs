@@ -0,0 +1,110 @@
+//! SOLITON Redacting Node
+//!
+//! Scrubs configured patterns from prompts and replies.
+
+use crate::*;
+use regex::Regex;
+use tracing::debug;
+
+/// Replacement text for redacted matches
+pub const REDACTED: &str = "[REDACTED]";
+
+/// SOLITON node that redacts matching text in both directions
+///
+/// Message contents and tool call arguments are scrubbed before they leave
+/// for the inner provider, and the same is done to the response before it
+/// is returned.
+pub struct RedactingProvider<P: Provider> {
+    inner: P,
+    patterns: Vec<Regex>,
+}
+
+impl<P: Provider> RedactingProvider<P> {
+    pub fn new(inner: P, patterns: Vec<Regex>) -> Self {
+        Self { inner, patterns }
+    }
+
+    /// Compile pattern strings, failing on the first invalid one
+    pub fn from_patterns(inner: P, patterns: &[String]) -> std::result::Result<Self, regex::Error> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Regex::new(p))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Self::new(inner, patterns))
+    }
+
+    /// Wrapped provider
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Replace every configured match in `text`
+    pub fn redact(&self, text: &str) -> String {
+        let mut redacted = text.to_string();
+        for pattern in &self.patterns {
+            if pattern.is_match(&redacted) {
+                debug!("◆ REDACTING MATCHES FOR /{}/", pattern.as_str());
+                redacted = pattern.replace_all(&redacted, REDACTED).into_owned();
+            }
+        }
+        redacted
+    }
+
+    /// Redact every string inside a JSON value, such as tool call arguments
+    pub fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.redact(text),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact_value(v)),
+            Value::Object(fields) => fields.values_mut().for_each(|v| self.redact_value(v)),
+            _ => {}
+        }
+    }
+}
+
+#[async_trait]
+impl<P: Provider> Provider for RedactingProvider<P> {
+    async fn chat(&self, mut params: ChatParams) -> Result<ChatResponse> {
+        if self.patterns.is_empty() {
+            return self.inner.chat(params).await;
+        }
+
+        for message in &mut params.messages {
+            if let Some(content) = &message.content {
+                message.content = Some(self.redact(content));
+            }
+            for call in message.tool_calls.iter_mut().flatten() {
+                self.redact_value(&mut call.function.arguments);
+            }
+        }
+
+        let mut response = self.inner.chat(params).await?;
+        if let Some(content) = &response.content {
+            response.content = Some(self.redact(content));
+        }
+        for call in &mut response.tool_calls {
+            self.redact_value(&mut call.arguments);
+        }
+        Ok(response)
+    }
+
+    fn default_model(&self) -> String {
+        self.inner.default_model()
+    }
+
+    fn is_configured(&self) -> bool {
+        self.inner.is_configured()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let texts: Vec<String> = texts.iter().map(|t| self.redact(t)).collect();
+        self.inner.embed(&texts).await
+    }
+}
@@ -0,0 +1,146 @@
+//! SOLITON Retry
+//!
+//! When to resend a request the upstream rejected as rate limited or failed.
+
+use crate::backoff::Backoff;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Retries allowed no matter what is configured
+pub const MAX_RETRIES: u32 = 10;
+
+/// Longest single wait, including one asked for by `Retry-After`
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Retry policy for 429 and 5xx responses
+///
+/// Delays double from `base_delay_ms`. With `respect_retry_after`, a
+/// `Retry-After` header in seconds replaces the computed delay. Both the
+/// retry count and each delay are capped so a call cannot hang forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay_ms: u64,
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay_ms: 500,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Policy that never retries
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a response with `status` is worth retrying
+    pub fn is_retryable(status: StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    }
+
+    /// Retries allowed after the first attempt
+    pub fn retries(&self) -> u32 {
+        self.max_retries.min(MAX_RETRIES)
+    }
+
+    /// Wait before retry `attempt` (0-based) of a response with `headers`
+    pub fn delay(&self, attempt: u32, headers: &HeaderMap) -> Duration {
+        if self.respect_retry_after {
+            if let Some(after) = retry_after(headers) {
+                return after.min(MAX_RETRY_DELAY);
+            }
+        }
+        Backoff::new(Duration::from_millis(self.base_delay_ms), MAX_RETRY_DELAY).delay(attempt)
+    }
+}
+
+/// `Retry-After` given in seconds; HTTP dates are ignored
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs: f64 = headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_delay_doubles_from_base() {
+        let retry = RetryConfig {
+            base_delay_ms: 100,
+            ..RetryConfig::default()
+        };
+        let delays: Vec<_> = (0..3).map(|n| retry.delay(n, &HeaderMap::new())).collect();
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_millis(100),
+                Duration::from_millis(200),
+                Duration::from_millis(400)
+            ]
+        );
+        assert_eq!(retry.delay(30, &HeaderMap::new()), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_retry_after_seconds_honored_and_capped() {
+        let retry = RetryConfig::default();
+        assert_eq!(retry.delay(0, &headers("2")), Duration::from_secs(2));
+        assert_eq!(retry.delay(0, &headers("3600")), MAX_RETRY_DELAY);
+        // Dates fall back to backoff
+        assert_eq!(
+            retry.delay(0, &headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Duration::from_millis(500)
+        );
+
+        let ignoring = RetryConfig {
+            respect_retry_after: false,
+            ..retry
+        };
+        assert_eq!(ignoring.delay(0, &headers("2")), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_retry_count_is_capped() {
+        let retry = RetryConfig {
+            max_retries: 1000,
+            ..RetryConfig::default()
+        };
+        assert_eq!(retry.retries(), MAX_RETRIES);
+        assert_eq!(RetryConfig::disabled().retries(), 0);
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        assert!(RetryConfig::is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(RetryConfig::is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(!RetryConfig::is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!RetryConfig::is_retryable(StatusCode::OK));
+    }
+}
@@ -0,0 +1,44 @@
+//! Extra header tests against a mock HTTP server
+
+use opensam_provider::{ChatParams, Message, OpenRouterProvider, Provider};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn test_extra_headers_sent_on_request() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/chat/completions")
+        .match_header("authorization", "Bearer sk-test")
+        .match_header("x-org-id", "org-42")
+        .match_header("x-project", "alpha")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "choices": [{"message": {"content": "ok"}, "finish_reason": "stop"}]
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let mut headers = HashMap::new();
+    headers.insert("X-Org-Id".to_string(), "org-42".to_string());
+    headers.insert("X-Project".to_string(), "alpha".to_string());
+    let provider = OpenRouterProvider::new("sk-test", Some(server.url()), None)
+        .with_extra_headers(headers)
+        .unwrap();
+
+    let response = provider
+        .chat(ChatParams {
+            model: "gpt-4".to_string(),
+            messages: vec![Message::user("Hello")],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("ok"));
+    mock.assert_async().await;
+}
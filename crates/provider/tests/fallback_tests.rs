@@ -0,0 +1,152 @@
+//! Fallback chain tests with scripted nodes
+
+use async_trait::async_trait;
+use opensam_provider::{ChatParams, ChatResponse, FallbackProvider, Provider, ProviderError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Node that always fails with a fresh copy of one error, or always answers
+struct ScriptedNode {
+    name: &'static str,
+    error: Option<fn() -> ProviderError>,
+    calls: Arc<AtomicUsize>,
+}
+
+impl ScriptedNode {
+    fn failing(name: &'static str, error: fn() -> ProviderError) -> (Box<Self>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let node = Self {
+            name,
+            error: Some(error),
+            calls: calls.clone(),
+        };
+        (Box::new(node), calls)
+    }
+
+    fn answering(name: &'static str) -> (Box<Self>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let node = Self {
+            name,
+            error: None,
+            calls: calls.clone(),
+        };
+        (Box::new(node), calls)
+    }
+}
+
+#[async_trait]
+impl Provider for ScriptedNode {
+    async fn chat(&self, _params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        match self.error {
+            Some(error) => Err(error()),
+            None => Ok(ChatResponse::text(format!("from {}", self.name))),
+        }
+    }
+
+    fn default_model(&self) -> String {
+        format!("{}-model", self.name)
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+fn unavailable() -> ProviderError {
+    ProviderError::Unavailable {
+        status: 503,
+        message: "down".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_falls_back_to_second_node() {
+    let (primary, primary_calls) = ScriptedNode::failing("primary", || ProviderError::RateLimited);
+    let (backup, backup_calls) = ScriptedNode::answering("backup");
+    let provider = FallbackProvider::new(vec![primary, backup]);
+
+    let response = provider.chat(ChatParams::default()).await.unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("from backup"));
+    assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+    assert_eq!(backup_calls.load(Ordering::SeqCst), 1);
+    assert_eq!(provider.last_answered(), Some(1));
+}
+
+#[tokio::test]
+async fn test_first_success_stops_the_chain() {
+    let (primary, _) = ScriptedNode::answering("primary");
+    let (backup, backup_calls) = ScriptedNode::answering("backup");
+    let provider = FallbackProvider::new(vec![primary, backup]);
+
+    let response = provider.chat(ChatParams::default()).await.unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("from primary"));
+    assert_eq!(backup_calls.load(Ordering::SeqCst), 0);
+    assert_eq!(provider.last_answered(), Some(0));
+}
+
+#[tokio::test]
+async fn test_all_failing_returns_last_error() {
+    let (first, _) = ScriptedNode::failing("first", || ProviderError::RateLimited);
+    let (second, second_calls) = ScriptedNode::failing("second", unavailable);
+    let provider = FallbackProvider::new(vec![first, second]);
+
+    let error = provider.chat(ChatParams::default()).await.unwrap_err();
+
+    assert!(
+        matches!(error, ProviderError::Unavailable { status: 503, .. }),
+        "{:?}",
+        error
+    );
+    assert_eq!(second_calls.load(Ordering::SeqCst), 1);
+    assert_eq!(provider.last_answered(), None);
+}
+
+#[tokio::test]
+async fn test_non_retriable_error_is_not_failed_over() {
+    let (primary, _) =
+        ScriptedNode::failing("primary", || ProviderError::Api("bad request".to_string()));
+    let (backup, backup_calls) = ScriptedNode::answering("backup");
+    let provider = FallbackProvider::new(vec![primary, backup]);
+
+    let error = provider.chat(ChatParams::default()).await.unwrap_err();
+
+    assert!(matches!(error, ProviderError::Api(_)));
+    assert_eq!(backup_calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_empty_chain_errors() {
+    let provider = FallbackProvider::new(Vec::new());
+    assert!(!provider.is_configured());
+    assert!(matches!(
+        provider.chat(ChatParams::default()).await,
+        Err(ProviderError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_reports_primary_model() {
+    let (primary, _) = ScriptedNode::answering("primary");
+    let (backup, _) = ScriptedNode::answering("backup");
+    let provider = FallbackProvider::new(vec![primary, backup]);
+
+    assert_eq!(provider.default_model(), "primary-model");
+    assert_eq!(provider.name(), "fallback");
+    assert_eq!(provider.nodes().len(), 2);
+}
+
+#[test]
+fn test_retriable_errors() {
+    assert!(ProviderError::RateLimited.is_retriable());
+    assert!(unavailable().is_retriable());
+    assert!(ProviderError::Timeout(std::time::Duration::from_secs(1)).is_retriable());
+    assert!(!ProviderError::Api("no".to_string()).is_retriable());
+    assert!(!ProviderError::NoApiKey.is_retriable());
+}
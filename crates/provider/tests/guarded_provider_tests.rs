@@ -0,0 +1,148 @@
+//! GuardedProvider Tests
+//!
+//! Verifies prompt trimming and the hard ceiling using a mocked inner provider.
+
+use async_trait::async_trait;
+use mockall::mock;
+use opensam_provider::{
+    estimate_tokens, ChatParams, ChatResponse, GuardedProvider, Message, Provider, ProviderError,
+    ToolCallDef,
+};
+use serde_json::json;
+
+mock! {
+    pub Provider {}
+
+    #[async_trait]
+    impl Provider for Provider {
+        async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError>;
+        fn default_model(&self) -> String;
+        fn is_configured(&self) -> bool;
+    }
+}
+
+fn params(messages: Vec<Message>) -> ChatParams {
+    ChatParams {
+        messages,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_estimate_tokens() {
+    // 40 chars -> 10 tokens + 4 overhead
+    let messages = vec![Message::user("a".repeat(40))];
+    assert_eq!(estimate_tokens(&messages), 14);
+    assert_eq!(estimate_tokens(&[]), 0);
+}
+
+#[tokio::test]
+async fn test_under_budget_passes_through() {
+    let mut mock = MockProvider::new();
+    mock.expect_chat()
+        .withf(|p| p.messages.len() == 2)
+        .times(1)
+        .returning(|_| Ok(ChatResponse::text("ok")));
+
+    let guarded = GuardedProvider::new(mock, 1000);
+    let result = guarded
+        .chat(params(vec![Message::system("sys"), Message::user("hi")]))
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_over_budget_trims_oldest_non_system() {
+    let mut mock = MockProvider::new();
+    mock.expect_chat()
+        .withf(|p| {
+            let contents: Vec<_> = p
+                .messages
+                .iter()
+                .map(|m| m.content.clone().unwrap_or_default())
+                .collect();
+            contents == vec!["sys".to_string(), "b".repeat(80), "latest".to_string()]
+        })
+        .times(1)
+        .returning(|_| Ok(ChatResponse::text("ok")));
+
+    // Each 80-char message is ~24 tokens; the full prompt is ~75
+    let guarded = GuardedProvider::new(mock, 50);
+    let result = guarded
+        .chat(params(vec![
+            Message::system("sys"),
+            Message::user("a".repeat(80)),
+            Message::assistant("b".repeat(80)),
+            Message::user("latest"),
+        ]))
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_trimming_drops_orphaned_tool_results() {
+    let mut mock = MockProvider::new();
+    mock.expect_chat()
+        .withf(|p| p.messages.iter().all(|m| m.role != "tool") && p.messages.len() == 2)
+        .times(1)
+        .returning(|_| Ok(ChatResponse::text("ok")));
+
+    let mut call = Message::assistant("");
+    call.tool_calls = Some(vec![ToolCallDef::new(
+        "call_1",
+        "read_file",
+        json!({"path": "x".repeat(200)}),
+    )]);
+
+    let guarded = GuardedProvider::new(mock, 40);
+    let result = guarded
+        .chat(params(vec![
+            Message::system("sys"),
+            call,
+            Message::tool("call_1", "read_file", "c".repeat(200)),
+            Message::user("latest"),
+        ]))
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_hard_over_budget_errors_without_calling_inner() {
+    let mut mock = MockProvider::new();
+    mock.expect_chat().times(0);
+
+    let guarded = GuardedProvider::new(mock, 20);
+    let result = guarded
+        .chat(params(vec![
+            Message::system("sys"),
+            Message::user("x".repeat(400)),
+        ]))
+        .await;
+
+    match result {
+        Err(ProviderError::PromptTooLarge { estimated, limit }) => {
+            assert_eq!(limit, 20);
+            assert!(estimated > limit);
+        }
+        other => panic!(
+            "Expected PromptTooLarge, got {:?}",
+            other.map(|r| r.content)
+        ),
+    }
+}
+
+#[test]
+fn test_delegates_model_and_configuration() {
+    let mut mock = MockProvider::new();
+    mock.expect_default_model()
+        .returning(|| "inner/model".to_string());
+    mock.expect_is_configured().returning(|| true);
+
+    let guarded = GuardedProvider::new(mock, 100);
+    assert_eq!(guarded.default_model(), "inner/model");
+    assert!(guarded.is_configured());
+    assert_eq!(guarded.max_prompt_tokens(), 100);
+}
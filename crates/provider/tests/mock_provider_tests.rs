@@ -6,7 +6,8 @@
 use async_trait::async_trait;
 use mockall::mock;
 use opensam_provider::{
-    ChatParams, ChatResponse, Message, Provider, ProviderError, Tool, ToolChoice,
+    ChatParams, ChatResponse, Message, Provider, ProviderCapabilities, ProviderError, Tool,
+    ToolChoice,
 };
 use serde_json::json;
 
@@ -76,6 +77,7 @@ async fn test_mock_provider_chat_with_tool_calls() {
                     arguments: json!({"arg": "value"}),
                 }],
                 finish_reason: "tool_calls".to_string(),
+                model: None,
                 usage: opensam_provider::Usage {
                     prompt_tokens: 10,
                     completion_tokens: 5,
@@ -91,6 +93,7 @@ async fn test_mock_provider_chat_with_tool_calls() {
         max_tokens: 100,
         temperature: 0.5,
         tool_choice: ToolChoice::Auto,
+        ..Default::default()
     };
 
     let response = mock.chat(params).await.unwrap();
@@ -152,6 +155,7 @@ async fn test_mock_provider_multiple_calls() {
             max_tokens: 100,
             temperature: 0.7,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let response = mock.chat(params).await.unwrap();
@@ -209,6 +213,7 @@ async fn test_mock_provider_with_complex_params() {
                 content: Some("Success!".to_string()),
                 tool_calls: vec![],
                 finish_reason: "stop".to_string(),
+                model: None,
                 usage: opensam_provider::Usage {
                     prompt_tokens: 100,
                     completion_tokens: 50,
@@ -228,6 +233,7 @@ async fn test_mock_provider_with_complex_params() {
         max_tokens: 2048,
         temperature: 0.5,
         tool_choice: ToolChoice::Auto,
+        ..Default::default()
     };
 
     let response = mock.chat(params).await.unwrap();
@@ -255,6 +261,7 @@ async fn test_mock_provider_different_responses_based_on_input() {
                     arguments: json!({}),
                 }],
                 finish_reason: "tool_calls".to_string(),
+                model: None,
                 usage: opensam_provider::Usage::default(),
             })
         } else {
@@ -294,6 +301,7 @@ impl ProviderConsumer {
             max_tokens: 100,
             temperature: 0.7,
             tool_choice: ToolChoice::Auto,
+            ..Default::default()
         };
 
         let response = self.provider.chat(params).await?;
@@ -331,12 +339,12 @@ async fn test_mock_provider_invalid_response_error() {
 
     mock.expect_chat()
         .times(1)
-        .returning(|_| Err(ProviderError::InvalidResponse));
+        .returning(|_| Err(ProviderError::InvalidResponse("empty".to_string())));
 
     let params = ChatParams::default();
     let result = mock.chat(params).await;
 
-    assert!(matches!(result, Err(ProviderError::InvalidResponse)));
+    assert!(matches!(result, Err(ProviderError::InvalidResponse(_))));
 }
 
 #[tokio::test]
@@ -354,3 +362,42 @@ async fn test_mock_provider_json_error() {
 
     assert!(matches!(result, Err(ProviderError::Json(_))));
 }
+
+/// Provider implementing only the required methods
+struct StubProvider;
+
+#[async_trait]
+impl Provider for StubProvider {
+    async fn chat(&self, _params: ChatParams) -> Result<ChatResponse, ProviderError> {
+        Ok(ChatResponse::text("stub"))
+    }
+
+    fn default_model(&self) -> String {
+        "stub/model".to_string()
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_stub_provider_reports_conservative_capabilities() {
+    let caps = StubProvider.capabilities();
+
+    assert_eq!(caps, ProviderCapabilities::default());
+    assert!(caps.tools);
+    assert!(!caps.streaming);
+    assert!(!caps.vision);
+    assert!(!caps.json_mode);
+}
+
+#[tokio::test]
+async fn test_stub_provider_has_no_embeddings() {
+    let err = StubProvider
+        .embed(&["hello".to_string()])
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind(), "unsupported");
+}
@@ -0,0 +1,165 @@
+//! RecordReplayProvider Tests
+//!
+//! Verifies cassettes record live calls and replay them without a provider.
+
+use async_trait::async_trait;
+use mockall::mock;
+use opensam_provider::{
+    ChatParams, ChatResponse, Message, Provider, ProviderCapabilities, ProviderError,
+    RecordReplayProvider,
+};
+
+mock! {
+    pub Provider {}
+
+    #[async_trait]
+    impl Provider for Provider {
+        async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError>;
+        fn default_model(&self) -> String;
+        fn is_configured(&self) -> bool;
+        fn capabilities(&self) -> ProviderCapabilities;
+    }
+}
+
+fn params(content: &str) -> ChatParams {
+    ChatParams {
+        model: "test-model".to_string(),
+        messages: vec![Message::system("You are helpful"), Message::user(content)],
+        ..Default::default()
+    }
+}
+
+fn echo_mock() -> MockProvider {
+    let mut mock = MockProvider::new();
+    mock.expect_chat().times(2).returning(|params| {
+        let last = params.messages.last().unwrap().content.clone().unwrap();
+        Ok(ChatResponse::text(format!("echo: {}", last)))
+    });
+    mock
+}
+
+async fn record(path: &std::path::Path) {
+    let recorder = RecordReplayProvider::record(echo_mock(), path);
+    recorder.chat(params("first")).await.unwrap();
+    recorder.chat(params("second")).await.unwrap();
+    assert!(!recorder.is_replay());
+    assert_eq!(recorder.interactions().len(), 2);
+}
+
+#[tokio::test]
+async fn test_record_writes_cassette() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("golden.json");
+    record(&path).await;
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json["interactions"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        json["interactions"][1]["response"]["content"],
+        "echo: second"
+    );
+}
+
+#[tokio::test]
+async fn test_replay_is_deterministic() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("golden.json");
+    record(&path).await;
+
+    for _ in 0..2 {
+        let replay = RecordReplayProvider::replay(&path).unwrap();
+        assert!(replay.is_replay());
+        assert!(replay.is_configured());
+        assert_eq!(replay.default_model(), "test-model");
+
+        let first = replay.chat(params("first")).await.unwrap();
+        let second = replay.chat(params("second")).await.unwrap();
+        assert_eq!(first.content.as_deref(), Some("echo: first"));
+        assert_eq!(second.content.as_deref(), Some("echo: second"));
+    }
+}
+
+#[tokio::test]
+async fn test_replay_errors_on_unexpected_request() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("golden.json");
+    record(&path).await;
+
+    let replay = RecordReplayProvider::replay(&path).unwrap();
+    let err = replay.chat(params("unrecorded")).await.unwrap_err();
+    assert_eq!(err.kind(), "cassette");
+}
+
+#[tokio::test]
+async fn test_replay_does_not_serve_an_interaction_twice() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("golden.json");
+    record(&path).await;
+
+    let replay = RecordReplayProvider::replay(&path).unwrap();
+    replay.chat(params("first")).await.unwrap();
+    assert!(replay.chat(params("first")).await.is_err());
+}
+
+#[tokio::test]
+async fn test_replay_matches_sampling_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("golden.json");
+    record(&path).await;
+
+    let replay = RecordReplayProvider::replay(&path).unwrap();
+    let sampled = ChatParams {
+        top_p: Some(0.5),
+        stop: vec!["END".to_string()],
+        ..params("first")
+    };
+    assert!(replay.chat(sampled).await.is_err());
+    assert!(replay.chat(params("first")).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_replay_cassette_without_sampling_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("golden.json");
+    record(&path).await;
+
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    for interaction in json["interactions"].as_array_mut().unwrap() {
+        let request = interaction["request"].as_object_mut().unwrap();
+        for field in ["top_p", "frequency_penalty", "presence_penalty", "stop"] {
+            request.remove(field);
+        }
+    }
+    std::fs::write(&path, json.to_string()).unwrap();
+
+    let replay = RecordReplayProvider::replay(&path).unwrap();
+    let first = replay.chat(params("first")).await.unwrap();
+    assert_eq!(first.content.as_deref(), Some("echo: first"));
+}
+
+#[test]
+fn test_record_forwards_capabilities() {
+    let mut mock = MockProvider::new();
+    mock.expect_capabilities()
+        .returning(|| ProviderCapabilities {
+            tools: false,
+            vision: true,
+            ..Default::default()
+        });
+    let dir = tempfile::tempdir().unwrap();
+    let recorder = RecordReplayProvider::record(mock, dir.path().join("golden.json"));
+
+    let capabilities = recorder.capabilities();
+    assert!(!capabilities.tools);
+    assert!(capabilities.vision);
+}
+
+#[test]
+fn test_replay_missing_cassette() {
+    let err = RecordReplayProvider::replay("/nonexistent/golden.json")
+        .err()
+        .unwrap();
+    assert!(matches!(err, ProviderError::Cassette(_)));
+}
@@ -0,0 +1,133 @@
+//! RedactingProvider Tests
+//!
+//! Verifies patterns are scrubbed from prompts and replies.
+
+use async_trait::async_trait;
+use mockall::mock;
+use opensam_provider::{
+    ChatParams, ChatResponse, Message, Provider, ProviderError, RedactingProvider, ToolCall,
+    ToolCallDef,
+};
+
+mock! {
+    pub Provider {}
+
+    #[async_trait]
+    impl Provider for Provider {
+        async fn chat(&self, params: ChatParams) -> Result<ChatResponse, ProviderError>;
+        fn default_model(&self) -> String;
+        fn is_configured(&self) -> bool;
+    }
+}
+
+fn patterns() -> Vec<String> {
+    vec![
+        r"[\w.+-]+@[\w-]+\.[\w.]+".to_string(),
+        r"sk-[A-Za-z0-9]{8,}".to_string(),
+    ]
+}
+
+fn params(content: &str) -> ChatParams {
+    ChatParams {
+        messages: vec![Message::system("You are helpful"), Message::user(content)],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_redacts_outgoing_prompt() {
+    let mut mock = MockProvider::new();
+    mock.expect_chat()
+        .withf(|params| {
+            params.messages[1].content.as_deref()
+                == Some("Mail [REDACTED] with key [REDACTED] please")
+        })
+        .times(1)
+        .returning(|_| Ok(ChatResponse::text("ok")));
+
+    let provider = RedactingProvider::from_patterns(mock, &patterns()).unwrap();
+    let response = provider
+        .chat(params(
+            "Mail snake@foxhound.mil with key sk-abcdef123456 please",
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("ok"));
+}
+
+#[tokio::test]
+async fn test_redacts_returned_content() {
+    let mut mock = MockProvider::new();
+    mock.expect_chat()
+        .times(1)
+        .returning(|_| Ok(ChatResponse::text("Contact otacon@philanthropy.org")));
+
+    let provider = RedactingProvider::from_patterns(mock, &patterns()).unwrap();
+    let response = provider.chat(params("Who do I call?")).await.unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("Contact [REDACTED]"));
+}
+
+#[tokio::test]
+async fn test_non_matching_content_untouched() {
+    let mut mock = MockProvider::new();
+    mock.expect_chat()
+        .withf(|params| {
+            params.messages[0].content.as_deref() == Some("You are helpful")
+                && params.messages[1].content.as_deref() == Some("Status report")
+        })
+        .times(1)
+        .returning(|_| Ok(ChatResponse::text("All quiet")));
+
+    let provider = RedactingProvider::from_patterns(mock, &patterns()).unwrap();
+    let response = provider.chat(params("Status report")).await.unwrap();
+
+    assert_eq!(response.content.as_deref(), Some("All quiet"));
+}
+
+#[tokio::test]
+async fn test_redacts_tool_call_arguments() {
+    let mut mock = MockProvider::new();
+    mock.expect_chat()
+        .withf(|params| {
+            let calls = params.messages[1].tool_calls.as_ref().unwrap();
+            calls[0].function.arguments
+                == serde_json::json!({"to": ["[REDACTED]"], "subject": "hi"})
+        })
+        .times(1)
+        .returning(|_| {
+            let mut response = ChatResponse::text("");
+            response.tool_calls = vec![ToolCall {
+                id: "call_2".to_string(),
+                name: "send".to_string(),
+                arguments: serde_json::json!({"key": "sk-abcdef123456"}),
+            }];
+            Ok(response)
+        });
+
+    let mut sent = Message::assistant("");
+    sent.tool_calls = Some(vec![ToolCallDef::new(
+        "call_1",
+        "send",
+        serde_json::json!({"to": ["snake@foxhound.mil"], "subject": "hi"}),
+    )]);
+    let params = ChatParams {
+        messages: vec![Message::system("You are helpful"), sent],
+        ..Default::default()
+    };
+
+    let provider = RedactingProvider::from_patterns(mock, &patterns()).unwrap();
+    let response = provider.chat(params).await.unwrap();
+
+    assert_eq!(
+        response.tool_calls[0].arguments,
+        serde_json::json!({"key": "[REDACTED]"})
+    );
+}
+
+#[test]
+fn test_invalid_pattern_rejected() {
+    let result = RedactingProvider::from_patterns(MockProvider::new(), &["(unclosed".to_string()]);
+    assert!(result.is_err());
+}
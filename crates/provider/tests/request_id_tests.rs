@@ -0,0 +1,153 @@
+//! Request id header tests against a mock HTTP server
+
+use opensam_provider::{
+    ChatParams, Message, OpenRouterProvider, Provider, ProviderError, RetryConfig,
+};
+use serde_json::json;
+
+fn params() -> ChatParams {
+    ChatParams {
+        model: "gpt-4".to_string(),
+        messages: vec![Message::user("Hello")],
+        ..Default::default()
+    }
+}
+
+/// Mock that replies with the request id header it received
+async fn echo_request_id(server: &mut mockito::ServerGuard, header: &'static str) -> mockito::Mock {
+    server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            let id = request
+                .header(header)
+                .first()
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+                .to_string();
+            json!({
+                "choices": [{"message": {"content": id}, "finish_reason": "stop"}]
+            })
+            .to_string()
+            .into_bytes()
+        })
+        .expect(2)
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn test_each_call_sends_distinct_request_id() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = echo_request_id(&mut server, "x-request-id").await;
+    let provider = OpenRouterProvider::new("sk-test", Some(server.url()), None);
+
+    let first = provider.chat(params()).await.unwrap().content.unwrap();
+    let second = provider.chat(params()).await.unwrap().content.unwrap();
+
+    assert_eq!(first.len(), 36);
+    assert_eq!(second.len(), 36);
+    assert_ne!(first, second);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_request_id_header_is_configurable() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = echo_request_id(&mut server, "x-trace").await;
+    let provider = OpenRouterProvider::new("sk-test", Some(server.url()), None)
+        .with_request_id_header("X-Trace")
+        .unwrap();
+
+    for _ in 0..2 {
+        let id = provider.chat(params()).await.unwrap().content.unwrap();
+        assert_eq!(id.len(), 36);
+    }
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_request_id_header_can_be_disabled() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = echo_request_id(&mut server, "x-request-id").await;
+    let provider = OpenRouterProvider::new("sk-test", Some(server.url()), None)
+        .with_request_id_header("")
+        .unwrap();
+
+    for _ in 0..2 {
+        let id = provider.chat(params()).await.unwrap().content.unwrap();
+        assert_eq!(id, "");
+    }
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_invalid_request_id_header() {
+    let result =
+        OpenRouterProvider::new("sk-test", None, None).with_request_id_header("Bad Header");
+    assert!(matches!(result, Err(ProviderError::InvalidHeader(_))));
+}
+
+#[tokio::test]
+async fn test_failed_request_reports_request_id() {
+    let mut server = mockito::Server::new_async().await;
+    let sent_id = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+    let captured = sent_id.clone();
+    let mock = server
+        .mock("POST", "/chat/completions")
+        .with_status(500)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            if let Some(id) = request.header("x-request-id").first() {
+                *captured.lock().unwrap() = id.to_str().unwrap().to_string();
+            }
+            json!({"error": {"message": "upstream exploded"}})
+                .to_string()
+                .into_bytes()
+        })
+        .create_async()
+        .await;
+    let provider = OpenRouterProvider::new("sk-test", Some(server.url()), None)
+        .with_retry(RetryConfig::disabled());
+
+    let error = provider.chat(params()).await.unwrap_err();
+    let sent_id = sent_id.lock().unwrap().clone();
+
+    assert!(matches!(
+        error.untagged(),
+        ProviderError::Unavailable { status: 500, .. }
+    ));
+    assert!(!sent_id.is_empty());
+    assert_eq!(error.request_id(), Some(sent_id.as_str()));
+    assert_eq!(
+        error.to_string(),
+        format!("NODE DOWN (500): upstream exploded [request {}]", sent_id)
+    );
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_rate_limited_request_reports_request_id() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/chat/completions")
+        .with_status(429)
+        .with_header("content-type", "application/json")
+        .with_body(json!({"error": {"message": "slow down"}}).to_string())
+        .create_async()
+        .await;
+    let provider = OpenRouterProvider::new("sk-test", Some(server.url()), None)
+        .with_retry(RetryConfig::disabled());
+
+    let error = provider.chat(params()).await.unwrap_err();
+
+    assert!(matches!(error.untagged(), ProviderError::RateLimited));
+    assert_eq!(error.kind(), "rate_limited");
+    assert!(error.is_retriable());
+    let request_id = error.request_id().unwrap();
+    assert!(error
+        .to_string()
+        .ends_with(&format!("[request {}]", request_id)));
+    mock.assert_async().await;
+}
@@ -0,0 +1,209 @@
+//! Rate limit retry tests against a mock HTTP server
+
+use opensam_provider::{
+    ChatParams, Message, OpenRouterProvider, Provider, ProviderError, RetryConfig,
+};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn params() -> ChatParams {
+    ChatParams {
+        model: "gpt-4".to_string(),
+        messages: vec![Message::user("Hello")],
+        ..Default::default()
+    }
+}
+
+fn ok_body() -> String {
+    json!({
+        "choices": [{"message": {"content": "ok"}, "finish_reason": "stop"}]
+    })
+    .to_string()
+}
+
+fn fast_retry() -> RetryConfig {
+    RetryConfig {
+        max_retries: 3,
+        base_delay_ms: 50,
+        respect_retry_after: true,
+    }
+}
+
+/// Mock answering `status` for the first `failures` requests, logging arrivals
+async fn failing_then_ok(
+    server: &mut mockito::ServerGuard,
+    status: usize,
+    failures: usize,
+    retry_after: Option<&str>,
+) -> (mockito::Mock, mockito::Mock, Arc<Mutex<Vec<Instant>>>) {
+    let arrivals = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&arrivals);
+    let mut failing = server
+        .mock("POST", "/chat/completions")
+        .with_status(status)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |_| {
+            log.lock().unwrap().push(Instant::now());
+            json!({"error": {"message": "slow down"}})
+                .to_string()
+                .into_bytes()
+        })
+        .expect(failures);
+    if let Some(after) = retry_after {
+        failing = failing.with_header("retry-after", after);
+    }
+    let failing = failing.create_async().await;
+
+    let log = Arc::clone(&arrivals);
+    let ok = server
+        .mock("POST", "/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |_| {
+            log.lock().unwrap().push(Instant::now());
+            ok_body().into_bytes()
+        })
+        .create_async()
+        .await;
+    (failing, ok, arrivals)
+}
+
+fn gaps(arrivals: &[Instant]) -> Vec<Duration> {
+    arrivals.windows(2).map(|w| w[1] - w[0]).collect()
+}
+
+#[tokio::test]
+async fn test_rate_limit_retried_with_growing_delays() {
+    let mut server = mockito::Server::new_async().await;
+    let (failing, ok, arrivals) = failing_then_ok(&mut server, 429, 2, None).await;
+    let provider =
+        OpenRouterProvider::new("sk-test", Some(server.url()), None).with_retry(fast_retry());
+
+    let response = provider.chat(params()).await.unwrap();
+    assert_eq!(response.content.as_deref(), Some("ok"));
+    failing.assert_async().await;
+    ok.assert_async().await;
+
+    let gaps = gaps(&arrivals.lock().unwrap());
+    assert_eq!(gaps.len(), 2);
+    assert!(gaps[0] >= Duration::from_millis(50), "{:?}", gaps);
+    assert!(gaps[1] >= Duration::from_millis(100), "{:?}", gaps);
+    assert!(gaps[1] > gaps[0], "{:?}", gaps);
+}
+
+#[tokio::test]
+async fn test_server_errors_are_retried() {
+    let mut server = mockito::Server::new_async().await;
+    let (failing, ok, _) = failing_then_ok(&mut server, 503, 1, None).await;
+    let provider =
+        OpenRouterProvider::new("sk-test", Some(server.url()), None).with_retry(fast_retry());
+
+    assert!(provider.chat(params()).await.is_ok());
+    failing.assert_async().await;
+    ok.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retry_after_header_is_honored() {
+    let mut server = mockito::Server::new_async().await;
+    let (_failing, _ok, arrivals) = failing_then_ok(&mut server, 429, 1, Some("1")).await;
+    let provider =
+        OpenRouterProvider::new("sk-test", Some(server.url()), None).with_retry(fast_retry());
+
+    assert!(provider.chat(params()).await.is_ok());
+    assert!(gaps(&arrivals.lock().unwrap())[0] >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_retry_after_header_can_be_ignored() {
+    let mut server = mockito::Server::new_async().await;
+    let (_failing, _ok, arrivals) = failing_then_ok(&mut server, 429, 1, Some("30")).await;
+    let provider =
+        OpenRouterProvider::new("sk-test", Some(server.url()), None).with_retry(RetryConfig {
+            respect_retry_after: false,
+            ..fast_retry()
+        });
+
+    assert!(provider.chat(params()).await.is_ok());
+    assert!(gaps(&arrivals.lock().unwrap())[0] < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_retries() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/chat/completions")
+        .with_status(429)
+        .with_header("content-type", "application/json")
+        .with_body(json!({"error": {"message": "slow down"}}).to_string())
+        .expect(2)
+        .create_async()
+        .await;
+    let provider =
+        OpenRouterProvider::new("sk-test", Some(server.url()), None).with_retry(RetryConfig {
+            max_retries: 1,
+            ..fast_retry()
+        });
+
+    let error = provider.chat(params()).await.unwrap_err();
+    assert!(matches!(error.untagged(), ProviderError::RateLimited));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_client_errors_are_not_retried() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/chat/completions")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(json!({"error": {"message": "bad request"}}).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let provider =
+        OpenRouterProvider::new("sk-test", Some(server.url()), None).with_retry(fast_retry());
+
+    let error = provider.chat(params()).await.unwrap_err();
+    assert!(matches!(error.untagged(), ProviderError::Api(_)));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_html_error_pages_keep_their_status() {
+    let mut server = mockito::Server::new_async().await;
+    let down = server
+        .mock("POST", "/chat/completions")
+        .with_status(503)
+        .with_header("content-type", "text/html")
+        .with_body("<html><body>Service Unavailable</body></html>")
+        .create_async()
+        .await;
+    let provider =
+        OpenRouterProvider::new("sk-test", Some(server.url()), None).with_retry(RetryConfig {
+            max_retries: 0,
+            ..fast_retry()
+        });
+
+    let error = provider.chat(params()).await.unwrap_err();
+    assert!(matches!(
+        error.untagged(),
+        ProviderError::Unavailable { status: 503, .. }
+    ));
+    assert!(error.is_retriable());
+    down.remove_async().await;
+
+    let rejected = server
+        .mock("POST", "/chat/completions")
+        .with_status(400)
+        .with_header("content-type", "text/html")
+        .with_body("<html><body>Bad Request</body></html>")
+        .create_async()
+        .await;
+
+    let error = provider.chat(params()).await.unwrap_err();
+    assert!(matches!(error.untagged(), ProviderError::Api(_)));
+    assert!(!error.is_retriable());
+    rejected.assert_async().await;
+}
@@ -0,0 +1,66 @@
+//! Request timeout tests against a server that never answers
+
+use opensam_provider::{
+    ChatParams, Message, OpenRouterProvider, Provider, ProviderError, RetryConfig,
+};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+
+fn params() -> ChatParams {
+    ChatParams {
+        model: "gpt-4".to_string(),
+        messages: vec![Message::user("Hello")],
+        ..Default::default()
+    }
+}
+
+/// Accept connections and hold them open without replying
+async fn silent_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            held.push(stream);
+        }
+    });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn test_default_timeout() {
+    let provider = OpenRouterProvider::new("sk-test", None, None);
+    assert_eq!(provider.timeout(), Duration::from_secs(120));
+}
+
+#[tokio::test]
+async fn test_slow_chat_times_out_within_bound() {
+    let base = silent_server().await;
+    let provider = OpenRouterProvider::new("sk-test", Some(base), None)
+        .with_timeout(Duration::from_millis(200))
+        .with_retry(RetryConfig::disabled());
+
+    let start = Instant::now();
+    let err = provider.chat(params()).await.unwrap_err();
+    let elapsed = start.elapsed();
+
+    assert!(
+        matches!(err.untagged(), ProviderError::Timeout(t) if *t == Duration::from_millis(200)),
+        "{:?}",
+        err
+    );
+    assert_eq!(err.kind(), "timeout");
+    assert!(err.request_id().is_some());
+    assert!(elapsed >= Duration::from_millis(200), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(2), "{:?}", elapsed);
+}
+
+#[tokio::test]
+async fn test_slow_embed_times_out() {
+    let base = silent_server().await;
+    let provider = OpenRouterProvider::new("sk-test", Some(base), None)
+        .with_timeout(Duration::from_millis(200));
+
+    let err = provider.embed(&["hi".to_string()]).await.unwrap_err();
+    assert!(matches!(err, ProviderError::Timeout(_)), "{:?}", err);
+}
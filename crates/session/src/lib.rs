@@ -1,14 +1,89 @@
 //! Session management for conversation history
 
 use chrono::{DateTime, Local};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use opensam_provider::Backoff;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use tracing::{debug, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, warn};
+
+pub mod shared;
+pub mod writer;
+
+pub use shared::{SessionGuard, SharedSessionManager};
+pub use writer::{FsWriter, SessionWriter};
 
 /// Default maximum number of messages in a session
 pub const DEFAULT_MAX_MESSAGES: usize = 100;
 
+/// Default number of recent messages sent to the model
+pub const DEFAULT_CONTEXT_WINDOW: usize = 20;
+
+/// Default maximum number of session metadata entries
+pub const DEFAULT_MAX_METADATA_ENTRIES: usize = 256;
+
+/// Default number of attempts when saving a session
+pub const DEFAULT_SAVE_ATTEMPTS: u32 = 3;
+
+/// Content prefix of the message written by `summarize_old`
+pub const SUMMARY_PREFIX: &str = "◆ EARLIER CONTEXT SUMMARY:\n";
+
+/// Message `extra` flag marking a summary
+pub const SUMMARY_KEY: &str = "summary";
+
+/// Metadata key holding text appended to this session's system prompt
+pub const SYSTEM_SUFFIX_KEY: &str = "system_suffix";
+
+/// Gzip stream magic bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// On-disk encoding for saved sessions
+///
+/// Loading detects gzip by its magic bytes, so files written in any format
+/// remain readable whatever the current setting is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SessionFormat {
+    /// Indented JSON
+    #[default]
+    Pretty,
+    /// Single-line JSON
+    Compact,
+    /// Compact JSON compressed with gzip
+    GzippedJson,
+}
+
+impl SessionFormat {
+    /// Encode a session in this format
+    pub fn encode(&self, session: &Session) -> std::io::Result<Vec<u8>> {
+        match self {
+            SessionFormat::Pretty => Ok(serde_json::to_vec_pretty(session)?),
+            SessionFormat::Compact => Ok(serde_json::to_vec(session)?),
+            SessionFormat::GzippedJson => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(&serde_json::to_vec(session)?)?;
+                encoder.finish()
+            }
+        }
+    }
+
+    /// Decode a session written in any format
+    pub fn decode(bytes: &[u8]) -> std::io::Result<Session> {
+        if bytes.starts_with(&GZIP_MAGIC) {
+            let mut json = Vec::new();
+            GzDecoder::new(bytes).read_to_end(&mut json)?;
+            Ok(serde_json::from_slice(&json)?)
+        } else {
+            Ok(serde_json::from_slice(bytes)?)
+        }
+    }
+}
+
 /// A conversation session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
@@ -26,12 +101,26 @@ pub struct Session {
     /// Maximum number of messages before truncation
     #[serde(default = "default_max_messages")]
     pub max_messages: usize,
+    /// Number of recent messages returned by `history`
+    #[serde(default = "default_context_window")]
+    pub context_window: usize,
+    /// Maximum number of entries accepted by `set_metadata`
+    #[serde(skip, default = "default_max_metadata_entries")]
+    pub max_metadata_entries: usize,
+}
+
+fn default_max_metadata_entries() -> usize {
+    DEFAULT_MAX_METADATA_ENTRIES
 }
 
 fn default_max_messages() -> usize {
     DEFAULT_MAX_MESSAGES
 }
 
+fn default_context_window() -> usize {
+    DEFAULT_CONTEXT_WINDOW
+}
+
 /// A message in the session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
@@ -54,6 +143,11 @@ impl Session {
 
     /// Create a new session with specified max_messages
     pub fn with_max_messages(key: impl Into<String>, max_messages: usize) -> Self {
+        Self::with_limits(key, max_messages, DEFAULT_CONTEXT_WINDOW)
+    }
+
+    /// Create a new session with separate storage and context limits
+    pub fn with_limits(key: impl Into<String>, max_messages: usize, context_window: usize) -> Self {
         let now = Local::now();
         Self {
             key: key.into(),
@@ -62,7 +156,51 @@ impl Session {
             updated_at: now,
             metadata: HashMap::new(),
             max_messages,
+            context_window,
+            max_metadata_entries: DEFAULT_MAX_METADATA_ENTRIES,
+        }
+    }
+
+    /// Set a metadata entry
+    ///
+    /// New keys beyond `max_metadata_entries` are dropped with a warning;
+    /// existing keys can always be updated. Returns whether the value was stored.
+    pub fn set_metadata(&mut self, key: impl Into<String>, value: impl Serialize) -> bool {
+        let key = key.into();
+        let Ok(value) = serde_json::to_value(value) else {
+            return false;
+        };
+        if !self.metadata.contains_key(&key) && self.metadata.len() >= self.max_metadata_entries {
+            warn!(
+                "Session {} metadata cap ({}) reached, dropping '{}'",
+                self.key, self.max_metadata_entries, key
+            );
+            return false;
+        }
+        self.metadata.insert(key, value);
+        self.updated_at = Local::now();
+        true
+    }
+
+    /// Text appended to the system prompt for this session
+    pub fn system_suffix(&self) -> Option<&str> {
+        self.metadata
+            .get(SYSTEM_SUFFIX_KEY)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Set the system prompt suffix; blank text removes it
+    ///
+    /// Returns whether the suffix was stored (see `set_metadata`).
+    pub fn set_system_suffix(&mut self, text: &str) -> bool {
+        if text.trim().is_empty() {
+            self.metadata.remove(SYSTEM_SUFFIX_KEY);
+            self.updated_at = Local::now();
+            return true;
         }
+        self.set_metadata(SYSTEM_SUFFIX_KEY, text.trim())
     }
 
     /// Add a message to the session
@@ -107,6 +245,39 @@ impl Session {
             .collect()
     }
 
+    /// Get the most recent `context_window` messages for LLM context
+    pub fn history(&self) -> Vec<opensam_provider::Message> {
+        self.get_history(self.context_window)
+    }
+
+    /// Messages older than the `keep_recent` most recent ones
+    pub fn old_messages(&self, keep_recent: usize) -> &[Message] {
+        &self.messages[..self.messages.len().saturating_sub(keep_recent)]
+    }
+
+    /// Replace all but the `keep_recent` most recent messages with a summary
+    ///
+    /// The summary is stored as a leading system message. Returns how many
+    /// messages it replaced.
+    pub fn summarize_old(&mut self, keep_recent: usize, summary: impl Into<String>) -> usize {
+        let old = self.old_messages(keep_recent).len();
+        if old == 0 {
+            return 0;
+        }
+        let mut extra = HashMap::new();
+        extra.insert(SUMMARY_KEY.to_string(), serde_json::Value::Bool(true));
+        let summary = Message {
+            role: "system".to_string(),
+            content: format!("{}{}", SUMMARY_PREFIX, summary.into()),
+            timestamp: Local::now(),
+            extra,
+        };
+        self.messages.splice(0..old, [summary]);
+        self.updated_at = Local::now();
+        debug!("Session {} summarized {} messages", self.key, old);
+        old
+    }
+
     /// Clear all messages
     pub fn clear(&mut self) {
         self.messages.clear();
@@ -123,6 +294,16 @@ impl Session {
         self.max_messages = max_messages;
         self.enforce_max_messages();
     }
+
+    /// Get the context window size
+    pub fn context_window(&self) -> usize {
+        self.context_window
+    }
+
+    /// Set the number of recent messages returned by `history`
+    pub fn set_context_window(&mut self, context_window: usize) {
+        self.context_window = context_window;
+    }
 }
 
 /// Manages conversation sessions
@@ -130,6 +311,12 @@ pub struct SessionManager {
     sessions_dir: PathBuf,
     cache: HashMap<String, Session>,
     max_messages: usize,
+    context_window: usize,
+    max_metadata_entries: usize,
+    format: SessionFormat,
+    writer: Arc<dyn SessionWriter>,
+    save_attempts: u32,
+    save_backoff: Backoff,
 }
 
 impl SessionManager {
@@ -140,6 +327,15 @@ impl SessionManager {
 
     /// Create a new session manager with specified max_messages
     pub fn with_max_messages(sessions_dir: impl AsRef<Path>, max_messages: usize) -> Self {
+        Self::with_limits(sessions_dir, max_messages, DEFAULT_CONTEXT_WINDOW)
+    }
+
+    /// Create a new session manager with separate storage and context limits
+    pub fn with_limits(
+        sessions_dir: impl AsRef<Path>,
+        max_messages: usize,
+        context_window: usize,
+    ) -> Self {
         let sessions_dir = sessions_dir.as_ref().to_path_buf();
         std::fs::create_dir_all(&sessions_dir).ok();
 
@@ -147,40 +343,123 @@ impl SessionManager {
             sessions_dir,
             cache: HashMap::new(),
             max_messages,
+            context_window,
+            max_metadata_entries: DEFAULT_MAX_METADATA_ENTRIES,
+            format: SessionFormat::default(),
+            writer: Arc::new(FsWriter),
+            save_attempts: DEFAULT_SAVE_ATTEMPTS,
+            save_backoff: Backoff::new(Duration::from_millis(100), Duration::from_secs(2)),
         }
     }
 
+    /// Persist sessions through `writer` instead of the filesystem
+    pub fn with_writer(mut self, writer: impl SessionWriter + 'static) -> Self {
+        self.writer = Arc::new(writer);
+        self
+    }
+
+    /// Attempt each save up to `attempts` times in total
+    pub fn with_save_attempts(mut self, attempts: u32) -> Self {
+        self.save_attempts = attempts.max(1);
+        self
+    }
+
+    /// Retry failed saves up to `attempts` times in total, waiting per `backoff`
+    pub fn with_save_retry(mut self, attempts: u32, backoff: Backoff) -> Self {
+        self.save_attempts = attempts.max(1);
+        self.save_backoff = backoff;
+        self
+    }
+
+    /// Get the metadata entry cap for new sessions
+    pub fn max_metadata_entries(&self) -> usize {
+        self.max_metadata_entries
+    }
+
+    /// Set the metadata entry cap for sessions created or loaded later
+    pub fn with_max_metadata_entries(mut self, max_metadata_entries: usize) -> Self {
+        self.max_metadata_entries = max_metadata_entries;
+        self
+    }
+
+    /// Set the format used when saving sessions
+    pub fn with_format(mut self, format: SessionFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Get the save format
+    pub fn format(&self) -> SessionFormat {
+        self.format
+    }
+
+    /// Change the format used for future saves
+    pub fn set_format(&mut self, format: SessionFormat) {
+        self.format = format;
+    }
+
     /// Get or create a session
     pub async fn get_or_create(&mut self, key: &str) -> &mut Session {
         if !self.cache.contains_key(key) {
-            let session = self
-                .load(key)
-                .await
-                .unwrap_or_else(|| Session::with_max_messages(key, self.max_messages));
+            let mut session = self.load(key).await.unwrap_or_else(|| {
+                Session::with_limits(key, self.max_messages, self.context_window)
+            });
+            session.max_metadata_entries = self.max_metadata_entries;
             self.cache.insert(key.to_string(), session);
         }
         self.cache.get_mut(key).unwrap()
     }
 
     /// Save a session
+    ///
+    /// Transient write failures are retried with backoff. If every attempt
+    /// fails the error is returned; the in-memory session is untouched, so
+    /// the next save writes it in full.
     pub async fn save(&self, session: &Session) -> std::io::Result<()> {
         let path = self.session_path(&session.key);
-        let content = serde_json::to_string_pretty(session)?;
-        tokio::fs::write(path, content).await?;
-        debug!("Saved session: {}", session.key);
-        Ok(())
+        let content = self.format.encode(session)?;
+
+        let mut attempt = 0;
+        loop {
+            match self.writer.write(&path, &content).await {
+                Ok(()) => {
+                    debug!("Saved session: {}", session.key);
+                    return Ok(());
+                }
+                Err(e) if attempt + 1 < self.save_attempts => {
+                    let delay = self.save_backoff.delay(attempt);
+                    warn!(
+                        "Failed to save session {} (attempt {}/{}), retrying in {:?}: {}",
+                        session.key,
+                        attempt + 1,
+                        self.save_attempts,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    error!(
+                        "Giving up saving session {} after {} attempts, keeping it in memory: {}",
+                        session.key, self.save_attempts, e
+                    );
+                    return Err(e);
+                }
+            }
+        }
     }
 
     /// Load a session from disk
-    async fn load(&self, key: &str) -> Option<Session> {
+    pub(crate) async fn load(&self, key: &str) -> Option<Session> {
         let path = self.session_path(key);
         if !path.exists() {
             return None;
         }
 
-        match tokio::fs::read_to_string(&path).await {
+        match tokio::fs::read(&path).await {
             Ok(content) => {
-                match serde_json::from_str::<Session>(&content) {
+                match SessionFormat::decode(&content) {
                     Ok(mut session) => {
                         // Update max_messages to current setting if different
                         if session.max_messages != self.max_messages {
@@ -188,6 +467,7 @@ impl SessionManager {
                             // Truncate if necessary
                             session.enforce_max_messages();
                         }
+                        session.context_window = self.context_window;
                         debug!("Loaded session: {}", key);
                         Some(session)
                     }
@@ -204,6 +484,12 @@ impl SessionManager {
         }
     }
 
+    /// When a session's file was last modified, if it exists
+    pub(crate) async fn modified_at(&self, key: &str) -> Option<std::time::SystemTime> {
+        let metadata = tokio::fs::metadata(self.session_path(key)).await.ok()?;
+        metadata.modified().ok()
+    }
+
     /// Get the file path for a session
     fn session_path(&self, key: &str) -> PathBuf {
         let safe_key = key.replace([':', '/'], "_");
@@ -252,4 +538,17 @@ impl SessionManager {
             session.set_max_messages(max_messages);
         }
     }
+
+    /// Get the context window setting
+    pub fn context_window(&self) -> usize {
+        self.context_window
+    }
+
+    /// Update context_window for all cached sessions and future sessions
+    pub fn set_context_window(&mut self, context_window: usize) {
+        self.context_window = context_window;
+        for session in self.cache.values_mut() {
+            session.set_context_window(context_window);
+        }
+    }
 }
@@ -0,0 +1,197 @@
+//! Session manager shareable across tasks
+//!
+//! Each session key has its own lock, so turns on different sessions run
+//! concurrently while access to the same session is serialized. A cached
+//! session is loaded again when its file changes on disk, so edits made by
+//! another process (such as `sam session set-suffix`) are not overwritten.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::{Session, SessionManager};
+
+type SessionSlot = Arc<Mutex<Option<Cached>>>;
+type DirtySet = Arc<std::sync::Mutex<HashSet<String>>>;
+
+/// A loaded session and the file modification time it matches
+struct Cached {
+    session: Session,
+    synced_at: Option<SystemTime>,
+}
+
+/// Cloneable handle to sessions with per-key locking
+#[derive(Clone)]
+pub struct SharedSessionManager {
+    manager: Arc<SessionManager>,
+    slots: Arc<std::sync::Mutex<HashMap<String, SessionSlot>>>,
+    dirty: DirtySet,
+}
+
+impl SharedSessionManager {
+    /// Share a manager; its limits and format apply to every session
+    pub fn new(manager: SessionManager) -> Self {
+        Self {
+            manager: Arc::new(manager),
+            slots: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            dirty: DirtySet::default(),
+        }
+    }
+
+    /// Lock a session, loading or creating it on first use
+    ///
+    /// Only the target session is locked; the guard releases it on drop.
+    /// Mutable access marks the session dirty until it is next saved. A
+    /// clean session whose file changed since it was loaded or saved is
+    /// loaded again; a dirty one keeps its unsaved changes.
+    pub async fn lock(&self, key: &str) -> SessionGuard {
+        let slot = {
+            let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+            Arc::clone(slots.entry(key.to_string()).or_default())
+        };
+
+        let mut guard = slot.lock_owned().await;
+        let modified = self.manager.modified_at(key).await;
+        let reload = match guard.as_mut() {
+            None => true,
+            Some(cached) if cached.synced_at == modified => false,
+            Some(cached) if self.is_dirty(key) => {
+                warn!(
+                    "Session {} changed on disk but has unsaved changes; keeping them",
+                    key
+                );
+                cached.synced_at = modified;
+                false
+            }
+            Some(_) => {
+                debug!("Session {} changed on disk, reloading", key);
+                true
+            }
+        };
+        if reload {
+            let mut session = self.manager.load(key).await.unwrap_or_else(|| {
+                Session::with_limits(
+                    key,
+                    self.manager.max_messages(),
+                    self.manager.context_window(),
+                )
+            });
+            session.max_metadata_entries = self.manager.max_metadata_entries();
+            *guard = Some(Cached {
+                session,
+                synced_at: modified,
+            });
+        }
+        SessionGuard {
+            guard,
+            key: key.to_string(),
+            dirty: Arc::clone(&self.dirty),
+        }
+    }
+
+    /// Run `f` with exclusive access to one session, marking it dirty
+    pub async fn with_session<R>(&self, key: &str, f: impl FnOnce(&mut Session) -> R) -> R {
+        let mut session = self.lock(key).await;
+        f(&mut session)
+    }
+
+    /// Run `f` with read access to one session, leaving it clean
+    pub async fn read_session<R>(&self, key: &str, f: impl FnOnce(&Session) -> R) -> R {
+        let session = self.lock(key).await;
+        f(&session)
+    }
+
+    /// Persist a session, holding its lock while writing
+    pub async fn save(&self, key: &str) -> std::io::Result<()> {
+        let mut session = self.lock(key).await;
+        self.manager.save(&session).await?;
+        session.cached_mut().synced_at = self.manager.modified_at(key).await;
+        lock_dirty(&self.dirty).remove(key);
+        Ok(())
+    }
+
+    /// Whether a session changed since it was last saved
+    pub fn is_dirty(&self, key: &str) -> bool {
+        lock_dirty(&self.dirty).contains(key)
+    }
+
+    /// Save every dirty session, returning how many were written
+    ///
+    /// Sessions that fail to save stay dirty for the next flush.
+    pub async fn flush(&self) -> usize {
+        let keys: Vec<String> = lock_dirty(&self.dirty).iter().cloned().collect();
+        let mut saved = 0;
+        for key in keys {
+            match self.save(&key).await {
+                Ok(()) => saved += 1,
+                Err(e) => warn!("Failed to flush session {}: {}", key, e),
+            }
+        }
+        saved
+    }
+
+    /// Flush dirty sessions every `interval` until the task is aborted
+    pub fn spawn_flush(&self, interval: Duration) -> JoinHandle<()> {
+        let shared = self.clone();
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        tokio::spawn(async move {
+            loop {
+                ticker.tick().await;
+                let saved = shared.flush().await;
+                if saved > 0 {
+                    debug!("Flushed {} dirty sessions", saved);
+                }
+            }
+        })
+    }
+
+    /// Underlying manager settings
+    pub fn manager(&self) -> &SessionManager {
+        &self.manager
+    }
+}
+
+/// Exclusive access to a loaded session
+pub struct SessionGuard {
+    guard: OwnedMutexGuard<Option<Cached>>,
+    key: String,
+    dirty: DirtySet,
+}
+
+impl SessionGuard {
+    /// Cache entry behind the guard, without marking the session dirty
+    fn cached_mut(&mut self) -> &mut Cached {
+        self.guard
+            .as_mut()
+            .expect("session loaded before guard is handed out")
+    }
+}
+
+impl Deref for SessionGuard {
+    type Target = Session;
+
+    fn deref(&self) -> &Session {
+        &self
+            .guard
+            .as_ref()
+            .expect("session loaded before guard is handed out")
+            .session
+    }
+}
+
+impl DerefMut for SessionGuard {
+    fn deref_mut(&mut self) -> &mut Session {
+        lock_dirty(&self.dirty).insert(self.key.clone());
+        &mut self.cached_mut().session
+    }
+}
+
+fn lock_dirty(dirty: &DirtySet) -> std::sync::MutexGuard<'_, HashSet<String>> {
+    dirty.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,25 @@
+//! Session persistence backend
+//!
+//! Saving goes through a `SessionWriter` so the filesystem can be swapped
+//! out, e.g. for a fake that fails on demand in tests.
+
+use async_trait::async_trait;
+use std::path::Path;
+
+/// Writes encoded sessions to storage
+#[async_trait]
+pub trait SessionWriter: Send + Sync {
+    /// Replace the contents at `path` with `bytes`
+    async fn write(&self, path: &Path, bytes: &[u8]) -> std::io::Result<()>;
+}
+
+/// Writes sessions to the local filesystem
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsWriter;
+
+#[async_trait]
+impl SessionWriter for FsWriter {
+    async fn write(&self, path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+        tokio::fs::write(path, bytes).await
+    }
+}
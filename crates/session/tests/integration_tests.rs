@@ -4,6 +4,7 @@
 //! - Session creation
 //! - Adding messages
 //! - History retrieval with limits
+//! - Context window separate from storage cap
 //! - Clear operation
 //! - SessionManager creation
 //! - Save/load roundtrip
@@ -11,8 +12,19 @@
 //! - Session key sanitization
 //! - List operations
 //! - Delete operations
-
-use opensam_session::{Session, SessionManager};
+//! - Persistence formats
+//! - Shared manager per-key locking
+//! - Metadata entry cap
+//! - Save retries
+//! - Periodic flush of dirty sessions
+//! - Summarizing old history
+
+use opensam_provider::Backoff;
+use opensam_session::{
+    FsWriter, Session, SessionFormat, SessionManager, SessionWriter, SharedSessionManager,
+    SUMMARY_KEY, SUMMARY_PREFIX, SYSTEM_SUFFIX_KEY,
+};
+use std::sync::Arc;
 
 use std::time::Duration;
 use tokio::time::sleep;
@@ -31,6 +43,20 @@ fn test_session_creation() {
     assert_eq!(session.created_at, session.updated_at);
 }
 
+#[test]
+fn test_session_system_suffix_set_and_clear() {
+    let mut session = Session::new("test:123");
+    assert_eq!(session.system_suffix(), None);
+
+    assert!(session.set_system_suffix("  Sprint goal: ship v2\n"));
+    assert_eq!(session.system_suffix(), Some("Sprint goal: ship v2"));
+    assert_eq!(session.metadata[SYSTEM_SUFFIX_KEY], "Sprint goal: ship v2");
+
+    assert!(session.set_system_suffix(" "));
+    assert_eq!(session.system_suffix(), None);
+    assert!(!session.metadata.contains_key(SYSTEM_SUFFIX_KEY));
+}
+
 #[test]
 fn test_session_creation_with_different_key_types() {
     // String key
@@ -600,6 +626,62 @@ fn test_history_exceeds_available() {
     assert_eq!(history.len(), 1);
 }
 
+#[test]
+fn test_history_defaults_to_context_window() {
+    let mut session = Session::with_limits("test:window", 50, 10);
+
+    for i in 0..30 {
+        session.add_message("user", format!("Message {}", i));
+    }
+
+    // Storage keeps everything up to max_messages
+    assert_eq!(session.messages.len(), 30);
+
+    // History sent to the model is the recent window only
+    let history = session.history();
+    assert_eq!(history.len(), 10);
+    assert_eq!(history[0].content.as_deref(), Some("Message 20"));
+    assert_eq!(history[9].content.as_deref(), Some("Message 29"));
+
+    // Explicit limits still work
+    assert_eq!(session.get_history(25).len(), 25);
+}
+
+#[test]
+fn test_context_window_independent_of_storage_cap() {
+    let mut session = Session::with_limits("test:window", 5, 20);
+
+    for i in 0..10 {
+        session.add_message("user", format!("Message {}", i));
+    }
+
+    assert_eq!(session.messages.len(), 5);
+    assert_eq!(session.history().len(), 5);
+    assert_eq!(session.context_window(), 20);
+}
+
+#[tokio::test]
+async fn test_session_manager_applies_context_window() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut manager = SessionManager::with_limits(temp_dir.path(), 100, 3);
+
+    let session = {
+        let s = manager.get_or_create("test:window").await;
+        for i in 0..8 {
+            s.add_message("user", format!("Message {}", i));
+        }
+        assert_eq!(s.history().len(), 3);
+        s.clone()
+    };
+    manager.save(&session).await.unwrap();
+
+    // A manager with a different window applies its own setting on load
+    let mut manager2 = SessionManager::with_limits(temp_dir.path(), 100, 6);
+    let loaded = manager2.get_or_create("test:window").await;
+    assert_eq!(loaded.messages.len(), 8);
+    assert_eq!(loaded.history().len(), 6);
+}
+
 #[tokio::test]
 async fn test_concurrent_session_operations() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -719,3 +801,445 @@ async fn test_empty_key_session() {
 
     assert_eq!(loaded.messages.len(), 1);
 }
+
+// ============================================================================
+// Persistence Format Tests
+// ============================================================================
+
+fn long_session(key: &str) -> Session {
+    let mut session = Session::new(key);
+    for i in 0..50 {
+        session.add_message("user", format!("Message number {} with some padding", i));
+    }
+    session
+}
+
+#[tokio::test]
+async fn test_compact_save_smaller_than_pretty() {
+    let pretty_dir = tempfile::tempdir().unwrap();
+    let compact_dir = tempfile::tempdir().unwrap();
+    let session = long_session("test:format");
+
+    let pretty = SessionManager::new(pretty_dir.path());
+    let compact = SessionManager::new(compact_dir.path()).with_format(SessionFormat::Compact);
+    pretty.save(&session).await.unwrap();
+    compact.save(&session).await.unwrap();
+
+    let pretty_len = std::fs::metadata(pretty_dir.path().join("test_format.json"))
+        .unwrap()
+        .len();
+    let compact_len = std::fs::metadata(compact_dir.path().join("test_format.json"))
+        .unwrap()
+        .len();
+    assert!(compact_len < pretty_len);
+}
+
+#[tokio::test]
+async fn test_gzipped_save_load_roundtrip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let session = long_session("test:gzip");
+
+    let manager = SessionManager::new(temp_dir.path()).with_format(SessionFormat::GzippedJson);
+    manager.save(&session).await.unwrap();
+
+    let bytes = std::fs::read(temp_dir.path().join("test_gzip.json")).unwrap();
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+
+    let mut manager2 = SessionManager::new(temp_dir.path());
+    let loaded = manager2.get_or_create("test:gzip").await;
+    assert_eq!(loaded.messages.len(), 50);
+    assert_eq!(loaded.messages[49].content, session.messages[49].content);
+}
+
+#[tokio::test]
+async fn test_pretty_files_load_under_any_format() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let session = long_session("test:legacy");
+    SessionManager::new(temp_dir.path())
+        .save(&session)
+        .await
+        .unwrap();
+
+    for format in [
+        SessionFormat::Pretty,
+        SessionFormat::Compact,
+        SessionFormat::GzippedJson,
+    ] {
+        let mut manager = SessionManager::new(temp_dir.path()).with_format(format);
+        let loaded = manager.get_or_create("test:legacy").await;
+        assert_eq!(loaded.messages.len(), 50, "format: {:?}", format);
+    }
+}
+
+// ============================================================================
+// SharedSessionManager
+// ============================================================================
+
+#[tokio::test]
+async fn test_shared_different_keys_run_concurrently() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shared = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+    let (a_locked_tx, a_locked_rx) = tokio::sync::oneshot::channel();
+    let (b_locked_tx, b_locked_rx) = tokio::sync::oneshot::channel();
+
+    // Each task holds its session until it sees the other holding its own,
+    // which only completes if the two locks are independent
+    let a = tokio::spawn({
+        let shared = shared.clone();
+        async move {
+            let mut session = shared.lock("chat:a").await;
+            a_locked_tx.send(()).unwrap();
+            b_locked_rx.await.unwrap();
+            session.add_message("user", "alpha");
+        }
+    });
+    let b = tokio::spawn({
+        let shared = shared.clone();
+        async move {
+            let mut session = shared.lock("chat:b").await;
+            b_locked_tx.send(()).unwrap();
+            a_locked_rx.await.unwrap();
+            session.add_message("user", "bravo");
+        }
+    });
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        a.await.unwrap();
+        b.await.unwrap();
+    })
+    .await
+    .expect("different sessions should not block each other");
+
+    let a_len = shared.read_session("chat:a", |s| s.messages.len()).await;
+    let b_len = shared.read_session("chat:b", |s| s.messages.len()).await;
+    assert_eq!((a_len, b_len), (1, 1));
+}
+
+#[tokio::test]
+async fn test_shared_same_key_is_serialized() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shared = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+
+    let mut tasks = Vec::new();
+    for i in 0..10 {
+        let shared = shared.clone();
+        tasks.push(tokio::spawn(async move {
+            let mut session = shared.lock("chat:same").await;
+            let before = session.messages.len();
+            // Yield while holding the lock; another holder would see `before`
+            sleep(Duration::from_millis(2)).await;
+            session.add_message("user", format!("turn {}", i));
+            assert_eq!(session.messages.len(), before + 1);
+        }));
+    }
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        for task in tasks {
+            task.await.unwrap();
+        }
+    })
+    .await
+    .expect("same-session access should not deadlock");
+
+    let len = shared.read_session("chat:same", |s| s.messages.len()).await;
+    assert_eq!(len, 10);
+}
+
+#[tokio::test]
+async fn test_shared_save_and_reload() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shared = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+
+    shared
+        .with_session("chat:saved", |s| s.add_message("user", "persist me"))
+        .await;
+    shared.save("chat:saved").await.unwrap();
+
+    let reloaded = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+    let content = reloaded
+        .read_session("chat:saved", |s| s.messages[0].content.clone())
+        .await;
+    assert_eq!(content, "persist me");
+}
+
+#[test]
+fn test_session_metadata_cap_stops_growth() {
+    let mut session = Session::new("chat:meta");
+    session.max_metadata_entries = 3;
+
+    for i in 0..10 {
+        session.set_metadata(format!("key_{}", i), i);
+    }
+
+    assert_eq!(session.metadata.len(), 3);
+    assert!(session.metadata.contains_key("key_0"));
+    assert!(session.metadata.contains_key("key_2"));
+    assert!(!session.metadata.contains_key("key_3"));
+
+    // Existing keys can still be updated at the cap
+    assert!(session.set_metadata("key_0", "updated"));
+    assert_eq!(session.metadata["key_0"], "updated");
+}
+
+#[tokio::test]
+async fn test_manager_applies_metadata_cap() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut manager = SessionManager::new(temp_dir.path()).with_max_metadata_entries(1);
+
+    let session = manager.get_or_create("chat:meta").await;
+    assert!(session.set_metadata("first", true));
+    assert!(!session.set_metadata("second", true));
+    assert_eq!(session.metadata.len(), 1);
+}
+
+/// Writer that fails a fixed number of times before delegating to disk
+struct FlakyWriter {
+    failures_left: std::sync::atomic::AtomicU32,
+    attempts: Arc<std::sync::atomic::AtomicU32>,
+}
+
+impl FlakyWriter {
+    fn new(failures: u32) -> (Self, Arc<std::sync::atomic::AtomicU32>) {
+        let attempts = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let writer = Self {
+            failures_left: std::sync::atomic::AtomicU32::new(failures),
+            attempts: Arc::clone(&attempts),
+        };
+        (writer, attempts)
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionWriter for FlakyWriter {
+    async fn write(&self, path: &std::path::Path, bytes: &[u8]) -> std::io::Result<()> {
+        use std::sync::atomic::Ordering;
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        let remaining = self.failures_left.load(Ordering::SeqCst);
+        if remaining > 0 {
+            self.failures_left.store(remaining - 1, Ordering::SeqCst);
+            return Err(std::io::Error::other("no space left on device"));
+        }
+        FsWriter.write(path, bytes).await
+    }
+}
+
+fn fast_backoff() -> Backoff {
+    Backoff::new(Duration::from_millis(1), Duration::from_millis(5))
+}
+
+#[tokio::test]
+async fn test_save_retries_transient_failure() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let (writer, attempts) = FlakyWriter::new(1);
+    let manager = SessionManager::new(temp_dir.path())
+        .with_writer(writer)
+        .with_save_retry(3, fast_backoff());
+
+    let mut session = Session::new("chat:flaky");
+    session.add_message("user", "keep me");
+    manager.save(&session).await.unwrap();
+
+    assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+    let mut reloaded = SessionManager::new(temp_dir.path());
+    let loaded = reloaded.get_or_create("chat:flaky").await;
+    assert_eq!(loaded.messages[0].content, "keep me");
+}
+
+#[tokio::test]
+async fn test_save_gives_up_after_attempts_and_keeps_session() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let (writer, attempts) = FlakyWriter::new(10);
+    let shared = SharedSessionManager::new(
+        SessionManager::new(temp_dir.path())
+            .with_writer(writer)
+            .with_save_retry(2, fast_backoff()),
+    );
+
+    shared
+        .with_session("chat:down", |s| s.add_message("user", "not lost"))
+        .await;
+    assert!(shared.save("chat:down").await.is_err());
+    assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    // The turn stays in memory for the next save
+    let len = shared.read_session("chat:down", |s| s.messages.len()).await;
+    assert_eq!(len, 1);
+}
+
+/// Sleep in 1ms steps until `done` holds, giving up after about a second
+///
+/// The paused clock does not auto-advance while file writes run on blocking
+/// threads, so each step also waits for pending writes.
+async fn settle(done: impl Fn() -> bool) {
+    for _ in 0..1000 {
+        if done() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_shared_mutation_marks_dirty_until_saved() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shared = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+
+    shared
+        .with_session("chat:a", |s| s.add_message("user", "hi"))
+        .await;
+    assert!(shared.is_dirty("chat:a"));
+
+    shared.save("chat:a").await.unwrap();
+    assert!(!shared.is_dirty("chat:a"));
+}
+
+#[tokio::test]
+async fn test_shared_read_leaves_session_clean() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shared = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+
+    shared
+        .with_session("chat:a", |s| s.add_message("user", "hi"))
+        .await;
+    shared.save("chat:a").await.unwrap();
+
+    let len = shared.read_session("chat:a", |s| s.messages.len()).await;
+    assert_eq!(len, 1);
+    assert!(!shared.is_dirty("chat:a"));
+    assert_eq!(shared.flush().await, 0);
+}
+
+#[tokio::test]
+async fn test_shared_flush_saves_only_dirty_sessions() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shared = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+
+    shared
+        .with_session("chat:a", |s| s.add_message("user", "hi"))
+        .await;
+    shared
+        .with_session("chat:b", |s| s.add_message("user", "yo"))
+        .await;
+    shared.save("chat:b").await.unwrap();
+
+    assert_eq!(shared.flush().await, 1);
+    assert!(temp_dir.path().join("chat_a.json").exists());
+    assert_eq!(shared.flush().await, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_shared_periodic_flush_writes_without_save() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shared = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+    let path = temp_dir.path().join("chat_a.json");
+
+    let task = shared.spawn_flush(Duration::from_secs(30));
+    shared
+        .with_session("chat:a", |s| s.add_message("user", "unsaved"))
+        .await;
+
+    tokio::time::advance(Duration::from_secs(20)).await;
+    settle(|| path.exists()).await;
+    assert!(!path.exists());
+
+    tokio::time::advance(Duration::from_secs(15)).await;
+    settle(|| !shared.is_dirty("chat:a")).await;
+    assert!(path.exists());
+    assert!(!shared.is_dirty("chat:a"));
+
+    task.abort();
+    let mut manager = SessionManager::new(temp_dir.path());
+    let session = manager.get_or_create("chat:a").await;
+    assert_eq!(session.messages[0].content, "unsaved");
+}
+
+/// Set a suffix the way `sam session set-suffix` does, from its own manager
+async fn set_suffix_elsewhere(dir: &std::path::Path, key: &str, text: &str) {
+    let mut manager = SessionManager::new(dir);
+    let session = manager.get_or_create(key).await;
+    assert!(session.set_system_suffix(text));
+    let session = session.clone();
+    manager.save(&session).await.unwrap();
+
+    // Keep the change visible on filesystems with coarse timestamps
+    let path = dir.join(format!("{}.json", key.replace(':', "_")));
+    std::fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() + Duration::from_secs(5))
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_shared_reloads_session_changed_on_disk() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shared = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+    shared
+        .with_session("chat:a", |s| s.add_message("user", "first"))
+        .await;
+    shared.save("chat:a").await.unwrap();
+
+    set_suffix_elsewhere(temp_dir.path(), "chat:a", "Be brief").await;
+
+    shared
+        .with_session("chat:a", |s| s.add_message("user", "second"))
+        .await;
+    shared.save("chat:a").await.unwrap();
+
+    let mut manager = SessionManager::new(temp_dir.path());
+    let session = manager.get_or_create("chat:a").await;
+    assert_eq!(session.system_suffix(), Some("Be brief"));
+    assert_eq!(session.messages.len(), 2);
+}
+
+#[tokio::test]
+async fn test_shared_keeps_unsaved_changes_over_disk_edits() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shared = SharedSessionManager::new(SessionManager::new(temp_dir.path()));
+    shared.save("chat:a").await.unwrap();
+    shared
+        .with_session("chat:a", |s| s.add_message("user", "unsaved"))
+        .await;
+
+    set_suffix_elsewhere(temp_dir.path(), "chat:a", "Be brief").await;
+
+    let (messages, suffix) = shared
+        .read_session("chat:a", |s| {
+            (s.messages.len(), s.system_suffix().map(str::to_string))
+        })
+        .await;
+    assert_eq!(messages, 1);
+    assert_eq!(suffix, None);
+}
+
+#[test]
+fn test_summarize_old_replaces_older_messages() {
+    let mut session = Session::new("test:summary");
+    for i in 0..6 {
+        session.add_message("user", format!("msg {}", i));
+    }
+
+    assert_eq!(session.old_messages(2).len(), 4);
+    assert_eq!(session.summarize_old(2, "four earlier messages"), 4);
+
+    assert_eq!(session.messages.len(), 3);
+    assert_eq!(session.messages[0].role, "system");
+    assert_eq!(
+        session.messages[0].content,
+        format!("{}four earlier messages", SUMMARY_PREFIX)
+    );
+    assert_eq!(session.messages[0].extra[SUMMARY_KEY], true);
+    assert_eq!(session.messages[1].content, "msg 4");
+    assert_eq!(session.messages[2].content, "msg 5");
+}
+
+#[test]
+fn test_summarize_old_noop_when_nothing_old() {
+    let mut session = Session::new("test:summary");
+    session.add_message("user", "only");
+
+    assert_eq!(session.summarize_old(5, "unused"), 0);
+    assert_eq!(session.messages.len(), 1);
+    assert_eq!(session.messages[0].content, "only");
+}